@@ -75,7 +75,7 @@ fn main() {
 
 	for value in test_values {
 		match value.parse::<Side>() {
-			Ok(side) => println!("  '{}' -> {} ({})", value, side, format!("{:?}", side)),
+			Ok(side) => println!("  '{}' -> {} ({:?})", value, side, side),
 			Err(_) => println!("  '{}' -> Invalid side value", value),
 		}
 	}
@@ -83,12 +83,12 @@ fn main() {
 
 	// Example 6: Working with Collections:
 	println!("6. Working with Collections:");
-	let sides = vec![Side::Buy, Side::Sell];
+	let sides = [Side::Buy, Side::Sell];
 	let side_strings: Vec<String> = sides.iter().map(|s| s.to_string()).collect();
 
 	println!("  Sides as strings: {:?}", side_strings);
 
-	let statuses = vec![OrdStatus::New, OrdStatus::PartiallyFilled, OrdStatus::Filled];
+	let statuses = [OrdStatus::New, OrdStatus::PartiallyFilled, OrdStatus::Filled];
 
 	println!("  Status progression: {}", statuses.iter().map(|s| format!("{}", s)).collect::<Vec<_>>().join(" -> "));
 	println!();
@@ -63,7 +63,7 @@ fn main() {
 	println!("6000=TEST1234                - Custom Field");
 	println!(
 		"10={}                          - CheckSum (calculated automatically)",
-		fix_wire.split('\x01').last().unwrap_or("10=?")
+		fix_wire.split('\x01').next_back().unwrap_or("10=?")
 	);
 
 	println!("let msg = FixMessage::builder(\"D\".parse().unwrap(), ...)  // NewOrderSingle");
@@ -0,0 +1,40 @@
+//! Exchange simulator serving the acceptor sessions of a settings file
+//!
+//! Usage: `fix-simulator <settings.cfg>`
+//!
+//! Every acceptor session of the file trades against one shared set of order
+//! books; messages are stored as configured by its FileStorePath.
+
+use fix_learning::{Acceptor, SessionConfig, SessionSettings, simulator::Simulator};
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+	let Some(path) = std::env::args().nth(1) else {
+		eprintln!("Usage: fix-simulator <settings.cfg>");
+		return ExitCode::FAILURE;
+	};
+	let acceptor = match SessionSettings::load(&path) {
+		Ok(settings) => Acceptor::from_settings(&settings, SessionConfig::file_store),
+		Err(e) => {
+			eprintln!("Cannot load {}: {}", path, e);
+			return ExitCode::FAILURE;
+		},
+	};
+	let simulator = match acceptor {
+		Ok(acceptor) => Simulator::new(acceptor),
+		Err(e) => {
+			eprintln!("Cannot start the simulator: {}", e);
+			return ExitCode::FAILURE;
+		},
+	};
+	match simulator.local_addr() {
+		Ok(address) => println!("Simulator listening on {}", address),
+		Err(e) => eprintln!("Simulator listening on an unknown address: {}", e),
+	}
+
+	loop {
+		if let Err(e) = simulator.accept() {
+			eprintln!("Connection refused: {}", e);
+		}
+	}
+}
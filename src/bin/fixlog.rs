@@ -0,0 +1,406 @@
+//! Search and pretty-print FIX logs
+//!
+//! Usage: `fixlog [options] [file...]`
+//!
+//! Reads the given log files, or stdin when there are none or for `-`, finds
+//! the FIX frames of every line and prints those matching the filters.
+//!
+//! Options:
+//!
+//! - `-t, --msg-type <type>`: keep this MsgType, given by code (`D`) or name (`NewOrderSingle`)
+//! - `-c, --comp-id <id>`: keep messages sent by or to this CompID
+//! - `-w, --where <tag=value>`: keep messages holding this field, the tag given by number or name
+//! - `--from <time>`, `--to <time>`: keep messages of this time range (`YYYYMMDD-HH:MM:SS[.sss]`)
+//! - `-o, --output <pretty|json|csv|raw>`: output format, `pretty` by default
+//! - `--columns <tag,...>`: fields of the CSV output, MsgType, SenderCompID, TargetCompID and MsgSeqNum by default
+//! - `-s, --stats`: print per-MsgType statistics instead of the messages
+//! - `-d, --dictionary <FIX42.xml>`: QuickFIX data dictionary naming the fields
+//!
+//! Options taking a value may be repeated; messages must match every kind of
+//! filter given and any of the values given for it.
+//!
+//! `fixlog diff [-i] [--ignore <tag>] [-d <FIX42.xml>] <left> <right>` compares
+//! two messages field by field. Each message is given as text (fields separated
+//! by SOH, `|` or `^A`) or as a file holding it; `-i, --ignore-volatile` leaves
+//! out BodyLength, CheckSum, MsgSeqNum and SendingTime. The exit status is 0
+//! when the messages match and 1 when they differ.
+
+use fix_learning::{
+	Dictionary, PrettyPrinter, SOH,
+	diff::{DiffOptions, diff_raw},
+	logs::{LogFilter, LogReader, LogRecord, LogStats, parse_line},
+	parse_fix_timestamp,
+};
+use std::{
+	fs::{self, File},
+	io::{self, BufRead, BufReader, BufWriter, Write},
+	process::ExitCode,
+};
+use time::OffsetDateTime;
+
+const USAGE: &str = "Usage: fixlog [-t msg-type] [-c comp-id] [-w tag=value] [--from time] [--to time] \
+	[-o pretty|json|csv|raw] [--columns tag,...] [-s] [-d dictionary.xml] [file...]\n       \
+	fixlog diff [-i] [--ignore tag] [-d dictionary.xml] <left> <right>";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Output {
+	Pretty,
+	Json,
+	Csv,
+	Raw,
+}
+
+#[derive(Debug)]
+struct Options {
+	filter: LogFilter,
+	output: Output,
+	columns: Vec<u32>,
+	stats: bool,
+	dictionary: Dictionary,
+	files: Vec<String>,
+}
+
+fn main() -> ExitCode {
+	if std::env::args().nth(1).as_deref() == Some("diff") {
+		return match run_diff(std::env::args().skip(2)) {
+			Ok(true) => ExitCode::SUCCESS,
+			Ok(false) => ExitCode::from(1),
+			Err(message) => {
+				eprintln!("{}\n{}", message, USAGE);
+				ExitCode::from(2)
+			},
+		};
+	}
+	let options = match parse_args(std::env::args().skip(1)) {
+		Ok(Some(options)) => options,
+		Ok(None) => {
+			println!("Search and pretty-print FIX logs\n{}", USAGE);
+			return ExitCode::SUCCESS;
+		},
+		Err(message) => {
+			eprintln!("{}\n{}", message, USAGE);
+			return ExitCode::FAILURE;
+		},
+	};
+	match run(&options) {
+		Ok(()) => ExitCode::SUCCESS,
+		// Output piped into `head` and the like
+		Err(e) if e.kind() == io::ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+		Err(e) => {
+			eprintln!("fixlog: {}", e);
+			ExitCode::FAILURE
+		},
+	}
+}
+
+/// Print the differences between two messages, returning whether they match
+fn run_diff(mut args: impl Iterator<Item = String>) -> Result<bool, String> {
+	let mut ignore_volatile = false;
+	let mut ignored = Vec::new();
+	let mut dictionary = None;
+	let mut messages = Vec::new();
+	while let Some(arg) = args.next() {
+		let mut value = || args.next().ok_or_else(|| format!("Missing value for {}", arg));
+		match arg.as_str() {
+			"-h" | "--help" => {
+				println!("Compare two FIX messages field by field\n{}", USAGE);
+				return Ok(true);
+			},
+			"-i" | "--ignore-volatile" => ignore_volatile = true,
+			"--ignore" => ignored.push(value()?),
+			"-d" | "--dictionary" => {
+				let path = value()?;
+				dictionary = Some(Dictionary::load(&path).map_err(|e| format!("Cannot load {}: {}", path, e))?);
+			},
+			_ if arg.starts_with('-') => return Err(format!("Unknown option {}", arg)),
+			_ => messages.push(arg),
+		}
+	}
+	let [left, right] = messages.as_slice() else { return Err("Expected two messages".to_string()) };
+
+	let dictionary = dictionary.unwrap_or_else(|| Dictionary::fix42().clone());
+	let mut options = DiffOptions::new();
+	if ignore_volatile {
+		options = options.ignore_volatile();
+	}
+	for tag in ignored {
+		options = options.ignore_tag(resolve_tag(&dictionary, &tag)?);
+	}
+	let changes = diff_raw(&read_message(left)?, &read_message(right)?, &options);
+	if !changes.is_empty() {
+		println!("{}", changes.format(&dictionary));
+	}
+	Ok(changes.is_empty())
+}
+
+/// The first frame of `source`, a file or the text of a message
+///
+/// Only a source holding a field (`tag=value`) is taken as text when it is not a
+/// readable file, so a mistyped path is reported as such.
+fn read_message(source: &str) -> Result<String, String> {
+	let text = match fs::read_to_string(source) {
+		Ok(text) => text,
+		Err(_) if source.contains('=') => source.to_string(),
+		Err(e) => return Err(format!("Cannot read {}: {}", source, e)),
+	};
+	let record = text.lines().enumerate().find_map(|(line, text)| parse_line(line + 1, text).into_iter().next());
+	record.map(|record| record.frame).ok_or_else(|| format!("No FIX message in {}", source))
+}
+
+/// Options of the command line, `None` when help was asked for
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<Options>, String> {
+	let mut raw_filters = Vec::new();
+	let mut output = Output::Pretty;
+	let mut columns = None;
+	let mut stats = false;
+	let mut dictionary = None;
+	let mut files = Vec::new();
+	while let Some(arg) = args.next() {
+		let mut value = || args.next().ok_or_else(|| format!("Missing value for {}", arg));
+		match arg.as_str() {
+			"-t" | "--msg-type" | "-c" | "--comp-id" | "-w" | "--where" | "--from" | "--to" => {
+				let value = value()?;
+				raw_filters.push((arg, value));
+			},
+			"-o" | "--output" => {
+				output = match value()?.as_str() {
+					"pretty" => Output::Pretty,
+					"json" => Output::Json,
+					"csv" => Output::Csv,
+					"raw" => Output::Raw,
+					other => return Err(format!("Unknown output format {}", other)),
+				}
+			},
+			"--columns" => columns = Some(value()?),
+			"-s" | "--stats" => stats = true,
+			"-d" | "--dictionary" => {
+				let path = value()?;
+				dictionary = Some(Dictionary::load(&path).map_err(|e| format!("Cannot load {}: {}", path, e))?);
+			},
+			"-h" | "--help" => return Ok(None),
+			_ if arg.starts_with('-') && arg != "-" => return Err(format!("Unknown option {}", arg)),
+			_ => files.push(arg),
+		}
+	}
+
+	// Names are resolved once the dictionary is known
+	let dictionary = dictionary.unwrap_or_else(|| Dictionary::fix42().clone());
+	let mut filter = LogFilter::new();
+	for (option, value) in raw_filters {
+		filter = match option.as_str() {
+			"-t" | "--msg-type" => filter.with_msg_type(msg_type_code(&dictionary, &value)),
+			"-c" | "--comp-id" => filter.with_comp_id(value),
+			"-w" | "--where" => {
+				let (tag, value) = value.split_once('=').ok_or_else(|| format!("Expected tag=value, got {}", value))?;
+				filter.with_field(resolve_tag(&dictionary, tag)?, value)
+			},
+			"--from" => filter.with_from(parse_time(&value)?),
+			_ => filter.with_to(parse_time(&value)?),
+		};
+	}
+	let columns = match columns {
+		Some(columns) => {
+			columns.split(',').map(|tag| resolve_tag(&dictionary, tag.trim())).collect::<Result<_, _>>()?
+		},
+		None => vec![35, 49, 56, 34],
+	};
+	Ok(Some(Options { filter, output, columns, stats, dictionary, files }))
+}
+
+/// A MsgType given by code or by the name of its value
+fn msg_type_code(dictionary: &Dictionary, msg_type: &str) -> String {
+	dictionary
+		.field(35)
+		.and_then(|field| field.values.iter().find(|(_, name)| name.eq_ignore_ascii_case(msg_type)))
+		.map_or_else(|| msg_type.to_string(), |(code, _)| code.clone())
+}
+
+fn resolve_tag(dictionary: &Dictionary, tag: &str) -> Result<u32, String> {
+	tag.parse().ok().or_else(|| dictionary.tag(tag)).ok_or_else(|| format!("Unknown field {}", tag))
+}
+
+fn parse_time(time: &str) -> Result<OffsetDateTime, String> {
+	parse_fix_timestamp(time).map_err(|e| format!("Invalid time {}: {}", time, e))
+}
+
+fn run(options: &Options) -> io::Result<()> {
+	let stdout = io::stdout();
+	let mut out = BufWriter::new(stdout.lock());
+	let printer = PrettyPrinter::new(&options.dictionary);
+	let mut stats = LogStats::new();
+	let mut first = true;
+
+	if options.output == Output::Csv && !options.stats {
+		let names = options.columns.iter().map(|tag| column_name(&options.dictionary, *tag));
+		let header: Vec<String> = ["Time".to_string(), "Line".to_string()].into_iter().chain(names).collect();
+		writeln!(out, "{}", header.join(","))?;
+	}
+
+	let files = if options.files.is_empty() { vec!["-".to_string()] } else { options.files.clone() };
+	for path in &files {
+		let input: Box<dyn BufRead> = if path == "-" {
+			Box::new(io::stdin().lock())
+		} else {
+			let file = File::open(path).map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path, e)))?;
+			Box::new(BufReader::new(file))
+		};
+		for record in LogReader::new(input) {
+			let record = record?;
+			if !options.filter.matches(&record) {
+				continue;
+			}
+			if options.stats {
+				stats.add(&record);
+				continue;
+			}
+			match options.output {
+				Output::Pretty => {
+					if !first {
+						writeln!(out)?;
+					}
+					writeln!(out, "{}", record_heading(path, &record))?;
+					writeln!(out, "{}", printer.format_raw(&record.frame))?;
+				},
+				Output::Json => writeln!(out, "{}", to_json(&options.dictionary, &record))?,
+				Output::Csv => {
+					let time = record.time().map(format_time).unwrap_or_default();
+					let mut row = vec![time, record.line.to_string()];
+					row.extend(options.columns.iter().map(|tag| csv_escape(record.field(*tag).unwrap_or_default())));
+					writeln!(out, "{}", row.join(","))?;
+				},
+				Output::Raw => writeln!(out, "{}", record.frame.replace(SOH, "|"))?,
+			}
+			first = false;
+		}
+	}
+
+	if options.stats {
+		write_stats(&mut out, &options.dictionary, &stats)?;
+	}
+	out.flush()
+}
+
+fn record_heading(path: &str, record: &LogRecord) -> String {
+	let mut heading = format!("--- {}:{}", if path == "-" { "<stdin>" } else { path }, record.line);
+	if let Some(time) = record.time() {
+		heading.push(' ');
+		heading.push_str(&format_time(time));
+	}
+	if let Err(e) = record.parse() {
+		heading.push_str(&format!(" [invalid: {}]", e));
+	}
+	heading
+}
+
+fn write_stats(out: &mut impl Write, dictionary: &Dictionary, stats: &LogStats) -> io::Result<()> {
+	writeln!(out, "{:<8} {:<32} {:>8} {:>8}  {:<21}  Last", "MsgType", "Name", "Count", "Invalid", "First")?;
+	for (msg_type, entry) in stats.iter() {
+		let name = dictionary.value_description(35, msg_type).unwrap_or_default();
+		let first = entry.first.map(format_time).unwrap_or_default();
+		let last = entry.last.map(format_time).unwrap_or_default();
+		writeln!(
+			out,
+			"{:<8} {:<32} {:>8} {:>8}  {:<21}  {:<21}",
+			msg_type, name, entry.count, entry.invalid, first, last
+		)?;
+	}
+	writeln!(out, "{:<8} {:<32} {:>8}", "", "Total", stats.total())
+}
+
+fn column_name(dictionary: &Dictionary, tag: u32) -> String {
+	dictionary.field_name(tag).map_or_else(|| tag.to_string(), str::to_string)
+}
+
+fn format_time(time: OffsetDateTime) -> String {
+	let mut buf = String::with_capacity(21);
+	fix_learning::common::write_timestamp(&mut buf, time);
+	buf
+}
+
+fn csv_escape(value: &str) -> String {
+	if value.contains([',', '"', '\n']) { format!("\"{}\"", value.replace('"', "\"\"")) } else { value.to_string() }
+}
+
+/// One JSON object per record, its fields kept in wire order since tags repeat in groups
+fn to_json(dictionary: &Dictionary, record: &LogRecord) -> String {
+	let fields: Vec<String> = record
+		.fields()
+		.map(|(tag, value)| {
+			let name = dictionary.field_name(tag).map_or_else(|| "null".to_string(), json_string);
+			format!("{{\"tag\":{},\"name\":{},\"value\":{}}}", tag, name, json_string(value))
+		})
+		.collect();
+	let time = record.time().map_or_else(|| "null".to_string(), |time| json_string(&format_time(time)));
+	let error = match record.parse() {
+		Ok(_) => "null".to_string(),
+		Err(e) => json_string(&e.to_string()),
+	};
+	format!("{{\"line\":{},\"time\":{},\"error\":{},\"fields\":[{}]}}", record.line, time, error, fields.join(","))
+}
+
+fn json_string(value: &str) -> String {
+	let mut escaped = String::with_capacity(value.len() + 2);
+	escaped.push('"');
+	for c in value.chars() {
+		match c {
+			'"' => escaped.push_str("\\\""),
+			'\\' => escaped.push_str("\\\\"),
+			'\n' => escaped.push_str("\\n"),
+			'\r' => escaped.push_str("\\r"),
+			'\t' => escaped.push_str("\\t"),
+			c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+			c => escaped.push(c),
+		}
+	}
+	escaped.push('"');
+	escaped
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn args(args: &str) -> impl Iterator<Item = String> {
+		args.split_whitespace().map(str::to_string).collect::<Vec<_>>().into_iter()
+	}
+
+	#[test]
+	fn test_parse_args() {
+		let options = parse_args(args("-t NewOrderSingle -w Symbol=AAPL -o csv --columns 11,Side -s a.log -")).unwrap();
+		let options = options.unwrap();
+		assert_eq!(options.output, Output::Csv);
+		assert_eq!(options.columns, vec![11, 54]);
+		assert!(options.stats);
+		assert_eq!(options.files, vec!["a.log", "-"]);
+
+		let default = parse_args(args("")).unwrap().unwrap();
+		assert_eq!((default.output, default.columns), (Output::Pretty, vec![35, 49, 56, 34]));
+		assert!(parse_args(args("-h")).unwrap().is_none());
+		assert_eq!(parse_args(args("-o xml")).err().unwrap(), "Unknown output format xml");
+		assert_eq!(parse_args(args("-w Symbol")).err().unwrap(), "Expected tag=value, got Symbol");
+		assert_eq!(parse_args(args("--columns NoSuchField")).err().unwrap(), "Unknown field NoSuchField");
+		assert_eq!(parse_args(args("--from")).err().unwrap(), "Missing value for --from");
+		assert_eq!(parse_args(args("-x")).err().unwrap(), "Unknown option -x");
+	}
+
+	#[test]
+	fn test_read_message() {
+		assert_eq!(read_message("8=FIX.4.2|35=0|10=000|").unwrap(), "8=FIX.4.2\x0135=0\x0110=000\x01");
+		assert!(read_message("no/such/file.log").unwrap_err().starts_with("Cannot read no/such/file.log"));
+	}
+
+	#[test]
+	fn test_csv_escape() {
+		assert_eq!(csv_escape("AAPL"), "AAPL");
+		assert_eq!(csv_escape("a,b"), "\"a,b\"");
+		assert_eq!(csv_escape("say \"hi\""), "\"say \"\"hi\"\"\"");
+		assert_eq!(csv_escape("two\nlines"), "\"two\nlines\"");
+	}
+
+	#[test]
+	fn test_json_string() {
+		assert_eq!(json_string("AAPL"), "\"AAPL\"");
+		assert_eq!(json_string("a\"b\\c"), "\"a\\\"b\\\\c\"");
+		assert_eq!(json_string("\n\t\u{1}"), "\"\\n\\t\\u0001\"");
+	}
+}
@@ -7,12 +7,12 @@
 use crate::{
 	FixMessage, OrdStatus,
 	common::{
-		EncryptMethod, FixHeader, FixTrailer, MsgType, Side,
+		BusinessRejectReason, CxlRejReason, CxlRejResponseTo, DkReason, EncryptMethod, FixHeader, FixTrailer,
+		MdEntryType, MdReqRejReason, MdUpdateType, MsgType, SOH, SessionRejectReason, Side, SubscriptionRequestType,
+		TimeInForce, calculate_checksum,
 		validation::{FixFieldHandler, WriteTo},
 	},
-	messages::{
-		ExecutionReportBody, FixMessageBody, HeartbeatBody, LogonBody, NewOrderSingleBody, OrderCancelRequestBody,
-	},
+	messages::{FixMessageBody, MdEntry, MdIncrementalEntry, RelatedSym},
 };
 
 use time::OffsetDateTime;
@@ -31,15 +31,7 @@ impl FixMessageBuilder {
 		target_comp_id: impl Into<String>,
 		msg_seq_num: u32,
 	) -> Self {
-		let body = match msg_type {
-			MsgType::Heartbeat => FixMessageBody::Heartbeat(HeartbeatBody::default()),
-			MsgType::Logon => FixMessageBody::Logon(LogonBody::default()),
-			MsgType::NewOrderSingle => FixMessageBody::NewOrderSingle(NewOrderSingleBody::default()),
-			MsgType::ExecutionReport => FixMessageBody::ExecutionReport(ExecutionReportBody::default()),
-			MsgType::OrderCancelRequest => FixMessageBody::OrderCancelRequest(OrderCancelRequestBody::default()),
-			_ => FixMessageBody::Other,
-		};
-
+		let body = FixMessageBody::for_msg_type(&msg_type);
 		let header = FixHeader::new(msg_type, sender_comp_id, target_comp_id, msg_seq_num);
 		let trailer = FixTrailer::default();
 
@@ -79,10 +71,22 @@ impl FixMessageBuilder {
 
 	// Heartbeat body setters
 
-	/// Set the test request ID for heartbeat messages
+	/// Set the test request ID for heartbeat and test request messages
 	pub fn test_req_id(mut self, test_req_id: impl Into<String>) -> Self {
-		if let FixMessageBody::Heartbeat(ref mut body) = self.message.body {
-			body.test_req_id = Some(test_req_id.into());
+		match self.message.body {
+			FixMessageBody::Heartbeat(ref mut body) => body.test_req_id = Some(test_req_id.into()),
+			FixMessageBody::TestRequest(ref mut body) => body.test_req_id = test_req_id.into(),
+			_ => {},
+		}
+		self
+	}
+
+	// Logout body setters
+
+	/// Set the explanatory text for logout messages
+	pub fn logout_text(mut self, text: impl Into<String>) -> Self {
+		if let FixMessageBody::Logout(body) = &mut self.message.body {
+			body.text = Some(text.into());
 		}
 		self
 	}
@@ -131,87 +135,275 @@ impl FixMessageBuilder {
 		self
 	}
 
+	/// Set the raw data (e.g. an authentication token) for logon messages
+	pub fn raw_data(mut self, raw_data: impl Into<String>) -> Self {
+		if let FixMessageBody::Logon(body) = &mut self.message.body {
+			body.raw_data = Some(raw_data.into());
+		}
+		self
+	}
+
+	/// Set the username for logon messages
+	pub fn username(mut self, username: impl Into<String>) -> Self {
+		if let FixMessageBody::Logon(body) = &mut self.message.body {
+			body.username = Some(username.into());
+		}
+		self
+	}
+
+	/// Set the password for logon messages
+	pub fn password(mut self, password: impl Into<String>) -> Self {
+		if let FixMessageBody::Logon(body) = &mut self.message.body {
+			body.password = Some(password.into());
+		}
+		self
+	}
+
+	// Resend Request body setters
+
+	/// Set the first sequence number to resend
+	pub const fn begin_seq_no(mut self, seq_num: u32) -> Self {
+		if let FixMessageBody::ResendRequest(body) = &mut self.message.body {
+			body.begin_seq_no = seq_num;
+		}
+		self
+	}
+
+	/// Set the last sequence number to resend (0 for infinity)
+	pub const fn end_seq_no(mut self, seq_num: u32) -> Self {
+		if let FixMessageBody::ResendRequest(body) = &mut self.message.body {
+			body.end_seq_no = seq_num;
+		}
+		self
+	}
+
+	// Reject and Business Message Reject body setters
+
+	/// Set the MsgSeqNum of the rejected message
+	pub fn ref_seq_num(mut self, seq_num: u32) -> Self {
+		match self.message.body {
+			FixMessageBody::Reject(ref mut body) => body.ref_seq_num = seq_num,
+			FixMessageBody::BusinessMessageReject(ref mut body) => body.ref_seq_num = Some(seq_num),
+			_ => {},
+		}
+		self
+	}
+
+	/// Set the tag number of the field at fault
+	pub const fn ref_tag_id(mut self, tag: u32) -> Self {
+		if let FixMessageBody::Reject(body) = &mut self.message.body {
+			body.ref_tag_id = Some(tag);
+		}
+		self
+	}
+
+	/// Set the MsgType of the rejected message
+	pub fn ref_msg_type(mut self, msg_type: MsgType) -> Self {
+		match self.message.body {
+			FixMessageBody::Reject(ref mut body) => body.ref_msg_type = Some(msg_type),
+			FixMessageBody::BusinessMessageReject(ref mut body) => body.ref_msg_type = msg_type,
+			_ => {},
+		}
+		self
+	}
+
+	/// Set the reason of a session-level reject
+	pub fn session_reject_reason(mut self, reason: SessionRejectReason) -> Self {
+		if let FixMessageBody::Reject(body) = &mut self.message.body {
+			body.session_reject_reason = Some(reason);
+		}
+		self
+	}
+
+	/// Set the business-level ID of the rejected message, e.g. its ClOrdID
+	pub fn business_reject_ref_id(mut self, ref_id: impl Into<String>) -> Self {
+		if let FixMessageBody::BusinessMessageReject(body) = &mut self.message.body {
+			body.business_reject_ref_id = Some(ref_id.into());
+		}
+		self
+	}
+
+	/// Set the reason of a business-level reject
+	pub fn business_reject_reason(mut self, reason: BusinessRejectReason) -> Self {
+		if let FixMessageBody::BusinessMessageReject(body) = &mut self.message.body {
+			body.business_reject_reason = reason;
+		}
+		self
+	}
+
+	/// Set the explanatory text for reject and business message reject messages
+	pub fn reject_text(mut self, text: impl Into<String>) -> Self {
+		match self.message.body {
+			FixMessageBody::Reject(ref mut body) => body.text = Some(text.into()),
+			FixMessageBody::BusinessMessageReject(ref mut body) => body.text = Some(text.into()),
+			_ => {},
+		}
+		self
+	}
+
+	// Sequence Reset body setters
+
+	/// Set the gap fill flag for sequence reset messages
+	pub const fn gap_fill_flag(mut self, flag: bool) -> Self {
+		if let FixMessageBody::SequenceReset(body) = &mut self.message.body {
+			body.gap_fill_flag = Some(flag);
+		}
+		self
+	}
+
+	/// Set the new sequence number for sequence reset messages
+	pub const fn new_seq_no(mut self, seq_num: u32) -> Self {
+		if let FixMessageBody::SequenceReset(body) = &mut self.message.body {
+			body.new_seq_no = seq_num;
+		}
+		self
+	}
+
 	pub fn cl_ord_id(mut self, cl_ord_id: impl Into<String>) -> Self {
-		if let FixMessageBody::NewOrderSingle(body) = &mut self.message.body {
-			body.cl_ord_id = cl_ord_id.into();
+		let cl_ord_id = cl_ord_id.into();
+		match &mut self.message.body {
+			FixMessageBody::NewOrderSingle(body) => body.cl_ord_id = cl_ord_id,
+			FixMessageBody::OrderCancelReplaceRequest(body) => body.cl_ord_id = cl_ord_id,
+			FixMessageBody::OrderCancelReject(body) => body.cl_ord_id = cl_ord_id,
+			FixMessageBody::OrderStatusRequest(body) => body.cl_ord_id = cl_ord_id,
+			_ => {},
 		}
 		self
 	}
 
 	pub fn handl_inst(mut self, handl_inst: impl Into<String>) -> Self {
-		if let FixMessageBody::NewOrderSingle(body) = &mut self.message.body {
-			body.handl_inst = handl_inst.into();
+		let handl_inst = handl_inst.into();
+		match &mut self.message.body {
+			FixMessageBody::NewOrderSingle(body) => body.handl_inst = handl_inst,
+			FixMessageBody::OrderCancelReplaceRequest(body) => body.handl_inst = handl_inst,
+			_ => {},
 		}
 		self
 	}
 
 	pub fn symbol(mut self, symbol: impl Into<String>) -> Self {
-		if let FixMessageBody::NewOrderSingle(body) = &mut self.message.body {
-			body.symbol = symbol.into();
+		let symbol = symbol.into();
+		match &mut self.message.body {
+			FixMessageBody::NewOrderSingle(body) => body.symbol = symbol,
+			FixMessageBody::OrderCancelReplaceRequest(body) => body.symbol = symbol,
+			FixMessageBody::OrderStatusRequest(body) => body.symbol = symbol,
+			FixMessageBody::DontKnowTrade(body) => body.symbol = symbol,
+			FixMessageBody::MarketDataSnapshotFullRefresh(body) => body.symbol = symbol,
+			FixMessageBody::ExecutionReport(body) => body.symbol = symbol,
+			_ => {},
 		}
 		self
 	}
 
 	pub const fn side(mut self, side: Side) -> Self {
-		if let FixMessageBody::NewOrderSingle(body) = &mut self.message.body {
-			body.side = side;
+		match &mut self.message.body {
+			FixMessageBody::NewOrderSingle(body) => body.side = side,
+			FixMessageBody::OrderCancelReplaceRequest(body) => body.side = side,
+			FixMessageBody::OrderStatusRequest(body) => body.side = side,
+			FixMessageBody::DontKnowTrade(body) => body.side = side,
+			FixMessageBody::ExecutionReport(body) => body.side = side,
+			_ => {},
 		}
 		self
 	}
 
 	pub const fn transact_time(mut self, transact_time: OffsetDateTime) -> Self {
-		if let FixMessageBody::NewOrderSingle(body) = &mut self.message.body {
-			body.transact_time = transact_time;
+		match &mut self.message.body {
+			FixMessageBody::NewOrderSingle(body) => body.transact_time = transact_time,
+			FixMessageBody::OrderCancelReplaceRequest(body) => body.transact_time = transact_time,
+			_ => {},
 		}
 		self
 	}
 
 	pub fn ord_type(mut self, ord_type: impl Into<String>) -> Self {
-		if let FixMessageBody::NewOrderSingle(body) = &mut self.message.body {
-			body.ord_type = ord_type.into();
+		let ord_type = ord_type.into();
+		match &mut self.message.body {
+			FixMessageBody::NewOrderSingle(body) => body.ord_type = ord_type,
+			FixMessageBody::OrderCancelReplaceRequest(body) => body.ord_type = ord_type,
+			_ => {},
 		}
 		self
 	}
 
 	pub const fn order_qty(mut self, order_qty: f64) -> Self {
-		if let FixMessageBody::NewOrderSingle(body) = &mut self.message.body {
-			body.order_qty = Some(order_qty);
+		match &mut self.message.body {
+			FixMessageBody::NewOrderSingle(body) => body.order_qty = Some(order_qty),
+			FixMessageBody::OrderCancelReplaceRequest(body) => body.order_qty = Some(order_qty),
+			FixMessageBody::DontKnowTrade(body) => body.order_qty = Some(order_qty),
+			_ => {},
 		}
 		self
 	}
 
 	pub const fn cash_order_qty(mut self, cash_order_qty: f64) -> Self {
-		if let FixMessageBody::NewOrderSingle(body) = &mut self.message.body {
-			body.cash_order_qty = Some(cash_order_qty);
+		match &mut self.message.body {
+			FixMessageBody::NewOrderSingle(body) => body.cash_order_qty = Some(cash_order_qty),
+			FixMessageBody::OrderCancelReplaceRequest(body) => body.cash_order_qty = Some(cash_order_qty),
+			FixMessageBody::DontKnowTrade(body) => body.cash_order_qty = Some(cash_order_qty),
+			_ => {},
 		}
 		self
 	}
 
 	pub fn security_exchange(mut self, security_exchange: impl Into<String>) -> Self {
-		if let FixMessageBody::NewOrderSingle(body) = &mut self.message.body {
-			body.security_exchange = Some(security_exchange.into());
+		let security_exchange = security_exchange.into();
+		match &mut self.message.body {
+			FixMessageBody::NewOrderSingle(body) => body.security_exchange = Some(security_exchange),
+			FixMessageBody::OrderCancelReplaceRequest(body) => body.security_exchange = Some(security_exchange),
+			FixMessageBody::OrderStatusRequest(body) => body.security_exchange = Some(security_exchange),
+			_ => {},
 		}
 		self
 	}
 
 	pub const fn price(mut self, price: f64) -> Self {
-		if let FixMessageBody::NewOrderSingle(body) = &mut self.message.body {
-			body.price = Some(price);
+		match &mut self.message.body {
+			FixMessageBody::NewOrderSingle(body) => body.price = Some(price),
+			FixMessageBody::OrderCancelReplaceRequest(body) => body.price = Some(price),
+			_ => {},
 		}
 		self
 	}
 
 	// Execution Report setters (minimal subset)
 	pub fn order_id(mut self, order_id: impl Into<String>) -> Self {
-		if let FixMessageBody::ExecutionReport(body) = &mut self.message.body {
-			body.order_id = order_id.into();
+		let order_id = order_id.into();
+		match &mut self.message.body {
+			FixMessageBody::ExecutionReport(body) => body.order_id = order_id,
+			FixMessageBody::OrderCancelReplaceRequest(body) => body.order_id = Some(order_id),
+			FixMessageBody::OrderCancelReject(body) => body.order_id = order_id,
+			FixMessageBody::OrderStatusRequest(body) => body.order_id = Some(order_id),
+			FixMessageBody::DontKnowTrade(body) => body.order_id = order_id,
+			_ => {},
 		}
 		self
 	}
 
 	pub fn exec_id(mut self, exec_id: impl Into<String>) -> Self {
+		let exec_id = exec_id.into();
+		match &mut self.message.body {
+			FixMessageBody::ExecutionReport(body) => body.exec_id = exec_id,
+			FixMessageBody::DontKnowTrade(body) => body.exec_id = exec_id,
+			_ => {},
+		}
+		self
+	}
+
+	/// Set ExecRefID (Tag 19), the ExecID a trade cancel or correct refers to
+	pub fn exec_ref_id(mut self, exec_ref_id: impl Into<String>) -> Self {
 		if let FixMessageBody::ExecutionReport(body) = &mut self.message.body {
-			body.exec_id = exec_id.into();
+			body.exec_ref_id = Some(exec_ref_id.into());
+		}
+		self
+	}
+
+	pub fn account(mut self, account: impl Into<String>) -> Self {
+		let account = account.into();
+		match &mut self.message.body {
+			FixMessageBody::ExecutionReport(body) => body.account = Some(account),
+			FixMessageBody::OrderStatusRequest(body) => body.account = Some(account),
+			_ => {},
 		}
 		self
 	}
@@ -231,8 +423,10 @@ impl FixMessageBuilder {
 	}
 
 	pub const fn ord_status(mut self, v: OrdStatus) -> Self {
-		if let FixMessageBody::ExecutionReport(body) = &mut self.message.body {
-			body.ord_status = v;
+		match &mut self.message.body {
+			FixMessageBody::ExecutionReport(body) => body.ord_status = v,
+			FixMessageBody::OrderCancelReject(body) => body.ord_status = v,
+			_ => {},
 		}
 		self
 	}
@@ -259,23 +453,31 @@ impl FixMessageBuilder {
 	}
 
 	pub const fn last_shares(mut self, qty: f64) -> Self {
-		if let FixMessageBody::ExecutionReport(body) = &mut self.message.body {
-			body.last_shares = Some(qty);
+		match &mut self.message.body {
+			FixMessageBody::ExecutionReport(body) => body.last_shares = Some(qty),
+			FixMessageBody::DontKnowTrade(body) => body.last_shares = Some(qty),
+			_ => {},
 		}
 		self
 	}
 
 	pub const fn last_px(mut self, px: f64) -> Self {
-		if let FixMessageBody::ExecutionReport(body) = &mut self.message.body {
-			body.last_px = Some(px);
+		match &mut self.message.body {
+			FixMessageBody::ExecutionReport(body) => body.last_px = Some(px),
+			FixMessageBody::DontKnowTrade(body) => body.last_px = Some(px),
+			_ => {},
 		}
 		self
 	}
 
 	// Order Cancel Request setters
 	pub fn orig_cl_ord_id(mut self, v: impl Into<String>) -> Self {
-		if let FixMessageBody::OrderCancelRequest(body) = &mut self.message.body {
-			body.orig_cl_ord_id = v.into();
+		let v = v.into();
+		match &mut self.message.body {
+			FixMessageBody::OrderCancelRequest(body) => body.orig_cl_ord_id = v,
+			FixMessageBody::OrderCancelReplaceRequest(body) => body.orig_cl_ord_id = v,
+			FixMessageBody::OrderCancelReject(body) => body.orig_cl_ord_id = v,
+			_ => {},
 		}
 		self
 	}
@@ -324,14 +526,161 @@ impl FixMessageBuilder {
 	}
 
 	pub fn cancel_account(mut self, v: impl Into<String>) -> Self {
-		if let FixMessageBody::OrderCancelRequest(body) = &mut self.message.body {
-			body.account = Some(v.into());
+		let v = v.into();
+		match &mut self.message.body {
+			FixMessageBody::OrderCancelRequest(body) => body.account = Some(v),
+			FixMessageBody::OrderCancelReplaceRequest(body) => body.account = Some(v),
+			_ => {},
 		}
 		self
 	}
 
 	pub fn cancel_text(mut self, v: impl Into<String>) -> Self {
-		if let FixMessageBody::OrderCancelRequest(body) = &mut self.message.body {
+		let v = v.into();
+		match &mut self.message.body {
+			FixMessageBody::OrderCancelRequest(body) => body.text = Some(v),
+			FixMessageBody::OrderCancelReplaceRequest(body) => body.text = Some(v),
+			FixMessageBody::OrderCancelReject(body) => body.text = Some(v),
+			_ => {},
+		}
+		self
+	}
+
+	// Order Cancel/Replace Request setters; the replacement order fields share the
+	// New Order Single setters above
+	pub const fn time_in_force(mut self, v: TimeInForce) -> Self {
+		if let FixMessageBody::OrderCancelReplaceRequest(body) = &mut self.message.body {
+			body.time_in_force = Some(v);
+		}
+		self
+	}
+
+	pub const fn stop_px(mut self, px: f64) -> Self {
+		if let FixMessageBody::OrderCancelReplaceRequest(body) = &mut self.message.body {
+			body.stop_px = Some(px);
+		}
+		self
+	}
+
+	pub const fn expire_time(mut self, t: OffsetDateTime) -> Self {
+		if let FixMessageBody::OrderCancelReplaceRequest(body) = &mut self.message.body {
+			body.expire_time = Some(t);
+		}
+		self
+	}
+
+	// Order Cancel Reject setters
+	pub const fn cxl_rej_response_to(mut self, v: CxlRejResponseTo) -> Self {
+		if let FixMessageBody::OrderCancelReject(body) = &mut self.message.body {
+			body.cxl_rej_response_to = v;
+		}
+		self
+	}
+
+	pub const fn cxl_rej_reason(mut self, v: CxlRejReason) -> Self {
+		if let FixMessageBody::OrderCancelReject(body) = &mut self.message.body {
+			body.cxl_rej_reason = Some(v);
+		}
+		self
+	}
+
+	// Don't Know Trade setters
+	pub const fn dk_reason(mut self, v: DkReason) -> Self {
+		if let FixMessageBody::DontKnowTrade(body) = &mut self.message.body {
+			body.dk_reason = v;
+		}
+		self
+	}
+
+	pub fn dk_text(mut self, v: impl Into<String>) -> Self {
+		if let FixMessageBody::DontKnowTrade(body) = &mut self.message.body {
+			body.text = Some(v.into());
+		}
+		self
+	}
+
+	// Market data setters
+	pub fn md_req_id(mut self, v: impl Into<String>) -> Self {
+		let v = v.into();
+		match &mut self.message.body {
+			FixMessageBody::MarketDataRequest(body) => body.md_req_id = v,
+			FixMessageBody::MarketDataSnapshotFullRefresh(body) => body.md_req_id = Some(v),
+			FixMessageBody::MarketDataIncrementalRefresh(body) => body.md_req_id = Some(v),
+			FixMessageBody::MarketDataRequestReject(body) => body.md_req_id = v,
+			_ => {},
+		}
+		self
+	}
+
+	pub const fn subscription_request_type(mut self, v: SubscriptionRequestType) -> Self {
+		if let FixMessageBody::MarketDataRequest(body) = &mut self.message.body {
+			body.subscription_request_type = v;
+		}
+		self
+	}
+
+	pub const fn market_depth(mut self, depth: u32) -> Self {
+		if let FixMessageBody::MarketDataRequest(body) = &mut self.message.body {
+			body.market_depth = depth;
+		}
+		self
+	}
+
+	pub const fn md_update_type(mut self, v: MdUpdateType) -> Self {
+		if let FixMessageBody::MarketDataRequest(body) = &mut self.message.body {
+			body.md_update_type = Some(v);
+		}
+		self
+	}
+
+	pub const fn aggregated_book(mut self, flag: bool) -> Self {
+		if let FixMessageBody::MarketDataRequest(body) = &mut self.message.body {
+			body.aggregated_book = Some(flag);
+		}
+		self
+	}
+
+	/// Add an entry to the NoMDEntryTypes group of a Market Data Request
+	pub fn md_entry_type(mut self, v: MdEntryType) -> Self {
+		if let FixMessageBody::MarketDataRequest(body) = &mut self.message.body {
+			body.md_entry_types.push(v);
+		}
+		self
+	}
+
+	/// Add an instrument to the NoRelatedSym group of a Market Data Request
+	pub fn related_sym(mut self, v: RelatedSym) -> Self {
+		if let FixMessageBody::MarketDataRequest(body) = &mut self.message.body {
+			body.related_syms.push(v);
+		}
+		self
+	}
+
+	/// Add an entry to the NoMDEntries group of a Market Data Snapshot/Full Refresh
+	pub fn md_entry(mut self, v: MdEntry) -> Self {
+		if let FixMessageBody::MarketDataSnapshotFullRefresh(body) = &mut self.message.body {
+			body.md_entries.push(v);
+		}
+		self
+	}
+
+	/// Add an entry to the NoMDEntries group of a Market Data Incremental Refresh
+	pub fn md_incremental_entry(mut self, v: MdIncrementalEntry) -> Self {
+		if let FixMessageBody::MarketDataIncrementalRefresh(body) = &mut self.message.body {
+			body.md_entries.push(v);
+		}
+		self
+	}
+
+	pub const fn md_req_rej_reason(mut self, v: MdReqRejReason) -> Self {
+		if let FixMessageBody::MarketDataRequestReject(body) = &mut self.message.body {
+			body.md_req_rej_reason = Some(v);
+		}
+		self
+	}
+
+	pub fn md_reject_text(mut self, v: impl Into<String>) -> Self {
+		if let FixMessageBody::MarketDataRequestReject(body) = &mut self.message.body {
 			body.text = Some(v.into());
 		}
 		self
@@ -349,8 +698,10 @@ impl FixMessageBuilder {
 		self.message.trailer.write_body_fields(&mut body_content);
 
 		// Calculate body length and checksum
+		// The checksum covers every byte up to tag 10, including the BeginString and BodyLength fields
 		let body_length = body_content.len() as u32;
-		let checksum: u32 = body_content.bytes().map(|b| b as u32).sum::<u32>() % 256;
+		let prefix = format!("8={}{}9={}{}", self.message.header.begin_string, SOH, body_length, SOH);
+		let checksum = calculate_checksum(prefix.as_bytes()).wrapping_add(calculate_checksum(body_content.as_bytes()));
 
 		// Set calculated values
 		self.message.header.body_length = body_length;
@@ -363,7 +714,7 @@ impl FixMessageBuilder {
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use crate::common::{EncryptMethod, MsgType};
+	use crate::common::{EncryptMethod, MdUpdateAction, MsgType};
 
 	#[test]
 	fn test_builder_creation() {
@@ -468,6 +819,12 @@ mod tests {
 		let expected_body_length = 67;
 
 		assert_eq!(message.header.body_length, expected_body_length);
+
+		// Checksum covers every byte of the wire message before the "10=" field
+		let wire = message.to_fix_string();
+		let checksum_pos = wire.rfind("10=").unwrap();
+		let expected_checksum = calculate_checksum(&wire.as_bytes()[..checksum_pos]);
+		assert_eq!(message.trailer.checksum, format!("{:03}", expected_checksum));
 	}
 
 	#[test]
@@ -541,4 +898,172 @@ mod tests {
 			assert_eq!(body_content.len() as u32, message.header.body_length);
 		}
 	}
+
+	#[test]
+	fn test_reject_builders() {
+		let reject = FixMessageBuilder::new(MsgType::Reject, "BROKER", "CLIENT", 3)
+			.ref_seq_num(2)
+			.ref_tag_id(54)
+			.ref_msg_type(MsgType::NewOrderSingle)
+			.session_reject_reason(SessionRejectReason::ValueIsIncorrect)
+			.reject_text("Invalid Side")
+			.build();
+		assert!(reject.to_fix_string().contains("\x0145=2\x01371=54\x01372=D\x01373=5\x0158=Invalid Side\x01"));
+
+		let business_reject = FixMessageBuilder::new(MsgType::BusinessMessageReject, "BROKER", "CLIENT", 4)
+			.ref_seq_num(2)
+			.ref_msg_type(MsgType::NewOrderSingle)
+			.business_reject_ref_id("ORD1")
+			.business_reject_reason(BusinessRejectReason::UnknownSecurity)
+			.reject_text("Unknown symbol")
+			.build();
+		assert!(business_reject.is_valid());
+		assert!(
+			business_reject.to_fix_string().contains("\x0145=2\x01372=D\x01379=ORD1\x01380=2\x0158=Unknown symbol\x01")
+		);
+	}
+
+	#[test]
+	fn test_order_cancel_replace_builder() {
+		let message = FixMessageBuilder::new(MsgType::OrderCancelReplaceRequest, "CLIENT", "BROKER", 7)
+			.order_id("BRK1")
+			.orig_cl_ord_id("ORD1")
+			.cl_ord_id("ORD2")
+			.handl_inst("1")
+			.symbol("AAPL")
+			.side(Side::Buy)
+			.order_qty(200.0)
+			.ord_type("2")
+			.price(151.25)
+			.time_in_force(TimeInForce::Day)
+			.cancel_text("Raise limit")
+			.build();
+		assert!(message.is_valid());
+
+		let parsed = FixMessage::from_fix_string(&message.to_fix_string()).unwrap();
+		let FixMessageBody::OrderCancelReplaceRequest(body) = &parsed.body else {
+			panic!("Expected OrderCancelReplaceRequest body");
+		};
+		assert_eq!(body.order_id.as_deref(), Some("BRK1"));
+		assert_eq!(body.orig_cl_ord_id, "ORD1");
+		assert_eq!(body.cl_ord_id, "ORD2");
+		assert_eq!(body.order_qty, Some(200.0));
+		assert_eq!(body.price, Some(151.25));
+		assert_eq!(body.time_in_force, Some(TimeInForce::Day));
+		assert_eq!(body.text.as_deref(), Some("Raise limit"));
+	}
+
+	#[test]
+	fn test_order_cancel_reject_builder() {
+		let message = FixMessageBuilder::new(MsgType::OrderCancelReject, "BROKER", "CLIENT", 8)
+			.order_id("BRK1")
+			.cl_ord_id("CXL1")
+			.orig_cl_ord_id("ORD1")
+			.ord_status(OrdStatus::Filled)
+			.cxl_rej_response_to(CxlRejResponseTo::OrderCancelRequest)
+			.cxl_rej_reason(CxlRejReason::TooLateToCancel)
+			.cancel_text("Order already filled")
+			.build();
+		assert!(message.is_valid());
+		assert!(
+			message
+				.to_fix_string()
+				.contains("\x0137=BRK1\x0111=CXL1\x0141=ORD1\x0139=2\x01434=1\x01102=0\x0158=Order already filled\x01")
+		);
+
+		let parsed = FixMessage::from_fix_string(&message.to_fix_string()).unwrap();
+		assert_eq!(parsed.header.msg_type, MsgType::OrderCancelReject);
+		assert_eq!(parsed.body, message.body);
+	}
+
+	#[test]
+	fn test_order_status_request_and_dk_builders() {
+		let status = FixMessageBuilder::new(MsgType::OrderStatusRequest, "CLIENT", "BROKER", 9)
+			.order_id("BRK1")
+			.cl_ord_id("ORD1")
+			.symbol("AAPL")
+			.side(Side::Sell)
+			.build();
+		assert!(status.is_valid());
+		assert!(status.to_fix_string().contains("\x0137=BRK1\x0111=ORD1\x0155=AAPL\x0154=2\x01"));
+
+		let dk = FixMessageBuilder::new(MsgType::DontKnowTrade, "CLIENT", "BROKER", 10)
+			.order_id("BRK1")
+			.exec_id("EXEC1")
+			.dk_reason(DkReason::NoMatchingOrder)
+			.symbol("AAPL")
+			.side(Side::Buy)
+			.order_qty(100.0)
+			.last_shares(100.0)
+			.last_px(150.5)
+			.dk_text("No such order")
+			.build();
+		assert!(dk.is_valid());
+
+		let parsed = FixMessage::from_fix_string(&dk.to_fix_string()).unwrap();
+		assert_eq!(parsed.header.msg_type, MsgType::DontKnowTrade);
+		assert_eq!(parsed.body, dk.body);
+	}
+
+	#[test]
+	fn test_trade_correct_builder() {
+		let correct = FixMessageBuilder::new(MsgType::ExecutionReport, "BROKER", "CLIENT", 11)
+			.order_id("BRK1")
+			.exec_id("EXEC2")
+			.exec_type("G")
+			.symbol("AAPL")
+			.side(Side::Sell)
+			.account("ACC1")
+			.last_shares(40.0)
+			.last_px(12.0)
+			.build();
+		assert!(!correct.is_valid());
+
+		let correct = FixMessageBuilder::from_message(correct).exec_ref_id("EXEC1").build();
+		assert!(correct.is_valid());
+		let parsed = FixMessage::from_fix_string(&correct.to_fix_string()).unwrap();
+		let FixMessageBody::ExecutionReport(body) = parsed.body else { panic!("expected an execution report") };
+		assert_eq!((body.exec_ref_id.as_deref(), body.account.as_deref()), (Some("EXEC1"), Some("ACC1")));
+		assert_eq!((body.symbol.as_str(), body.side), ("AAPL", Side::Sell));
+	}
+
+	#[test]
+	fn test_market_data_builders() {
+		let request = FixMessageBuilder::new(MsgType::MarketDataRequest, "CLIENT", "BROKER", 11)
+			.md_req_id("MD1")
+			.subscription_request_type(SubscriptionRequestType::SnapshotPlusUpdates)
+			.market_depth(1)
+			.md_update_type(MdUpdateType::IncrementalRefresh)
+			.md_entry_type(MdEntryType::Bid)
+			.md_entry_type(MdEntryType::Offer)
+			.related_sym(RelatedSym::new("AAPL"))
+			.build();
+		assert!(request.is_valid());
+		assert!(request.to_fix_string().contains("\x01267=2\x01269=0\x01269=1\x01146=1\x0155=AAPL\x01"));
+
+		let snapshot = FixMessageBuilder::new(MsgType::MarketDataSnapshotFullRefresh, "BROKER", "CLIENT", 12)
+			.md_req_id("MD1")
+			.symbol("AAPL")
+			.md_entry(MdEntry::new(MdEntryType::Bid, 150.25).with_size(300.0))
+			.md_entry(MdEntry::new(MdEntryType::Offer, 150.5).with_size(200.0))
+			.build();
+		let parsed = FixMessage::from_fix_string(&snapshot.to_fix_string()).unwrap();
+		assert_eq!(parsed.body, snapshot.body);
+
+		let mut entry = MdIncrementalEntry::new(MdUpdateAction::New);
+		entry.md_entry_type = Some(MdEntryType::Trade);
+		entry.md_entry_px = Some(150.3);
+		let incremental = FixMessageBuilder::new(MsgType::MarketDataIncrementalRefresh, "BROKER", "CLIENT", 13)
+			.md_incremental_entry(entry)
+			.build();
+		let parsed = FixMessage::from_fix_string(&incremental.to_fix_string()).unwrap();
+		assert_eq!(parsed.body, incremental.body);
+
+		let reject = FixMessageBuilder::new(MsgType::MarketDataRequestReject, "BROKER", "CLIENT", 14)
+			.md_req_id("MD2")
+			.md_req_rej_reason(MdReqRejReason::UnknownSymbol)
+			.md_reject_text("Unknown symbol")
+			.build();
+		assert!(reject.to_fix_string().contains("\x01262=MD2\x01281=0\x0158=Unknown symbol\x01"));
+	}
 }
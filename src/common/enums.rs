@@ -9,11 +9,69 @@ use crate::macros::fix_enum;
 // FIX 4.2 Message Types
 fix_enum!(Loose MsgType {
 	Heartbeat => "0",
+	TestRequest => "1",
+	ResendRequest => "2",
+	Reject => "3",
+	SequenceReset => "4",
+	Logout => "5",
 	Logon => "A",
 	NewOrderSingle => "D",
 	ExecutionReport => "8",
 	OrderCancelRequest => "F",
+	OrderCancelReplaceRequest => "G",
+	OrderCancelReject => "9",
+	OrderStatusRequest => "H",
+	DontKnowTrade => "Q",
 	MarketDataRequest => "V",
+	MarketDataSnapshotFullRefresh => "W",
+	MarketDataIncrementalRefresh => "X",
+	MarketDataRequestReject => "Y",
+	BusinessMessageReject => "j",
+});
+
+impl MsgType {
+	/// Whether this is a session-level (administrative) message type
+	///
+	/// Administrative messages are never resent during message recovery; they are
+	/// replaced by a SequenceReset-GapFill instead.
+	pub const fn is_admin(&self) -> bool {
+		matches!(
+			self,
+			Self::Heartbeat
+				| Self::TestRequest
+				| Self::ResendRequest
+				| Self::Reject
+				| Self::SequenceReset
+				| Self::Logout
+				| Self::Logon
+		)
+	}
+}
+
+// SessionRejectReason (Tag 373) of a session-level Reject
+fix_enum!(Strict SessionRejectReason {
+	InvalidTagNumber                   => "0",
+	RequiredTagMissing                 => "1",
+	TagNotDefinedForThisMessageType    => "2",
+	UndefinedTag                       => "3",
+	TagSpecifiedWithoutAValue          => "4",
+	ValueIsIncorrect                   => "5",
+	IncorrectDataFormatForValue        => "6",
+	DecryptionProblem                  => "7",
+	SignatureProblem                   => "8",
+	CompIdProblem                      => "9",
+	SendingTimeAccuracyProblem         => "10",
+	InvalidMsgType                     => "11",
+});
+
+// BusinessRejectReason (Tag 380) of a Business Message Reject
+fix_enum!(Strict BusinessRejectReason {
+	Other                              => "0",
+	UnknownId                          => "1",
+	UnknownSecurity                    => "2",
+	UnsupportedMessageType             => "3",
+	ApplicationNotAvailable            => "4",
+	ConditionallyRequiredFieldMissing  => "5",
 });
 
 // Trading side enumeration
@@ -41,6 +99,88 @@ fix_enum!(Strict OrdStatus {
 	PendingReplace     => "E",
 });
 
+// CxlRejResponseTo (Tag 434): the kind of request an OrderCancelReject answers
+fix_enum!(Strict CxlRejResponseTo {
+	OrderCancelRequest        => "1",
+	OrderCancelReplaceRequest => "2",
+});
+
+// CxlRejReason (Tag 102)
+fix_enum!(Strict CxlRejReason {
+	TooLateToCancel               => "0",
+	UnknownOrder                  => "1",
+	BrokerOption                  => "2",
+	AlreadyPendingCancelOrReplace => "3",
+});
+
+// DKReason (Tag 127): why a Don't Know Trade disputes an execution
+fix_enum!(Strict DkReason {
+	UnknownSymbol        => "A",
+	WrongSide            => "B",
+	QuantityExceedsOrder => "C",
+	NoMatchingOrder      => "D",
+	PriceExceedsLimit    => "E",
+	Other                => "Z",
+});
+
+// SubscriptionRequestType (Tag 263)
+fix_enum!(Strict SubscriptionRequestType {
+	Snapshot                => "0",
+	SnapshotPlusUpdates     => "1",
+	DisablePreviousSnapshot => "2",
+});
+
+// MDUpdateType (Tag 265): how a subscription is to be refreshed
+fix_enum!(Strict MdUpdateType {
+	FullRefresh        => "0",
+	IncrementalRefresh => "1",
+});
+
+// MDEntryType (Tag 269)
+fix_enum!(Strict MdEntryType {
+	Bid                     => "0",
+	Offer                   => "1",
+	Trade                   => "2",
+	IndexValue              => "3",
+	OpeningPrice            => "4",
+	ClosingPrice            => "5",
+	SettlementPrice         => "6",
+	TradingSessionHighPrice => "7",
+	TradingSessionLowPrice  => "8",
+	TradingSessionVwapPrice => "9",
+});
+
+// MDUpdateAction (Tag 279)
+fix_enum!(Strict MdUpdateAction {
+	New    => "0",
+	Change => "1",
+	Delete => "2",
+});
+
+// MDReqRejReason (Tag 281)
+fix_enum!(Strict MdReqRejReason {
+	UnknownSymbol                      => "0",
+	DuplicateMdReqId                   => "1",
+	InsufficientBandwidth              => "2",
+	InsufficientPermissions            => "3",
+	UnsupportedSubscriptionRequestType => "4",
+	UnsupportedMarketDepth             => "5",
+	UnsupportedMdUpdateType            => "6",
+	UnsupportedAggregatedBook          => "7",
+	UnsupportedMdEntryType             => "8",
+});
+
+// TimeInForce (Tag 59)
+fix_enum!(Strict TimeInForce {
+	Day               => "0",
+	GoodTillCancel    => "1",
+	AtTheOpening      => "2",
+	ImmediateOrCancel => "3",
+	FillOrKill        => "4",
+	GoodTillCrossing  => "5",
+	GoodTillDate      => "6",
+});
+
 // ExecType (Tag 150) extends OrdStatus semantics with additional values for trade events & restatements
 fix_enum!(Strict ExecType {
 	New             => "0",
@@ -107,6 +247,15 @@ mod tests {
 		assert_eq!(format!("{}", MsgType::Other("CUSTOM".to_string())), "CUSTOM");
 	}
 
+	#[test]
+	fn test_msg_type_is_admin() {
+		assert!(MsgType::Heartbeat.is_admin());
+		assert!(MsgType::SequenceReset.is_admin());
+		assert!(MsgType::Logon.is_admin());
+		assert!(!MsgType::NewOrderSingle.is_admin());
+		assert!(!MsgType::Other("UNKNOWN".to_string()).is_admin());
+	}
+
 	#[test]
 	fn test_side_parsing() {
 		assert_eq!(Side::from_str("1").unwrap(), Side::Buy);
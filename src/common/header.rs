@@ -7,7 +7,7 @@ use crate::{
 	SOH,
 	common::{
 		enums::MsgType,
-		validation::{FixFieldHandler, Validate, ValidationError, WriteTo},
+		validation::{FieldError, FixFieldHandler, Validate, ValidationError, WriteTo},
 		write_tag_timestamp,
 	},
 };
@@ -16,19 +16,23 @@ use time::{Duration, OffsetDateTime};
 
 /// Standard FIX message header
 #[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FixHeader {
 	// Required Header Fields
-	pub begin_string: &'static str,   // Tag 8 - Always "FIX.4.2"
-	pub body_length: u32,             // Tag 9 - Length of message body
-	pub msg_type: MsgType,            // Tag 35 - Message type
-	pub sender_comp_id: String,       // Tag 49 - Sender's company ID
-	pub target_comp_id: String,       // Tag 56 - Target's company ID
-	pub msg_seq_num: u32,             // Tag 34 - Message sequence number
+	#[cfg_attr(feature = "serde", serde(skip_deserializing, default = "crate::serde_fix::begin_string"))]
+	pub begin_string: &'static str, // Tag 8 - Always "FIX.4.2"
+	pub body_length: u32,       // Tag 9 - Length of message body
+	pub msg_type: MsgType,      // Tag 35 - Message type
+	pub sender_comp_id: String, // Tag 49 - Sender's company ID
+	pub target_comp_id: String, // Tag 56 - Target's company ID
+	pub msg_seq_num: u32,       // Tag 34 - Message sequence number
+	#[cfg_attr(feature = "serde", serde(with = "crate::serde_fix::timestamp"))]
 	pub sending_time: OffsetDateTime, // Tag 52 - Time of message transmission
 
 	// Optional Header Fields
-	pub poss_dup_flag: Option<bool>,               // Tag 43 - Possible duplicate flag
-	pub poss_resend: Option<bool>,                 // Tag 97 - Possible resend flag
+	pub poss_dup_flag: Option<bool>, // Tag 43 - Possible duplicate flag
+	pub poss_resend: Option<bool>,   // Tag 97 - Possible resend flag
+	#[cfg_attr(feature = "serde", serde(default, with = "crate::serde_fix::timestamp::option"))]
 	pub orig_sending_time: Option<OffsetDateTime>, // Tag 122 - Original sending time
 }
 
@@ -85,11 +89,11 @@ impl WriteTo for FixHeader {
 }
 
 impl FixFieldHandler for FixHeader {
-	fn parse_field(&mut self, tag: u32, value: &str) -> Result<(), String> {
+	fn parse_field(&mut self, tag: u32, value: &str) -> Result<(), FieldError> {
 		match tag {
 			8 => {
 				if value != "FIX.4.2" {
-					return Err(format!("Unsupported FIX version: {}", value));
+					return Err(format!("Unsupported FIX version: {}", value).into());
 				}
 				// begin_string is already set as constant
 			},
@@ -121,7 +125,7 @@ impl FixFieldHandler for FixHeader {
 			122 => {
 				self.orig_sending_time = Some(parse_fix_timestamp(value)?);
 			},
-			_ => return Err(format!("Unknown header field: {}", tag)),
+			_ => return Err(FieldError::Unknown(format!("Unknown header field: {}", tag))),
 		}
 		Ok(())
 	}
@@ -132,11 +136,11 @@ impl FixFieldHandler for FixHeader {
 		write!(buffer, "56={}{}", self.target_comp_id, SOH).unwrap();
 		write!(buffer, "34={}{}", self.msg_seq_num, SOH).unwrap();
 		write_tag_timestamp(buffer, 52, self.sending_time);
-		if let Some(ref poss_dup_flag) = self.poss_dup_flag {
-			write!(buffer, "43={}{}", poss_dup_flag, SOH).unwrap();
+		if let Some(poss_dup_flag) = self.poss_dup_flag {
+			write!(buffer, "43={}{}", if poss_dup_flag { "Y" } else { "N" }, SOH).unwrap();
 		}
-		if let Some(ref poss_resend) = self.poss_resend {
-			write!(buffer, "97={}{}", poss_resend, SOH).unwrap();
+		if let Some(poss_resend) = self.poss_resend {
+			write!(buffer, "97={}{}", if poss_resend { "Y" } else { "N" }, SOH).unwrap();
 		}
 		if let Some(ref orig_sending_time) = self.orig_sending_time {
 			write_tag_timestamp(buffer, 122, *orig_sending_time);
@@ -9,15 +9,61 @@ pub mod trailer;
 pub mod validation;
 
 // Re-export commonly used types
-pub use enums::{EncryptMethod, MsgType, OrdStatus, Side};
+pub use enums::{
+	BusinessRejectReason, CxlRejReason, CxlRejResponseTo, DkReason, EncryptMethod, MdEntryType, MdReqRejReason,
+	MdUpdateAction, MdUpdateType, MsgType, OrdStatus, SessionRejectReason, Side, SubscriptionRequestType, TimeInForce,
+};
 pub use header::{FixHeader, parse_fix_timestamp};
+use std::fmt::{Display, Write};
 use time::OffsetDateTime;
 pub use trailer::FixTrailer;
-pub use validation::{Validate, ValidationError};
+pub use validation::{FieldError, ParseError, Validate, ValidationError};
 
 /// The Start of Heading control character, value 0x01, used for field termination.
 pub const SOH: &str = "\x01";
 
+/// Quantities are compared with this tolerance
+pub(crate) const QTY_EPSILON: f64 = 1e-9;
+
+/// Computes the FIX CheckSum (Tag 10) of `data`: the sum of all bytes modulo 256.
+///
+/// `data` must be every byte of the message preceding the `10=` field, starting
+/// with `8=` and including the delimiter that ends the last field.
+#[inline]
+pub fn calculate_checksum(data: &[u8]) -> u8 {
+	data.iter().fold(0u8, |sum, &b| sum.wrapping_add(b))
+}
+
+/// Splits one `tag=value` field of a message in wire format.
+///
+/// Returns `None` when the field has no `=` or its tag is not a number.
+pub fn wire_field(field: &str) -> Option<(u32, &str)> {
+	let (tag, value) = field.split_once('=')?;
+	Some((tag.parse().ok()?, value))
+}
+
+/// Fields of a message in wire format, separated by SOH, in wire order.
+///
+/// Malformed fields, see [`wire_field`], are skipped.
+pub fn wire_fields(message: &str) -> impl Iterator<Item = (u32, &str)> {
+	message.split(SOH).filter_map(wire_field)
+}
+
+/// Assembles a message in wire format from the fields between BodyLength and CheckSum.
+///
+/// BeginString, BodyLength and CheckSum are added around `fields`, the last two
+/// computed over them, so the result parses as a complete message.
+pub fn wire_message<V: Display>(begin_string: &str, fields: impl IntoIterator<Item = (u32, V)>) -> String {
+	let mut body = String::with_capacity(256);
+	for (tag, value) in fields {
+		write!(body, "{}={}{}", tag, value, SOH).unwrap();
+	}
+	let mut message = format!("8={}{}9={}{}{}", begin_string, SOH, body.len(), SOH, body);
+	let checksum = calculate_checksum(message.as_bytes());
+	write!(message, "10={:03}{}", checksum, SOH).unwrap();
+	message
+}
+
 /// Formats a FIX timestamp field with its tag number.
 ///
 /// ### Why not use `time::format()`?
@@ -64,6 +110,17 @@ pub fn write_tag_timestamp(buf: &mut String, tag: u16, time: OffsetDateTime) {
 
 	buf.push_str(temp.format(tag));
 	buf.push('=');
+	write_timestamp(buf, time);
+	buf.push_str(SOH);
+}
+
+/// Formats a FIX timestamp (`YYYYMMDD-HH:MM:SS.sss`) without tag or delimiter.
+///
+/// This is the value part of [`write_tag_timestamp`], useful wherever a timestamp
+/// is needed outside of a tag=value field (e.g. persisted session state).
+#[inline(always)]
+pub fn write_timestamp(buf: &mut String, time: OffsetDateTime) {
+	let mut temp = itoa::Buffer::new();
 
 	// Year
 	buf.push_str(temp.format(time.year()));
@@ -116,6 +173,4 @@ pub fn write_tag_timestamp(buf: &mut String, tag: u16, time: OffsetDateTime) {
 		buf.push('0');
 	}
 	buf.push_str(temp.format(ms));
-
-	buf.push_str(SOH);
 }
@@ -5,12 +5,13 @@
 
 use crate::{
 	SOH,
-	common::validation::{FixFieldHandler, Validate, ValidationError, WriteTo},
+	common::validation::{FieldError, FixFieldHandler, Validate, ValidationError, WriteTo},
 };
 use std::fmt::Write;
 
 /// Standard FIX message trailer
 #[derive(Debug, Clone, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FixTrailer {
 	// Required Trailer Fields
 	// TODO: This always has len == 3, so we can probably avoid using a String.
@@ -28,7 +29,7 @@ impl Validate for FixTrailer {
 }
 
 impl FixFieldHandler for FixTrailer {
-	fn parse_field(&mut self, tag: u32, value: &str) -> Result<(), String> {
+	fn parse_field(&mut self, tag: u32, value: &str) -> Result<(), FieldError> {
 		match tag {
 			10 => {
 				self.checksum = value.to_string();
@@ -39,7 +40,7 @@ impl FixFieldHandler for FixTrailer {
 			89 => {
 				self.signature = Some(value.to_string());
 			},
-			_ => return Err(format!("Unknown trailer field: {}", tag)),
+			_ => return Err(FieldError::Unknown(format!("Unknown trailer field: {}", tag))),
 		}
 		Ok(())
 	}
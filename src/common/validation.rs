@@ -3,6 +3,8 @@
 //! This module provides the validation framework used throughout the FIX library
 //! to ensure message integrity and compliance with the FIX 4.2 specification.
 
+use crate::common::{BusinessRejectReason, FixHeader, SessionRejectReason};
+use crate::dictionary::Dictionary;
 use std::fmt::Display;
 
 /// Validation error types for FIX messages
@@ -10,6 +12,8 @@ use std::fmt::Display;
 pub enum ValidationError {
 	/// A required field is missing from the message
 	MissingRequiredField(String),
+	/// A field required by the application in this context is missing
+	ConditionallyRequiredFieldMissing(String),
 	/// A field has an invalid value
 	InvalidFieldValue(String, String),
 	/// The message checksum is invalid
@@ -32,6 +36,9 @@ impl Display for ValidationError {
 			Self::MissingRequiredField(field) => {
 				write!(f, "Missing required field: {}", field)
 			},
+			Self::ConditionallyRequiredFieldMissing(field) => {
+				write!(f, "Conditionally required field missing: {}", field)
+			},
 			Self::InvalidFieldValue(field, value) => {
 				write!(f, "Invalid value '{}' for field '{}'", value, field)
 			},
@@ -59,6 +66,101 @@ impl Display for ValidationError {
 
 impl std::error::Error for ValidationError {}
 
+impl ValidationError {
+	/// SessionRejectReason (Tag 373) reported when an inbound message fails this validation
+	pub const fn reject_reason(&self) -> SessionRejectReason {
+		match self {
+			Self::MissingRequiredField(_) | Self::ConditionallyRequiredFieldMissing(_) => {
+				SessionRejectReason::RequiredTagMissing
+			},
+			Self::InvalidFormat(..) => SessionRejectReason::IncorrectDataFormatForValue,
+			_ => SessionRejectReason::ValueIsIncorrect,
+		}
+	}
+
+	/// BusinessRejectReason (Tag 380) for errors an application message is refused for
+	///
+	/// Such errors are answered with a Business Message Reject rather than a
+	/// session-level Reject.
+	pub const fn business_reject_reason(&self) -> Option<BusinessRejectReason> {
+		match self {
+			Self::ConditionallyRequiredFieldMissing(_) => Some(BusinessRejectReason::ConditionallyRequiredFieldMissing),
+			_ => None,
+		}
+	}
+
+	/// Tag number of the offending field, when it is known
+	pub fn ref_tag_id(&self) -> Option<u32> {
+		let field = match self {
+			Self::MissingRequiredField(field)
+			| Self::ConditionallyRequiredFieldMissing(field)
+			| Self::InvalidFieldValue(field, _)
+			| Self::ValueOutOfRange(field, _)
+			| Self::InvalidFormat(field, _) => field,
+			_ => return None,
+		};
+		field_tag(field)
+	}
+}
+
+/// Tag number of a field named in a [`ValidationError`], looked up in the FIX 4.2 dictionary
+///
+/// "OrderQty or CashOrderQty" names the first of the alternatives.
+fn field_tag(field: &str) -> Option<u32> {
+	let name = field.split(" or ").next().unwrap_or(field);
+	Dictionary::fix42().tag(name)
+}
+
+/// Error raised while parsing an inbound message
+///
+/// Carries what a session needs to answer the message with a Reject (MsgType=3):
+/// the reason, the offending tag and the header of the message, when enough of
+/// it could be read to know who sent it and with which MsgSeqNum.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ParseError {
+	/// SessionRejectReason (Tag 373)
+	pub reason: SessionRejectReason,
+	/// Offending tag (Tag 371), if known
+	pub ref_tag_id: Option<u32>,
+	/// Explanation, suitable for the Reject's Text (Tag 58)
+	pub text: String,
+	/// Header of the message, `None` when it is too garbled to be answered
+	pub header: Option<Box<FixHeader>>,
+	/// Set when an application message is well formed but lacks a field the
+	/// application needs; it is answered with a Business Message Reject instead
+	pub business_reject_reason: Option<BusinessRejectReason>,
+}
+
+impl ParseError {
+	/// Create an error; without [`ParseError::with_header`] the message cannot be answered
+	pub fn new(reason: SessionRejectReason, ref_tag_id: Option<u32>, text: impl Into<String>) -> Self {
+		Self { reason, ref_tag_id, text: text.into(), header: None, business_reject_reason: None }
+	}
+
+	/// Attach the header of the offending message
+	pub fn with_header(mut self, header: &FixHeader) -> Self {
+		self.header = Some(Box::new(header.clone()));
+		self
+	}
+}
+
+impl Display for ParseError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}", self.text)
+	}
+}
+
+impl std::error::Error for ParseError {}
+
+impl From<ValidationError> for ParseError {
+	fn from(e: ValidationError) -> Self {
+		Self {
+			business_reject_reason: e.business_reject_reason(),
+			..Self::new(e.reject_reason(), e.ref_tag_id(), e.to_string())
+		}
+	}
+}
+
 /// Trait for message validation
 ///
 /// All FIX message components (header, body, trailer) implement this trait
@@ -85,6 +187,37 @@ pub trait WriteTo {
 	fn write_to(&self, buffer: &mut String);
 }
 
+/// Error raised by [`FixFieldHandler::parse_field`]
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum FieldError {
+	/// The tag is not defined for the component
+	Unknown(String),
+	/// The value is not valid for the field
+	Invalid(String),
+}
+
+impl Display for FieldError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Unknown(text) | Self::Invalid(text) => write!(f, "{}", text),
+		}
+	}
+}
+
+impl std::error::Error for FieldError {}
+
+impl From<String> for FieldError {
+	fn from(text: String) -> Self {
+		Self::Invalid(text)
+	}
+}
+
+impl From<&str> for FieldError {
+	fn from(text: &str) -> Self {
+		Self::Invalid(text.to_string())
+	}
+}
+
 /// Trait for handling FIX field operations
 ///
 /// This trait provides a unified interface for parsing fields from tag-value pairs
@@ -98,8 +231,8 @@ pub trait FixFieldHandler {
 	///
 	/// # Returns
 	/// * `Ok(())` if the field was successfully parsed
-	/// * `Err(String)` if the field is unknown or invalid
-	fn parse_field(&mut self, tag: u32, value: &str) -> Result<(), String>;
+	/// * `Err(FieldError)` if the field is unknown or invalid
+	fn parse_field(&mut self, tag: u32, value: &str) -> Result<(), FieldError>;
 
 	/// Write only the fields that contribute to body length calculation
 	///
@@ -109,4 +242,12 @@ pub trait FixFieldHandler {
 	/// # Arguments
 	/// * `buffer` - The string buffer to write to
 	fn write_body_fields(&self, buffer: &mut String);
+
+	/// Delimiter (first field) of the repeating group counted by the NumInGroup field `count_tag`
+	///
+	/// Parsing uses it to check that the number of group entries matches the
+	/// declared count. Messages without repeating groups keep the default.
+	fn group_delimiter(&self, _count_tag: u32) -> Option<u32> {
+		None
+	}
 }
@@ -0,0 +1,369 @@
+//! FIX data dictionary
+//!
+//! A [`Dictionary`] names tags, describes enumerated values and knows which
+//! fields make up each repeating group. [`Dictionary::fix42`] covers the fields
+//! this crate implements, with value descriptions taken from the enums of
+//! [`common::enums`](crate::common::enums); a QuickFIX XML data dictionary
+//! (e.g. `FIX42.xml`) can be loaded for everything else.
+
+use crate::common::{
+	BusinessRejectReason, CxlRejReason, CxlRejResponseTo, DkReason, EncryptMethod, MdEntryType, MdReqRejReason,
+	MdUpdateAction, MdUpdateType, MsgType, OrdStatus, SessionRejectReason, Side, SubscriptionRequestType, TimeInForce,
+	enums::{ExecTransType, ExecType},
+};
+use crate::xml::{ElementKind, XmlElements};
+use std::{collections::HashMap, fmt::Display, fs, io, path::Path, sync::OnceLock};
+
+/// Errors raised while loading a data dictionary
+#[derive(Debug)]
+pub enum DictionaryError {
+	/// The dictionary file could not be read
+	Io(io::Error),
+	/// The dictionary is not well formed
+	Invalid(String),
+}
+
+impl Display for DictionaryError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Io(e) => write!(f, "Dictionary I/O error: {}", e),
+			Self::Invalid(message) => write!(f, "Invalid dictionary: {}", message),
+		}
+	}
+}
+
+impl std::error::Error for DictionaryError {}
+
+impl From<io::Error> for DictionaryError {
+	fn from(e: io::Error) -> Self {
+		Self::Io(e)
+	}
+}
+
+/// Definition of one field
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldDef {
+	pub tag: u32,
+	pub name: String,
+	/// Enumerated values with their description, empty for free-form fields
+	pub values: Vec<(String, String)>,
+}
+
+/// Names, enumerated values and repeating groups of FIX fields
+#[derive(Debug, Clone, Default)]
+pub struct Dictionary {
+	fields: HashMap<u32, FieldDef>,
+	tags: HashMap<String, u32>,
+	/// Member tags of each repeating group, by the tag of its NumInGroup field
+	groups: HashMap<u32, Vec<u32>>,
+}
+
+/// Fields of the built-in dictionary
+const FIX42_FIELDS: &[(u32, &str)] = &[
+	(1, "Account"),
+	(6, "AvgPx"),
+	(7, "BeginSeqNo"),
+	(8, "BeginString"),
+	(9, "BodyLength"),
+	(10, "CheckSum"),
+	(11, "ClOrdID"),
+	(14, "CumQty"),
+	(15, "Currency"),
+	(16, "EndSeqNo"),
+	(17, "ExecID"),
+	(18, "ExecInst"),
+	(19, "ExecRefID"),
+	(20, "ExecTransType"),
+	(21, "HandlInst"),
+	(31, "LastPx"),
+	(32, "LastShares"),
+	(34, "MsgSeqNum"),
+	(35, "MsgType"),
+	(36, "NewSeqNo"),
+	(37, "OrderID"),
+	(38, "OrderQty"),
+	(39, "OrdStatus"),
+	(40, "OrdType"),
+	(41, "OrigClOrdID"),
+	(43, "PossDupFlag"),
+	(44, "Price"),
+	(45, "RefSeqNum"),
+	(49, "SenderCompID"),
+	(50, "SenderSubID"),
+	(52, "SendingTime"),
+	(54, "Side"),
+	(55, "Symbol"),
+	(56, "TargetCompID"),
+	(57, "TargetSubID"),
+	(58, "Text"),
+	(59, "TimeInForce"),
+	(60, "TransactTime"),
+	(89, "Signature"),
+	(93, "SignatureLength"),
+	(95, "RawDataLength"),
+	(96, "RawData"),
+	(97, "PossResend"),
+	(98, "EncryptMethod"),
+	(99, "StopPx"),
+	(102, "CxlRejReason"),
+	(103, "OrdRejReason"),
+	(108, "HeartBtInt"),
+	(112, "TestReqID"),
+	(115, "OnBehalfOfCompID"),
+	(122, "OrigSendingTime"),
+	(123, "GapFillFlag"),
+	(126, "ExpireTime"),
+	(127, "DKReason"),
+	(128, "DeliverToCompID"),
+	(141, "ResetSeqNumFlag"),
+	(146, "NoRelatedSym"),
+	(150, "ExecType"),
+	(151, "LeavesQty"),
+	(152, "CashOrderQty"),
+	(207, "SecurityExchange"),
+	(262, "MDReqID"),
+	(263, "SubscriptionRequestType"),
+	(264, "MarketDepth"),
+	(265, "MDUpdateType"),
+	(266, "AggregatedBook"),
+	(267, "NoMDEntryTypes"),
+	(268, "NoMDEntries"),
+	(269, "MDEntryType"),
+	(270, "MDEntryPx"),
+	(271, "MDEntrySize"),
+	(278, "MDEntryID"),
+	(279, "MDUpdateAction"),
+	(280, "MDEntryRefID"),
+	(281, "MDReqRejReason"),
+	(290, "MDEntryPositionNo"),
+	(346, "NumberOfOrders"),
+	(371, "RefTagID"),
+	(372, "RefMsgType"),
+	(373, "SessionRejectReason"),
+	(379, "BusinessRejectRefID"),
+	(380, "BusinessRejectReason"),
+	(383, "MaxMessageSize"),
+	(434, "CxlRejResponseTo"),
+	(553, "Username"),
+	(554, "Password"),
+	(789, "NextExpectedMsgSeqNum"),
+];
+
+/// Values of the Boolean fields of the built-in dictionary
+const BOOLEAN: &[(&str, &str)] = &[("Y", "Yes"), ("N", "No")];
+
+impl Dictionary {
+	/// The built-in FIX 4.2 dictionary
+	pub fn fix42() -> &'static Self {
+		static FIX42: OnceLock<Dictionary> = OnceLock::new();
+		FIX42.get_or_init(|| {
+			let mut dictionary = Self::default();
+			for &(tag, name) in FIX42_FIELDS {
+				dictionary.add_field(FieldDef { tag, name: name.to_string(), values: Vec::new() });
+			}
+			let enums: &[(u32, &[(&str, &str)])] = &[
+				(20, ExecTransType::VALUES),
+				(35, MsgType::VALUES),
+				(39, OrdStatus::VALUES),
+				(43, BOOLEAN),
+				(54, Side::VALUES),
+				(59, TimeInForce::VALUES),
+				(97, BOOLEAN),
+				(98, EncryptMethod::VALUES),
+				(102, CxlRejReason::VALUES),
+				(123, BOOLEAN),
+				(127, DkReason::VALUES),
+				(141, BOOLEAN),
+				(150, ExecType::VALUES),
+				(263, SubscriptionRequestType::VALUES),
+				(265, MdUpdateType::VALUES),
+				(266, BOOLEAN),
+				(269, MdEntryType::VALUES),
+				(279, MdUpdateAction::VALUES),
+				(281, MdReqRejReason::VALUES),
+				(373, SessionRejectReason::VALUES),
+				(380, BusinessRejectReason::VALUES),
+				(434, CxlRejResponseTo::VALUES),
+			];
+			for &(tag, values) in enums {
+				let field = dictionary.fields.get_mut(&tag).expect("enumerated field is defined");
+				field.values = values.iter().map(|&(code, name)| (code.to_string(), name.to_string())).collect();
+			}
+			dictionary.groups.insert(146, vec![55, 207]);
+			dictionary.groups.insert(267, vec![269]);
+			// Members of the groups of both the snapshot and the incremental refresh
+			dictionary.groups.insert(268, vec![279, 269, 278, 280, 55, 270, 271, 290, 346]);
+			dictionary
+		})
+	}
+
+	/// Load a QuickFIX XML data dictionary from `path`
+	pub fn load(path: impl AsRef<Path>) -> Result<Self, DictionaryError> {
+		Self::from_quickfix_xml(&fs::read_to_string(path)?)
+	}
+
+	/// Read a QuickFIX XML data dictionary
+	///
+	/// Field definitions come from the `<fields>` section and repeating groups
+	/// from the `<group>` elements of the messages, header and trailer; fields
+	/// of components used inside groups are not resolved.
+	pub fn from_quickfix_xml(xml: &str) -> Result<Self, DictionaryError> {
+		let mut dictionary = Self::default();
+		let mut current_field: Option<FieldDef> = None;
+		// Groups being read, innermost last, with the names of their members
+		let mut open_groups: Vec<(String, Vec<String>)> = Vec::new();
+		let mut groups: Vec<(String, Vec<String>)> = Vec::new();
+
+		for element in XmlElements::new(xml) {
+			let element = element.map_err(DictionaryError::Invalid)?;
+			match (element.name, element.kind) {
+				("field", ElementKind::Start | ElementKind::Empty) if element.attribute("number").is_some() => {
+					let number = element.attribute("number").unwrap_or_default();
+					let tag = number.parse().map_err(|_| invalid(format!("Invalid field number {}", number)))?;
+					let name = element.required("name").map_err(invalid)?;
+					let field = FieldDef { tag, name, values: Vec::new() };
+					if element.kind == ElementKind::Empty {
+						dictionary.add_field(field);
+					} else {
+						current_field = Some(field);
+					}
+				},
+				("field", ElementKind::End) => {
+					if let Some(field) = current_field.take() {
+						dictionary.add_field(field);
+					}
+				},
+				("value", ElementKind::Start | ElementKind::Empty) => {
+					let field = current_field.as_mut().ok_or_else(|| invalid("Value outside of a field"))?;
+					field.values.push((
+						element.required("enum").map_err(invalid)?,
+						element.required("description").map_err(invalid)?,
+					));
+				},
+				("field", _) => {
+					if let Some((_, members)) = open_groups.last_mut() {
+						members.push(element.required("name").map_err(invalid)?);
+					}
+				},
+				("group", ElementKind::Start) => {
+					let name = element.required("name").map_err(invalid)?;
+					if let Some((_, members)) = open_groups.last_mut() {
+						members.push(name.clone());
+					}
+					open_groups.push((name, Vec::new()));
+				},
+				("group", ElementKind::End) => {
+					groups.push(open_groups.pop().ok_or_else(|| invalid("Unbalanced group"))?);
+				},
+				_ => {},
+			}
+		}
+
+		for (name, members) in groups {
+			let tag = dictionary.tag(&name).ok_or_else(|| invalid(format!("Unknown group field {}", name)))?;
+			let members = members
+				.iter()
+				.map(|member| dictionary.tag(member).ok_or_else(|| invalid(format!("Unknown field {}", member))))
+				.collect::<Result<Vec<u32>, _>>()?;
+			let group = dictionary.groups.entry(tag).or_default();
+			for member in members {
+				if !group.contains(&member) {
+					group.push(member);
+				}
+			}
+		}
+		Ok(dictionary)
+	}
+
+	pub fn field(&self, tag: u32) -> Option<&FieldDef> {
+		self.fields.get(&tag)
+	}
+
+	pub fn field_name(&self, tag: u32) -> Option<&str> {
+		self.fields.get(&tag).map(|field| field.name.as_str())
+	}
+
+	/// Tag of the field called `name`
+	pub fn tag(&self, name: &str) -> Option<u32> {
+		self.tags.get(name).copied()
+	}
+
+	/// Description of `value` for the enumerated field `tag`
+	pub fn value_description(&self, tag: u32, value: &str) -> Option<&str> {
+		let field = self.fields.get(&tag)?;
+		field.values.iter().find(|(code, _)| code == value).map(|(_, description)| description.as_str())
+	}
+
+	/// Member tags of the repeating group counted by `count_tag`, the first one starting each entry
+	pub fn group_members(&self, count_tag: u32) -> Option<&[u32]> {
+		self.groups.get(&count_tag).map(Vec::as_slice)
+	}
+
+	fn add_field(&mut self, field: FieldDef) {
+		self.tags.insert(field.name.clone(), field.tag);
+		self.fields.insert(field.tag, field);
+	}
+}
+
+fn invalid(message: impl Into<String>) -> DictionaryError {
+	DictionaryError::Invalid(message.into())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_builtin_dictionary() {
+		let dictionary = Dictionary::fix42();
+		assert_eq!(dictionary.field_name(35), Some("MsgType"));
+		assert_eq!(dictionary.tag("ClOrdID"), Some(11));
+		assert_eq!(dictionary.value_description(35, "D"), Some("NewOrderSingle"));
+		assert_eq!(dictionary.value_description(54, "1"), Some("Buy"));
+		assert_eq!(dictionary.value_description(43, "Y"), Some("Yes"));
+		assert_eq!(dictionary.value_description(55, "AAPL"), None);
+		assert_eq!(dictionary.group_members(146), Some(&[55, 207][..]));
+		// Every enumerated field is named
+		assert!(dictionary.fields.values().all(|field| !field.name.is_empty()));
+	}
+
+	#[test]
+	fn test_quickfix_xml() {
+		let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<fix major="4" minor="2">
+ <header><field name="BeginString" required="Y"/></header>
+ <messages>
+  <message name="MarketDataRequest" msgtype="V" msgcat="app">
+   <field name="MDReqID" required="Y"/>
+   <group name="NoRelatedSym" required="Y">
+    <field name="Symbol" required="Y"/>
+    <group name="NoLegs" required="N"><field name="LegSymbol" required="N"/></group>
+   </group>
+  </message>
+ </messages>
+ <!-- <field number="1" name="Ignored"/> -->
+ <fields>
+  <field number="8" name="BeginString" type="STRING"/>
+  <field number="54" name="Side" type="CHAR">
+   <value enum="1" description="BUY"/>
+   <value enum="2" description="SELL &amp; SHORT"/>
+  </field>
+  <field number="55" name="Symbol" type="STRING"/>
+  <field number="146" name="NoRelatedSym" type="NUMINGROUP"/>
+  <field number="262" name="MDReqID" type="STRING"/>
+  <field number="555" name="NoLegs" type="NUMINGROUP"/>
+  <field number="600" name="LegSymbol" type="STRING"/>
+ </fields>
+</fix>"#;
+		let dictionary = Dictionary::from_quickfix_xml(xml).unwrap();
+		assert_eq!(dictionary.field_name(54), Some("Side"));
+		assert_eq!(dictionary.field_name(1), None);
+		assert_eq!(dictionary.value_description(54, "2"), Some("SELL & SHORT"));
+		assert_eq!(dictionary.group_members(146), Some(&[55, 555][..]));
+		assert_eq!(dictionary.group_members(555), Some(&[600][..]));
+
+		let unknown_member = xml.replace(r#"<field number="600" name="LegSymbol" type="STRING"/>"#, "");
+		assert!(matches!(Dictionary::from_quickfix_xml(&unknown_member), Err(DictionaryError::Invalid(_))));
+		assert!(matches!(Dictionary::load("/nonexistent/FIX42.xml"), Err(DictionaryError::Io(_))));
+	}
+}
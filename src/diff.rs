@@ -0,0 +1,199 @@
+//! Field by field comparison of FIX messages
+//!
+//! [`diff`] compares two messages on their wire fields, so typed bodies and
+//! messages of unknown types ([`FixMessageBody::Other`](crate::FixMessageBody::Other))
+//! are compared alike. Repeated tags, as found in repeating groups, are paired
+//! by occurrence: the second 269 of one message against the second 269 of the other.
+
+use crate::{FixMessage, common::wire_fields, dictionary::Dictionary};
+use std::fmt::Display;
+
+/// Fields that differ between any two messages: BodyLength, CheckSum, MsgSeqNum and SendingTime
+pub const VOLATILE_TAGS: &[u32] = &[9, 10, 34, 52];
+
+/// One difference between two messages
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldDiff {
+	/// The field is only in the right message
+	Added { tag: u32, value: String },
+	/// The field is only in the left message
+	Removed { tag: u32, value: String },
+	/// The field has another value in the right message
+	Changed { tag: u32, left: String, right: String },
+}
+
+impl FieldDiff {
+	pub const fn tag(&self) -> u32 {
+		match self {
+			Self::Added { tag, .. } | Self::Removed { tag, .. } | Self::Changed { tag, .. } => *tag,
+		}
+	}
+}
+
+/// Fields left out of a comparison
+#[derive(Debug, Clone, Default)]
+pub struct DiffOptions {
+	ignored: Vec<u32>,
+}
+
+impl DiffOptions {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn ignore_tag(mut self, tag: u32) -> Self {
+		self.ignored.push(tag);
+		self
+	}
+
+	/// Ignore the [`VOLATILE_TAGS`]
+	pub fn ignore_volatile(mut self) -> Self {
+		self.ignored.extend_from_slice(VOLATILE_TAGS);
+		self
+	}
+}
+
+/// The differences between two messages, in the order of their fields
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct MessageDiff {
+	pub fields: Vec<FieldDiff>,
+}
+
+impl MessageDiff {
+	pub fn is_empty(&self) -> bool {
+		self.fields.is_empty()
+	}
+
+	/// One line per difference, naming fields and values with `dictionary`
+	///
+	/// Lines start with `+` for added, `-` for removed and `~` for changed fields,
+	/// e.g. `~ 54 Side: 1 (Buy) -> 2 (Sell)`.
+	pub fn format(&self, dictionary: &Dictionary) -> String {
+		let field = |tag: u32| match dictionary.field_name(tag) {
+			Some(name) => format!("{} {}", tag, name),
+			None => tag.to_string(),
+		};
+		let value = |tag: u32, value: &str| match dictionary.value_description(tag, value) {
+			Some(description) => format!("{} ({})", value, description),
+			None => value.to_string(),
+		};
+		let lines: Vec<String> = self
+			.fields
+			.iter()
+			.map(|diff| match diff {
+				FieldDiff::Added { tag, value: v } => format!("+ {} = {}", field(*tag), value(*tag, v)),
+				FieldDiff::Removed { tag, value: v } => format!("- {} = {}", field(*tag), value(*tag, v)),
+				FieldDiff::Changed { tag, left, right } => {
+					format!("~ {}: {} -> {}", field(*tag), value(*tag, left), value(*tag, right))
+				},
+			})
+			.collect();
+		lines.join("\n")
+	}
+}
+
+/// Formats with the built-in FIX 4.2 dictionary, see [`MessageDiff::format`]
+impl Display for MessageDiff {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}", self.format(Dictionary::fix42()))
+	}
+}
+
+/// Compare every field of two messages
+pub fn diff(left: &FixMessage, right: &FixMessage) -> MessageDiff {
+	diff_with(left, right, &DiffOptions::default())
+}
+
+/// Compare two messages, leaving out the fields ignored by `options`
+pub fn diff_with(left: &FixMessage, right: &FixMessage, options: &DiffOptions) -> MessageDiff {
+	diff_raw(&left.to_fix_string(), &right.to_fix_string(), options)
+}
+
+/// Compare two messages in wire format, also when they do not parse
+pub fn diff_raw(left: &str, right: &str, options: &DiffOptions) -> MessageDiff {
+	let left = occurrences(left, options);
+	let right = occurrences(right, options);
+	let mut fields = Vec::new();
+	for (key, value) in &left {
+		match right.iter().find(|(other, _)| other == key) {
+			Some((_, other)) if other != value => {
+				fields.push(FieldDiff::Changed { tag: key.0, left: value.to_string(), right: other.to_string() });
+			},
+			Some(_) => {},
+			None => fields.push(FieldDiff::Removed { tag: key.0, value: value.to_string() }),
+		}
+	}
+	for (key, value) in &right {
+		if !left.iter().any(|(other, _)| other == key) {
+			fields.push(FieldDiff::Added { tag: key.0, value: value.to_string() });
+		}
+	}
+	MessageDiff { fields }
+}
+
+/// Fields keyed by tag and occurrence of the tag, in wire order
+fn occurrences<'a>(message: &'a str, options: &DiffOptions) -> Vec<((u32, usize), &'a str)> {
+	let mut fields: Vec<((u32, usize), &str)> = Vec::new();
+	for (tag, value) in wire_fields(message).filter(|(tag, _)| !options.ignored.contains(tag)) {
+		let occurrence = fields.iter().filter(|((t, _), _)| *t == tag).count();
+		fields.push(((tag, occurrence), value));
+	}
+	fields
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Side, test_support::order_builder};
+
+	#[test]
+	fn test_diff_typed_messages() {
+		let left = order_builder(1).build();
+		assert!(diff(&left, &left).is_empty());
+
+		let right = order_builder(2).side(Side::Sell).ord_type("1").order_qty(200.0).build();
+		let all = diff(&left, &right);
+		assert!(all.fields.contains(&FieldDiff::Changed { tag: 34, left: "1".into(), right: "2".into() }));
+
+		let changes = diff_with(&left, &right, &DiffOptions::new().ignore_volatile());
+		assert_eq!(
+			changes.fields,
+			[
+				FieldDiff::Changed { tag: 54, left: "1".into(), right: "2".into() },
+				FieldDiff::Changed { tag: 40, left: "2".into(), right: "1".into() },
+				FieldDiff::Changed { tag: 38, left: "100".into(), right: "200".into() },
+			]
+		);
+		assert_eq!(
+			changes.to_string(),
+			"~ 54 Side: 1 (Buy) -> 2 (Sell)\n~ 40 OrdType: 2 -> 1\n~ 38 OrderQty: 100 -> 200"
+		);
+	}
+
+	#[test]
+	fn test_diff_unknown_messages() {
+		let news = |text: &str| {
+			FixMessage::parse(&format!(
+				"8=FIX.4.2\x019=0\x0135=B\x0149=A\x0156=B\x0134=1\x0152=20240102-03:04:05\x01148={}\x0133=1\x0158=x\x0110=000\x01",
+				text
+			))
+			.unwrap()
+		};
+		let changes = diff_with(&news("Hello"), &news("World"), &DiffOptions::new().ignore_volatile());
+		assert_eq!(changes.fields, [FieldDiff::Changed { tag: 148, left: "Hello".into(), right: "World".into() }]);
+		assert_eq!(changes.to_string(), "~ 148: Hello -> World");
+
+		// Repeated tags are paired by occurrence
+		let left = "35=W\x01268=2\x01269=0\x01270=10\x01269=1\x01270=11\x01";
+		let right = "35=W\x01268=1\x01269=0\x01270=10\x01";
+		let changes = diff_raw(left, right, &DiffOptions::new().ignore_tag(35));
+		assert_eq!(
+			changes.fields,
+			[
+				FieldDiff::Changed { tag: 268, left: "2".into(), right: "1".into() },
+				FieldDiff::Removed { tag: 269, value: "1".into() },
+				FieldDiff::Removed { tag: 270, value: "11".into() },
+			]
+		);
+	}
+}
@@ -0,0 +1,336 @@
+//! FIXML encoding
+//!
+//! Converts New Order Single, Execution Report and Order Cancel Request
+//! messages to and from FIXML with the abbreviated element and attribute names
+//! of the FIXML schema. Header fields go to the `<Hdr>` element, the symbol to
+//! `<Instrmt>` and quantities to `<OrdQty>`:
+//!
+//! ```xml
+//! <FIXML><Order ID="ORD1" HandlInst="1" Side="1" TxnTm="2024-01-02T03:04:05.000" Typ="2" Px="10.5">
+//!   <Hdr SID="CLIENT" TID="SERVER" SeqNum="7" Snt="2024-01-02T03:04:05.006"/>
+//!   <Instrmt Sym="AAPL"/><OrdQty Qty="100"/>
+//! </Order></FIXML>
+//! ```
+//!
+//! Timestamps are written in ISO 8601, as FIXML expects. Messages are checked
+//! with [`Validate`] before they are encoded and after they are decoded.
+//!
+//! Requires the `fixml` feature.
+
+use crate::{
+	FixMessage, MsgType, ParseError,
+	common::{Validate, ValidationError, wire_fields, wire_message},
+	xml::{ElementKind, XmlElements, escape, unescape},
+};
+use std::fmt::Display;
+
+/// FIXML attribute of each field: tag, element (`None` for the message element) and abbreviation
+const FIELDS: &[(u32, Option<&str>, &str)] = &[
+	(49, Some("Hdr"), "SID"),
+	(56, Some("Hdr"), "TID"),
+	(34, Some("Hdr"), "SeqNum"),
+	(52, Some("Hdr"), "Snt"),
+	(43, Some("Hdr"), "PosDup"),
+	(97, Some("Hdr"), "PosRsnd"),
+	(122, Some("Hdr"), "OrigSnt"),
+	(37, None, "OrdID"),
+	(11, None, "ID"),
+	(41, None, "OrigID"),
+	(17, None, "ExecID"),
+	(19, None, "ExecRefID"),
+	(20, None, "TransTyp"),
+	(150, None, "ExecTyp"),
+	(39, None, "Stat"),
+	(103, None, "RejRsn"),
+	(1, None, "Acct"),
+	(21, None, "HandlInst"),
+	(54, None, "Side"),
+	(60, None, "TxnTm"),
+	(40, None, "Typ"),
+	(44, None, "Px"),
+	(32, None, "LastQty"),
+	(31, None, "LastPx"),
+	(151, None, "LeavesQty"),
+	(14, None, "CumQty"),
+	(6, None, "AvgPx"),
+	(58, None, "Txt"),
+	(55, Some("Instrmt"), "Sym"),
+	(207, Some("Instrmt"), "Exch"),
+	(38, Some("OrdQty"), "Qty"),
+	(152, Some("OrdQty"), "Cash"),
+];
+/// Child elements of a message, in the order they are written
+const COMPONENTS: &[&str] = &["Hdr", "Instrmt", "OrdQty"];
+/// UTCTimestamp fields, written in ISO 8601
+const TIMESTAMP_TAGS: &[u32] = &[52, 60, 122];
+/// Fields implied by the FIXML document or computed from the rest of the message
+const OMITTED_TAGS: &[u32] = &[8, 9, 35, 10];
+
+/// Errors raised while converting to or from FIXML
+#[derive(Debug)]
+pub enum FixmlError {
+	/// The message type or one of its fields has no FIXML mapping here
+	Unsupported(String),
+	/// The text is not well-formed XML
+	Syntax(String),
+	/// The XML does not have the shape of a FIXML message
+	Invalid(String),
+	/// The fields do not form a FIX message
+	Parse(ParseError),
+	/// The message fails validation
+	Validation(ValidationError),
+}
+
+impl Display for FixmlError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Unsupported(what) => write!(f, "Not supported in FIXML: {}", what),
+			Self::Syntax(message) => write!(f, "FIXML syntax error: {}", message),
+			Self::Invalid(message) => write!(f, "Invalid FIXML: {}", message),
+			Self::Parse(e) => write!(f, "FIXML message does not parse: {}", e),
+			Self::Validation(e) => write!(f, "FIXML message is not valid: {}", e),
+		}
+	}
+}
+
+impl std::error::Error for FixmlError {}
+
+impl From<ParseError> for FixmlError {
+	fn from(e: ParseError) -> Self {
+		Self::Parse(e)
+	}
+}
+
+impl From<ValidationError> for FixmlError {
+	fn from(e: ValidationError) -> Self {
+		Self::Validation(e)
+	}
+}
+
+/// FIXML element of the messages that can be converted
+fn message_element(msg_type: &MsgType) -> Option<&'static str> {
+	match msg_type {
+		MsgType::NewOrderSingle => Some("Order"),
+		MsgType::ExecutionReport => Some("ExecRpt"),
+		MsgType::OrderCancelRequest => Some("OrdCxlReq"),
+		_ => None,
+	}
+}
+
+fn message_type(element: &str) -> Option<MsgType> {
+	match element {
+		"Order" => Some(MsgType::NewOrderSingle),
+		"ExecRpt" => Some(MsgType::ExecutionReport),
+		"OrdCxlReq" => Some(MsgType::OrderCancelRequest),
+		_ => None,
+	}
+}
+
+/// Encode a message as a `<FIXML>` document
+pub fn encode(message: &FixMessage) -> Result<String, FixmlError> {
+	message.validate()?;
+	let msg_type = &message.header.msg_type;
+	let element = message_element(msg_type).ok_or_else(|| FixmlError::Unsupported(format!("MsgType {}", msg_type)))?;
+
+	let mut attributes: Vec<(usize, String)> = Vec::new();
+	let wire = message.to_fix_string();
+	for (tag, value) in wire_fields(&wire).filter(|(tag, _)| !OMITTED_TAGS.contains(tag)) {
+		let position = FIELDS
+			.iter()
+			.position(|(field, ..)| *field == tag)
+			.ok_or_else(|| FixmlError::Unsupported(format!("tag {}", tag)))?;
+		let value = if TIMESTAMP_TAGS.contains(&tag) { fix_to_iso(value) } else { value.to_string() };
+		attributes.push((position, value));
+	}
+	attributes.sort_by_key(|(position, _)| *position);
+	let attributes: Vec<(Option<&str>, &str, String)> =
+		attributes.into_iter().map(|(position, value)| (FIELDS[position].1, FIELDS[position].2, value)).collect();
+
+	let write_attributes = |fixml: &mut String, component: Option<&str>| {
+		for (_, name, value) in attributes.iter().filter(|(c, ..)| *c == component) {
+			fixml.push_str(&format!(" {}=\"{}\"", name, escape(value)));
+		}
+	};
+	let mut fixml = format!("<FIXML><{}", element);
+	write_attributes(&mut fixml, None);
+	fixml.push('>');
+	for &component in COMPONENTS {
+		if attributes.iter().any(|(c, ..)| *c == Some(component)) {
+			fixml.push_str(&format!("<{}", component));
+			write_attributes(&mut fixml, Some(component));
+			fixml.push_str("/>");
+		}
+	}
+	fixml.push_str(&format!("</{}></FIXML>", element));
+	Ok(fixml)
+}
+
+/// Decode a FIXML document, or a message element on its own, holding one message
+pub fn decode(fixml: &str) -> Result<FixMessage, FixmlError> {
+	let mut msg_type = None;
+	let mut fields: Vec<(u32, String)> = Vec::new();
+	for element in XmlElements::new(fixml) {
+		let element = element.map_err(FixmlError::Syntax)?;
+		if element.kind == ElementKind::End || element.name == "FIXML" {
+			continue;
+		}
+		let component = if msg_type.is_none() {
+			let element_type = message_type(element.name)
+				.ok_or_else(|| FixmlError::Unsupported(format!("element <{}>", element.name)))?;
+			msg_type = Some(element_type);
+			None
+		} else if COMPONENTS.contains(&element.name) {
+			Some(element.name)
+		} else {
+			return Err(invalid(format!("Unknown element <{}>", element.name)));
+		};
+		for &(name, value) in &element.attributes {
+			let &(tag, ..) = FIELDS
+				.iter()
+				.find(|(_, c, field)| *c == component && *field == name)
+				.ok_or_else(|| invalid(format!("Unknown attribute {} of <{}>", name, element.name)))?;
+			let value = unescape(value);
+			let value = if TIMESTAMP_TAGS.contains(&tag) { iso_to_fix(&value)? } else { value };
+			fields.push((tag, value));
+		}
+	}
+	let msg_type = msg_type.ok_or_else(|| invalid("No message element"))?;
+	fields.sort_by_key(|(tag, _)| FIELDS.iter().position(|(field, ..)| field == tag));
+
+	let fields = std::iter::once((35, msg_type.to_string())).chain(fields);
+	let message = FixMessage::parse_decoded(&wire_message("FIX.4.2", fields))?;
+	message.validate()?;
+	Ok(message)
+}
+
+fn invalid(message: impl Into<String>) -> FixmlError {
+	FixmlError::Invalid(message.into())
+}
+
+/// `20240102-03:04:05.006` to `2024-01-02T03:04:05.006`
+fn fix_to_iso(value: &str) -> String {
+	match (value.get(..4), value.get(4..6), value.get(6..8), value.get(8..9), value.get(9..)) {
+		(Some(year), Some(month), Some(day), Some("-"), Some(time)) => format!("{}-{}-{}T{}", year, month, day, time),
+		_ => value.to_string(),
+	}
+}
+
+/// `2024-01-02T03:04:05.006`, optionally ending in `Z`, to `20240102-03:04:05.006`
+fn iso_to_fix(value: &str) -> Result<String, FixmlError> {
+	let time = value.strip_suffix('Z').unwrap_or(value);
+	match (time.get(..4), time.get(4..5), time.get(5..7), time.get(7..8), time.get(8..10), time.get(10..11)) {
+		(Some(year), Some("-"), Some(month), Some("-"), Some(day), Some("T")) => {
+			Ok(format!("{}{}{}-{}", year, month, day, &time[11..]))
+		},
+		_ => Err(invalid(format!("Invalid timestamp {}", value))),
+	}
+}
+
+impl FixMessage {
+	/// Encode the message as FIXML, see [`fixml`](crate::fixml)
+	pub fn to_fixml(&self) -> Result<String, FixmlError> {
+		encode(self)
+	}
+
+	/// Decode a FIXML message, see [`fixml`](crate::fixml)
+	pub fn from_fixml(fixml: &str) -> Result<Self, FixmlError> {
+		decode(fixml)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		ExecutionReportBody, FixMessageBody, FixMessageBuilder, OrdStatus, Side,
+		common::enums::ExecType,
+		test_support::{assert_round_trip, cancel, order},
+	};
+	use time::macros::datetime;
+
+	fn round_trip(message: &FixMessage) {
+		assert_round_trip(message, |message| FixMessage::from_fixml(&message.to_fixml()?));
+	}
+
+	#[test]
+	fn test_encode_order() {
+		let order = order();
+		assert_eq!(
+			order.to_fixml().unwrap(),
+			concat!(
+				r#"<FIXML><Order ID="ORD1" HandlInst="1" Side="1" TxnTm="2024-01-02T03:04:05.000" Typ="2" Px="10.5">"#,
+				r#"<Hdr SID="CLIENT" TID="SERVER" SeqNum="7" Snt="2024-01-02T03:04:05.006"/>"#,
+				r#"<Instrmt Sym="AAPL"/><OrdQty Qty="100"/></Order></FIXML>"#
+			)
+		);
+		round_trip(&order);
+	}
+
+	#[test]
+	fn test_execution_report_and_cancel_round_trip() {
+		let body = ExecutionReportBody {
+			exec_type: ExecType::PartialFill,
+			ord_status: OrdStatus::PartiallyFilled,
+			symbol: "AAPL".into(),
+			side: Side::Buy,
+			leaves_qty: 60.0,
+			cum_qty: 40.0,
+			avg_px: 10.5,
+			last_shares: Some(40.0),
+			last_px: Some(10.5),
+			transact_time: Some(datetime!(2024-01-02 03:04:05 UTC)),
+			cl_ord_id: Some("ORD1".into()),
+			account: Some("Smith & Co <Retail>".into()),
+			..ExecutionReportBody::new("OID1", "EXEC1")
+		};
+		let header = FixMessage::builder(MsgType::ExecutionReport, "SERVER", "CLIENT", 3)
+			.sending_time(datetime!(2024-01-02 03:04:05 UTC))
+			.build();
+		let report =
+			FixMessageBuilder::from_message(FixMessage { body: FixMessageBody::ExecutionReport(body), ..header })
+				.build();
+		let fixml = report.to_fixml().unwrap();
+		assert!(
+			fixml.starts_with(
+				r#"<FIXML><ExecRpt OrdID="OID1" ID="ORD1" ExecID="EXEC1" TransTyp="0" ExecTyp="1" Stat="1""#
+			)
+		);
+		assert!(fixml.contains(r#" Acct="Smith &amp; Co &lt;Retail&gt;""#));
+		round_trip(&report);
+
+		let cancel = cancel();
+		round_trip(&cancel);
+
+		// Written by hand, with a declaration, whitespace and a time zone designator
+		let fixml = r#"<?xml version="1.0"?>
+<FIXML v="4.4">
+  <OrdCxlReq OrigID="ORD1" ID="ORD2" Side="1" TxnTm="2024-01-02T03:04:05Z" Txt="Too late">
+    <Hdr SID="CLIENT" TID="SERVER" SeqNum="8" Snt="2024-01-02T03:04:05"/>
+    <Instrmt Sym="AAPL"/>
+    <OrdQty Qty="100"/>
+  </OrdCxlReq>
+</FIXML>"#;
+		assert_eq!(FixMessage::from_fixml(fixml).unwrap(), cancel);
+	}
+
+	#[test]
+	fn test_errors() {
+		let heartbeat = FixMessage::builder(MsgType::Heartbeat, "CLIENT", "SERVER", 1).build();
+		assert!(matches!(heartbeat.to_fixml(), Err(FixmlError::Unsupported(_))));
+		let no_quantity = FixMessage::builder(MsgType::NewOrderSingle, "CLIENT", "SERVER", 1).cl_ord_id("ORD1").build();
+		assert!(matches!(no_quantity.to_fixml(), Err(FixmlError::Validation(_))));
+
+		let order = order().to_fixml().unwrap();
+		assert!(matches!(FixMessage::from_fixml("<Order ID=ORD1>"), Err(FixmlError::Syntax(_))));
+		assert!(matches!(FixMessage::from_fixml("<FIXML><Heartbeat/></FIXML>"), Err(FixmlError::Unsupported(_))));
+		assert!(matches!(FixMessage::from_fixml("<FIXML></FIXML>"), Err(FixmlError::Invalid(_))));
+		let unknown = order.replace("<Instrmt", "<Instrument");
+		assert!(matches!(FixMessage::from_fixml(&unknown), Err(FixmlError::Invalid(_))));
+		let unknown = order.replace(r#" Px="#, r#" Price="#);
+		assert!(matches!(FixMessage::from_fixml(&unknown), Err(FixmlError::Invalid(_))));
+		let invalid_time = order.replace("2024-01-02T03:04:05.006", "yesterday");
+		assert!(matches!(FixMessage::from_fixml(&invalid_time), Err(FixmlError::Invalid(_))));
+		let no_quantity = order.replace(r#"<OrdQty Qty="100"/>"#, "");
+		assert!(matches!(FixMessage::from_fixml(&no_quantity), Err(FixmlError::Parse(_))));
+	}
+}
@@ -0,0 +1,358 @@
+//! FIX-JSON encoding
+//!
+//! Encodes messages as described by the FIX Trading Community JSON encoding:
+//! one object with `Header`, `Body` and `Trailer` members, each keyed by field
+//! name, with every value a string and repeating groups as arrays of objects
+//! under the name of their NumInGroup field.
+//!
+//! ```json
+//! {
+//!   "Header": { "BeginString": "FIX.4.2", "MsgType": "V", "SenderCompID": "CLIENT", ... },
+//!   "Body": { "MDReqID": "REQ1", "NoRelatedSym": [ { "Symbol": "AAPL" } ], ... },
+//!   "Trailer": {}
+//! }
+//! ```
+//!
+//! BodyLength and CheckSum are left out and computed again when decoding.
+//! Fields the [`Dictionary`] does not name are keyed by their tag number, and
+//! fields repeated outside of the groups it knows become arrays of strings.
+//!
+//! Requires the `json` feature.
+
+use crate::{
+	FixMessage, ParseError,
+	common::{wire_fields, wire_message},
+	dictionary::Dictionary,
+};
+use serde_json::{Map, Value};
+use std::fmt::Display;
+
+/// Standard header fields, which go to the `Header` object
+const HEADER_TAGS: &[u32] = &[
+	8, 9, 35, 49, 56, 115, 128, 90, 91, 34, 50, 142, 57, 143, 116, 144, 129, 145, 43, 97, 52, 122, 212, 213, 347, 369,
+	370,
+];
+/// Standard trailer fields, which go to the `Trailer` object
+const TRAILER_TAGS: &[u32] = &[93, 89, 10];
+/// Fields computed from the rest of the message
+const COMPUTED_TAGS: &[u32] = &[9, 10];
+
+/// Errors raised while decoding FIX-JSON
+#[derive(Debug)]
+pub enum JsonError {
+	/// The text is not JSON
+	Syntax(serde_json::Error),
+	/// The JSON does not have the shape of a FIX-JSON message
+	Invalid(String),
+	/// The fields do not form a valid FIX message
+	Parse(ParseError),
+}
+
+impl Display for JsonError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Syntax(e) => write!(f, "FIX-JSON syntax error: {}", e),
+			Self::Invalid(message) => write!(f, "Invalid FIX-JSON: {}", message),
+			Self::Parse(e) => write!(f, "FIX-JSON message does not parse: {}", e),
+		}
+	}
+}
+
+impl std::error::Error for JsonError {}
+
+impl From<serde_json::Error> for JsonError {
+	fn from(e: serde_json::Error) -> Self {
+		Self::Syntax(e)
+	}
+}
+
+impl From<ParseError> for JsonError {
+	fn from(e: ParseError) -> Self {
+		Self::Parse(e)
+	}
+}
+
+/// Converts between FIX messages and FIX-JSON, naming fields with a [`Dictionary`]
+#[derive(Debug, Clone, Copy)]
+pub struct JsonCodec<'d> {
+	dictionary: &'d Dictionary,
+}
+
+impl Default for JsonCodec<'static> {
+	fn default() -> Self {
+		Self::new(Dictionary::fix42())
+	}
+}
+
+impl<'d> JsonCodec<'d> {
+	pub const fn new(dictionary: &'d Dictionary) -> Self {
+		Self { dictionary }
+	}
+
+	pub fn encode(&self, message: &FixMessage) -> Value {
+		self.encode_raw(&message.to_fix_string())
+	}
+
+	/// Encode a message in wire format, fields separated by SOH
+	pub fn encode_raw(&self, message: &str) -> Value {
+		let fields: Vec<(u32, &str)> = wire_fields(message).filter(|(tag, _)| !COMPUTED_TAGS.contains(tag)).collect();
+
+		let mut header = Map::new();
+		let mut trailer = Map::new();
+		let mut body_fields = Vec::with_capacity(fields.len());
+		for (tag, value) in fields {
+			if HEADER_TAGS.contains(&tag) {
+				header.insert(self.name(tag), value.into());
+			} else if TRAILER_TAGS.contains(&tag) {
+				trailer.insert(self.name(tag), value.into());
+			} else {
+				body_fields.push((tag, value));
+			}
+		}
+		let body = self.encode_fields(&body_fields, &mut 0, None);
+
+		let mut message = Map::new();
+		message.insert("Header".into(), Value::Object(header));
+		message.insert("Body".into(), Value::Object(body));
+		message.insert("Trailer".into(), Value::Object(trailer));
+		Value::Object(message)
+	}
+
+	/// Encode fields from `fields[*position]` on
+	///
+	/// Within a group (`members` set) encoding stops at the first tag that is not
+	/// a member or that starts the next entry. Elsewhere a repeated tag, such as a
+	/// member of a group the dictionary does not know, is kept as the list of its
+	/// values.
+	fn encode_fields(
+		&self,
+		fields: &[(u32, &str)],
+		position: &mut usize,
+		members: Option<&[u32]>,
+	) -> Map<String, Value> {
+		let mut object = Map::new();
+		while let Some(&(tag, value)) = fields.get(*position) {
+			let name = self.name(tag);
+			if let Some(members) = members
+				&& (!members.contains(&tag) || object.contains_key(&name))
+			{
+				// Outside of the group, or the start of its next entry
+				break;
+			}
+			*position += 1;
+			let group = self.dictionary.group_members(tag).zip(value.parse::<usize>().ok());
+			let Some((group_members, count)) = group else {
+				match object.get_mut(&name) {
+					Some(Value::Array(values)) => values.push(value.into()),
+					Some(first) => *first = Value::Array(vec![first.take(), value.into()]),
+					None => {
+						object.insert(name, value.into());
+					},
+				}
+				continue;
+			};
+			let mut entries = Vec::with_capacity(count);
+			while entries.len() < count {
+				let start = *position;
+				let entry = self.encode_fields(fields, position, Some(group_members));
+				if *position == start {
+					break;
+				}
+				entries.push(Value::Object(entry));
+			}
+			object.insert(name, Value::Array(entries));
+		}
+		object
+	}
+
+	pub fn decode(&self, json: &Value) -> Result<FixMessage, JsonError> {
+		Ok(FixMessage::parse_decoded(&self.decode_raw(json)?)?)
+	}
+
+	/// Decode FIX-JSON into wire format
+	pub fn decode_raw(&self, json: &Value) -> Result<String, JsonError> {
+		let message = json.as_object().ok_or_else(|| invalid("Message is not an object"))?;
+		let section = |name: &str| match message.get(name) {
+			Some(Value::Object(section)) => Ok(Some(section)),
+			Some(_) => Err(invalid(format!("{} is not an object", name))),
+			None => Ok(None),
+		};
+		let header = section("Header")?.ok_or_else(|| invalid("Missing Header"))?;
+		let body = section("Body")?;
+		let trailer = section("Trailer")?;
+
+		let mut header_fields = self.decode_fields(header)?;
+		header_fields.retain(|(tag, _)| !COMPUTED_TAGS.contains(tag));
+		let begin_string = match header_fields.iter().position(|(tag, _)| *tag == 8) {
+			Some(index) => header_fields.remove(index).1,
+			None => "FIX.4.2".to_string(),
+		};
+		let mut fields = header_fields;
+		if let Some(body) = body {
+			fields.extend(self.decode_fields(body)?);
+		}
+		if let Some(trailer) = trailer {
+			fields.extend(self.decode_fields(trailer)?.into_iter().filter(|(tag, _)| !COMPUTED_TAGS.contains(tag)));
+		}
+		Ok(wire_message(&begin_string, fields))
+	}
+
+	fn decode_fields(&self, object: &Map<String, Value>) -> Result<Vec<(u32, String)>, JsonError> {
+		let mut fields = Vec::with_capacity(object.len());
+		for (name, value) in object {
+			let tag = self.tag(name)?;
+			match value {
+				Value::String(value) => fields.push((tag, value.clone())),
+				Value::Number(value) => fields.push((tag, value.to_string())),
+				// The values of a repeated field rather than the entries of a group
+				Value::Array(values) if values.iter().any(|value| value.is_string() || value.is_number()) => {
+					for value in values {
+						let value = match value {
+							Value::String(value) => value.clone(),
+							Value::Number(value) => value.to_string(),
+							_ => return Err(invalid(format!("Value of {} is neither a string nor a number", name))),
+						};
+						fields.push((tag, value));
+					}
+				},
+				Value::Array(entries) => {
+					fields.push((tag, entries.len().to_string()));
+					for entry in entries {
+						let entry =
+							entry.as_object().ok_or_else(|| invalid(format!("Entry of {} is not an object", name)))?;
+						let mut entry_fields = self.decode_fields(entry)?;
+						// The first member of a group starts each of its entries on the wire
+						if let Some(members) = self.dictionary.group_members(tag) {
+							entry_fields.sort_by_key(|(tag, _)| {
+								members.iter().position(|member| member == tag).unwrap_or(usize::MAX)
+							});
+						}
+						fields.extend(entry_fields);
+					}
+				},
+				_ => return Err(invalid(format!("{} is neither a string nor a group", name))),
+			}
+		}
+		Ok(fields)
+	}
+
+	fn name(&self, tag: u32) -> String {
+		self.dictionary.field_name(tag).map_or_else(|| tag.to_string(), str::to_string)
+	}
+
+	fn tag(&self, name: &str) -> Result<u32, JsonError> {
+		self.dictionary
+			.tag(name)
+			.or_else(|| name.parse().ok())
+			.ok_or_else(|| invalid(format!("Unknown field {}", name)))
+	}
+}
+
+fn invalid(message: impl Into<String>) -> JsonError {
+	JsonError::Invalid(message.into())
+}
+
+impl FixMessage {
+	/// Encode the message as FIX-JSON with the built-in dictionary
+	pub fn to_fix_json(&self) -> String {
+		JsonCodec::default().encode(self).to_string()
+	}
+
+	/// Decode a FIX-JSON message with the built-in dictionary
+	pub fn from_fix_json(json: &str) -> Result<Self, JsonError> {
+		JsonCodec::default().decode(&serde_json::from_str(json)?)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		FixMessageBuilder, MdEntryType, MsgType, SubscriptionRequestType,
+		messages::RelatedSym,
+		test_support::{assert_round_trip, order},
+	};
+	use serde_json::json;
+	use time::macros::datetime;
+
+	fn round_trip(message: &FixMessage) {
+		assert_round_trip(message, |message| FixMessage::from_fix_json(&message.to_fix_json()));
+	}
+
+	#[test]
+	fn test_encode_order() {
+		let order = order();
+		let json = JsonCodec::default().encode(&order);
+		assert_eq!(
+			json["Header"],
+			json!({
+				"BeginString": "FIX.4.2",
+				"MsgType": "D",
+				"SenderCompID": "CLIENT",
+				"TargetCompID": "SERVER",
+				"MsgSeqNum": "7",
+				"SendingTime": "20240102-03:04:05.006",
+			})
+		);
+		assert_eq!(json["Body"]["Side"], "1");
+		assert_eq!(json["Body"]["Price"], "10.5");
+		assert_eq!(json["Trailer"], json!({}));
+		round_trip(&order);
+	}
+
+	#[test]
+	fn test_groups_round_trip() {
+		let request = FixMessage::builder(MsgType::MarketDataRequest, "CLIENT", "SERVER", 2)
+			.sending_time(datetime!(2024-01-02 03:04:05 UTC))
+			.md_req_id("REQ1")
+			.subscription_request_type(SubscriptionRequestType::Snapshot)
+			.market_depth(1)
+			.md_entry_type(MdEntryType::Bid)
+			.md_entry_type(MdEntryType::Offer)
+			.related_sym(RelatedSym::new("AAPL"))
+			.related_sym(RelatedSym::new("MSFT"))
+			.build();
+		let json = JsonCodec::default().encode(&request);
+		assert_eq!(json["Body"]["NoMDEntryTypes"], json!([{ "MDEntryType": "0" }, { "MDEntryType": "1" }]));
+		assert_eq!(json["Body"]["NoRelatedSym"], json!([{ "Symbol": "AAPL" }, { "Symbol": "MSFT" }]));
+		round_trip(&request);
+
+		let snapshot = "8=FIX.4.2\x019=0\x0135=W\x0149=SERVER\x0156=CLIENT\x0134=3\x0152=20240102-03:04:05\x01262=REQ1\x0155=AAPL\x01268=2\x01269=0\x01270=10.5\x01271=100\x01269=1\x01270=10.6\x01271=200\x0110=000\x01";
+		let snapshot = FixMessageBuilder::from_message(FixMessage::parse(snapshot).unwrap()).build();
+		round_trip(&snapshot);
+
+		// Entries written by hand need not start with the first member of their group
+		let json = json!({
+			"Header": { "MsgType": "W", "SenderCompID": "SERVER", "TargetCompID": "CLIENT", "MsgSeqNum": 3, "SendingTime": "20240102-03:04:05" },
+			"Body": {
+				"MDReqID": "REQ1",
+				"Symbol": "AAPL",
+				"NoMDEntries": [
+					{ "MDEntryPx": "10.5", "MDEntrySize": "100", "MDEntryType": "0" },
+					{ "MDEntryType": "1", "MDEntryPx": "10.6", "MDEntrySize": "200" },
+				],
+			},
+		});
+		assert_eq!(JsonCodec::default().decode(&json).unwrap(), snapshot);
+	}
+
+	#[test]
+	fn test_unknown_group_round_trip() {
+		// News (MsgType=B) is not supported and its LinesOfText group is not in the dictionary
+		let fields = [(35, "B"), (49, "SERVER"), (56, "CLIENT"), (34, "4"), (52, "20240102-03:04:05.006")];
+		let body = [(148, "Headline"), (33, "2"), (58, "a"), (58, "b"), (61, "0")];
+		let news = FixMessage::parse(&wire_message("FIX.4.2", fields.into_iter().chain(body))).unwrap();
+		let json = JsonCodec::default().encode(&news);
+		assert_eq!(json["Body"], json!({ "148": "Headline", "33": "2", "Text": ["a", "b"], "61": "0" }));
+		round_trip(&news);
+	}
+
+	#[test]
+	fn test_decode_errors() {
+		assert!(matches!(FixMessage::from_fix_json("{"), Err(JsonError::Syntax(_))));
+		assert!(matches!(FixMessage::from_fix_json("[]"), Err(JsonError::Invalid(_))));
+		assert!(matches!(FixMessage::from_fix_json(r#"{"Body": {}}"#), Err(JsonError::Invalid(_))));
+		assert!(matches!(FixMessage::from_fix_json(r#"{"Header": {"NoSuchField": "1"}}"#), Err(JsonError::Invalid(_))));
+		assert!(matches!(FixMessage::from_fix_json(r#"{"Header": {"MsgType": "0"}}"#), Err(JsonError::Parse(_))));
+	}
+}
@@ -43,21 +43,56 @@
 
 pub mod builder;
 pub mod common;
+pub mod dictionary;
+pub mod diff;
+#[cfg(feature = "fixml")]
+pub mod fixml;
+#[cfg(feature = "json")]
+pub mod json;
+pub mod logs;
 pub mod macros;
 pub mod messages;
+pub mod oms;
+pub mod orderbook;
+pub mod pretty;
+#[cfg(feature = "sbe")]
+pub mod sbe;
+#[cfg(feature = "serde")]
+pub mod serde_fix;
+pub mod session;
+pub mod simulator;
+#[cfg(test)]
+mod test_support;
+pub mod transport;
+mod xml;
 
-use std::{collections::HashMap, fmt::Display};
+use std::fmt::Display;
 
 // Re-export commonly used types
 pub use builder::FixMessageBuilder;
 pub use common::{
-	EncryptMethod, FixHeader, FixTrailer, MsgType, OrdStatus, SOH, Side, Validate, ValidationError, parse_fix_timestamp,
+	BusinessRejectReason, CxlRejReason, CxlRejResponseTo, DkReason, EncryptMethod, FixHeader, FixTrailer, MdEntryType,
+	MdReqRejReason, MdUpdateAction, MdUpdateType, MsgType, OrdStatus, ParseError, SOH, SessionRejectReason, Side,
+	SubscriptionRequestType, TimeInForce, Validate, ValidationError, parse_fix_timestamp,
 };
+pub use dictionary::Dictionary;
 pub use messages::{
-	ExecutionReportBody, FixMessageBody, HeartbeatBody, LogonBody, NewOrderSingleBody, OrderCancelRequestBody,
+	BusinessMessageRejectBody, DontKnowTradeBody, ExecutionReportBody, FixMessageBody, HeartbeatBody, LogonBody,
+	LogoutBody, MarketDataIncrementalRefreshBody, MarketDataRequestBody, MarketDataRequestRejectBody,
+	MarketDataSnapshotFullRefreshBody, MdEntry, MdIncrementalEntry, NewOrderSingleBody, OrderCancelRejectBody,
+	OrderCancelReplaceRequestBody, OrderCancelRequestBody, OrderStatusRequestBody, RejectBody, RelatedSym,
+	ResendRequestBody, SequenceResetBody, TestRequestBody,
+};
+pub use oms::OrderTracker;
+pub use orderbook::OrderBook;
+pub use pretty::PrettyPrinter;
+pub use session::{
+	Application, FileStore, MemoryStore, MessageStore, Session, SessionConfig, SessionError, SessionId,
+	SessionSchedule, SessionSettings, SessionState, StoreError,
 };
+pub use transport::{Acceptor, Connection, Initiator, TransportError};
 
-use crate::common::validation::{FixFieldHandler, WriteTo};
+use crate::common::validation::{FieldError, FixFieldHandler, WriteTo};
 
 /// Main FIX 4.2 Message structure
 ///
@@ -66,6 +101,7 @@ use crate::common::validation::{FixFieldHandler, WriteTo};
 /// The message body uses an enum to provide memory-efficient storage by only allocating fields needed for each
 /// specific message type.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FixMessage {
 	/// Standard message header with required and optional fields
 	pub header: FixHeader,
@@ -75,8 +111,13 @@ pub struct FixMessage {
 	pub trailer: FixTrailer,
 }
 
+/// `{}` shows the wire format with " | " between fields; `{:#}` shows one
+/// annotated field per line, as formatted by [`PrettyPrinter`].
 impl Display for FixMessage {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		if f.alternate() {
+			return write!(f, "{}", PrettyPrinter::default().format(self));
+		}
 		let fix_string = self.to_fix_string();
 		let readable = fix_string.replace(SOH, " | ");
 		write!(f, "{}", readable)
@@ -101,14 +142,7 @@ impl FixMessage {
 		target_comp_id: impl Into<String>,
 		msg_seq_num: u32,
 	) -> Self {
-		let body = match msg_type {
-			MsgType::Heartbeat => FixMessageBody::Heartbeat(HeartbeatBody::default()),
-			MsgType::Logon => FixMessageBody::Logon(LogonBody::default()),
-			MsgType::NewOrderSingle => FixMessageBody::NewOrderSingle(NewOrderSingleBody::default()),
-			MsgType::ExecutionReport => FixMessageBody::ExecutionReport(ExecutionReportBody::default()),
-			MsgType::OrderCancelRequest => FixMessageBody::OrderCancelRequest(OrderCancelRequestBody::default()),
-			_ => FixMessageBody::Other,
-		};
+		let body = FixMessageBody::for_msg_type(&msg_type);
 		let header = FixHeader::new(msg_type, sender_comp_id, target_comp_id, msg_seq_num);
 		let trailer = FixTrailer::default();
 		Self { header, body, trailer }
@@ -135,57 +169,129 @@ impl FixMessage {
 
 	/// Parse a FIX message from wire format
 	pub fn from_fix_string(fix_string: &str) -> Result<Self, String> {
+		Self::parse(fix_string).map_err(|e| e.to_string())
+	}
+
+	/// Parse a FIX message from wire format, describing failures as session-level rejects
+	///
+	/// When the header could be read, the error carries it so the message can be
+	/// answered with a Reject (MsgType=3), see [`RejectBody::for_parse_error`].
+	pub fn parse(fix_string: &str) -> Result<Self, ParseError> {
 		let fields: Vec<&str> = fix_string.split(SOH).filter(|s| !s.is_empty()).collect();
 
 		if fields.is_empty() {
-			return Err("Empty FIX message".to_string());
+			return Err(ParseError::new(SessionRejectReason::RequiredTagMissing, Some(8), "Empty FIX message"));
 		}
 
-		// Parse fields into key-value pairs with tags as numbers
-		let mut field_map = HashMap::new();
+		// Parse fields into key-value pairs with tags as numbers, remembering the first malformed field.
+		// Wire order is kept since repeating groups are delimited by the position of their fields.
+		let mut field_list = Vec::with_capacity(fields.len());
+		let mut malformed = None;
 		for field in fields {
 			if let Some((tag_str, value)) = field.split_once('=') {
-				let tag: u32 = tag_str.parse().map_err(|_| format!("Invalid tag: {}", tag_str))?;
-				field_map.insert(tag, value);
+				match tag_str.parse::<u32>() {
+					Ok(tag) => field_list.push((tag, value)),
+					Err(_) => {
+						let text = format!("Invalid tag: {}", tag_str);
+						malformed.get_or_insert((SessionRejectReason::InvalidTagNumber, None, text));
+					},
+				}
 			}
 		}
 
 		// Extract required fields for message creation
-		let msg_type_str = field_map.get(&35).ok_or("Missing MsgType (35)")?;
-		let msg_type = msg_type_str.parse().map_err(|_| "Invalid MsgType")?;
-
-		let sender_comp_id = field_map.get(&49).ok_or("Missing SenderCompID (49)")?.to_string();
-		let target_comp_id = field_map.get(&56).ok_or("Missing TargetCompID (56)")?.to_string();
-
-		let msg_seq_num: u32 =
-			field_map.get(&34).ok_or("Missing MsgSeqNum (34)")?.parse().map_err(|_| "Invalid MsgSeqNum")?;
+		let required = |tag: u32, name: &str| {
+			field_list.iter().find(|&&(t, _)| t == tag).map(|&(_, value)| value).ok_or_else(|| {
+				let text = format!("Missing {} ({})", name, tag);
+				ParseError::new(SessionRejectReason::RequiredTagMissing, Some(tag), text)
+			})
+		};
+		let msg_type = required(35, "MsgType")?
+			.parse()
+			.map_err(|_| ParseError::new(SessionRejectReason::InvalidMsgType, Some(35), "Invalid MsgType"))?;
+		let sender_comp_id = required(49, "SenderCompID")?.to_string();
+		let target_comp_id = required(56, "TargetCompID")?.to_string();
+		let msg_seq_num: u32 = required(34, "MsgSeqNum")?.parse().map_err(|_| {
+			ParseError::new(SessionRejectReason::IncorrectDataFormatForValue, Some(34), "Invalid MsgSeqNum")
+		})?;
 
 		// Create message with basic required fields
 		let mut message = Self::new(msg_type, sender_comp_id, target_comp_id, msg_seq_num);
 
-		// Parse all fields generically using parse_field methods
-		for (&tag, &value) in &field_map {
-			match tag {
-				// Header fields (8, 9, 35, 49, 56, 34, 52, 43, 97, 122)
-				8 | 9 | 35 | 49 | 56 | 34 | 52 | 43 | 97 | 122 => {
-					message.header.parse_field(tag, value).map_err(|e| format!("Header parse error: {}", e))?;
-				},
+		// Header flags are read first so every later error carries a complete header
+		let is_header = |tag: u32| matches!(tag, 8 | 9 | 35 | 49 | 56 | 34 | 52 | 43 | 97 | 122);
+		for &(tag, value) in field_list.iter().filter(|&&(tag, _)| is_header(tag)) {
+			if let Err(e) = message.header.parse_field(tag, value) {
+				let text = format!("Header parse error: {}", e);
+				malformed.get_or_insert((SessionRejectReason::IncorrectDataFormatForValue, Some(tag), text));
+			}
+		}
+		if let Some((reason, ref_tag_id, text)) = malformed {
+			return Err(ParseError::new(reason, ref_tag_id, text).with_header(&message.header));
+		}
+
+		// Parse all other fields generically using parse_field methods, counting the entries of each
+		// repeating group as (NumInGroup tag, delimiter tag, declared count, entries seen)
+		let mut group: Option<(u32, u32, usize, usize)> = None;
+		for &(tag, value) in field_list.iter().filter(|&&(tag, _)| !is_header(tag)) {
+			if value.is_empty() {
+				let text = format!("Tag specified without a value: {}", tag);
+				let reason = SessionRejectReason::TagSpecifiedWithoutAValue;
+				return Err(ParseError::new(reason, Some(tag), text).with_header(&message.header));
+			}
+			let (result, part) = match tag {
 				// Trailer fields (10, 93, 89)
-				10 | 93 | 89 => {
-					message.trailer.parse_field(tag, value).map_err(|e| format!("Trailer parse error: {}", e))?;
-				},
+				10 | 93 | 89 => (message.trailer.parse_field(tag, value), "Trailer"),
 				// Body fields - delegate to message body
-				_ => {
-					message.body.parse_field(tag, value).map_err(|e| format!("Body parse error: {}", e))?;
-				},
+				_ => (message.body.parse_field(tag, value), "Body"),
+			};
+			if let Err(e) = result {
+				let reason = match e {
+					FieldError::Unknown(_) => SessionRejectReason::TagNotDefinedForThisMessageType,
+					FieldError::Invalid(_) => SessionRejectReason::IncorrectDataFormatForValue,
+				};
+				let text = format!("{} parse error: {}", part, e);
+				return Err(ParseError::new(reason, Some(tag), text).with_header(&message.header));
+			}
+			if let Some(delimiter) = message.body.group_delimiter(tag) {
+				check_group_count(group.take()).map_err(|e| e.with_header(&message.header))?;
+				// The body has already parsed the count successfully
+				group = Some((tag, delimiter, value.parse().unwrap_or_default(), 0));
+			} else if let Some((_, delimiter, _, ref mut seen)) = group
+				&& tag == delimiter
+			{
+				*seen += 1;
 			}
 		}
+		check_group_count(group).map_err(|e| e.with_header(&message.header))?;
 
 		// Validate message
-		message.validate().map_err(|e| e.to_string())?;
+		message.validate().map_err(|e| ParseError::from(e).with_header(&message.header))?;
 
 		Ok(message)
 	}
+
+	/// Parse a message that a codec assembled from decoded fields, see [`common::wire_message`]
+	///
+	/// Values may be written out differently from how they were decoded, such as a
+	/// SendingTime without milliseconds, so BodyLength and CheckSum are those of the
+	/// message as [`to_fix_string`](Self::to_fix_string) writes it.
+	#[cfg(any(feature = "json", feature = "fixml"))]
+	pub(crate) fn parse_decoded(wire: &str) -> Result<Self, ParseError> {
+		Ok(FixMessageBuilder::from_message(Self::parse(wire)?).build())
+	}
+}
+
+/// Check that a repeating group has as many entries as its NumInGroup field declared
+fn check_group_count(group: Option<(u32, u32, usize, usize)>) -> Result<(), ParseError> {
+	match group {
+		Some((count_tag, _, declared, seen)) if declared != seen => {
+			let text =
+				format!("Incorrect NumInGroup count for tag {}: declared {}, found {}", count_tag, declared, seen);
+			Err(ParseError::new(SessionRejectReason::ValueIsIncorrect, Some(count_tag), text))
+		},
+		_ => Ok(()),
+	}
 }
 
 impl Default for FixMessage {
@@ -209,7 +315,7 @@ impl FixMessage {
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use crate::common::EncryptMethod;
+	use crate::common::{EncryptMethod, SessionRejectReason};
 
 	#[test]
 	fn test_generic_from_fix_string() {
@@ -309,4 +415,99 @@ mod tests {
 
 		println!("Message-specific tag parsing test passed!");
 	}
+
+	#[test]
+	fn test_parse_errors_describe_rejects() {
+		let order = FixMessage::builder(MsgType::NewOrderSingle, "CLIENT", "BROKER", 7)
+			.cl_ord_id("ORD1")
+			.handl_inst("1")
+			.symbol("AAPL")
+			.side(Side::Buy)
+			.order_qty(100.0)
+			.ord_type("1")
+			.build()
+			.to_fix_string();
+		let parse = |wire: &str| FixMessage::parse(wire).unwrap_err();
+
+		let error = parse(&order.replace("\x0154=1\x01", "\x0154=X\x01"));
+		assert_eq!(error.reason, SessionRejectReason::IncorrectDataFormatForValue);
+		assert_eq!(error.ref_tag_id, Some(54));
+		let header = error.header.unwrap();
+		assert_eq!((header.msg_seq_num, header.msg_type), (7, MsgType::NewOrderSingle));
+
+		let error = parse(&order.replace("\x0154=1\x01", "\x01X4=1\x01"));
+		assert_eq!((error.reason, error.ref_tag_id), (SessionRejectReason::InvalidTagNumber, None));
+
+		let error = parse(&order.replace("\x0154=1\x01", "\x01112=1\x01"));
+		assert_eq!(error.reason, SessionRejectReason::TagNotDefinedForThisMessageType);
+		assert_eq!(error.ref_tag_id, Some(112));
+
+		// Only the tag decides, not the wording of the value error
+		let error = parse(&order.replace("\x0160=", "\x0160=Unknown"));
+		assert_eq!((error.reason, error.ref_tag_id), (SessionRejectReason::IncorrectDataFormatForValue, Some(60)));
+
+		let error = parse(&order.replace("\x0155=AAPL\x01", "\x0155=\x01"));
+		assert_eq!((error.reason, error.ref_tag_id), (SessionRejectReason::TagSpecifiedWithoutAValue, Some(55)));
+
+		let error = parse(&order.replace("\x0138=100\x01", "\x01"));
+		assert_eq!((error.reason, error.ref_tag_id), (SessionRejectReason::RequiredTagMissing, Some(38)));
+		assert!(error.header.is_some());
+
+		// Without a readable MsgSeqNum the message cannot be answered
+		let error = parse(&order.replace("\x0134=7\x01", "\x01"));
+		assert_eq!((error.reason, error.ref_tag_id), (SessionRejectReason::RequiredTagMissing, Some(34)));
+		assert!(error.header.is_none());
+		assert_eq!(
+			FixMessage::from_fix_string(&order.replace("\x0134=7\x01", "\x01")).unwrap_err(),
+			"Missing MsgSeqNum (34)"
+		);
+	}
+
+	#[test]
+	fn test_validation_errors_name_the_rejected_tag() {
+		let missing = |field: &str| ValidationError::MissingRequiredField(field.into()).ref_tag_id();
+		assert_eq!(missing("HandlInst"), Some(21));
+		assert_eq!(missing("OrdType"), Some(40));
+		assert_eq!(missing("OrderQty or CashOrderQty"), Some(38));
+		assert_eq!(ValidationError::ConditionallyRequiredFieldMissing("ExecRefID".into()).ref_tag_id(), Some(19));
+		assert_eq!(missing("NotAField"), None);
+	}
+
+	#[test]
+	fn test_wire_helpers() {
+		let heartbeat = FixMessage::builder(MsgType::Heartbeat, "CLIENT", "SERVER", 1).build().to_fix_string();
+		let fields: Vec<_> = common::wire_fields(&heartbeat).filter(|(tag, _)| ![8, 9, 10].contains(tag)).collect();
+		assert_eq!(fields[0], (35, "0"));
+		assert_eq!(common::wire_message("FIX.4.2", fields), heartbeat);
+		assert_eq!(common::wire_field("X4=1"), None);
+		assert_eq!(common::wire_field("58=a=b"), Some((58, "a=b")));
+	}
+
+	#[test]
+	fn test_repeating_groups_keep_wire_order() {
+		let snapshot = FixMessage::builder(MsgType::MarketDataSnapshotFullRefresh, "BROKER", "CLIENT", 3)
+			.symbol("AAPL")
+			.md_entry(MdEntry::new(MdEntryType::Bid, 150.25).with_size(300.0))
+			.md_entry(MdEntry::new(MdEntryType::Offer, 150.5))
+			.build()
+			.to_fix_string();
+
+		let parsed = FixMessage::parse(&snapshot).unwrap();
+		let FixMessageBody::MarketDataSnapshotFullRefresh(body) = &parsed.body else {
+			panic!("Expected MarketDataSnapshotFullRefresh body");
+		};
+		assert_eq!(body.md_entries[0], MdEntry::new(MdEntryType::Bid, 150.25).with_size(300.0));
+		assert_eq!(body.md_entries[1], MdEntry::new(MdEntryType::Offer, 150.5));
+
+		for declared in ["268=1", "268=3"] {
+			let error = FixMessage::parse(&snapshot.replace("268=2", declared)).unwrap_err();
+			assert_eq!((error.reason, error.ref_tag_id), (SessionRejectReason::ValueIsIncorrect, Some(268)));
+			assert!(error.header.is_some());
+		}
+
+		// A group field before the field starting its entry is malformed
+		let error =
+			FixMessage::parse(&snapshot.replace("\x01269=0\x01270=150.25", "\x01270=150.25\x01269=0")).unwrap_err();
+		assert_eq!((error.reason, error.ref_tag_id), (SessionRejectReason::IncorrectDataFormatForValue, Some(270)));
+	}
 }
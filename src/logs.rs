@@ -0,0 +1,349 @@
+//! Reading FIX log files
+//!
+//! Logs hold FIX frames in many shapes: raw wire format, QuickFIX message logs
+//! with a timestamp prefix (`20240102-03:04:05.123 : 8=FIX.4.2...`), or lines
+//! where SOH was replaced by `|` or `^A`. [`LogReader`] finds every frame of
+//! every line and yields it as a [`LogRecord`] in wire format, together with
+//! the timestamp of its line when there is one.
+//!
+//! [`LogFilter`] selects records by MsgType, CompID, field values and time
+//! range, and [`LogStats`] counts them per MsgType.
+
+use crate::{
+	FixMessage, ParseError, SOH,
+	common::{parse_fix_timestamp, wire_fields},
+};
+use std::{
+	collections::{BTreeMap, VecDeque},
+	io::{self, BufRead},
+};
+use time::OffsetDateTime;
+
+/// Delimiters accepted between the fields of a logged frame
+const DELIMITERS: &[&str] = &[SOH, "^A", "|"];
+
+/// One FIX frame found in a log
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogRecord {
+	/// Line of the log holding the frame, starting at 1
+	pub line: usize,
+	/// Timestamp of the log line, when it has one
+	pub timestamp: Option<OffsetDateTime>,
+	/// Text of the line before the frame
+	pub prefix: String,
+	/// The frame in wire format, fields separated and terminated by SOH
+	pub frame: String,
+}
+
+impl LogRecord {
+	/// Fields of the frame in wire order, skipping malformed ones
+	pub fn fields(&self) -> impl Iterator<Item = (u32, &str)> {
+		wire_fields(&self.frame)
+	}
+
+	/// Value of the first occurrence of `tag`
+	pub fn field(&self, tag: u32) -> Option<&str> {
+		self.fields().find(|&(t, _)| t == tag).map(|(_, value)| value)
+	}
+
+	pub fn msg_type(&self) -> Option<&str> {
+		self.field(35)
+	}
+
+	/// Time of the record: the timestamp of its line, else its SendingTime
+	pub fn time(&self) -> Option<OffsetDateTime> {
+		self.timestamp.or_else(|| parse_fix_timestamp(self.field(52)?).ok())
+	}
+
+	/// Parse the frame with [`FixMessage::parse`]
+	pub fn parse(&self) -> Result<FixMessage, ParseError> {
+		FixMessage::parse(&self.frame)
+	}
+}
+
+/// Find the frames of one log line
+pub fn parse_line(line_number: usize, line: &str) -> Vec<LogRecord> {
+	let mut records = Vec::new();
+	let mut rest = line;
+	let mut prefix_start = 0;
+	while let Some(start) = rest.find("8=FIX") {
+		let Some((frame, length)) = extract_frame(&rest[start..]) else {
+			rest = &rest[start + 1..];
+			continue;
+		};
+		let prefix = line[prefix_start..line.len() - rest.len() + start].trim();
+		let timestamp = find_timestamp(prefix).or_else(|| records.last().and_then(|r: &LogRecord| r.timestamp));
+		records.push(LogRecord { line: line_number, timestamp, prefix: prefix.to_string(), frame });
+		rest = &rest[start + length..];
+		prefix_start = line.len() - rest.len();
+	}
+	records
+}
+
+/// Read the frame at the start of `text`, returning it with SOH delimiters and the length it covered
+///
+/// The frame ends after its CheckSum field, or at the end of `text` when it was truncated.
+fn extract_frame(text: &str) -> Option<(String, usize)> {
+	let begin_string_end = text.find(|c: char| !(c.is_ascii_alphanumeric() || c == '.' || c == '='))?;
+	let delimiter = DELIMITERS.iter().find(|delimiter| text[begin_string_end..].starts_with(**delimiter))?;
+
+	let mut frame = String::with_capacity(text.len());
+	let mut position = 0;
+	while position < text.len() {
+		let end = text[position..].find(delimiter).map_or(text.len(), |end| position + end);
+		let field = &text[position..end];
+		if let Some(checksum) = field.strip_prefix("10=") {
+			// The CheckSum is three digits, whatever follows them belongs to the log line
+			let digits = checksum.bytes().take(3).take_while(u8::is_ascii_digit).count();
+			frame.push_str(&field[..3 + digits]);
+			frame.push_str(SOH);
+			let length = position + 3 + digits;
+			let length =
+				if digits == 3 && text[length..].starts_with(delimiter) { end + delimiter.len() } else { length };
+			return Some((frame, length));
+		}
+		if field.is_empty() || !field.contains('=') {
+			// Trailing text after a truncated frame
+			return Some((frame, position));
+		}
+		frame.push_str(field);
+		frame.push_str(SOH);
+		position = end + delimiter.len();
+	}
+	Some((frame, text.len()))
+}
+
+/// Find a `YYYYMMDD-HH:MM:SS[.sss]` timestamp in the prefix of a log line
+fn find_timestamp(prefix: &str) -> Option<OffsetDateTime> {
+	const PATTERN: &[u8] = b"dddddddd-dd:dd:dd";
+	let bytes = prefix.as_bytes();
+	(0..bytes.len().saturating_sub(PATTERN.len() - 1)).find_map(|start| {
+		let candidate = &bytes[start..start + PATTERN.len()];
+		let matches = candidate
+			.iter()
+			.zip(PATTERN)
+			.all(|(byte, pattern)| if *pattern == b'd' { byte.is_ascii_digit() } else { byte == pattern });
+		if !matches {
+			return None;
+		}
+		let mut end = start + PATTERN.len();
+		if bytes.get(end) == Some(&b'.') {
+			end += 1 + bytes[end + 1..].iter().take_while(|byte| byte.is_ascii_digit()).count();
+		}
+		parse_fix_timestamp(&prefix[start..end]).ok()
+	})
+}
+
+/// Yields the [`LogRecord`]s of a log, line by line
+pub struct LogReader<R> {
+	input: R,
+	line_number: usize,
+	pending: VecDeque<LogRecord>,
+}
+
+impl<R: BufRead> LogReader<R> {
+	pub fn new(input: R) -> Self {
+		Self { input, line_number: 0, pending: VecDeque::new() }
+	}
+}
+
+impl<R: BufRead> Iterator for LogReader<R> {
+	type Item = io::Result<LogRecord>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let mut line = Vec::new();
+		while self.pending.is_empty() {
+			line.clear();
+			match self.input.read_until(b'\n', &mut line) {
+				Ok(0) => return None,
+				Ok(_) => {
+					self.line_number += 1;
+					self.pending.extend(parse_line(self.line_number, &String::from_utf8_lossy(&line)));
+				},
+				Err(e) => return Some(Err(e)),
+			}
+		}
+		self.pending.pop_front().map(Ok)
+	}
+}
+
+/// Selects log records; every criterion set must match
+#[derive(Debug, Clone, Default)]
+pub struct LogFilter {
+	msg_types: Vec<String>,
+	comp_ids: Vec<String>,
+	fields: Vec<(u32, String)>,
+	from: Option<OffsetDateTime>,
+	to: Option<OffsetDateTime>,
+}
+
+impl LogFilter {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Keep records of this MsgType; several calls keep any of them
+	pub fn with_msg_type(mut self, msg_type: impl Into<String>) -> Self {
+		self.msg_types.push(msg_type.into());
+		self
+	}
+
+	/// Keep records sent by or to this CompID; several calls keep any of them
+	pub fn with_comp_id(mut self, comp_id: impl Into<String>) -> Self {
+		self.comp_ids.push(comp_id.into());
+		self
+	}
+
+	/// Keep records where some occurrence of `tag` holds `value`
+	pub fn with_field(mut self, tag: u32, value: impl Into<String>) -> Self {
+		self.fields.push((tag, value.into()));
+		self
+	}
+
+	/// Keep records of `from` or later
+	pub const fn with_from(mut self, from: OffsetDateTime) -> Self {
+		self.from = Some(from);
+		self
+	}
+
+	/// Keep records of `to` or earlier
+	pub const fn with_to(mut self, to: OffsetDateTime) -> Self {
+		self.to = Some(to);
+		self
+	}
+
+	pub fn matches(&self, record: &LogRecord) -> bool {
+		if !self.msg_types.is_empty() && !record.msg_type().is_some_and(|t| self.msg_types.iter().any(|m| m == t)) {
+			return false;
+		}
+		if !self.comp_ids.is_empty() {
+			let comp_ids = [record.field(49), record.field(56)];
+			if !comp_ids.iter().flatten().any(|comp_id| self.comp_ids.iter().any(|c| c == comp_id)) {
+				return false;
+			}
+		}
+		if !self.fields.iter().all(|(tag, value)| record.fields().any(|(t, v)| t == *tag && v == value)) {
+			return false;
+		}
+		if self.from.is_some() || self.to.is_some() {
+			let Some(time) = record.time() else { return false };
+			if self.from.is_some_and(|from| time < from) || self.to.is_some_and(|to| time > to) {
+				return false;
+			}
+		}
+		true
+	}
+}
+
+/// Counts of the records of one MsgType
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MsgTypeStats {
+	pub count: usize,
+	/// Records the parser rejected
+	pub invalid: usize,
+	pub first: Option<OffsetDateTime>,
+	pub last: Option<OffsetDateTime>,
+}
+
+/// Per-MsgType statistics of a log
+#[derive(Debug, Clone, Default)]
+pub struct LogStats {
+	by_msg_type: BTreeMap<String, MsgTypeStats>,
+}
+
+impl LogStats {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn add(&mut self, record: &LogRecord) {
+		let msg_type = record.msg_type().unwrap_or_default().to_string();
+		let stats = self.by_msg_type.entry(msg_type).or_default();
+		stats.count += 1;
+		if record.parse().is_err() {
+			stats.invalid += 1;
+		}
+		if let Some(time) = record.time() {
+			stats.first = Some(stats.first.map_or(time, |first| first.min(time)));
+			stats.last = Some(stats.last.map_or(time, |last| last.max(time)));
+		}
+	}
+
+	/// Statistics by MsgType, in MsgType order; frames without a MsgType count under ""
+	pub fn iter(&self) -> impl Iterator<Item = (&str, &MsgTypeStats)> {
+		self.by_msg_type.iter().map(|(msg_type, stats)| (msg_type.as_str(), stats))
+	}
+
+	pub fn total(&self) -> usize {
+		self.by_msg_type.values().map(|stats| stats.count).sum()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::test_support::order_builder;
+	use time::macros::datetime;
+
+	fn order(seq_num: u32, symbol: &str) -> String {
+		order_builder(seq_num).cl_ord_id(format!("ORD{}", seq_num)).symbol(symbol).build().to_fix_string()
+	}
+
+	#[test]
+	fn test_extract_frames() {
+		let frame = order(1, "AAPL");
+		let log = format!(
+			"{frame}\n20240102-03:04:06.250 : {}\n<20240102-03:04:07, FIX.4.2:CLIENT->SERVER, outgoing> ({})\nno frame here\n{}{} trailing\n",
+			frame,
+			frame.replace(SOH, "|"),
+			frame.replace(SOH, "^A"),
+			frame.trim_end_matches(SOH),
+		);
+		let records: Vec<LogRecord> = LogReader::new(log.as_bytes()).collect::<io::Result<_>>().unwrap();
+		assert_eq!(records.len(), 5);
+		assert!(records.iter().all(|record| record.frame == frame && record.parse().is_ok()));
+		assert_eq!(records.iter().map(|r| r.line).collect::<Vec<_>>(), [1, 2, 3, 5, 5]);
+		assert_eq!(records[0].timestamp, None);
+		assert_eq!(records[0].time(), Some(datetime!(2024-01-02 03:04:05.006 UTC)));
+		assert_eq!(records[1].timestamp, Some(datetime!(2024-01-02 03:04:06.250 UTC)));
+		assert_eq!(records[1].prefix, "20240102-03:04:06.250 :");
+		assert_eq!(records[2].timestamp, Some(datetime!(2024-01-02 03:04:07 UTC)));
+
+		// A truncated frame is kept as far as it goes
+		let truncated = parse_line(1, "8=FIX.4.2|9=5|35=0|");
+		assert_eq!(truncated[0].frame, "8=FIX.4.2\u{1}9=5\u{1}35=0\u{1}");
+		assert!(truncated[0].parse().is_err());
+	}
+
+	#[test]
+	fn test_filter_and_stats() {
+		let log = format!(
+			"20240102-09:00:00 : {}\n20240102-10:00:00 : {}\n20240102-11:00:00 : 8=FIX.4.2|35=0|49=OTHER|56=SERVER|10=000|\n",
+			order(1, "AAPL"),
+			order(2, "MSFT"),
+		);
+		let records: Vec<LogRecord> = LogReader::new(log.as_bytes()).collect::<io::Result<_>>().unwrap();
+		let count = |filter: LogFilter| records.iter().filter(|record| filter.matches(record)).count();
+		assert_eq!(count(LogFilter::new()), 3);
+		assert_eq!(count(LogFilter::new().with_msg_type("D")), 2);
+		assert_eq!(count(LogFilter::new().with_msg_type("D").with_msg_type("0")), 3);
+		assert_eq!(count(LogFilter::new().with_comp_id("OTHER")), 1);
+		assert_eq!(count(LogFilter::new().with_comp_id("SERVER").with_field(55, "MSFT")), 1);
+		assert_eq!(count(LogFilter::new().with_from(datetime!(2024-01-02 10:00 UTC))), 2);
+		assert_eq!(
+			count(LogFilter::new().with_from(datetime!(2024-01-02 09:30 UTC)).with_to(datetime!(2024-01-02 10:30 UTC))),
+			1
+		);
+
+		let mut stats = LogStats::new();
+		records.iter().for_each(|record| stats.add(record));
+		assert_eq!(stats.total(), 3);
+		let by_type: Vec<_> = stats.iter().collect();
+		assert_eq!(by_type[0].0, "0");
+		assert_eq!((by_type[0].1.count, by_type[0].1.invalid), (1, 1));
+		assert_eq!(by_type[1].0, "D");
+		assert_eq!((by_type[1].1.count, by_type[1].1.invalid), (2, 0));
+		assert_eq!(by_type[1].1.first, Some(datetime!(2024-01-02 09:00 UTC)));
+		assert_eq!(by_type[1].1.last, Some(datetime!(2024-01-02 10:00 UTC)));
+	}
+}
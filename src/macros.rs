@@ -26,6 +26,9 @@
 ///
 /// assert!("1".parse::<Side>().is_ok());
 /// assert!("X".parse::<Side>().is_err());
+/// assert_eq!(Side::Sell.name(), "Sell");
+/// assert_eq!(Side::from_name("Buy"), Some(Side::Buy));
+/// assert_eq!(Side::VALUES, &[("1", "Buy"), ("2", "Sell")]);
 /// ```
 ///
 /// Loose mode (store unknown values in `Other(String)`):
@@ -66,6 +69,36 @@ macro_rules! fix_enum {
                 }
             }
         }
+
+        impl $name {
+            /// Every FIX code of the enum with the name of its variant
+            pub const VALUES: &'static [(&'static str, &'static str)] = &[$( ($code, stringify!($variant)), )*];
+
+            /// Name of the variant, e.g. `Buy` for `Side::Buy`
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $( Self::$variant => stringify!($variant), )*
+                }
+            }
+
+            /// Variant called `name`, the inverse of [`Self::name`]
+            pub fn from_name(name: &str) -> Option<Self> {
+                match name {
+                    $( stringify!($variant) => Some(Self::$variant), )*
+                    _ => None,
+                }
+            }
+        }
+
+        impl $crate::macros::FixEnum for $name {
+            fn name(&self) -> &'static str {
+                $name::name(self)
+            }
+
+            fn from_name(name: &str) -> Option<Self> {
+                $name::from_name(name)
+            }
+        }
     };
 
     // Loose mode: unknown values stored in Other(String)
@@ -94,7 +127,50 @@ macro_rules! fix_enum {
                 }
             }
         }
+
+        impl $name {
+            /// Every FIX code of the enum with the name of its variant
+            pub const VALUES: &'static [(&'static str, &'static str)] = &[$( ($code, stringify!($variant)), )*];
+
+            /// Name of the variant, `Other` for codes outside of [`Self::VALUES`]
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $( Self::$variant => stringify!($variant), )*
+                    Self::Other(_) => "Other",
+                }
+            }
+
+            /// Variant called `name`, the inverse of [`Self::name`] for the codes of [`Self::VALUES`]
+            pub fn from_name(name: &str) -> Option<Self> {
+                match name {
+                    $( stringify!($variant) => Some(Self::$variant), )*
+                    _ => None,
+                }
+            }
+        }
+
+        impl $crate::macros::FixEnum for $name {
+            fn name(&self) -> &'static str {
+                $name::name(self)
+            }
+
+            fn from_name(name: &str) -> Option<Self> {
+                $name::from_name(name)
+            }
+        }
     };
 }
 
 pub use fix_enum;
+
+/// Conversions shared by every enum defined with [`fix_enum!`]
+///
+/// The FIX code of a value is its `Display` and `FromStr` form; its name is
+/// the name of its variant.
+pub trait FixEnum: Sized + core::fmt::Display + core::str::FromStr {
+	/// Name of the variant, e.g. `Buy` for `Side::Buy`
+	fn name(&self) -> &'static str;
+
+	/// Variant called `name`
+	fn from_name(name: &str) -> Option<Self>;
+}
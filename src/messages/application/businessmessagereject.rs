@@ -0,0 +1,173 @@
+//! Business Message Reject implementation (MsgType=j)
+//!
+//! This module implements the FIX 4.2 Business Message Reject message, which
+//! rejects an application message that passed session-level validation but
+//! cannot be processed by the application, e.g. an unsupported message type.
+
+use crate::{
+	FixMessage,
+	common::{
+		BusinessRejectReason, MsgType, SOH, Validate, ValidationError,
+		validation::{FieldError, FixFieldHandler, WriteTo},
+	},
+	messages::FixMessageBody,
+};
+use std::fmt::Write;
+
+/// Business Message Reject body (Tag 35=j)
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BusinessMessageRejectBody {
+	/// MsgSeqNum of the rejected message (Tag 45) - Optional
+	pub ref_seq_num: Option<u32>,
+	/// MsgType of the rejected message (Tag 372) - Required
+	pub ref_msg_type: MsgType,
+	/// Business-level ID of the rejected message, e.g. its ClOrdID (Tag 379) - Optional
+	pub business_reject_ref_id: Option<String>,
+	/// Code identifying the reject reason (Tag 380) - Required
+	pub business_reject_reason: BusinessRejectReason,
+	/// Free format explanation of the reject (Tag 58) - Optional
+	pub text: Option<String>,
+}
+
+impl Default for BusinessMessageRejectBody {
+	fn default() -> Self {
+		Self {
+			ref_seq_num: None,
+			ref_msg_type: MsgType::Other(String::new()),
+			business_reject_ref_id: None,
+			business_reject_reason: BusinessRejectReason::Other,
+			text: None,
+		}
+	}
+}
+
+impl Validate for BusinessMessageRejectBody {
+	fn validate(&self) -> Result<(), ValidationError> {
+		if matches!(&self.ref_msg_type, MsgType::Other(msg_type) if msg_type.is_empty()) {
+			return Err(ValidationError::MissingRequiredField("RefMsgType".to_string()));
+		}
+		Ok(())
+	}
+}
+
+impl WriteTo for BusinessMessageRejectBody {
+	fn write_to(&self, buffer: &mut String) {
+		if let Some(seq_num) = self.ref_seq_num {
+			write!(buffer, "45={}{}", seq_num, SOH).unwrap();
+		}
+		write!(buffer, "372={}{}", self.ref_msg_type, SOH).unwrap();
+		if let Some(ref ref_id) = self.business_reject_ref_id {
+			write!(buffer, "379={}{}", ref_id, SOH).unwrap();
+		}
+		write!(buffer, "380={}{}", self.business_reject_reason, SOH).unwrap();
+		if let Some(ref text) = self.text {
+			write!(buffer, "58={}{}", text, SOH).unwrap();
+		}
+	}
+}
+
+impl BusinessMessageRejectBody {
+	/// Create a reject for a message of type `ref_msg_type`
+	pub fn new(ref_msg_type: MsgType, business_reject_reason: BusinessRejectReason) -> Self {
+		Self { ref_msg_type, business_reject_reason, ..Default::default() }
+	}
+
+	/// Create the reject answering an inbound application message
+	///
+	/// RefSeqNum and RefMsgType come from the message's header, and
+	/// BusinessRejectRefID from its business-level ID (ClOrdID of orders and
+	/// cancel requests, ExecID of execution reports) when it has one.
+	pub fn for_message(message: &FixMessage, business_reject_reason: BusinessRejectReason) -> Self {
+		Self {
+			ref_seq_num: Some(message.header.msg_seq_num),
+			business_reject_ref_id: business_ref_id(&message.body).map(str::to_string),
+			..Self::new(message.header.msg_type.clone(), business_reject_reason)
+		}
+	}
+
+	/// Set the explanatory text
+	pub fn with_text(mut self, text: impl Into<String>) -> Self {
+		self.text = Some(text.into());
+		self
+	}
+}
+
+/// Business-level ID a reject of `body` refers to
+fn business_ref_id(body: &FixMessageBody) -> Option<&str> {
+	let id = match body {
+		FixMessageBody::NewOrderSingle(body) => &body.cl_ord_id,
+		FixMessageBody::OrderCancelRequest(body) => &body.cl_ord_id,
+		FixMessageBody::OrderCancelReplaceRequest(body) => &body.cl_ord_id,
+		FixMessageBody::OrderCancelReject(body) => &body.cl_ord_id,
+		FixMessageBody::OrderStatusRequest(body) => &body.cl_ord_id,
+		FixMessageBody::DontKnowTrade(body) => &body.exec_id,
+		FixMessageBody::MarketDataRequest(body) => &body.md_req_id,
+		FixMessageBody::ExecutionReport(body) => &body.exec_id,
+		_ => return None,
+	};
+	Some(id.as_str()).filter(|id| !id.is_empty())
+}
+
+impl FixFieldHandler for BusinessMessageRejectBody {
+	fn parse_field(&mut self, tag: u32, value: &str) -> Result<(), FieldError> {
+		match tag {
+			45 => self.ref_seq_num = Some(value.parse().map_err(|_| "Invalid RefSeqNum")?),
+			372 => self.ref_msg_type = value.parse().map_err(|_| "Invalid RefMsgType")?,
+			379 => self.business_reject_ref_id = Some(value.to_string()),
+			380 => self.business_reject_reason = value.parse().map_err(|_| "Invalid BusinessRejectReason")?,
+			58 => self.text = Some(value.to_string()),
+			_ => return Err(FieldError::Unknown(format!("Unknown business message reject field: {}", tag))),
+		}
+		Ok(())
+	}
+
+	fn write_body_fields(&self, buffer: &mut String) {
+		self.write_to(buffer);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_business_message_reject_parse_and_write() {
+		let mut body = BusinessMessageRejectBody::default();
+		assert!(!body.is_valid());
+
+		assert!(body.parse_field(45, "7").is_ok());
+		assert!(body.parse_field(372, "D").is_ok());
+		assert!(body.parse_field(379, "ORD1").is_ok());
+		assert!(body.parse_field(380, "3").is_ok());
+		assert!(body.parse_field(58, "Unknown account").is_ok());
+		assert!(body.is_valid());
+		assert_eq!(body.ref_msg_type, MsgType::NewOrderSingle);
+		assert_eq!(body.business_reject_reason, BusinessRejectReason::UnsupportedMessageType);
+		assert!(body.parse_field(380, "9").is_err());
+		assert!(body.parse_field(999, "x").is_err());
+
+		let mut s = String::new();
+		body.write_to(&mut s);
+		assert_eq!(s, "45=7\x01372=D\x01379=ORD1\x01380=3\x0158=Unknown account\x01");
+	}
+
+	#[test]
+	fn test_reject_for_inbound_message() {
+		let order = FixMessage::builder(MsgType::NewOrderSingle, "CLIENT", "BROKER", 12).cl_ord_id("ORD1").build();
+		let body = BusinessMessageRejectBody::for_message(&order, BusinessRejectReason::UnknownSecurity)
+			.with_text("Unknown symbol");
+		assert_eq!(body.ref_seq_num, Some(12));
+		assert_eq!(body.ref_msg_type, MsgType::NewOrderSingle);
+		assert_eq!(body.business_reject_ref_id.as_deref(), Some("ORD1"));
+		assert_eq!(body.business_reject_reason, BusinessRejectReason::UnknownSecurity);
+		assert!(body.is_valid());
+
+		let wire =
+			"8=FIX.4.2\x019=45\x0135=ZZ\x0149=CLIENT\x0156=BROKER\x0134=13\x0152=20240301-09:00:00\x0110=000\x01";
+		let other = FixMessage::from_fix_string(wire).unwrap();
+		let body = BusinessMessageRejectBody::for_message(&other, BusinessRejectReason::UnsupportedMessageType);
+		assert_eq!(body.ref_msg_type, MsgType::Other("ZZ".to_string()));
+		assert_eq!(body.business_reject_ref_id, None);
+	}
+}
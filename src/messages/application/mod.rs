@@ -0,0 +1,4 @@
+pub mod businessmessagereject;
+
+// Re-export message body types for convenience
+pub use businessmessagereject::BusinessMessageRejectBody;
@@ -0,0 +1,208 @@
+//! Market Data - Incremental Refresh message implementation (MsgType=X)
+//!
+//! FIX 4.2 Market Data Incremental Refresh, carrying changes to the books of one
+//! or more instruments. Each entry of the NoMDEntries(268) group adds, changes
+//! or deletes a single book entry as told by its MDUpdateAction(279).
+
+use crate::{
+	SOH,
+	common::{
+		MdEntryType, MdUpdateAction, Validate, ValidationError,
+		validation::{FieldError, FixFieldHandler, WriteTo},
+	},
+	messages::marketdata::outside_group,
+};
+use std::fmt::Write;
+
+/// Entry of the NoMDEntries (Tag 268) group of an incremental refresh
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MdIncrementalEntry {
+	/// (Tag 279) - Required, starts the group entry
+	pub md_update_action: MdUpdateAction,
+	/// (Tag 269) - Required for new entries
+	pub md_entry_type: Option<MdEntryType>,
+	/// Identifier of the book entry (Tag 278) - Optional
+	pub md_entry_id: Option<String>,
+	/// MDEntryID of the entry being changed or deleted (Tag 280) - Optional
+	pub md_entry_ref_id: Option<String>,
+	// (Tag 55) - Optional, defaults to the instrument of the previous entry
+	pub symbol: Option<String>,
+	// (Tag 270) - Optional
+	pub md_entry_px: Option<f64>,
+	// (Tag 271) - Optional
+	pub md_entry_size: Option<f64>,
+	/// Position of the entry in the book, 1 being the best (Tag 290) - Optional
+	pub md_entry_position_no: Option<u32>,
+}
+
+impl MdIncrementalEntry {
+	pub const fn new(md_update_action: MdUpdateAction) -> Self {
+		Self {
+			md_update_action,
+			md_entry_type: None,
+			md_entry_id: None,
+			md_entry_ref_id: None,
+			symbol: None,
+			md_entry_px: None,
+			md_entry_size: None,
+			md_entry_position_no: None,
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MarketDataIncrementalRefreshBody {
+	/// MDReqID of the subscription being refreshed (Tag 262) - Required when answering a request
+	pub md_req_id: Option<String>,
+	/// The NoMDEntries (Tag 268) group - At least one required
+	pub md_entries: Vec<MdIncrementalEntry>,
+}
+
+impl Validate for MarketDataIncrementalRefreshBody {
+	fn validate(&self) -> Result<(), ValidationError> {
+		if self.md_entries.is_empty() {
+			return Err(ValidationError::MissingRequiredField("NoMDEntries".into()));
+		}
+		let untyped_new =
+			|entry: &MdIncrementalEntry| entry.md_update_action == MdUpdateAction::New && entry.md_entry_type.is_none();
+		if self.md_entries.iter().any(untyped_new) {
+			return Err(ValidationError::ConditionallyRequiredFieldMissing("MDEntryType".into()));
+		}
+		Ok(())
+	}
+}
+
+impl WriteTo for MarketDataIncrementalRefreshBody {
+	fn write_to(&self, buffer: &mut String) {
+		if let Some(ref md_req_id) = self.md_req_id {
+			write!(buffer, "262={}{}", md_req_id, SOH).unwrap();
+		}
+		write!(buffer, "268={}{}", self.md_entries.len(), SOH).unwrap();
+		for entry in &self.md_entries {
+			write!(buffer, "279={}{}", entry.md_update_action, SOH).unwrap();
+			if let Some(ref md_entry_type) = entry.md_entry_type {
+				write!(buffer, "269={}{}", md_entry_type, SOH).unwrap();
+			}
+			if let Some(ref md_entry_id) = entry.md_entry_id {
+				write!(buffer, "278={}{}", md_entry_id, SOH).unwrap();
+			}
+			if let Some(ref md_entry_ref_id) = entry.md_entry_ref_id {
+				write!(buffer, "280={}{}", md_entry_ref_id, SOH).unwrap();
+			}
+			if let Some(ref symbol) = entry.symbol {
+				write!(buffer, "55={}{}", symbol, SOH).unwrap();
+			}
+			if let Some(md_entry_px) = entry.md_entry_px {
+				write!(buffer, "270={}{}", md_entry_px, SOH).unwrap();
+			}
+			if let Some(md_entry_size) = entry.md_entry_size {
+				write!(buffer, "271={}{}", md_entry_size, SOH).unwrap();
+			}
+			if let Some(md_entry_position_no) = entry.md_entry_position_no {
+				write!(buffer, "290={}{}", md_entry_position_no, SOH).unwrap();
+			}
+		}
+	}
+}
+
+impl MarketDataIncrementalRefreshBody {
+	fn current_entry(&mut self, name: &str, tag: u32) -> Result<&mut MdIncrementalEntry, String> {
+		self.md_entries.last_mut().ok_or_else(|| outside_group(name, tag, "NoMDEntries"))
+	}
+}
+
+impl FixFieldHandler for MarketDataIncrementalRefreshBody {
+	fn parse_field(&mut self, tag: u32, value: &str) -> Result<(), FieldError> {
+		match tag {
+			262 => self.md_req_id = Some(value.to_string()),
+			// Group entries are collected as their fields arrive, the declared count is checked by the parser
+			268 => {
+				value.parse::<usize>().map_err(|_| "Invalid NoMDEntries")?;
+			},
+			279 => {
+				let action = value.parse().map_err(|_| "Invalid MDUpdateAction")?;
+				self.md_entries.push(MdIncrementalEntry::new(action));
+			},
+			269 => {
+				let md_entry_type = value.parse().map_err(|_| "Invalid MDEntryType")?;
+				self.current_entry("MDEntryType", tag)?.md_entry_type = Some(md_entry_type);
+			},
+			278 => self.current_entry("MDEntryID", tag)?.md_entry_id = Some(value.to_string()),
+			280 => self.current_entry("MDEntryRefID", tag)?.md_entry_ref_id = Some(value.to_string()),
+			55 => self.current_entry("Symbol", tag)?.symbol = Some(value.to_string()),
+			270 => {
+				let px = value.parse().map_err(|_| "Invalid MDEntryPx")?;
+				self.current_entry("MDEntryPx", tag)?.md_entry_px = Some(px);
+			},
+			271 => {
+				let size = value.parse().map_err(|_| "Invalid MDEntrySize")?;
+				self.current_entry("MDEntrySize", tag)?.md_entry_size = Some(size);
+			},
+			290 => {
+				let position = value.parse().map_err(|_| "Invalid MDEntryPositionNo")?;
+				self.current_entry("MDEntryPositionNo", tag)?.md_entry_position_no = Some(position);
+			},
+			_ => return Err(FieldError::Unknown(format!("Unknown market data incremental refresh field: {}", tag))),
+		}
+		Ok(())
+	}
+
+	fn write_body_fields(&self, buffer: &mut String) {
+		self.write_to(buffer);
+	}
+
+	fn group_delimiter(&self, count_tag: u32) -> Option<u32> {
+		(count_tag == 268).then_some(279)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_validation() {
+		let mut body = MarketDataIncrementalRefreshBody::default();
+		assert_eq!(body.validate(), Err(ValidationError::MissingRequiredField("NoMDEntries".into())));
+		body.md_entries.push(MdIncrementalEntry::new(MdUpdateAction::New));
+		assert!(matches!(body.validate(), Err(ValidationError::ConditionallyRequiredFieldMissing(_))));
+		body.md_entries[0].md_entry_type = Some(MdEntryType::Bid);
+		assert!(body.validate().is_ok());
+	}
+
+	#[test]
+	fn test_parse_and_write() {
+		let mut body = MarketDataIncrementalRefreshBody::default();
+		for (tag, value) in [
+			(262, "MD1"),
+			(268, "2"),
+			(279, "0"),
+			(269, "0"),
+			(278, "B1"),
+			(55, "AAPL"),
+			(270, "150.25"),
+			(271, "300"),
+			(279, "2"),
+			(269, "1"),
+			(278, "A7"),
+		] {
+			body.parse_field(tag, value).unwrap();
+		}
+		assert!(body.validate().is_ok());
+		assert_eq!(body.md_entries[0].symbol.as_deref(), Some("AAPL"));
+		assert_eq!(body.md_entries[1].md_update_action, MdUpdateAction::Delete);
+		assert_eq!(body.md_entries[1].md_entry_id.as_deref(), Some("A7"));
+		assert!(body.parse_field(279, "3").is_err());
+		assert!(body.parse_field(999, "x").is_err());
+		assert!(MarketDataIncrementalRefreshBody::default().parse_field(269, "0").is_err());
+
+		let mut s = String::new();
+		body.write_to(&mut s);
+		assert_eq!(
+			s,
+			"262=MD1\x01268=2\x01279=0\x01269=0\x01278=B1\x0155=AAPL\x01270=150.25\x01271=300\x01279=2\x01269=1\x01278=A7\x01"
+		);
+	}
+}
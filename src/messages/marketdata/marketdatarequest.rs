@@ -0,0 +1,212 @@
+//! Market Data Request message implementation (MsgType=V)
+//!
+//! FIX 4.2 Market Data Request, used to ask for a snapshot of, or a subscription
+//! to, the market data of one or more instruments. The entry types wanted are
+//! listed in the NoMDEntryTypes(267) group and the instruments in the
+//! NoRelatedSym(146) group.
+
+use crate::{
+	SOH,
+	common::{
+		MdEntryType, MdUpdateType, SubscriptionRequestType, Validate, ValidationError,
+		validation::{FieldError, FixFieldHandler, WriteTo},
+	},
+	messages::marketdata::outside_group,
+};
+use std::fmt::Write;
+
+/// Instrument entry of the NoRelatedSym (Tag 146) group
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RelatedSym {
+	/// (Tag 55) - Required, starts the group entry
+	pub symbol: String,
+	/// (Tag 207) - Optional
+	pub security_exchange: Option<String>,
+}
+
+impl RelatedSym {
+	pub fn new(symbol: impl Into<String>) -> Self {
+		Self { symbol: symbol.into(), security_exchange: None }
+	}
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MarketDataRequestBody {
+	/// Unique identifier of the request (Tag 262) - Required
+	pub md_req_id: String,
+	// (Tag 263) - Required
+	pub subscription_request_type: SubscriptionRequestType,
+	/// Depth of book wanted, 0 for the full book and 1 for top of book (Tag 264) - Required
+	pub market_depth: u32,
+	/// (Tag 265) - Required when subscribing to updates
+	pub md_update_type: Option<MdUpdateType>,
+	// (Tag 266) - Optional
+	pub aggregated_book: Option<bool>,
+	/// Entry types wanted, the NoMDEntryTypes (Tag 267) group - At least one required
+	pub md_entry_types: Vec<MdEntryType>,
+	/// Instruments wanted, the NoRelatedSym (Tag 146) group - At least one required
+	pub related_syms: Vec<RelatedSym>,
+}
+
+impl Default for MarketDataRequestBody {
+	fn default() -> Self {
+		Self {
+			md_req_id: String::new(),
+			subscription_request_type: SubscriptionRequestType::Snapshot,
+			market_depth: 0,
+			md_update_type: None,
+			aggregated_book: None,
+			md_entry_types: Vec::new(),
+			related_syms: Vec::new(),
+		}
+	}
+}
+
+impl Validate for MarketDataRequestBody {
+	fn validate(&self) -> Result<(), ValidationError> {
+		if self.md_req_id.is_empty() {
+			return Err(ValidationError::MissingRequiredField("MDReqID".into()));
+		}
+		if self.md_entry_types.is_empty() {
+			return Err(ValidationError::MissingRequiredField("NoMDEntryTypes".into()));
+		}
+		if self.related_syms.is_empty() {
+			return Err(ValidationError::MissingRequiredField("NoRelatedSym".into()));
+		}
+		if self.related_syms.iter().any(|sym| sym.symbol.is_empty()) {
+			return Err(ValidationError::MissingRequiredField("Symbol".into()));
+		}
+		if self.subscription_request_type == SubscriptionRequestType::SnapshotPlusUpdates
+			&& self.md_update_type.is_none()
+		{
+			return Err(ValidationError::ConditionallyRequiredFieldMissing("MDUpdateType".into()));
+		}
+		Ok(())
+	}
+}
+
+impl WriteTo for MarketDataRequestBody {
+	fn write_to(&self, buffer: &mut String) {
+		write!(buffer, "262={}{}", self.md_req_id, SOH).unwrap();
+		write!(buffer, "263={}{}", self.subscription_request_type, SOH).unwrap();
+		write!(buffer, "264={}{}", self.market_depth, SOH).unwrap();
+		if let Some(ref md_update_type) = self.md_update_type {
+			write!(buffer, "265={}{}", md_update_type, SOH).unwrap();
+		}
+		if let Some(flag) = self.aggregated_book {
+			write!(buffer, "266={}{}", if flag { "Y" } else { "N" }, SOH).unwrap();
+		}
+		write!(buffer, "267={}{}", self.md_entry_types.len(), SOH).unwrap();
+		for md_entry_type in &self.md_entry_types {
+			write!(buffer, "269={}{}", md_entry_type, SOH).unwrap();
+		}
+		write!(buffer, "146={}{}", self.related_syms.len(), SOH).unwrap();
+		for sym in &self.related_syms {
+			write!(buffer, "55={}{}", sym.symbol, SOH).unwrap();
+			if let Some(ref security_exchange) = sym.security_exchange {
+				write!(buffer, "207={}{}", security_exchange, SOH).unwrap();
+			}
+		}
+	}
+}
+
+impl FixFieldHandler for MarketDataRequestBody {
+	fn parse_field(&mut self, tag: u32, value: &str) -> Result<(), FieldError> {
+		match tag {
+			262 => self.md_req_id = value.to_string(),
+			263 => self.subscription_request_type = value.parse().map_err(|_| "Invalid SubscriptionRequestType")?,
+			264 => self.market_depth = value.parse().map_err(|_| "Invalid MarketDepth")?,
+			265 => self.md_update_type = Some(value.parse().map_err(|_| "Invalid MDUpdateType")?),
+			266 => self.aggregated_book = Some(value == "Y"),
+			// Group entries are collected as their fields arrive, the declared counts are checked by the parser
+			267 => {
+				value.parse::<usize>().map_err(|_| "Invalid NoMDEntryTypes")?;
+			},
+			269 => self.md_entry_types.push(value.parse().map_err(|_| "Invalid MDEntryType")?),
+			146 => {
+				value.parse::<usize>().map_err(|_| "Invalid NoRelatedSym")?;
+			},
+			55 => self.related_syms.push(RelatedSym::new(value)),
+			207 => {
+				let sym = self
+					.related_syms
+					.last_mut()
+					.ok_or_else(|| outside_group("SecurityExchange", tag, "NoRelatedSym"))?;
+				sym.security_exchange = Some(value.to_string());
+			},
+			_ => return Err(FieldError::Unknown(format!("Unknown market data request field: {}", tag))),
+		}
+		Ok(())
+	}
+
+	fn write_body_fields(&self, buffer: &mut String) {
+		self.write_to(buffer);
+	}
+
+	fn group_delimiter(&self, count_tag: u32) -> Option<u32> {
+		match count_tag {
+			267 => Some(269),
+			146 => Some(55),
+			_ => None,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_validation() {
+		assert!(MarketDataRequestBody::default().validate().is_err());
+
+		let body = MarketDataRequestBody {
+			md_req_id: "MD1".into(),
+			subscription_request_type: SubscriptionRequestType::SnapshotPlusUpdates,
+			md_entry_types: vec![MdEntryType::Bid, MdEntryType::Offer],
+			related_syms: vec![RelatedSym::new("AAPL")],
+			..Default::default()
+		};
+		assert!(matches!(body.validate(), Err(ValidationError::ConditionallyRequiredFieldMissing(_))));
+		let body = MarketDataRequestBody { md_update_type: Some(MdUpdateType::IncrementalRefresh), ..body };
+		assert!(body.validate().is_ok());
+		let body = MarketDataRequestBody { related_syms: Vec::new(), ..body };
+		assert_eq!(body.validate(), Err(ValidationError::MissingRequiredField("NoRelatedSym".into())));
+	}
+
+	#[test]
+	fn test_parse_and_write() {
+		let mut body = MarketDataRequestBody::default();
+		for (tag, value) in [
+			(262, "MD1"),
+			(263, "1"),
+			(264, "1"),
+			(265, "1"),
+			(267, "2"),
+			(269, "0"),
+			(269, "1"),
+			(146, "2"),
+			(55, "AAPL"),
+			(207, "XNAS"),
+			(55, "MSFT"),
+		] {
+			body.parse_field(tag, value).unwrap();
+		}
+		assert!(body.validate().is_ok());
+		assert_eq!(body.md_entry_types, vec![MdEntryType::Bid, MdEntryType::Offer]);
+		assert_eq!(body.related_syms[0].security_exchange.as_deref(), Some("XNAS"));
+		assert_eq!(body.related_syms[1], RelatedSym::new("MSFT"));
+		assert!(body.parse_field(269, "Z").is_err());
+		assert!(body.parse_field(999, "x").is_err());
+		assert!(MarketDataRequestBody::default().parse_field(207, "XNAS").is_err());
+
+		let mut s = String::new();
+		body.write_to(&mut s);
+		assert_eq!(
+			s,
+			"262=MD1\x01263=1\x01264=1\x01265=1\x01267=2\x01269=0\x01269=1\x01146=2\x0155=AAPL\x01207=XNAS\x0155=MSFT\x01"
+		);
+	}
+}
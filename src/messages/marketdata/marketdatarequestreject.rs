@@ -0,0 +1,91 @@
+//! Market Data Request Reject message implementation (MsgType=Y)
+//!
+//! FIX 4.2 Market Data Request Reject, sent when a Market Data Request cannot
+//! be honoured, e.g. for an unknown symbol or an unsupported market depth.
+
+use crate::{
+	SOH,
+	common::{
+		MdReqRejReason, Validate, ValidationError,
+		validation::{FieldError, FixFieldHandler, WriteTo},
+	},
+};
+use std::fmt::Write;
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MarketDataRequestRejectBody {
+	/// MDReqID of the rejected request (Tag 262) - Required
+	pub md_req_id: String,
+	/// Code identifying the reject reason (Tag 281) - Optional
+	pub md_req_rej_reason: Option<MdReqRejReason>,
+	/// Free format explanation of the reject (Tag 58) - Optional
+	pub text: Option<String>,
+}
+
+impl Validate for MarketDataRequestRejectBody {
+	fn validate(&self) -> Result<(), ValidationError> {
+		if self.md_req_id.is_empty() {
+			return Err(ValidationError::MissingRequiredField("MDReqID".into()));
+		}
+		Ok(())
+	}
+}
+
+impl WriteTo for MarketDataRequestRejectBody {
+	fn write_to(&self, buffer: &mut String) {
+		write!(buffer, "262={}{}", self.md_req_id, SOH).unwrap();
+		if let Some(ref reason) = self.md_req_rej_reason {
+			write!(buffer, "281={}{}", reason, SOH).unwrap();
+		}
+		if let Some(ref text) = self.text {
+			write!(buffer, "58={}{}", text, SOH).unwrap();
+		}
+	}
+}
+
+impl MarketDataRequestRejectBody {
+	/// Create a reject of the request `md_req_id` for `reason`
+	pub fn new(md_req_id: impl Into<String>, reason: MdReqRejReason) -> Self {
+		Self { md_req_id: md_req_id.into(), md_req_rej_reason: Some(reason), text: None }
+	}
+}
+
+impl FixFieldHandler for MarketDataRequestRejectBody {
+	fn parse_field(&mut self, tag: u32, value: &str) -> Result<(), FieldError> {
+		match tag {
+			262 => self.md_req_id = value.to_string(),
+			281 => self.md_req_rej_reason = Some(value.parse().map_err(|_| "Invalid MDReqRejReason")?),
+			58 => self.text = Some(value.to_string()),
+			_ => return Err(FieldError::Unknown(format!("Unknown market data request reject field: {}", tag))),
+		}
+		Ok(())
+	}
+
+	fn write_body_fields(&self, buffer: &mut String) {
+		self.write_to(buffer);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_parse_and_write() {
+		let mut body = MarketDataRequestRejectBody::default();
+		assert!(body.validate().is_err());
+
+		for (tag, value) in [(262, "MD1"), (281, "0"), (58, "Unknown symbol")] {
+			body.parse_field(tag, value).unwrap();
+		}
+		assert!(body.validate().is_ok());
+		assert_eq!(body.md_req_rej_reason, Some(MdReqRejReason::UnknownSymbol));
+		assert!(body.parse_field(281, "9").is_err());
+		assert!(body.parse_field(999, "x").is_err());
+
+		let mut s = String::new();
+		body.write_to(&mut s);
+		assert_eq!(s, "262=MD1\x01281=0\x0158=Unknown symbol\x01");
+	}
+}
@@ -0,0 +1,200 @@
+//! Market Data - Snapshot/Full Refresh message implementation (MsgType=W)
+//!
+//! FIX 4.2 Market Data Snapshot/Full Refresh, carrying the complete state of the
+//! book of a single instrument: every bid, offer, trade and so on is one entry
+//! of the NoMDEntries(268) group.
+
+use crate::{
+	SOH,
+	common::{
+		MdEntryType, Validate, ValidationError,
+		validation::{FieldError, FixFieldHandler, WriteTo},
+	},
+	messages::marketdata::outside_group,
+};
+use std::fmt::Write;
+
+/// Entry of the NoMDEntries (Tag 268) group of a full refresh
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MdEntry {
+	/// (Tag 269) - Required, starts the group entry
+	pub md_entry_type: MdEntryType,
+	/// (Tag 270) - Required
+	pub md_entry_px: Option<f64>,
+	/// (Tag 271) - Optional
+	pub md_entry_size: Option<f64>,
+	/// Position of the entry in the book, 1 being the best (Tag 290) - Optional
+	pub md_entry_position_no: Option<u32>,
+	/// (Tag 346) - Optional
+	pub number_of_orders: Option<u32>,
+}
+
+impl MdEntry {
+	pub const fn new(md_entry_type: MdEntryType, md_entry_px: f64) -> Self {
+		Self {
+			md_entry_type,
+			md_entry_px: Some(md_entry_px),
+			md_entry_size: None,
+			md_entry_position_no: None,
+			number_of_orders: None,
+		}
+	}
+
+	pub const fn with_size(mut self, md_entry_size: f64) -> Self {
+		self.md_entry_size = Some(md_entry_size);
+		self
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MarketDataSnapshotFullRefreshBody {
+	/// MDReqID of the request being answered (Tag 262) - Required when answering a request
+	pub md_req_id: Option<String>,
+	// (Tag 55) - Required
+	pub symbol: String,
+	// (Tag 207) - Optional
+	pub security_exchange: Option<String>,
+	/// The NoMDEntries (Tag 268) group, empty for an empty book
+	pub md_entries: Vec<MdEntry>,
+}
+
+impl Validate for MarketDataSnapshotFullRefreshBody {
+	fn validate(&self) -> Result<(), ValidationError> {
+		if self.symbol.is_empty() {
+			return Err(ValidationError::MissingRequiredField("Symbol".into()));
+		}
+		if self.md_entries.iter().any(|entry| entry.md_entry_px.is_none()) {
+			return Err(ValidationError::MissingRequiredField("MDEntryPx".into()));
+		}
+		Ok(())
+	}
+}
+
+impl WriteTo for MarketDataSnapshotFullRefreshBody {
+	fn write_to(&self, buffer: &mut String) {
+		if let Some(ref md_req_id) = self.md_req_id {
+			write!(buffer, "262={}{}", md_req_id, SOH).unwrap();
+		}
+		write!(buffer, "55={}{}", self.symbol, SOH).unwrap();
+		if let Some(ref security_exchange) = self.security_exchange {
+			write!(buffer, "207={}{}", security_exchange, SOH).unwrap();
+		}
+		write!(buffer, "268={}{}", self.md_entries.len(), SOH).unwrap();
+		for entry in &self.md_entries {
+			write!(buffer, "269={}{}", entry.md_entry_type, SOH).unwrap();
+			if let Some(md_entry_px) = entry.md_entry_px {
+				write!(buffer, "270={}{}", md_entry_px, SOH).unwrap();
+			}
+			if let Some(md_entry_size) = entry.md_entry_size {
+				write!(buffer, "271={}{}", md_entry_size, SOH).unwrap();
+			}
+			if let Some(md_entry_position_no) = entry.md_entry_position_no {
+				write!(buffer, "290={}{}", md_entry_position_no, SOH).unwrap();
+			}
+			if let Some(number_of_orders) = entry.number_of_orders {
+				write!(buffer, "346={}{}", number_of_orders, SOH).unwrap();
+			}
+		}
+	}
+}
+
+impl MarketDataSnapshotFullRefreshBody {
+	fn current_entry(&mut self, name: &str, tag: u32) -> Result<&mut MdEntry, String> {
+		self.md_entries.last_mut().ok_or_else(|| outside_group(name, tag, "NoMDEntries"))
+	}
+}
+
+impl FixFieldHandler for MarketDataSnapshotFullRefreshBody {
+	fn parse_field(&mut self, tag: u32, value: &str) -> Result<(), FieldError> {
+		match tag {
+			262 => self.md_req_id = Some(value.to_string()),
+			55 => self.symbol = value.to_string(),
+			207 => self.security_exchange = Some(value.to_string()),
+			// Group entries are collected as their fields arrive, the declared count is checked by the parser
+			268 => {
+				value.parse::<usize>().map_err(|_| "Invalid NoMDEntries")?;
+			},
+			269 => {
+				let md_entry_type = value.parse().map_err(|_| "Invalid MDEntryType")?;
+				self.md_entries.push(MdEntry {
+					md_entry_type,
+					md_entry_px: None,
+					md_entry_size: None,
+					md_entry_position_no: None,
+					number_of_orders: None,
+				});
+			},
+			270 => {
+				let px = value.parse().map_err(|_| "Invalid MDEntryPx")?;
+				self.current_entry("MDEntryPx", tag)?.md_entry_px = Some(px);
+			},
+			271 => {
+				let size = value.parse().map_err(|_| "Invalid MDEntrySize")?;
+				self.current_entry("MDEntrySize", tag)?.md_entry_size = Some(size);
+			},
+			290 => {
+				let position = value.parse().map_err(|_| "Invalid MDEntryPositionNo")?;
+				self.current_entry("MDEntryPositionNo", tag)?.md_entry_position_no = Some(position);
+			},
+			346 => {
+				let orders = value.parse().map_err(|_| "Invalid NumberOfOrders")?;
+				self.current_entry("NumberOfOrders", tag)?.number_of_orders = Some(orders);
+			},
+			_ => return Err(FieldError::Unknown(format!("Unknown market data snapshot field: {}", tag))),
+		}
+		Ok(())
+	}
+
+	fn write_body_fields(&self, buffer: &mut String) {
+		self.write_to(buffer);
+	}
+
+	fn group_delimiter(&self, count_tag: u32) -> Option<u32> {
+		(count_tag == 268).then_some(269)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_parse_and_write() {
+		let mut body = MarketDataSnapshotFullRefreshBody::default();
+		assert!(body.validate().is_err());
+
+		for (tag, value) in [
+			(262, "MD1"),
+			(55, "AAPL"),
+			(268, "2"),
+			(269, "0"),
+			(270, "150.25"),
+			(271, "300"),
+			(290, "1"),
+			(269, "1"),
+			(270, "150.5"),
+			(271, "200"),
+		] {
+			body.parse_field(tag, value).unwrap();
+		}
+		assert!(body.validate().is_ok());
+		assert_eq!(body.md_entries.len(), 2);
+		assert_eq!(body.md_entries[0].md_entry_position_no, Some(1));
+		assert_eq!(body.md_entries[1], MdEntry::new(MdEntryType::Offer, 150.5).with_size(200.0));
+		assert!(body.parse_field(270, "abc").is_err());
+		body.parse_field(269, "2").unwrap();
+		assert_eq!(body.validate(), Err(ValidationError::MissingRequiredField("MDEntryPx".into())));
+		body.md_entries.pop();
+		assert!(body.parse_field(999, "x").is_err());
+		assert!(MarketDataSnapshotFullRefreshBody::default().parse_field(270, "1").is_err());
+
+		let mut s = String::new();
+		body.write_to(&mut s);
+		assert_eq!(
+			s,
+			"262=MD1\x0155=AAPL\x01268=2\x01269=0\x01270=150.25\x01271=300\x01290=1\x01269=1\x01270=150.5\x01271=200\x01"
+		);
+	}
+}
@@ -0,0 +1,15 @@
+pub mod marketdataincrementalrefresh;
+pub mod marketdatarequest;
+pub mod marketdatarequestreject;
+pub mod marketdatasnapshotfullrefresh;
+
+// Re-export message body types for convenience
+pub use marketdataincrementalrefresh::{MarketDataIncrementalRefreshBody, MdIncrementalEntry};
+pub use marketdatarequest::{MarketDataRequestBody, RelatedSym};
+pub use marketdatarequestreject::MarketDataRequestRejectBody;
+pub use marketdatasnapshotfullrefresh::{MarketDataSnapshotFullRefreshBody, MdEntry};
+
+/// Error for a repeating group field received before the field that starts its group
+fn outside_group(name: &str, tag: u32, group: &str) -> String {
+	format!("{} ({}) outside of {} group", name, tag, group)
+}
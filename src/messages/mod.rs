@@ -4,17 +4,30 @@
 //! organized by functionality (session, orders, market data, etc.).
 //! Each message type has its own validation logic and serialization methods.
 
+pub mod application;
+pub mod marketdata;
 pub mod order;
 pub mod session;
 
 use crate::common::{
-	Validate, ValidationError,
-	validation::{FixFieldHandler, WriteTo},
+	MsgType, SOH, Validate, ValidationError,
+	validation::{FieldError, FixFieldHandler, WriteTo},
 };
+use std::fmt::Write;
 
 // Re-export message body types
-pub use order::{ExecutionReportBody, NewOrderSingleBody, OrderCancelRequestBody};
-pub use session::{HeartbeatBody, LogonBody};
+pub use application::BusinessMessageRejectBody;
+pub use marketdata::{
+	MarketDataIncrementalRefreshBody, MarketDataRequestBody, MarketDataRequestRejectBody,
+	MarketDataSnapshotFullRefreshBody, MdEntry, MdIncrementalEntry, RelatedSym,
+};
+pub use order::{
+	DontKnowTradeBody, ExecutionReportBody, NewOrderSingleBody, OrderCancelRejectBody, OrderCancelReplaceRequestBody,
+	OrderCancelRequestBody, OrderStatusRequestBody,
+};
+pub use session::{
+	HeartbeatBody, LogonBody, LogoutBody, RejectBody, ResendRequestBody, SequenceResetBody, TestRequestBody,
+};
 
 /// Message-specific body that only allocates fields needed for each message type
 ///
@@ -22,30 +35,108 @@ pub use session::{HeartbeatBody, LogonBody};
 /// required for each specific message type, rather than having a single struct
 /// with all possible fields.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "body"))]
 pub enum FixMessageBody {
 	/// Heartbeat message body (MsgType=0)
 	Heartbeat(HeartbeatBody),
+	/// Test Request message body (MsgType=1)
+	TestRequest(TestRequestBody),
 	/// Logon message body (MsgType=A)
 	Logon(LogonBody),
+	/// Resend Request message body (MsgType=2)
+	ResendRequest(ResendRequestBody),
+	/// Reject message body (MsgType=3)
+	Reject(RejectBody),
+	/// Sequence Reset message body (MsgType=4)
+	SequenceReset(SequenceResetBody),
+	/// Logout message body (MsgType=5)
+	Logout(LogoutBody),
 	/// New Order Single message body (MsgType=D)
 	NewOrderSingle(NewOrderSingleBody),
 	/// Execution Report message body (MsgType=8)
 	ExecutionReport(ExecutionReportBody),
 	/// Order Cancel Request message body (MsgType=F)
 	OrderCancelRequest(OrderCancelRequestBody),
-	/// Placeholder for other message types not yet implemented with specific bodies
-	Other,
+	/// Order Cancel/Replace Request message body (MsgType=G)
+	OrderCancelReplaceRequest(OrderCancelReplaceRequestBody),
+	/// Order Cancel Reject message body (MsgType=9)
+	OrderCancelReject(OrderCancelRejectBody),
+	/// Order Status Request message body (MsgType=H)
+	OrderStatusRequest(OrderStatusRequestBody),
+	/// Don't Know Trade message body (MsgType=Q)
+	DontKnowTrade(DontKnowTradeBody),
+	/// Market Data Request message body (MsgType=V)
+	MarketDataRequest(MarketDataRequestBody),
+	/// Market Data Snapshot/Full Refresh message body (MsgType=W)
+	MarketDataSnapshotFullRefresh(MarketDataSnapshotFullRefreshBody),
+	/// Market Data Incremental Refresh message body (MsgType=X)
+	MarketDataIncrementalRefresh(MarketDataIncrementalRefreshBody),
+	/// Market Data Request Reject message body (MsgType=Y)
+	MarketDataRequestReject(MarketDataRequestRejectBody),
+	/// Business Message Reject message body (MsgType=j)
+	BusinessMessageReject(BusinessMessageRejectBody),
+	/// Fields of other message types not yet implemented with specific bodies, in wire order
+	Other(Vec<(u32, String)>),
+}
+
+impl FixMessageBody {
+	/// Create the default (empty) body matching a message type
+	pub fn for_msg_type(msg_type: &MsgType) -> Self {
+		match msg_type {
+			MsgType::Heartbeat => Self::Heartbeat(HeartbeatBody::default()),
+			MsgType::TestRequest => Self::TestRequest(TestRequestBody::default()),
+			MsgType::Logon => Self::Logon(LogonBody::default()),
+			MsgType::ResendRequest => Self::ResendRequest(ResendRequestBody::default()),
+			MsgType::Reject => Self::Reject(RejectBody::default()),
+			MsgType::SequenceReset => Self::SequenceReset(SequenceResetBody::default()),
+			MsgType::Logout => Self::Logout(LogoutBody::default()),
+			MsgType::NewOrderSingle => Self::NewOrderSingle(NewOrderSingleBody::default()),
+			MsgType::ExecutionReport => Self::ExecutionReport(ExecutionReportBody::default()),
+			MsgType::OrderCancelRequest => Self::OrderCancelRequest(OrderCancelRequestBody::default()),
+			MsgType::OrderCancelReplaceRequest => {
+				Self::OrderCancelReplaceRequest(OrderCancelReplaceRequestBody::default())
+			},
+			MsgType::OrderCancelReject => Self::OrderCancelReject(OrderCancelRejectBody::default()),
+			MsgType::OrderStatusRequest => Self::OrderStatusRequest(OrderStatusRequestBody::default()),
+			MsgType::DontKnowTrade => Self::DontKnowTrade(DontKnowTradeBody::default()),
+			MsgType::MarketDataRequest => Self::MarketDataRequest(MarketDataRequestBody::default()),
+			MsgType::MarketDataSnapshotFullRefresh => {
+				Self::MarketDataSnapshotFullRefresh(MarketDataSnapshotFullRefreshBody::default())
+			},
+			MsgType::MarketDataIncrementalRefresh => {
+				Self::MarketDataIncrementalRefresh(MarketDataIncrementalRefreshBody::default())
+			},
+			MsgType::MarketDataRequestReject => Self::MarketDataRequestReject(MarketDataRequestRejectBody::default()),
+			MsgType::BusinessMessageReject => Self::BusinessMessageReject(BusinessMessageRejectBody::default()),
+			_ => Self::Other(Vec::new()),
+		}
+	}
 }
 
 impl Validate for FixMessageBody {
 	fn validate(&self) -> Result<(), ValidationError> {
 		match self {
 			Self::Heartbeat(body) => body.validate(),
+			Self::TestRequest(body) => body.validate(),
 			Self::Logon(body) => body.validate(),
+			Self::ResendRequest(body) => body.validate(),
+			Self::Reject(body) => body.validate(),
+			Self::SequenceReset(body) => body.validate(),
+			Self::Logout(body) => body.validate(),
 			Self::NewOrderSingle(body) => body.validate(),
 			Self::ExecutionReport(body) => body.validate(),
 			Self::OrderCancelRequest(body) => body.validate(),
-			Self::Other => Ok(()), // No validation for unsupported types yet
+			Self::OrderCancelReplaceRequest(body) => body.validate(),
+			Self::OrderCancelReject(body) => body.validate(),
+			Self::OrderStatusRequest(body) => body.validate(),
+			Self::DontKnowTrade(body) => body.validate(),
+			Self::MarketDataRequest(body) => body.validate(),
+			Self::MarketDataSnapshotFullRefresh(body) => body.validate(),
+			Self::MarketDataIncrementalRefresh(body) => body.validate(),
+			Self::MarketDataRequestReject(body) => body.validate(),
+			Self::BusinessMessageReject(body) => body.validate(),
+			Self::Other(_) => Ok(()), // No validation for unsupported types yet
 		}
 	}
 }
@@ -54,24 +145,60 @@ impl WriteTo for FixMessageBody {
 	fn write_to(&self, buffer: &mut String) {
 		match self {
 			Self::Heartbeat(body) => body.write_to(buffer),
+			Self::TestRequest(body) => body.write_to(buffer),
 			Self::Logon(body) => body.write_to(buffer),
+			Self::ResendRequest(body) => body.write_to(buffer),
+			Self::Reject(body) => body.write_to(buffer),
+			Self::SequenceReset(body) => body.write_to(buffer),
+			Self::Logout(body) => body.write_to(buffer),
 			Self::NewOrderSingle(body) => body.write_to(buffer),
 			Self::ExecutionReport(body) => body.write_to(buffer),
 			Self::OrderCancelRequest(body) => body.write_to(buffer),
-			Self::Other => unimplemented!(),
+			Self::OrderCancelReplaceRequest(body) => body.write_to(buffer),
+			Self::OrderCancelReject(body) => body.write_to(buffer),
+			Self::OrderStatusRequest(body) => body.write_to(buffer),
+			Self::DontKnowTrade(body) => body.write_to(buffer),
+			Self::MarketDataRequest(body) => body.write_to(buffer),
+			Self::MarketDataSnapshotFullRefresh(body) => body.write_to(buffer),
+			Self::MarketDataIncrementalRefresh(body) => body.write_to(buffer),
+			Self::MarketDataRequestReject(body) => body.write_to(buffer),
+			Self::BusinessMessageReject(body) => body.write_to(buffer),
+			Self::Other(fields) => {
+				for (tag, value) in fields {
+					write!(buffer, "{}={}{}", tag, value, SOH).unwrap();
+				}
+			},
 		}
 	}
 }
 
 impl FixFieldHandler for FixMessageBody {
-	fn parse_field(&mut self, tag: u32, value: &str) -> Result<(), String> {
+	fn parse_field(&mut self, tag: u32, value: &str) -> Result<(), FieldError> {
 		match self {
 			Self::Heartbeat(body) => body.parse_field(tag, value),
+			Self::TestRequest(body) => body.parse_field(tag, value),
 			Self::Logon(body) => body.parse_field(tag, value),
+			Self::ResendRequest(body) => body.parse_field(tag, value),
+			Self::Reject(body) => body.parse_field(tag, value),
+			Self::SequenceReset(body) => body.parse_field(tag, value),
+			Self::Logout(body) => body.parse_field(tag, value),
 			Self::NewOrderSingle(body) => body.parse_field(tag, value),
 			Self::ExecutionReport(body) => body.parse_field(tag, value),
 			Self::OrderCancelRequest(body) => body.parse_field(tag, value),
-			Self::Other => Ok(()), // Ignore fields for unsupported types
+			Self::OrderCancelReplaceRequest(body) => body.parse_field(tag, value),
+			Self::OrderCancelReject(body) => body.parse_field(tag, value),
+			Self::OrderStatusRequest(body) => body.parse_field(tag, value),
+			Self::DontKnowTrade(body) => body.parse_field(tag, value),
+			Self::MarketDataRequest(body) => body.parse_field(tag, value),
+			Self::MarketDataSnapshotFullRefresh(body) => body.parse_field(tag, value),
+			Self::MarketDataIncrementalRefresh(body) => body.parse_field(tag, value),
+			Self::MarketDataRequestReject(body) => body.parse_field(tag, value),
+			Self::BusinessMessageReject(body) => body.parse_field(tag, value),
+			Self::Other(fields) => {
+				// Kept as they are since the fields of unsupported types are unknown
+				fields.push((tag, value.to_string()));
+				Ok(())
+			},
 		}
 	}
 
@@ -80,6 +207,15 @@ impl FixFieldHandler for FixMessageBody {
 		// since all message body fields contribute to body length
 		self.write_to(buffer);
 	}
+
+	fn group_delimiter(&self, count_tag: u32) -> Option<u32> {
+		match self {
+			Self::MarketDataRequest(body) => body.group_delimiter(count_tag),
+			Self::MarketDataSnapshotFullRefresh(body) => body.group_delimiter(count_tag),
+			Self::MarketDataIncrementalRefresh(body) => body.group_delimiter(count_tag),
+			_ => None,
+		}
+	}
 }
 
 #[cfg(test)]
@@ -98,7 +234,7 @@ mod tests {
 		assert!(logon_body.is_valid());
 
 		// Test Other variant
-		let other_body = FixMessageBody::Other;
+		let other_body = FixMessageBody::Other(Vec::new());
 		assert!(other_body.is_valid());
 	}
 
@@ -128,9 +264,12 @@ mod tests {
 		assert!(logon.parse_field(98, "1").is_ok()); // EncryptMethod::Pkcs
 		assert!(logon.parse_field(108, "60").is_ok()); // HeartBtInt
 
-		// Test other message type (should ignore fields)
-		let mut other = FixMessageBody::Other;
+		// Test other message type (should keep fields as they are)
+		let mut other = FixMessageBody::Other(Vec::new());
 		assert!(other.parse_field(999, "anything").is_ok());
+		let mut buffer = String::new();
+		other.write_to(&mut buffer);
+		assert_eq!(buffer, "999=anything\x01");
 	}
 
 	#[test]
@@ -143,8 +282,8 @@ mod tests {
 		let logon2 = FixMessageBody::Logon(LogonBody::default());
 		assert_eq!(logon1, logon2);
 
-		let other1 = FixMessageBody::Other;
-		let other2 = FixMessageBody::Other;
+		let other1 = FixMessageBody::Other(Vec::new());
+		let other2 = FixMessageBody::Other(Vec::new());
 		assert_eq!(other1, other2);
 
 		// Different variants should not be equal
@@ -158,15 +297,13 @@ mod tests {
 		// This test demonstrates that each variant only stores relevant fields
 		let heartbeat = FixMessageBody::Heartbeat(HeartbeatBody::default());
 		let logon = FixMessageBody::Logon(LogonBody::default());
-		let other = FixMessageBody::Other;
+		let other = FixMessageBody::Other(Vec::new());
 
 		// Each variant should be a different size, demonstrating memory efficiency
-		match (&heartbeat, &logon, &other) {
-			(FixMessageBody::Heartbeat(_), FixMessageBody::Logon(_), FixMessageBody::Other) => {
-				// This pattern match confirms the enum variants are properly structured
-				assert!(true);
-			},
-			_ => panic!("Enum variants not properly matched"),
-		}
+		// This pattern match confirms the enum variants are properly structured
+		assert!(matches!(
+			(&heartbeat, &logon, &other),
+			(FixMessageBody::Heartbeat(_), FixMessageBody::Logon(_), FixMessageBody::Other(_))
+		));
 	}
 }
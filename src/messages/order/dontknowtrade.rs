@@ -0,0 +1,157 @@
+//! Don't Know Trade message implementation (MsgType=Q)
+//!
+//! FIX 4.2 Don't Know Trade (DK), sent to dispute an execution that cannot be
+//! matched against an order on our books. The execution is identified by
+//! OrderID(37) and ExecID(17), and DKReason(127) says why it was refused.
+
+use crate::{
+	SOH, Side,
+	common::{
+		DkReason, Validate, ValidationError,
+		validation::{FieldError, FixFieldHandler, WriteTo},
+	},
+	messages::order::validate_order_qty,
+};
+use std::fmt::Write;
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DontKnowTradeBody {
+	/// Broker order ID of the disputed execution (Tag 37) - Required
+	pub order_id: String,
+	/// ExecID of the disputed execution (Tag 17) - Required
+	pub exec_id: String,
+	/// Reason the execution is not recognised (Tag 127) - Required
+	pub dk_reason: DkReason,
+	// (Tag 55) - Required
+	pub symbol: String,
+	// (Tag 54) - Required
+	pub side: Side,
+	// (Tag 38) - Either OrderQty or CashOrderQty is required
+	pub order_qty: Option<f64>,
+	// (Tag 152)
+	pub cash_order_qty: Option<f64>,
+	// (Tag 32) - Optional
+	pub last_shares: Option<f64>,
+	// (Tag 31) - Optional
+	pub last_px: Option<f64>,
+	// (Tag 58) - Optional
+	pub text: Option<String>,
+}
+
+impl Default for DontKnowTradeBody {
+	fn default() -> Self {
+		Self {
+			order_id: String::new(),
+			exec_id: String::new(),
+			dk_reason: DkReason::Other,
+			symbol: String::new(),
+			side: Side::Buy,
+			order_qty: None,
+			cash_order_qty: None,
+			last_shares: None,
+			last_px: None,
+			text: None,
+		}
+	}
+}
+
+impl Validate for DontKnowTradeBody {
+	fn validate(&self) -> Result<(), ValidationError> {
+		if self.order_id.is_empty() {
+			return Err(ValidationError::MissingRequiredField("OrderID".into()));
+		}
+		if self.exec_id.is_empty() {
+			return Err(ValidationError::MissingRequiredField("ExecID".into()));
+		}
+		if self.symbol.is_empty() {
+			return Err(ValidationError::MissingRequiredField("Symbol".into()));
+		}
+		validate_order_qty(self.order_qty, self.cash_order_qty)
+	}
+}
+
+impl WriteTo for DontKnowTradeBody {
+	fn write_to(&self, buffer: &mut String) {
+		write!(buffer, "37={}{}", self.order_id, SOH).unwrap();
+		write!(buffer, "17={}{}", self.exec_id, SOH).unwrap();
+		write!(buffer, "127={}{}", self.dk_reason, SOH).unwrap();
+		write!(buffer, "55={}{}", self.symbol, SOH).unwrap();
+		write!(buffer, "54={}{}", self.side, SOH).unwrap();
+		if let Some(order_qty) = self.order_qty {
+			write!(buffer, "38={}{}", order_qty, SOH).unwrap();
+		}
+		if let Some(cash_order_qty) = self.cash_order_qty {
+			write!(buffer, "152={}{}", cash_order_qty, SOH).unwrap();
+		}
+		if let Some(last_shares) = self.last_shares {
+			write!(buffer, "32={}{}", last_shares, SOH).unwrap();
+		}
+		if let Some(last_px) = self.last_px {
+			write!(buffer, "31={}{}", last_px, SOH).unwrap();
+		}
+		if let Some(ref text) = self.text {
+			write!(buffer, "58={}{}", text, SOH).unwrap();
+		}
+	}
+}
+
+impl FixFieldHandler for DontKnowTradeBody {
+	fn parse_field(&mut self, tag: u32, value: &str) -> Result<(), FieldError> {
+		match tag {
+			37 => self.order_id = value.to_string(),
+			17 => self.exec_id = value.to_string(),
+			127 => self.dk_reason = value.parse().map_err(|_| "Invalid DKReason")?,
+			55 => self.symbol = value.to_string(),
+			54 => self.side = value.parse().map_err(|_| "Invalid Side")?,
+			38 => self.order_qty = Some(value.parse().map_err(|_| "Invalid OrderQty")?),
+			152 => self.cash_order_qty = Some(value.parse().map_err(|_| "Invalid CashOrderQty")?),
+			32 => self.last_shares = Some(value.parse().map_err(|_| "Invalid LastShares")?),
+			31 => self.last_px = Some(value.parse().map_err(|_| "Invalid LastPx")?),
+			58 => self.text = Some(value.to_string()),
+			_ => return Err(FieldError::Unknown(format!("Unknown don't know trade field: {}", tag))),
+		}
+		Ok(())
+	}
+
+	fn write_body_fields(&self, buffer: &mut String) {
+		self.write_to(buffer);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_validation() {
+		assert!(DontKnowTradeBody::default().validate().is_err());
+
+		let body = DontKnowTradeBody {
+			order_id: "BRK1".into(),
+			exec_id: "EXEC1".into(),
+			symbol: "AAPL".into(),
+			..Default::default()
+		};
+		assert!(matches!(body.validate(), Err(ValidationError::ConditionallyRequiredFieldMissing(_))));
+		assert!(DontKnowTradeBody { order_qty: Some(100.0), ..body }.validate().is_ok());
+	}
+
+	#[test]
+	fn test_parse_and_write() {
+		let mut body = DontKnowTradeBody::default();
+		for (tag, value) in
+			[(37, "BRK1"), (17, "EXEC1"), (127, "B"), (55, "AAPL"), (54, "1"), (38, "100"), (32, "100"), (31, "150.5")]
+		{
+			body.parse_field(tag, value).unwrap();
+		}
+		assert!(body.validate().is_ok());
+		assert_eq!(body.dk_reason, DkReason::WrongSide);
+		assert!(body.parse_field(127, "F").is_err());
+		assert!(body.parse_field(999, "x").is_err());
+
+		let mut s = String::new();
+		body.write_to(&mut s);
+		assert_eq!(s, "37=BRK1\x0117=EXEC1\x01127=B\x0155=AAPL\x0154=1\x0138=100\x0132=100\x0131=150.5\x01");
+	}
+}
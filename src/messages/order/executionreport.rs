@@ -10,7 +10,7 @@ use crate::{
 		Validate, ValidationError,
 		enums::{ExecTransType, ExecType},
 		parse_fix_timestamp,
-		validation::{FixFieldHandler, WriteTo},
+		validation::{FieldError, FixFieldHandler, WriteTo},
 		write_tag_timestamp,
 	},
 };
@@ -18,11 +18,14 @@ use std::fmt::Write;
 use time::OffsetDateTime;
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExecutionReportBody {
 	// (Tag 37) Required
 	pub order_id: String,
 	// (Tag 17) Required
 	pub exec_id: String,
+	// (Tag 19) Conditionally required, the ExecID a trade cancel or correct refers to
+	pub exec_ref_id: Option<String>,
 	// (Tag 20) Required
 	pub exec_trans_type: ExecTransType,
 	// (Tag 150) Required
@@ -44,11 +47,14 @@ pub struct ExecutionReportBody {
 	// (Tag 31) Optional (Px of last fill)
 	pub last_px: Option<f64>,
 	// (Tag 60) Optional TransactTime
+	#[cfg_attr(feature = "serde", serde(default, with = "crate::serde_fix::timestamp::option"))]
 	pub transact_time: Option<OffsetDateTime>,
 	// (Tag 11) Optional ClOrdID for linkage
 	pub cl_ord_id: Option<String>,
 	// (Tag 41) Optional OrigClOrdID
 	pub orig_cl_ord_id: Option<String>,
+	// (Tag 1) Optional Account
+	pub account: Option<String>,
 	// (Tag 103) Optional OrdRejReason when Rejected
 	pub ord_rej_reason: Option<u32>,
 }
@@ -58,6 +64,7 @@ impl Default for ExecutionReportBody {
 		Self {
 			order_id: String::new(),
 			exec_id: String::new(),
+			exec_ref_id: None,
 			exec_trans_type: ExecTransType::New,
 			exec_type: ExecType::New,
 			ord_status: OrdStatus::New,
@@ -71,6 +78,7 @@ impl Default for ExecutionReportBody {
 			transact_time: None,
 			cl_ord_id: None,
 			orig_cl_ord_id: None,
+			account: None,
 			ord_rej_reason: None,
 		}
 	}
@@ -87,6 +95,11 @@ impl Validate for ExecutionReportBody {
 		if self.symbol.is_empty() {
 			return Err(ValidationError::MissingRequiredField("Symbol".into()));
 		}
+		let amends_trade = matches!(self.exec_trans_type, ExecTransType::Cancel | ExecTransType::Correct)
+			|| matches!(self.exec_type, ExecType::TradeCancel | ExecType::TradeCorrect);
+		if amends_trade && self.exec_ref_id.is_none() {
+			return Err(ValidationError::ConditionallyRequiredFieldMissing("ExecRefID".into()));
+		}
 		// LeavesQty + CumQty consistency (cannot be negative; allow zero for terminal states)
 		if self.leaves_qty < 0.0 {
 			return Err(ValidationError::InvalidFieldValue("LeavesQty".into(), self.leaves_qty.to_string()));
@@ -106,6 +119,9 @@ impl WriteTo for ExecutionReportBody {
 		write!(buffer, "37={}{}", self.order_id, SOH).unwrap();
 		write!(buffer, "17={}{}", self.exec_id, SOH).unwrap();
 		write!(buffer, "20={}{}", self.exec_trans_type, SOH).unwrap();
+		if let Some(ref exec_ref_id) = self.exec_ref_id {
+			write!(buffer, "19={}{}", exec_ref_id, SOH).unwrap();
+		}
 		write!(buffer, "150={}{}", self.exec_type, SOH).unwrap();
 		write!(buffer, "39={}{}", self.ord_status, SOH).unwrap();
 		if let Some(ref cl) = self.cl_ord_id {
@@ -114,6 +130,9 @@ impl WriteTo for ExecutionReportBody {
 		if let Some(ref orig) = self.orig_cl_ord_id {
 			write!(buffer, "41={}{}", orig, SOH).unwrap();
 		}
+		if let Some(ref account) = self.account {
+			write!(buffer, "1={}{}", account, SOH).unwrap();
+		}
 		write!(buffer, "55={}{}", self.symbol, SOH).unwrap();
 		write!(buffer, "54={}{}", self.side, SOH).unwrap();
 		if let Some(ts) = self.transact_time {
@@ -141,10 +160,11 @@ impl ExecutionReportBody {
 }
 
 impl FixFieldHandler for ExecutionReportBody {
-	fn parse_field(&mut self, tag: u32, value: &str) -> Result<(), String> {
+	fn parse_field(&mut self, tag: u32, value: &str) -> Result<(), FieldError> {
 		match tag {
 			37 => self.order_id = value.to_string(),
 			17 => self.exec_id = value.to_string(),
+			19 => self.exec_ref_id = Some(value.to_string()),
 			20 => self.exec_trans_type = value.parse().map_err(|_| "Invalid ExecTransType")?,
 			150 => self.exec_type = value.parse().map_err(|_| "Invalid ExecType")?,
 			39 => self.ord_status = value.parse().map_err(|_| "Invalid OrdStatus")?,
@@ -158,8 +178,9 @@ impl FixFieldHandler for ExecutionReportBody {
 			60 => self.transact_time = Some(parse_fix_timestamp(value)?),
 			11 => self.cl_ord_id = Some(value.to_string()),
 			41 => self.orig_cl_ord_id = Some(value.to_string()),
+			1 => self.account = Some(value.to_string()),
 			103 => self.ord_rej_reason = Some(value.parse().map_err(|_| "Invalid OrdRejReason")?),
-			_ => return Err(format!("Unknown execution report field: {}", tag)),
+			_ => return Err(FieldError::Unknown(format!("Unknown execution report field: {}", tag))),
 		}
 		Ok(())
 	}
@@ -213,4 +234,22 @@ mod tests {
 		assert!(s.contains("37=OID1"));
 		assert!(s.contains("150=0"));
 	}
+
+	#[test]
+	fn test_trade_cancel_requires_exec_ref_id() {
+		let mut body = ExecutionReportBody {
+			exec_type: ExecType::TradeCancel,
+			symbol: "AAPL".into(),
+			..ExecutionReportBody::new("OID", "EID2")
+		};
+		assert_eq!(body.validate(), Err(ValidationError::ConditionallyRequiredFieldMissing("ExecRefID".into())));
+		body.parse_field(19, "EID1").unwrap();
+		body.parse_field(1, "ACC1").unwrap();
+		assert!(body.validate().is_ok());
+
+		let mut s = String::new();
+		body.write_to(&mut s);
+		assert!(s.starts_with("37=OID\x0117=EID2\x0120=0\x0119=EID1\x01150=H\x01"));
+		assert!(s.contains("\x011=ACC1\x01"));
+	}
 }
@@ -1,8 +1,26 @@
+pub mod dontknowtrade;
 pub mod executionreport;
 pub mod newordersingle;
+pub mod ordercancelreject;
+pub mod ordercancelreplacerequest;
 pub mod ordercancelrequest;
+pub mod orderstatusrequest;
+
+use crate::common::ValidationError;
 
 // Re-export message body types for convenience
+pub use dontknowtrade::DontKnowTradeBody;
 pub use executionreport::ExecutionReportBody;
 pub use newordersingle::NewOrderSingleBody;
+pub use ordercancelreject::OrderCancelRejectBody;
+pub use ordercancelreplacerequest::OrderCancelReplaceRequestBody;
 pub use ordercancelrequest::OrderCancelRequestBody;
+pub use orderstatusrequest::OrderStatusRequestBody;
+
+/// Either OrderQty (Tag 38) or CashOrderQty (Tag 152) must be specified
+pub(crate) fn validate_order_qty(order_qty: Option<f64>, cash_order_qty: Option<f64>) -> Result<(), ValidationError> {
+	if order_qty.is_none() && cash_order_qty.is_none() {
+		return Err(ValidationError::ConditionallyRequiredFieldMissing("OrderQty or CashOrderQty".to_string()));
+	}
+	Ok(())
+}
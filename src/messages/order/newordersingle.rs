@@ -8,14 +8,16 @@ use crate::{
 	SOH, Side,
 	common::{
 		Validate, ValidationError, parse_fix_timestamp,
-		validation::{FixFieldHandler, WriteTo},
+		validation::{FieldError, FixFieldHandler, WriteTo},
 		write_tag_timestamp,
 	},
+	messages::order::validate_order_qty,
 };
 use std::fmt::Write;
 use time::OffsetDateTime;
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NewOrderSingleBody {
 	/// Unique identifier of the order as assigned by institution (Tag 11) - Required
 	pub cl_ord_id: String,
@@ -30,6 +32,7 @@ pub struct NewOrderSingleBody {
 	// (Tag 152)
 	pub cash_order_qty: Option<f64>,
 	// (Tag 60) - Required
+	#[cfg_attr(feature = "serde", serde(with = "crate::serde_fix::timestamp"))]
 	pub transact_time: OffsetDateTime,
 	// (Tag 40) - Required
 	pub ord_type: String,
@@ -42,10 +45,7 @@ pub struct NewOrderSingleBody {
 impl Validate for NewOrderSingleBody {
 	fn validate(&self) -> Result<(), ValidationError> {
 		// Rust is enforcing the presence of all the required values, so we should do a sanity check of the values.
-		if self.order_qty.is_none() && self.cash_order_qty.is_none() {
-			return Err(ValidationError::MissingRequiredField("OrderQty or CashOrderQty".to_string()));
-		}
-		Ok(())
+		validate_order_qty(self.order_qty, self.cash_order_qty)
 	}
 }
 
@@ -97,7 +97,7 @@ impl NewOrderSingleBody {
 }
 
 impl FixFieldHandler for NewOrderSingleBody {
-	fn parse_field(&mut self, tag: u32, value: &str) -> Result<(), String> {
+	fn parse_field(&mut self, tag: u32, value: &str) -> Result<(), FieldError> {
 		match tag {
 			11 => self.cl_ord_id = value.to_string(),
 			21 => self.handl_inst = value.to_string(),
@@ -108,7 +108,7 @@ impl FixFieldHandler for NewOrderSingleBody {
 			40 => self.ord_type = value.to_string(),
 			207 => self.security_exchange = Some(value.to_string()),
 			44 => self.price = Some(value.parse().map_err(|_| "Invalid price")?),
-			_ => return Err(format!("Unknown new order single field: {}", tag)),
+			_ => return Err(FieldError::Unknown(format!("Unknown new order single field: {}", tag))),
 		}
 		Ok(())
 	}
@@ -0,0 +1,155 @@
+//! Order Cancel Reject message implementation (MsgType=9)
+//!
+//! FIX 4.2 Order Cancel Reject, sent by the broker when an Order Cancel Request
+//! or Order Cancel/Replace Request cannot be honoured. CxlRejResponseTo(434)
+//! tells which of the two requests is being refused.
+
+use crate::common::{
+	CxlRejReason, CxlRejResponseTo, OrdStatus, SOH, Validate, ValidationError,
+	validation::{FieldError, FixFieldHandler, WriteTo},
+};
+use std::fmt::Write;
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OrderCancelRejectBody {
+	/// Broker order ID, "NONE" if the order is unknown (Tag 37) - Required
+	pub order_id: String,
+	/// ClOrdID of the rejected cancel or replace request (Tag 11) - Required
+	pub cl_ord_id: String,
+	/// ClOrdID of the order the request referred to (Tag 41) - Required
+	pub orig_cl_ord_id: String,
+	/// Status of the order after the reject (Tag 39) - Required
+	pub ord_status: OrdStatus,
+	/// Kind of request being rejected (Tag 434) - Required
+	pub cxl_rej_response_to: CxlRejResponseTo,
+	/// Code identifying the reject reason (Tag 102) - Optional
+	pub cxl_rej_reason: Option<CxlRejReason>,
+	/// Free format explanation of the reject (Tag 58) - Optional
+	pub text: Option<String>,
+}
+
+impl Default for OrderCancelRejectBody {
+	fn default() -> Self {
+		Self {
+			order_id: String::new(),
+			cl_ord_id: String::new(),
+			orig_cl_ord_id: String::new(),
+			ord_status: OrdStatus::New,
+			cxl_rej_response_to: CxlRejResponseTo::OrderCancelRequest,
+			cxl_rej_reason: None,
+			text: None,
+		}
+	}
+}
+
+impl Validate for OrderCancelRejectBody {
+	fn validate(&self) -> Result<(), ValidationError> {
+		if self.order_id.is_empty() {
+			return Err(ValidationError::MissingRequiredField("OrderID".into()));
+		}
+		if self.cl_ord_id.is_empty() {
+			return Err(ValidationError::MissingRequiredField("ClOrdID".into()));
+		}
+		if self.orig_cl_ord_id.is_empty() {
+			return Err(ValidationError::MissingRequiredField("OrigClOrdID".into()));
+		}
+		Ok(())
+	}
+}
+
+impl WriteTo for OrderCancelRejectBody {
+	fn write_to(&self, buffer: &mut String) {
+		write!(buffer, "37={}{}", self.order_id, SOH).unwrap();
+		write!(buffer, "11={}{}", self.cl_ord_id, SOH).unwrap();
+		write!(buffer, "41={}{}", self.orig_cl_ord_id, SOH).unwrap();
+		write!(buffer, "39={}{}", self.ord_status, SOH).unwrap();
+		write!(buffer, "434={}{}", self.cxl_rej_response_to, SOH).unwrap();
+		if let Some(ref reason) = self.cxl_rej_reason {
+			write!(buffer, "102={}{}", reason, SOH).unwrap();
+		}
+		if let Some(ref text) = self.text {
+			write!(buffer, "58={}{}", text, SOH).unwrap();
+		}
+	}
+}
+
+impl OrderCancelRejectBody {
+	/// Create a reject of the request `cl_ord_id` referring to order `orig_cl_ord_id`
+	pub fn new(
+		order_id: impl Into<String>,
+		cl_ord_id: impl Into<String>,
+		orig_cl_ord_id: impl Into<String>,
+		ord_status: OrdStatus,
+		cxl_rej_response_to: CxlRejResponseTo,
+	) -> Self {
+		Self {
+			order_id: order_id.into(),
+			cl_ord_id: cl_ord_id.into(),
+			orig_cl_ord_id: orig_cl_ord_id.into(),
+			ord_status,
+			cxl_rej_response_to,
+			cxl_rej_reason: None,
+			text: None,
+		}
+	}
+}
+
+impl FixFieldHandler for OrderCancelRejectBody {
+	fn parse_field(&mut self, tag: u32, value: &str) -> Result<(), FieldError> {
+		match tag {
+			37 => self.order_id = value.to_string(),
+			11 => self.cl_ord_id = value.to_string(),
+			41 => self.orig_cl_ord_id = value.to_string(),
+			39 => self.ord_status = value.parse().map_err(|_| "Invalid OrdStatus")?,
+			434 => self.cxl_rej_response_to = value.parse().map_err(|_| "Invalid CxlRejResponseTo")?,
+			102 => self.cxl_rej_reason = Some(value.parse().map_err(|_| "Invalid CxlRejReason")?),
+			58 => self.text = Some(value.to_string()),
+			_ => return Err(FieldError::Unknown(format!("Unknown order cancel reject field: {}", tag))),
+		}
+		Ok(())
+	}
+
+	fn write_body_fields(&self, buffer: &mut String) {
+		self.write_to(buffer);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_validation() {
+		assert!(OrderCancelRejectBody::default().validate().is_err());
+		let body = OrderCancelRejectBody::new(
+			"NONE",
+			"CXL1",
+			"ORD1",
+			OrdStatus::Rejected,
+			CxlRejResponseTo::OrderCancelRequest,
+		);
+		assert!(body.validate().is_ok());
+	}
+
+	#[test]
+	fn test_parse_and_write() {
+		let mut body = OrderCancelRejectBody::default();
+		for (tag, value) in
+			[(37, "BRK1"), (11, "ORD2"), (41, "ORD1"), (39, "2"), (434, "2"), (102, "0"), (58, "Already filled")]
+		{
+			body.parse_field(tag, value).unwrap();
+		}
+		assert!(body.validate().is_ok());
+		assert_eq!(body.ord_status, OrdStatus::Filled);
+		assert_eq!(body.cxl_rej_response_to, CxlRejResponseTo::OrderCancelReplaceRequest);
+		assert_eq!(body.cxl_rej_reason, Some(CxlRejReason::TooLateToCancel));
+		assert!(body.parse_field(434, "3").is_err());
+		assert!(body.parse_field(102, "9").is_err());
+		assert!(body.parse_field(999, "x").is_err());
+
+		let mut s = String::new();
+		body.write_to(&mut s);
+		assert_eq!(s, "37=BRK1\x0111=ORD2\x0141=ORD1\x0139=2\x01434=2\x01102=0\x0158=Already filled\x01");
+	}
+}
@@ -0,0 +1,249 @@
+//! Order Cancel/Replace Request message implementation (MsgType=G)
+//!
+//! FIX 4.2 Order Cancel/Replace Request, used to amend a live order, e.g. its
+//! price or quantity. The replacement order is described with the same fields as
+//! a New Order Single, plus OrigClOrdID(41) identifying the order to amend and
+//! the broker's OrderID(37) when known.
+
+use crate::{
+	SOH, Side,
+	common::{
+		TimeInForce, Validate, ValidationError, parse_fix_timestamp,
+		validation::{FieldError, FixFieldHandler, WriteTo},
+		write_tag_timestamp,
+	},
+	messages::order::validate_order_qty,
+};
+use std::fmt::Write;
+use time::OffsetDateTime;
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OrderCancelReplaceRequestBody {
+	/// Most recent order ID as assigned by the broker (Tag 37) - Optional
+	pub order_id: Option<String>,
+	/// ClOrdID of the order to amend (Tag 41) - Required
+	pub orig_cl_ord_id: String,
+	/// Unique identifier of the replacement order (Tag 11) - Required
+	pub cl_ord_id: String,
+	// (Tag 1) - Optional
+	pub account: Option<String>,
+	// (Tag 21) - Required
+	pub handl_inst: String,
+	// (Tag 18) - Optional
+	pub exec_inst: Option<String>,
+	// (Tag 55) - Required
+	pub symbol: String,
+	// (Tag 207) - Optional
+	pub security_exchange: Option<String>,
+	// (Tag 54) - Required, must match the original order
+	pub side: Side,
+	// (Tag 60) - Required
+	#[cfg_attr(feature = "serde", serde(with = "crate::serde_fix::timestamp"))]
+	pub transact_time: OffsetDateTime,
+	// (Tag 38) - Either OrderQty or CashOrderQty is required
+	pub order_qty: Option<f64>,
+	// (Tag 152)
+	pub cash_order_qty: Option<f64>,
+	// (Tag 40) - Required
+	pub ord_type: String,
+	// (Tag 44) - Optional
+	pub price: Option<f64>,
+	// (Tag 99) - Optional
+	pub stop_px: Option<f64>,
+	// (Tag 15) - Optional
+	pub currency: Option<String>,
+	// (Tag 59) - Optional
+	pub time_in_force: Option<TimeInForce>,
+	// (Tag 126) - Optional
+	#[cfg_attr(feature = "serde", serde(default, with = "crate::serde_fix::timestamp::option"))]
+	pub expire_time: Option<OffsetDateTime>,
+	// (Tag 58) - Optional
+	pub text: Option<String>,
+}
+
+impl Default for OrderCancelReplaceRequestBody {
+	fn default() -> Self {
+		Self {
+			order_id: None,
+			orig_cl_ord_id: String::new(),
+			cl_ord_id: String::new(),
+			account: None,
+			handl_inst: String::new(),
+			exec_inst: None,
+			symbol: String::new(),
+			security_exchange: None,
+			side: Side::Buy,
+			transact_time: OffsetDateTime::now_utc(),
+			order_qty: None,
+			cash_order_qty: None,
+			ord_type: String::new(),
+			price: None,
+			stop_px: None,
+			currency: None,
+			time_in_force: None,
+			expire_time: None,
+			text: None,
+		}
+	}
+}
+
+impl Validate for OrderCancelReplaceRequestBody {
+	fn validate(&self) -> Result<(), ValidationError> {
+		if self.orig_cl_ord_id.is_empty() {
+			return Err(ValidationError::MissingRequiredField("OrigClOrdID".into()));
+		}
+		if self.cl_ord_id.is_empty() {
+			return Err(ValidationError::MissingRequiredField("ClOrdID".into()));
+		}
+		if self.handl_inst.is_empty() {
+			return Err(ValidationError::MissingRequiredField("HandlInst".into()));
+		}
+		if self.symbol.is_empty() {
+			return Err(ValidationError::MissingRequiredField("Symbol".into()));
+		}
+		if self.ord_type.is_empty() {
+			return Err(ValidationError::MissingRequiredField("OrdType".into()));
+		}
+		validate_order_qty(self.order_qty, self.cash_order_qty)
+	}
+}
+
+impl WriteTo for OrderCancelReplaceRequestBody {
+	fn write_to(&self, buffer: &mut String) {
+		if let Some(ref order_id) = self.order_id {
+			write!(buffer, "37={}{}", order_id, SOH).unwrap();
+		}
+		write!(buffer, "41={}{}", self.orig_cl_ord_id, SOH).unwrap();
+		write!(buffer, "11={}{}", self.cl_ord_id, SOH).unwrap();
+		if let Some(ref account) = self.account {
+			write!(buffer, "1={}{}", account, SOH).unwrap();
+		}
+		write!(buffer, "21={}{}", self.handl_inst, SOH).unwrap();
+		if let Some(ref exec_inst) = self.exec_inst {
+			write!(buffer, "18={}{}", exec_inst, SOH).unwrap();
+		}
+		write!(buffer, "55={}{}", self.symbol, SOH).unwrap();
+		if let Some(ref security_exchange) = self.security_exchange {
+			write!(buffer, "207={}{}", security_exchange, SOH).unwrap();
+		}
+		write!(buffer, "54={}{}", self.side, SOH).unwrap();
+		write_tag_timestamp(buffer, 60, self.transact_time);
+		if let Some(order_qty) = self.order_qty {
+			write!(buffer, "38={}{}", order_qty, SOH).unwrap();
+		}
+		if let Some(cash_order_qty) = self.cash_order_qty {
+			write!(buffer, "152={}{}", cash_order_qty, SOH).unwrap();
+		}
+		write!(buffer, "40={}{}", self.ord_type, SOH).unwrap();
+		if let Some(price) = self.price {
+			write!(buffer, "44={}{}", price, SOH).unwrap();
+		}
+		if let Some(stop_px) = self.stop_px {
+			write!(buffer, "99={}{}", stop_px, SOH).unwrap();
+		}
+		if let Some(ref currency) = self.currency {
+			write!(buffer, "15={}{}", currency, SOH).unwrap();
+		}
+		if let Some(ref time_in_force) = self.time_in_force {
+			write!(buffer, "59={}{}", time_in_force, SOH).unwrap();
+		}
+		if let Some(expire_time) = self.expire_time {
+			write_tag_timestamp(buffer, 126, expire_time);
+		}
+		if let Some(ref text) = self.text {
+			write!(buffer, "58={}{}", text, SOH).unwrap();
+		}
+	}
+}
+
+impl FixFieldHandler for OrderCancelReplaceRequestBody {
+	fn parse_field(&mut self, tag: u32, value: &str) -> Result<(), FieldError> {
+		match tag {
+			37 => self.order_id = Some(value.to_string()),
+			41 => self.orig_cl_ord_id = value.to_string(),
+			11 => self.cl_ord_id = value.to_string(),
+			1 => self.account = Some(value.to_string()),
+			21 => self.handl_inst = value.to_string(),
+			18 => self.exec_inst = Some(value.to_string()),
+			55 => self.symbol = value.to_string(),
+			207 => self.security_exchange = Some(value.to_string()),
+			54 => self.side = value.parse().map_err(|_| "Invalid Side")?,
+			60 => self.transact_time = parse_fix_timestamp(value)?,
+			38 => self.order_qty = Some(value.parse().map_err(|_| "Invalid OrderQty")?),
+			152 => self.cash_order_qty = Some(value.parse().map_err(|_| "Invalid CashOrderQty")?),
+			40 => self.ord_type = value.to_string(),
+			44 => self.price = Some(value.parse().map_err(|_| "Invalid Price")?),
+			99 => self.stop_px = Some(value.parse().map_err(|_| "Invalid StopPx")?),
+			15 => self.currency = Some(value.to_string()),
+			59 => self.time_in_force = Some(value.parse().map_err(|_| "Invalid TimeInForce")?),
+			126 => self.expire_time = Some(parse_fix_timestamp(value)?),
+			58 => self.text = Some(value.to_string()),
+			_ => return Err(FieldError::Unknown(format!("Unknown order cancel replace request field: {}", tag))),
+		}
+		Ok(())
+	}
+
+	fn write_body_fields(&self, buffer: &mut String) {
+		self.write_to(buffer);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn amendment() -> OrderCancelReplaceRequestBody {
+		OrderCancelReplaceRequestBody {
+			orig_cl_ord_id: "ORD1".into(),
+			cl_ord_id: "ORD2".into(),
+			handl_inst: "1".into(),
+			symbol: "AAPL".into(),
+			ord_type: "2".into(),
+			order_qty: Some(200.0),
+			price: Some(151.25),
+			..Default::default()
+		}
+	}
+
+	#[test]
+	fn test_validation() {
+		assert!(OrderCancelReplaceRequestBody::default().validate().is_err());
+		assert!(amendment().validate().is_ok());
+
+		let no_qty = OrderCancelReplaceRequestBody { order_qty: None, ..amendment() };
+		assert!(matches!(no_qty.validate(), Err(ValidationError::ConditionallyRequiredFieldMissing(_))));
+		let cash = OrderCancelReplaceRequestBody { order_qty: None, cash_order_qty: Some(10_000.0), ..amendment() };
+		assert!(cash.validate().is_ok());
+	}
+
+	#[test]
+	fn test_parse_and_write() {
+		let mut body = OrderCancelReplaceRequestBody::default();
+		for (tag, value) in [
+			(37, "BRK1"),
+			(41, "ORD1"),
+			(11, "ORD2"),
+			(21, "1"),
+			(55, "AAPL"),
+			(54, "2"),
+			(60, "20240101-12:00:00.000"),
+			(38, "200"),
+			(40, "2"),
+			(44, "151.25"),
+			(59, "3"),
+		] {
+			body.parse_field(tag, value).unwrap();
+		}
+		assert!(body.validate().is_ok());
+		assert_eq!(body.side, Side::Sell);
+		assert_eq!(body.time_in_force, Some(TimeInForce::ImmediateOrCancel));
+		assert!(body.parse_field(59, "X").is_err());
+		assert!(body.parse_field(999, "x").is_err());
+
+		let mut s = String::new();
+		body.write_to(&mut s);
+		assert!(s.starts_with("37=BRK1\x0141=ORD1\x0111=ORD2\x0121=1\x0155=AAPL\x0154=2\x01"));
+		assert!(s.ends_with("38=200\x0140=2\x0144=151.25\x0159=3\x01"));
+	}
+}
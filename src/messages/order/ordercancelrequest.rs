@@ -8,25 +8,28 @@ use crate::{
 	SOH, Side,
 	common::{
 		Validate, ValidationError, parse_fix_timestamp,
-		validation::{FixFieldHandler, WriteTo},
+		validation::{FieldError, FixFieldHandler, WriteTo},
 		write_tag_timestamp,
 	},
+	messages::order::validate_order_qty,
 };
 use std::fmt::Write;
 use time::OffsetDateTime;
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OrderCancelRequestBody {
-	pub orig_cl_ord_id: String,        // 41 Required
-	pub cl_ord_id: String,             // 11 Required (unique id of this cancel request)
-	pub symbol: String,                // 55 Required
-	pub side: Side,                    // 54 Required
+	pub orig_cl_ord_id: String, // 41 Required
+	pub cl_ord_id: String,      // 11 Required (unique id of this cancel request)
+	pub symbol: String,         // 55 Required
+	pub side: Side,             // 54 Required
+	#[cfg_attr(feature = "serde", serde(with = "crate::serde_fix::timestamp"))]
 	pub transact_time: OffsetDateTime, // 60 Required
-	pub order_id: Option<String>,      // 37 Optional (most recent order id as assigned by broker)
-	pub order_qty: Option<f64>,        // 38 Either this or cash_order_qty required (per spec)
-	pub cash_order_qty: Option<f64>,   // 152
-	pub account: Option<String>,       // 1 Optional
-	pub text: Option<String>,          // 58 Optional
+	pub order_id: Option<String>, // 37 Optional (most recent order id as assigned by broker)
+	pub order_qty: Option<f64>, // 38 Either this or cash_order_qty required (per spec)
+	pub cash_order_qty: Option<f64>, // 152
+	pub account: Option<String>, // 1 Optional
+	pub text: Option<String>,   // 58 Optional
 }
 
 impl Default for OrderCancelRequestBody {
@@ -57,11 +60,8 @@ impl Validate for OrderCancelRequestBody {
 		if self.symbol.is_empty() {
 			return Err(ValidationError::MissingRequiredField("Symbol".into()));
 		}
-		// OrderQty or CashOrderQty required per spec (treat as required here)
-		if self.order_qty.is_none() && self.cash_order_qty.is_none() {
-			return Err(ValidationError::MissingRequiredField("OrderQty or CashOrderQty".into()));
-		}
-		Ok(())
+		// OrderQty or CashOrderQty required per spec
+		validate_order_qty(self.order_qty, self.cash_order_qty)
 	}
 }
 
@@ -92,7 +92,7 @@ impl WriteTo for OrderCancelRequestBody {
 }
 
 impl FixFieldHandler for OrderCancelRequestBody {
-	fn parse_field(&mut self, tag: u32, value: &str) -> Result<(), String> {
+	fn parse_field(&mut self, tag: u32, value: &str) -> Result<(), FieldError> {
 		match tag {
 			41 => self.orig_cl_ord_id = value.to_string(),
 			37 => self.order_id = Some(value.to_string()),
@@ -104,7 +104,7 @@ impl FixFieldHandler for OrderCancelRequestBody {
 			152 => self.cash_order_qty = Some(value.parse().map_err(|_| "Invalid CashOrderQty")?),
 			1 => self.account = Some(value.to_string()),
 			58 => self.text = Some(value.to_string()),
-			_ => return Err(format!("Unknown order cancel request field: {}", tag)),
+			_ => return Err(FieldError::Unknown(format!("Unknown order cancel request field: {}", tag))),
 		}
 		Ok(())
 	}
@@ -126,11 +126,13 @@ mod tests {
 
 	#[test]
 	fn test_validation_success() {
-		let mut body = OrderCancelRequestBody::default();
-		body.orig_cl_ord_id = "ORIG1".into();
-		body.cl_ord_id = "CXL1".into();
-		body.symbol = "AAPL".into();
-		body.order_qty = Some(100.0);
+		let body = OrderCancelRequestBody {
+			orig_cl_ord_id: "ORIG1".into(),
+			cl_ord_id: "CXL1".into(),
+			symbol: "AAPL".into(),
+			order_qty: Some(100.0),
+			..Default::default()
+		};
 		assert!(body.validate().is_ok());
 	}
 
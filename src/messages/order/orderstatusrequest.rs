@@ -0,0 +1,114 @@
+//! Order Status Request message implementation (MsgType=H)
+//!
+//! FIX 4.2 Order Status Request, used to query the current state of an order.
+//! The broker answers with an Execution Report carrying ExecTransType=Status.
+
+use crate::{
+	SOH, Side,
+	common::{
+		Validate, ValidationError,
+		validation::{FieldError, FixFieldHandler, WriteTo},
+	},
+};
+use std::fmt::Write;
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OrderStatusRequestBody {
+	/// Broker order ID (Tag 37) - Optional
+	pub order_id: Option<String>,
+	/// ClOrdID of the order being queried (Tag 11) - Required
+	pub cl_ord_id: String,
+	// (Tag 1) - Optional
+	pub account: Option<String>,
+	// (Tag 55) - Required
+	pub symbol: String,
+	// (Tag 207) - Optional
+	pub security_exchange: Option<String>,
+	// (Tag 54) - Required
+	pub side: Side,
+}
+
+impl Default for OrderStatusRequestBody {
+	fn default() -> Self {
+		Self {
+			order_id: None,
+			cl_ord_id: String::new(),
+			account: None,
+			symbol: String::new(),
+			security_exchange: None,
+			side: Side::Buy,
+		}
+	}
+}
+
+impl Validate for OrderStatusRequestBody {
+	fn validate(&self) -> Result<(), ValidationError> {
+		if self.cl_ord_id.is_empty() {
+			return Err(ValidationError::MissingRequiredField("ClOrdID".into()));
+		}
+		if self.symbol.is_empty() {
+			return Err(ValidationError::MissingRequiredField("Symbol".into()));
+		}
+		Ok(())
+	}
+}
+
+impl WriteTo for OrderStatusRequestBody {
+	fn write_to(&self, buffer: &mut String) {
+		if let Some(ref order_id) = self.order_id {
+			write!(buffer, "37={}{}", order_id, SOH).unwrap();
+		}
+		write!(buffer, "11={}{}", self.cl_ord_id, SOH).unwrap();
+		if let Some(ref account) = self.account {
+			write!(buffer, "1={}{}", account, SOH).unwrap();
+		}
+		write!(buffer, "55={}{}", self.symbol, SOH).unwrap();
+		if let Some(ref security_exchange) = self.security_exchange {
+			write!(buffer, "207={}{}", security_exchange, SOH).unwrap();
+		}
+		write!(buffer, "54={}{}", self.side, SOH).unwrap();
+	}
+}
+
+impl FixFieldHandler for OrderStatusRequestBody {
+	fn parse_field(&mut self, tag: u32, value: &str) -> Result<(), FieldError> {
+		match tag {
+			37 => self.order_id = Some(value.to_string()),
+			11 => self.cl_ord_id = value.to_string(),
+			1 => self.account = Some(value.to_string()),
+			55 => self.symbol = value.to_string(),
+			207 => self.security_exchange = Some(value.to_string()),
+			54 => self.side = value.parse().map_err(|_| "Invalid Side")?,
+			_ => return Err(FieldError::Unknown(format!("Unknown order status request field: {}", tag))),
+		}
+		Ok(())
+	}
+
+	fn write_body_fields(&self, buffer: &mut String) {
+		self.write_to(buffer);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_parse_and_write() {
+		let mut body = OrderStatusRequestBody::default();
+		assert!(body.validate().is_err());
+
+		for (tag, value) in [(37, "BRK1"), (11, "ORD1"), (55, "AAPL"), (54, "2")] {
+			body.parse_field(tag, value).unwrap();
+		}
+		assert!(body.validate().is_ok());
+		assert_eq!(body.side, Side::Sell);
+		assert!(body.parse_field(54, "X").is_err());
+		assert!(body.parse_field(999, "x").is_err());
+
+		let mut s = String::new();
+		body.write_to(&mut s);
+		assert_eq!(s, "37=BRK1\x0111=ORD1\x0155=AAPL\x0154=2\x01");
+	}
+}
@@ -6,7 +6,7 @@
 
 use crate::common::{
 	SOH, Validate, ValidationError,
-	validation::{FixFieldHandler, WriteTo},
+	validation::{FieldError, FixFieldHandler, WriteTo},
 };
 use std::fmt::Write;
 
@@ -16,6 +16,7 @@ use std::fmt::Write;
 /// It can also be sent in response to a Test Request message, in which case
 /// it must include the TestReqID from the original Test Request.
 #[derive(Debug, Clone, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HeartbeatBody {
 	/// Test request ID (Tag 112) - Required when the heartbeat is the result of a Test Request message
 	pub test_req_id: Option<String>,
@@ -50,10 +51,10 @@ impl HeartbeatBody {
 }
 
 impl FixFieldHandler for HeartbeatBody {
-	fn parse_field(&mut self, tag: u32, value: &str) -> Result<(), String> {
+	fn parse_field(&mut self, tag: u32, value: &str) -> Result<(), FieldError> {
 		match tag {
 			112 => self.test_req_id = Some(value.to_string()),
-			_ => return Err(format!("Unknown heartbeat field: {}", tag)),
+			_ => return Err(FieldError::Unknown(format!("Unknown heartbeat field: {}", tag))),
 		}
 		Ok(())
 	}
@@ -99,7 +100,10 @@ mod tests {
 		assert_eq!(heartbeat.test_req_id, Some("TEST_123".to_string()));
 
 		// Parse unknown field
-		assert!(heartbeat.parse_field(999, "unknown").is_err());
+		assert_eq!(
+			heartbeat.parse_field(999, "unknown"),
+			Err(FieldError::Unknown("Unknown heartbeat field: 999".into()))
+		);
 	}
 
 	#[test]
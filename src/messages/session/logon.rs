@@ -6,7 +6,7 @@
 
 use crate::common::{
 	EncryptMethod, SOH, Validate, ValidationError,
-	validation::{FixFieldHandler, WriteTo},
+	validation::{FieldError, FixFieldHandler, WriteTo},
 };
 use std::fmt::Write;
 
@@ -15,6 +15,7 @@ use std::fmt::Write;
 /// The Logon message is the first message sent to initiate a FIX session.
 /// It contains session parameters including encryption method and heartbeat interval.
 #[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LogonBody {
 	/// Encryption method (Tag 98) - Required
 	pub encrypt_method: EncryptMethod,
@@ -26,6 +27,12 @@ pub struct LogonBody {
 	pub next_expected_msg_seq_num: Option<u32>,
 	/// Maximum message size (Tag 383) - Optional
 	pub max_message_size: Option<u32>,
+	/// Raw data, e.g. an authentication token (Tag 96, length in Tag 95) - Optional
+	pub raw_data: Option<String>,
+	/// Username (Tag 553) - Optional, commonly accepted by FIX 4.2 venues
+	pub username: Option<String>,
+	/// Password (Tag 554) - Optional, commonly accepted by FIX 4.2 venues
+	pub password: Option<String>,
 }
 
 impl Default for LogonBody {
@@ -36,6 +43,9 @@ impl Default for LogonBody {
 			reset_seq_num_flag: None,
 			next_expected_msg_seq_num: None,
 			max_message_size: None,
+			raw_data: None,
+			username: None,
+			password: None,
 		}
 	}
 }
@@ -63,6 +73,15 @@ impl WriteTo for LogonBody {
 		if let Some(size) = self.max_message_size {
 			write!(buffer, "383={}{}", size, SOH).unwrap();
 		}
+		if let Some(raw_data) = &self.raw_data {
+			write!(buffer, "95={}{}96={}{}", raw_data.len(), SOH, raw_data, SOH).unwrap();
+		}
+		if let Some(username) = &self.username {
+			write!(buffer, "553={}{}", username, SOH).unwrap();
+		}
+		if let Some(password) = &self.password {
+			write!(buffer, "554={}{}", password, SOH).unwrap();
+		}
 	}
 }
 
@@ -89,10 +108,17 @@ impl LogonBody {
 		self.max_message_size = Some(size);
 		self
 	}
+
+	/// Set the username and password
+	pub fn with_credentials(mut self, username: impl Into<String>, password: impl Into<String>) -> Self {
+		self.username = Some(username.into());
+		self.password = Some(password.into());
+		self
+	}
 }
 
 impl FixFieldHandler for LogonBody {
-	fn parse_field(&mut self, tag: u32, value: &str) -> Result<(), String> {
+	fn parse_field(&mut self, tag: u32, value: &str) -> Result<(), FieldError> {
 		match tag {
 			98 => {
 				self.encrypt_method = value.parse().map_err(|_| "Invalid EncryptMethod")?;
@@ -109,7 +135,20 @@ impl FixFieldHandler for LogonBody {
 			383 => {
 				self.max_message_size = Some(value.parse().map_err(|_| "Invalid MaxMessageSize")?);
 			},
-			_ => return Err(format!("Unknown logon field: {}", tag)),
+			95 => {
+				// RawDataLength is recomputed from RawData when writing
+				value.parse::<usize>().map_err(|_| "Invalid RawDataLength")?;
+			},
+			96 => {
+				self.raw_data = Some(value.to_string());
+			},
+			553 => {
+				self.username = Some(value.to_string());
+			},
+			554 => {
+				self.password = Some(value.to_string());
+			},
+			_ => return Err(FieldError::Unknown(format!("Unknown logon field: {}", tag))),
 		}
 		Ok(())
 	}
@@ -197,6 +236,15 @@ mod tests {
 		assert!(logon.parse_field(383, "8192").is_ok());
 		assert_eq!(logon.max_message_size, Some(8192));
 
+		// Parse credentials
+		assert!(logon.parse_field(95, "5").is_ok());
+		assert!(logon.parse_field(96, "token").is_ok());
+		assert_eq!(logon.raw_data.as_deref(), Some("token"));
+		assert!(logon.parse_field(553, "trader").is_ok());
+		assert!(logon.parse_field(554, "secret").is_ok());
+		assert_eq!(logon.username.as_deref(), Some("trader"));
+		assert_eq!(logon.password.as_deref(), Some("secret"));
+
 		// Parse unknown field
 		assert!(logon.parse_field(999, "unknown").is_err());
 
@@ -0,0 +1,72 @@
+//! Logout message implementation (MsgType=5)
+//!
+//! This module implements the FIX 4.2 Logout message, which initiates or
+//! confirms the termination of a FIX session. The side receiving a Logout
+//! answers with its own Logout before the connection is closed.
+
+use crate::common::{
+	SOH, Validate, ValidationError,
+	validation::{FieldError, FixFieldHandler, WriteTo},
+};
+use std::fmt::Write;
+
+/// Logout message body (Tag 35=5)
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LogoutBody {
+	/// Free format explanation of the logout (Tag 58) - Optional
+	pub text: Option<String>,
+}
+
+impl Validate for LogoutBody {
+	fn validate(&self) -> Result<(), ValidationError> {
+		Ok(())
+	}
+}
+
+impl WriteTo for LogoutBody {
+	fn write_to(&self, buffer: &mut String) {
+		if let Some(ref text) = self.text {
+			write!(buffer, "58={}{}", text, SOH).unwrap();
+		}
+	}
+}
+
+impl LogoutBody {
+	/// Create a logout with an explanatory text
+	pub fn with_text(text: impl Into<String>) -> Self {
+		Self { text: Some(text.into()) }
+	}
+}
+
+impl FixFieldHandler for LogoutBody {
+	fn parse_field(&mut self, tag: u32, value: &str) -> Result<(), FieldError> {
+		match tag {
+			58 => self.text = Some(value.to_string()),
+			_ => return Err(FieldError::Unknown(format!("Unknown logout field: {}", tag))),
+		}
+		Ok(())
+	}
+
+	fn write_body_fields(&self, buffer: &mut String) {
+		self.write_to(buffer);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_logout_parse_and_write() {
+		let mut body = LogoutBody::default();
+		assert!(body.is_valid());
+		assert!(body.parse_field(58, "End of day").is_ok());
+		assert_eq!(body, LogoutBody::with_text("End of day"));
+		assert!(body.parse_field(999, "x").is_err());
+
+		let mut s = String::new();
+		body.write_to(&mut s);
+		assert_eq!(s, "58=End of day\x01");
+	}
+}
@@ -1,16 +1,26 @@
 //! Session-level FIX messages
 //!
 //! This module contains implementations for FIX messages that handle
-//! session management, including Heartbeat, Logon, Logout, and TestRequest.
+//! session management, including Heartbeat, Logon, Logout, TestRequest and Reject.
 //! These messages are fundamental to maintaining FIX session state and
 //! connection liveness.
 
 pub mod heartbeat;
 pub mod logon;
+pub mod logout;
+pub mod reject;
+pub mod resendrequest;
+pub mod sequencereset;
+pub mod testrequest;
 
 // Re-export message body types for convenience
 pub use heartbeat::HeartbeatBody;
 pub use logon::LogonBody;
+pub use logout::LogoutBody;
+pub use reject::RejectBody;
+pub use resendrequest::ResendRequestBody;
+pub use sequencereset::SequenceResetBody;
+pub use testrequest::TestRequestBody;
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,119 @@
+//! Reject message implementation (MsgType=3)
+//!
+//! This module implements the FIX 4.2 session-level Reject message, which is
+//! sent when a message is received but cannot be processed because it breaks
+//! session-level rules: an invalid tag, a missing required field, a value in
+//! the wrong format and so on. The rejected message still consumes its
+//! sequence number.
+
+use crate::common::{
+	MsgType, ParseError, SOH, SessionRejectReason, Validate, ValidationError,
+	validation::{FieldError, FixFieldHandler, WriteTo},
+};
+use std::fmt::Write;
+
+/// Reject message body (Tag 35=3)
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RejectBody {
+	/// MsgSeqNum of the rejected message (Tag 45) - Required
+	pub ref_seq_num: u32,
+	/// Tag number of the field at fault (Tag 371) - Optional
+	pub ref_tag_id: Option<u32>,
+	/// MsgType of the rejected message (Tag 372) - Optional
+	pub ref_msg_type: Option<MsgType>,
+	/// Code identifying the reject reason (Tag 373) - Optional
+	pub session_reject_reason: Option<SessionRejectReason>,
+	/// Free format explanation of the reject (Tag 58) - Optional
+	pub text: Option<String>,
+}
+
+impl Validate for RejectBody {
+	fn validate(&self) -> Result<(), ValidationError> {
+		if self.ref_seq_num == 0 {
+			return Err(ValidationError::MissingRequiredField("RefSeqNum".to_string()));
+		}
+		Ok(())
+	}
+}
+
+impl WriteTo for RejectBody {
+	fn write_to(&self, buffer: &mut String) {
+		write!(buffer, "45={}{}", self.ref_seq_num, SOH).unwrap();
+		if let Some(tag) = self.ref_tag_id {
+			write!(buffer, "371={}{}", tag, SOH).unwrap();
+		}
+		if let Some(ref msg_type) = self.ref_msg_type {
+			write!(buffer, "372={}{}", msg_type, SOH).unwrap();
+		}
+		if let Some(ref reason) = self.session_reject_reason {
+			write!(buffer, "373={}{}", reason, SOH).unwrap();
+		}
+		if let Some(ref text) = self.text {
+			write!(buffer, "58={}{}", text, SOH).unwrap();
+		}
+	}
+}
+
+impl RejectBody {
+	/// Create a reject of message `ref_seq_num` for `reason`
+	pub const fn new(ref_seq_num: u32, reason: SessionRejectReason) -> Self {
+		Self { ref_seq_num, ref_tag_id: None, ref_msg_type: None, session_reject_reason: Some(reason), text: None }
+	}
+
+	/// Create the reject answering a message that failed to parse
+	///
+	/// Returns `None` when the message was too garbled to know its MsgSeqNum.
+	pub fn for_parse_error(error: &ParseError) -> Option<Self> {
+		let header = error.header.as_ref()?;
+		Some(Self {
+			ref_tag_id: error.ref_tag_id,
+			ref_msg_type: Some(header.msg_type.clone()),
+			text: Some(error.text.clone()),
+			..Self::new(header.msg_seq_num, error.reason.clone())
+		})
+	}
+}
+
+impl FixFieldHandler for RejectBody {
+	fn parse_field(&mut self, tag: u32, value: &str) -> Result<(), FieldError> {
+		match tag {
+			45 => self.ref_seq_num = value.parse().map_err(|_| "Invalid RefSeqNum")?,
+			371 => self.ref_tag_id = Some(value.parse().map_err(|_| "Invalid RefTagID")?),
+			372 => self.ref_msg_type = Some(value.parse().map_err(|_| "Invalid RefMsgType")?),
+			373 => self.session_reject_reason = Some(value.parse().map_err(|_| "Invalid SessionRejectReason")?),
+			58 => self.text = Some(value.to_string()),
+			_ => return Err(FieldError::Unknown(format!("Unknown reject field: {}", tag))),
+		}
+		Ok(())
+	}
+
+	fn write_body_fields(&self, buffer: &mut String) {
+		self.write_to(buffer);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_reject_parse_and_write() {
+		let mut body = RejectBody::default();
+		assert!(!body.is_valid());
+
+		assert!(body.parse_field(45, "7").is_ok());
+		assert!(body.parse_field(371, "54").is_ok());
+		assert!(body.parse_field(372, "D").is_ok());
+		assert!(body.parse_field(373, "5").is_ok());
+		assert!(body.parse_field(58, "Invalid Side").is_ok());
+		assert!(body.is_valid());
+		assert_eq!(body.session_reject_reason, Some(SessionRejectReason::ValueIsIncorrect));
+		assert!(body.parse_field(373, "42").is_err());
+		assert!(body.parse_field(999, "x").is_err());
+
+		let mut s = String::new();
+		body.write_to(&mut s);
+		assert_eq!(s, "45=7\x01371=54\x01372=D\x01373=5\x0158=Invalid Side\x01");
+	}
+}
@@ -0,0 +1,99 @@
+//! Resend Request message implementation (MsgType=2)
+//!
+//! This module implements the FIX 4.2 Resend Request message, which is sent by
+//! the receiving side of a session when it detects a sequence gap, asking the
+//! counterparty to retransmit a range of messages.
+
+use crate::common::{
+	SOH, Validate, ValidationError,
+	validation::{FieldError, FixFieldHandler, WriteTo},
+};
+use std::fmt::Write;
+
+/// Resend Request message body (Tag 35=2)
+///
+/// Requests retransmission of messages `BeginSeqNo..=EndSeqNo`. An EndSeqNo of
+/// zero means "all messages after BeginSeqNo" (infinity).
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ResendRequestBody {
+	/// First message sequence number to resend (Tag 7) - Required
+	pub begin_seq_no: u32,
+	/// Last message sequence number to resend, 0 for infinity (Tag 16) - Required
+	pub end_seq_no: u32,
+}
+
+impl Default for ResendRequestBody {
+	fn default() -> Self {
+		Self { begin_seq_no: 1, end_seq_no: 0 }
+	}
+}
+
+impl Validate for ResendRequestBody {
+	fn validate(&self) -> Result<(), ValidationError> {
+		if self.begin_seq_no == 0 {
+			return Err(ValidationError::InvalidFieldValue("BeginSeqNo".to_string(), "0".to_string()));
+		}
+		if self.end_seq_no != 0 && self.end_seq_no < self.begin_seq_no {
+			return Err(ValidationError::ValueOutOfRange("EndSeqNo".to_string(), self.end_seq_no.to_string()));
+		}
+		Ok(())
+	}
+}
+
+impl WriteTo for ResendRequestBody {
+	fn write_to(&self, buffer: &mut String) {
+		write!(buffer, "7={}{}", self.begin_seq_no, SOH).unwrap();
+		write!(buffer, "16={}{}", self.end_seq_no, SOH).unwrap();
+	}
+}
+
+impl ResendRequestBody {
+	/// Create a resend request for `begin_seq_no..=end_seq_no` (0 = infinity)
+	pub const fn new(begin_seq_no: u32, end_seq_no: u32) -> Self {
+		Self { begin_seq_no, end_seq_no }
+	}
+}
+
+impl FixFieldHandler for ResendRequestBody {
+	fn parse_field(&mut self, tag: u32, value: &str) -> Result<(), FieldError> {
+		match tag {
+			7 => self.begin_seq_no = value.parse().map_err(|_| "Invalid BeginSeqNo")?,
+			16 => self.end_seq_no = value.parse().map_err(|_| "Invalid EndSeqNo")?,
+			_ => return Err(FieldError::Unknown(format!("Unknown resend request field: {}", tag))),
+		}
+		Ok(())
+	}
+
+	fn write_body_fields(&self, buffer: &mut String) {
+		self.write_to(buffer);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_resend_request_validation() {
+		assert!(ResendRequestBody::new(1, 0).is_valid());
+		assert!(ResendRequestBody::new(5, 10).is_valid());
+		assert!(!ResendRequestBody::new(0, 10).is_valid());
+		assert!(!ResendRequestBody::new(10, 5).is_valid());
+	}
+
+	#[test]
+	fn test_resend_request_parse_and_write() {
+		let mut body = ResendRequestBody::default();
+		assert!(body.parse_field(7, "3").is_ok());
+		assert!(body.parse_field(16, "9").is_ok());
+		assert_eq!(body, ResendRequestBody::new(3, 9));
+
+		let mut s = String::new();
+		body.write_to(&mut s);
+		assert_eq!(s, "7=3\x0116=9\x01");
+
+		assert!(body.parse_field(7, "abc").is_err());
+		assert!(body.parse_field(999, "1").is_err());
+	}
+}
@@ -0,0 +1,109 @@
+//! Sequence Reset message implementation (MsgType=4)
+//!
+//! This module implements the FIX 4.2 Sequence Reset message. In Gap Fill mode
+//! (GapFillFlag=Y) it is sent during message recovery in place of administrative
+//! messages that must not be resent; in Reset mode it forcibly moves the
+//! receiver's expected sequence number.
+
+use crate::common::{
+	SOH, Validate, ValidationError,
+	validation::{FieldError, FixFieldHandler, WriteTo},
+};
+use std::fmt::Write;
+
+/// Sequence Reset message body (Tag 35=4)
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SequenceResetBody {
+	/// Gap fill flag (Tag 123) - Optional, absent means Reset mode
+	pub gap_fill_flag: Option<bool>,
+	/// Next sequence number to be expected by the receiver (Tag 36) - Required
+	pub new_seq_no: u32,
+}
+
+impl Default for SequenceResetBody {
+	fn default() -> Self {
+		Self { gap_fill_flag: None, new_seq_no: 1 }
+	}
+}
+
+impl Validate for SequenceResetBody {
+	fn validate(&self) -> Result<(), ValidationError> {
+		if self.new_seq_no == 0 {
+			return Err(ValidationError::InvalidFieldValue("NewSeqNo".to_string(), "0".to_string()));
+		}
+		Ok(())
+	}
+}
+
+impl WriteTo for SequenceResetBody {
+	fn write_to(&self, buffer: &mut String) {
+		if let Some(flag) = self.gap_fill_flag {
+			write!(buffer, "123={}{}", if flag { "Y" } else { "N" }, SOH).unwrap();
+		}
+		write!(buffer, "36={}{}", self.new_seq_no, SOH).unwrap();
+	}
+}
+
+impl SequenceResetBody {
+	/// Create a Gap Fill sequence reset moving the receiver to `new_seq_no`
+	pub const fn gap_fill(new_seq_no: u32) -> Self {
+		Self { gap_fill_flag: Some(true), new_seq_no }
+	}
+
+	/// Create a Reset mode sequence reset moving the receiver to `new_seq_no`
+	pub const fn reset(new_seq_no: u32) -> Self {
+		Self { gap_fill_flag: None, new_seq_no }
+	}
+
+	/// Whether this is a Gap Fill (as opposed to a Reset)
+	pub fn is_gap_fill(&self) -> bool {
+		self.gap_fill_flag == Some(true)
+	}
+}
+
+impl FixFieldHandler for SequenceResetBody {
+	fn parse_field(&mut self, tag: u32, value: &str) -> Result<(), FieldError> {
+		match tag {
+			123 => self.gap_fill_flag = Some(value == "Y"),
+			36 => self.new_seq_no = value.parse().map_err(|_| "Invalid NewSeqNo")?,
+			_ => return Err(FieldError::Unknown(format!("Unknown sequence reset field: {}", tag))),
+		}
+		Ok(())
+	}
+
+	fn write_body_fields(&self, buffer: &mut String) {
+		self.write_to(buffer);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_sequence_reset_modes() {
+		let gap_fill = SequenceResetBody::gap_fill(10);
+		assert!(gap_fill.is_gap_fill());
+		assert!(gap_fill.is_valid());
+
+		let reset = SequenceResetBody::reset(10);
+		assert!(!reset.is_gap_fill());
+		assert!(!SequenceResetBody::reset(0).is_valid());
+	}
+
+	#[test]
+	fn test_sequence_reset_parse_and_write() {
+		let mut body = SequenceResetBody::default();
+		assert!(body.parse_field(123, "Y").is_ok());
+		assert!(body.parse_field(36, "42").is_ok());
+		assert_eq!(body, SequenceResetBody::gap_fill(42));
+
+		let mut s = String::new();
+		body.write_to(&mut s);
+		assert_eq!(s, "123=Y\x0136=42\x01");
+
+		assert!(body.parse_field(36, "x").is_err());
+		assert!(body.parse_field(999, "1").is_err());
+	}
+}
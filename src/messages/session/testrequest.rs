@@ -0,0 +1,77 @@
+//! Test Request message implementation (MsgType=1)
+//!
+//! This module implements the FIX 4.2 Test Request message, which forces the
+//! counterparty to send a Heartbeat carrying the same TestReqID. It is used to
+//! check that a silent connection is still alive.
+
+use crate::common::{
+	SOH, Validate, ValidationError,
+	validation::{FieldError, FixFieldHandler, WriteTo},
+};
+use std::fmt::Write;
+
+/// Test Request message body (Tag 35=1)
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TestRequestBody {
+	/// Identifier echoed back in the responding Heartbeat (Tag 112) - Required
+	pub test_req_id: String,
+}
+
+impl Validate for TestRequestBody {
+	fn validate(&self) -> Result<(), ValidationError> {
+		if self.test_req_id.is_empty() {
+			return Err(ValidationError::MissingRequiredField("TestReqID".to_string()));
+		}
+		Ok(())
+	}
+}
+
+impl WriteTo for TestRequestBody {
+	fn write_to(&self, buffer: &mut String) {
+		write!(buffer, "112={}{}", self.test_req_id, SOH).unwrap();
+	}
+}
+
+impl TestRequestBody {
+	/// Create a test request with the given TestReqID
+	pub fn new(test_req_id: impl Into<String>) -> Self {
+		Self { test_req_id: test_req_id.into() }
+	}
+}
+
+impl FixFieldHandler for TestRequestBody {
+	fn parse_field(&mut self, tag: u32, value: &str) -> Result<(), FieldError> {
+		match tag {
+			112 => self.test_req_id = value.to_string(),
+			_ => return Err(FieldError::Unknown(format!("Unknown test request field: {}", tag))),
+		}
+		Ok(())
+	}
+
+	fn write_body_fields(&self, buffer: &mut String) {
+		self.write_to(buffer);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_test_request_validation() {
+		assert!(TestRequestBody::new("TEST1").is_valid());
+		assert!(!TestRequestBody::default().is_valid());
+	}
+
+	#[test]
+	fn test_test_request_parse_and_write() {
+		let mut body = TestRequestBody::default();
+		assert!(body.parse_field(112, "PING").is_ok());
+		assert!(body.parse_field(999, "x").is_err());
+
+		let mut s = String::new();
+		body.write_to(&mut s);
+		assert_eq!(s, "112=PING\x01");
+	}
+}
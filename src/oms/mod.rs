@@ -0,0 +1,15 @@
+//! Order management building blocks
+//!
+//! Components an order management system builds on the message types: the
+//! [`OrderTracker`] following the state of the orders we send, the
+//! [`PreTradeRisk`] checks they pass before being sent and the
+//! [`PositionTracker`] keeping positions and P&L from their fills.
+
+pub mod position;
+pub mod risk;
+pub mod tracker;
+
+// Re-export commonly used types
+pub use position::{Position, PositionError, PositionTracker};
+pub use risk::{PreTradeRisk, RiskError, RiskGate, SymbolLimits};
+pub use tracker::{OrderTracker, PendingRequest, TrackedOrder, TrackerError};
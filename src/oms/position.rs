@@ -0,0 +1,302 @@
+//! Position and P&L keeping
+//!
+//! [`PositionTracker`] builds the net position, average cost and realised P&L
+//! of every account and symbol from the fills reported in Execution Reports
+//! (LastShares, LastPx and Side). Unrealised P&L is valued at a mark price,
+//! the last fill price of the symbol unless set explicitly.
+//!
+//! Trade cancels and corrects (ExecType H and G, or ExecTransType 1 and 2)
+//! remove or amend the fill named by their ExecRefID, after which the position
+//! is rebuilt from the remaining fills.
+
+use crate::{
+	Side,
+	common::enums::{ExecTransType, ExecType},
+	messages::ExecutionReportBody,
+};
+use std::{collections::HashMap, fmt::Display};
+
+/// Errors raised while applying fills
+#[derive(Debug, Clone, PartialEq)]
+pub enum PositionError {
+	/// A trade cancel or correct has no ExecRefID
+	MissingExecRefId(String),
+	/// The ExecRefID of a trade cancel or correct names no known fill
+	UnknownExecRefId(String),
+	/// A fill or trade correct lacks LastShares or LastPx
+	MissingFillDetails(String),
+}
+
+impl Display for PositionError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::MissingExecRefId(exec_id) => write!(f, "Execution {} amends a trade but has no ExecRefID", exec_id),
+			Self::UnknownExecRefId(exec_ref_id) => write!(f, "Unknown ExecRefID {}", exec_ref_id),
+			Self::MissingFillDetails(exec_id) => write!(f, "Execution {} lacks LastShares or LastPx", exec_id),
+		}
+	}
+}
+
+impl std::error::Error for PositionError {}
+
+/// Net position and P&L of one account in one symbol
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Position {
+	pub account: Option<String>,
+	pub symbol: String,
+	/// Quantity held, negative when short
+	pub net_qty: f64,
+	/// Average price of the quantity held, zero when flat
+	pub avg_cost: f64,
+	pub realized_pnl: f64,
+}
+
+impl Position {
+	/// P&L of the quantity held if it were closed at `mark`
+	pub fn unrealized_pnl(&self, mark: f64) -> f64 {
+		(mark - self.avg_cost) * self.net_qty
+	}
+
+	fn apply(&mut self, side: &Side, qty: f64, price: f64) {
+		// Nothing traded; averaging it into a flat position would divide by zero
+		if qty == 0.0 {
+			return;
+		}
+		let qty = match side {
+			Side::Buy => qty,
+			Side::Sell => -qty,
+		};
+		if self.net_qty == 0.0 || self.net_qty.signum() == qty.signum() {
+			let held = self.net_qty.abs();
+			self.avg_cost = (self.avg_cost * held + price * qty.abs()) / (held + qty.abs());
+			self.net_qty += qty;
+			return;
+		}
+
+		let closed = qty.abs().min(self.net_qty.abs());
+		self.realized_pnl += closed * (price - self.avg_cost) * self.net_qty.signum();
+		self.net_qty += qty;
+		if qty.abs() > closed {
+			// The fill went through flat and opened a position the other way
+			self.avg_cost = price;
+		} else if self.net_qty == 0.0 {
+			self.avg_cost = 0.0;
+		}
+	}
+}
+
+/// A fill counted in a position
+#[derive(Debug, Clone, PartialEq)]
+struct Trade {
+	exec_id: String,
+	side: Side,
+	qty: f64,
+	price: f64,
+}
+
+type PositionKey = (Option<String>, String);
+
+#[derive(Debug, Clone, Default)]
+struct Book {
+	trades: Vec<Trade>,
+	position: Position,
+}
+
+impl Book {
+	fn rebuild(&mut self) {
+		let mut position = Position {
+			account: self.position.account.take(),
+			symbol: std::mem::take(&mut self.position.symbol),
+			..Default::default()
+		};
+		for trade in &self.trades {
+			position.apply(&trade.side, trade.qty, trade.price);
+		}
+		self.position = position;
+	}
+}
+
+/// Positions and P&L per account and symbol, fed by Execution Reports
+#[derive(Debug, Clone, Default)]
+pub struct PositionTracker {
+	books: HashMap<PositionKey, Book>,
+	/// Position and ExecID of the original fill, by the ExecID of the fill and of its corrections
+	exec_ids: HashMap<String, (PositionKey, String)>,
+	marks: HashMap<String, f64>,
+}
+
+impl PositionTracker {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Value the positions in `symbol` at `price` until the next fill
+	pub fn set_mark_price(&mut self, symbol: impl Into<String>, price: f64) {
+		self.marks.insert(symbol.into(), price);
+	}
+
+	/// Apply the fill, trade cancel or trade correct carried by `report`
+	///
+	/// Returns the position it changed; reports without a fill, status reports
+	/// (ExecTransType 3) and fills whose ExecID was already applied change
+	/// nothing and return `None`.
+	pub fn on_execution_report(&mut self, report: &ExecutionReportBody) -> Result<Option<&Position>, PositionError> {
+		let canceled = report.exec_type == ExecType::TradeCancel || report.exec_trans_type == ExecTransType::Cancel;
+		let corrected = report.exec_type == ExecType::TradeCorrect || report.exec_trans_type == ExecTransType::Correct;
+		if canceled || corrected {
+			let exec_ref_id =
+				report.exec_ref_id.as_ref().ok_or_else(|| PositionError::MissingExecRefId(report.exec_id.clone()))?;
+			let (key, original) = self
+				.exec_ids
+				.get(exec_ref_id)
+				.cloned()
+				.ok_or_else(|| PositionError::UnknownExecRefId(exec_ref_id.clone()))?;
+			let book = self.books.get_mut(&key).expect("indexed position");
+			let index = book.trades.iter().position(|trade| trade.exec_id == original).expect("indexed fill");
+			if canceled {
+				book.trades.remove(index);
+				self.exec_ids.retain(|_, (_, exec_id)| *exec_id != original);
+			} else {
+				let (qty, price) = fill_details(report)?;
+				book.trades[index].qty = qty;
+				book.trades[index].price = price;
+				self.exec_ids.insert(report.exec_id.clone(), (key, original));
+			}
+			book.rebuild();
+			return Ok(Some(&book.position));
+		}
+
+		// Status reports repeat the last fill of the order rather than report a new one
+		let is_fill = matches!(report.exec_type, ExecType::PartialFill | ExecType::Fill | ExecType::Trade)
+			&& report.exec_trans_type != ExecTransType::Status;
+		if !is_fill || self.exec_ids.contains_key(&report.exec_id) {
+			return Ok(None);
+		}
+		let (qty, price) = fill_details(report)?;
+		let key = (report.account.clone(), report.symbol.clone());
+		self.exec_ids.insert(report.exec_id.clone(), (key.clone(), report.exec_id.clone()));
+		self.marks.insert(report.symbol.clone(), price);
+		let book = self.books.entry(key).or_insert_with(|| Book {
+			trades: Vec::new(),
+			position: Position { account: report.account.clone(), symbol: report.symbol.clone(), ..Default::default() },
+		});
+		book.trades.push(Trade { exec_id: report.exec_id.clone(), side: report.side.clone(), qty, price });
+		book.position.apply(&report.side, qty, price);
+		Ok(Some(&book.position))
+	}
+
+	pub fn position(&self, account: Option<&str>, symbol: &str) -> Option<&Position> {
+		self.books.get(&(account.map(str::to_string), symbol.to_string())).map(|book| &book.position)
+	}
+
+	pub fn positions(&self) -> impl Iterator<Item = &Position> {
+		self.books.values().map(|book| &book.position)
+	}
+
+	/// Unrealised P&L of a position at the mark price of its symbol
+	pub fn unrealized_pnl(&self, account: Option<&str>, symbol: &str) -> Option<f64> {
+		let position = self.position(account, symbol)?;
+		Some(position.unrealized_pnl(*self.marks.get(symbol)?))
+	}
+
+	/// Realised plus unrealised P&L of every position of `account`
+	pub fn total_pnl(&self, account: Option<&str>) -> f64 {
+		self.positions()
+			.filter(|position| position.account.as_deref() == account)
+			.map(|position| {
+				let mark = self.marks.get(&position.symbol).copied().unwrap_or(position.avg_cost);
+				position.realized_pnl + position.unrealized_pnl(mark)
+			})
+			.sum()
+	}
+}
+
+fn fill_details(report: &ExecutionReportBody) -> Result<(f64, f64), PositionError> {
+	match (report.last_shares, report.last_px) {
+		(Some(qty), Some(price)) => Ok((qty, price)),
+		_ => Err(PositionError::MissingFillDetails(report.exec_id.clone())),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn fill(exec_id: &str, side: Side, qty: f64, price: f64) -> ExecutionReportBody {
+		ExecutionReportBody {
+			exec_type: ExecType::PartialFill,
+			symbol: "AAPL".into(),
+			side,
+			last_shares: Some(qty),
+			last_px: Some(price),
+			account: Some("ACC1".into()),
+			..ExecutionReportBody::new("OID", exec_id)
+		}
+	}
+
+	#[test]
+	fn test_average_cost_and_pnl() {
+		let mut tracker = PositionTracker::new();
+		tracker.on_execution_report(&fill("E1", Side::Buy, 100.0, 10.0)).unwrap();
+		tracker.on_execution_report(&fill("E2", Side::Buy, 100.0, 12.0)).unwrap();
+		let position = tracker.position(Some("ACC1"), "AAPL").unwrap();
+		assert_eq!((position.net_qty, position.avg_cost), (200.0, 11.0));
+		assert_eq!(tracker.unrealized_pnl(Some("ACC1"), "AAPL"), Some(200.0));
+
+		let position = tracker.on_execution_report(&fill("E3", Side::Sell, 250.0, 13.0)).unwrap().unwrap();
+		assert_eq!((position.net_qty, position.avg_cost, position.realized_pnl), (-50.0, 13.0, 400.0));
+		tracker.set_mark_price("AAPL", 12.0);
+		assert_eq!(tracker.unrealized_pnl(Some("ACC1"), "AAPL"), Some(50.0));
+		assert_eq!(tracker.total_pnl(Some("ACC1")), 450.0);
+
+		// A replayed fill is counted once, other accounts are kept apart
+		assert_eq!(tracker.on_execution_report(&fill("E3", Side::Sell, 250.0, 13.0)).unwrap(), None);
+		let other = ExecutionReportBody { account: None, ..fill("E4", Side::Sell, 10.0, 12.0) };
+		tracker.on_execution_report(&other).unwrap();
+		assert_eq!(tracker.position(None, "AAPL").unwrap().net_qty, -10.0);
+		assert_eq!(tracker.positions().count(), 2);
+
+		let empty = tracker.on_execution_report(&fill("E6", Side::Buy, 0.0, 12.0)).unwrap().unwrap();
+		assert_eq!((empty.net_qty, empty.avg_cost), (-50.0, 13.0));
+		let mut flat = PositionTracker::new();
+		let empty = flat.on_execution_report(&fill("E1", Side::Buy, 0.0, 12.0)).unwrap().unwrap();
+		assert_eq!((empty.net_qty, empty.avg_cost), (0.0, 0.0));
+
+		let status =
+			ExecutionReportBody { exec_trans_type: ExecTransType::Status, ..fill("E5", Side::Buy, 10.0, 12.0) };
+		assert_eq!(tracker.on_execution_report(&status).unwrap(), None);
+		assert_eq!(tracker.position(Some("ACC1"), "AAPL").unwrap().net_qty, -50.0);
+	}
+
+	#[test]
+	fn test_trade_cancel_and_correct() {
+		let mut tracker = PositionTracker::new();
+		tracker.on_execution_report(&fill("E1", Side::Buy, 100.0, 10.0)).unwrap();
+		tracker.on_execution_report(&fill("E2", Side::Sell, 50.0, 11.0)).unwrap();
+
+		let correct = ExecutionReportBody {
+			exec_type: ExecType::TradeCorrect,
+			exec_ref_id: Some("E2".into()),
+			..fill("E3", Side::Sell, 40.0, 12.0)
+		};
+		let position = tracker.on_execution_report(&correct).unwrap().unwrap();
+		assert_eq!((position.net_qty, position.realized_pnl), (60.0, 80.0));
+
+		// The corrected fill can be canceled by the ExecID of its correction
+		let cancel = ExecutionReportBody {
+			exec_trans_type: ExecTransType::Cancel,
+			exec_ref_id: Some("E3".into()),
+			last_shares: None,
+			last_px: None,
+			..fill("E4", Side::Sell, 0.0, 0.0)
+		};
+		let position = tracker.on_execution_report(&cancel).unwrap().unwrap();
+		assert_eq!((position.net_qty, position.avg_cost, position.realized_pnl), (100.0, 10.0, 0.0));
+		assert_eq!(tracker.on_execution_report(&cancel), Err(PositionError::UnknownExecRefId("E3".into())));
+
+		let unreferenced = ExecutionReportBody { exec_ref_id: None, ..cancel };
+		assert_eq!(tracker.on_execution_report(&unreferenced), Err(PositionError::MissingExecRefId("E4".into())));
+		let incomplete = ExecutionReportBody { last_px: None, ..fill("E5", Side::Buy, 1.0, 1.0) };
+		assert_eq!(tracker.on_execution_report(&incomplete), Err(PositionError::MissingFillDetails("E5".into())));
+	}
+}
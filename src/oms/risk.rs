@@ -0,0 +1,383 @@
+//! Pre-trade risk checks
+//!
+//! [`PreTradeRisk`] checks every New Order Single before it leaves the process:
+//! restricted symbols, duplicate ClOrdIDs, maximum quantity and notional per
+//! symbol, price bands around a reference price and a per-session order rate.
+//! An engine calls [`PreTradeRisk::check`] before sending; [`RiskGate`] plugs the
+//! same checks into the outbound path of a [`Session`](crate::Session).
+
+use crate::{
+	FixMessage,
+	messages::{FixMessageBody, NewOrderSingleBody},
+	session::{Application, BusinessReject, DoNotSend, RejectLogon, SessionId},
+};
+use std::{
+	collections::{HashMap, HashSet, VecDeque},
+	fmt::Display,
+	sync::{Arc, Mutex, PoisonError},
+};
+use time::{Duration, OffsetDateTime};
+
+/// Reasons for refusing an order
+#[derive(Debug, Clone, PartialEq)]
+pub enum RiskError {
+	/// The symbol may not be traded
+	RestrictedSymbol(String),
+	/// The ClOrdID was already used on the session
+	DuplicateClOrdId(String),
+	/// OrderQty is above the limit of the symbol
+	MaxOrderQty { symbol: String, order_qty: f64, limit: f64 },
+	/// OrderQty times the price is above the limit of the symbol
+	MaxNotional { symbol: String, notional: f64, limit: f64 },
+	/// The price is too far from the reference price
+	OutsidePriceBand { symbol: String, price: f64, low: f64, high: f64 },
+	/// A check needs a reference price and the symbol has none
+	NoReferencePrice(String),
+	/// The session already sent the maximum number of orders in the window
+	RateLimit { max_orders: usize, window: Duration },
+}
+
+impl Display for RiskError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::RestrictedSymbol(symbol) => write!(f, "Symbol {} is restricted", symbol),
+			Self::DuplicateClOrdId(id) => write!(f, "Duplicate ClOrdID {}", id),
+			Self::MaxOrderQty { symbol, order_qty, limit } => {
+				write!(f, "OrderQty {} for {} exceeds the limit of {}", order_qty, symbol, limit)
+			},
+			Self::MaxNotional { symbol, notional, limit } => {
+				write!(f, "Notional {} for {} exceeds the limit of {}", notional, symbol, limit)
+			},
+			Self::OutsidePriceBand { symbol, price, low, high } => {
+				write!(f, "Price {} for {} is outside the band {} - {}", price, symbol, low, high)
+			},
+			Self::NoReferencePrice(symbol) => write!(f, "No reference price for {}", symbol),
+			Self::RateLimit { max_orders, window } => {
+				write!(f, "More than {} orders in {}", max_orders, window)
+			},
+		}
+	}
+}
+
+impl std::error::Error for RiskError {}
+
+/// Limits applying to the orders of one symbol
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct SymbolLimits {
+	/// Largest OrderQty allowed
+	pub max_order_qty: Option<f64>,
+	/// Largest OrderQty times price allowed, market orders are valued at the reference price
+	pub max_notional: Option<f64>,
+	/// Largest distance of a limit price from the reference price, as a fraction of it
+	pub price_band: Option<f64>,
+}
+
+/// Configurable checks run on outbound New Order Singles
+///
+/// Symbols without limits of their own use the default limits. An order passing
+/// every check is recorded: its ClOrdID can no longer be reused and it counts
+/// towards the rate limit of its session.
+#[derive(Debug, Clone, Default)]
+pub struct PreTradeRisk {
+	default_limits: SymbolLimits,
+	limits: HashMap<String, SymbolLimits>,
+	reference_prices: HashMap<String, f64>,
+	restricted: HashSet<String>,
+	rate_limit: Option<(usize, Duration)>,
+	cl_ord_ids: HashSet<(SessionId, String)>,
+	/// Times of the recent orders of each session, oldest first
+	sent: HashMap<SessionId, VecDeque<OffsetDateTime>>,
+	/// Error of the last order refused on each session
+	rejections: HashMap<SessionId, RiskError>,
+}
+
+impl PreTradeRisk {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Limits of the symbols without limits of their own
+	pub const fn with_default_limits(mut self, limits: SymbolLimits) -> Self {
+		self.default_limits = limits;
+		self
+	}
+
+	pub fn with_limits(mut self, symbol: impl Into<String>, limits: SymbolLimits) -> Self {
+		self.limits.insert(symbol.into(), limits);
+		self
+	}
+
+	/// Refuse every order for `symbol`
+	pub fn with_restricted_symbol(mut self, symbol: impl Into<String>) -> Self {
+		self.restricted.insert(symbol.into());
+		self
+	}
+
+	/// Allow at most `max_orders` orders per session in any `window`
+	pub const fn with_rate_limit(mut self, max_orders: usize, window: Duration) -> Self {
+		self.rate_limit = Some((max_orders, window));
+		self
+	}
+
+	/// Set the price that price bands and market order notionals of `symbol` are based on
+	pub fn set_reference_price(&mut self, symbol: impl Into<String>, price: f64) {
+		self.reference_prices.insert(symbol.into(), price);
+	}
+
+	/// The error of the last order refused on `session_id`
+	pub fn last_rejection(&self, session_id: &SessionId) -> Option<&RiskError> {
+		self.rejections.get(session_id)
+	}
+
+	/// Check `message` if it is a New Order Single; other messages pass
+	pub fn check_message(&mut self, session_id: &SessionId, message: &FixMessage) -> Result<(), RiskError> {
+		match &message.body {
+			FixMessageBody::NewOrderSingle(order) => self.check(session_id, order, message.header.sending_time),
+			_ => Ok(()),
+		}
+	}
+
+	/// Check an order about to be sent on `session_id` at `now`, recording it if it passes
+	pub fn check(
+		&mut self,
+		session_id: &SessionId,
+		order: &NewOrderSingleBody,
+		now: OffsetDateTime,
+	) -> Result<(), RiskError> {
+		let result = self.evaluate(session_id, order, now);
+		match &result {
+			Ok(()) => {
+				self.cl_ord_ids.insert((session_id.clone(), order.cl_ord_id.clone()));
+				if self.rate_limit.is_some() {
+					self.sent.entry(session_id.clone()).or_default().push_back(now);
+				}
+			},
+			Err(e) => {
+				self.rejections.insert(session_id.clone(), e.clone());
+			},
+		}
+		result
+	}
+
+	fn evaluate(
+		&mut self,
+		session_id: &SessionId,
+		order: &NewOrderSingleBody,
+		now: OffsetDateTime,
+	) -> Result<(), RiskError> {
+		let symbol = &order.symbol;
+		if self.restricted.contains(symbol) {
+			return Err(RiskError::RestrictedSymbol(symbol.clone()));
+		}
+		if self.cl_ord_ids.contains(&(session_id.clone(), order.cl_ord_id.clone())) {
+			return Err(RiskError::DuplicateClOrdId(order.cl_ord_id.clone()));
+		}
+
+		let limits = self.limits.get(symbol).copied().unwrap_or(self.default_limits);
+		let reference = self.reference_prices.get(symbol).copied();
+		let no_reference = || RiskError::NoReferencePrice(symbol.clone());
+		let order_qty = order.order_qty.unwrap_or_default();
+		if let Some(limit) = limits.max_order_qty
+			&& order_qty > limit
+		{
+			return Err(RiskError::MaxOrderQty { symbol: symbol.clone(), order_qty, limit });
+		}
+		if let Some(limit) = limits.max_notional {
+			let notional = match order.cash_order_qty {
+				Some(cash_order_qty) => cash_order_qty,
+				None => order_qty * order.price.or(reference).ok_or_else(no_reference)?,
+			};
+			if notional > limit {
+				return Err(RiskError::MaxNotional { symbol: symbol.clone(), notional, limit });
+			}
+		}
+		if let Some(band) = limits.price_band
+			&& let Some(price) = order.price
+		{
+			let reference = reference.ok_or_else(no_reference)?;
+			let (low, high) = (reference * (1.0 - band), reference * (1.0 + band));
+			if price < low || price > high {
+				return Err(RiskError::OutsidePriceBand { symbol: symbol.clone(), price, low, high });
+			}
+		}
+
+		if let Some((max_orders, window)) = self.rate_limit {
+			let sent = self.sent.entry(session_id.clone()).or_default();
+			while sent.front().is_some_and(|&time| time <= now - window) {
+				sent.pop_front();
+			}
+			if sent.len() >= max_orders {
+				return Err(RiskError::RateLimit { max_orders, window });
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Application running [`PreTradeRisk`] on the orders a session sends
+///
+/// Refused orders are vetoed with [`DoNotSend`], so [`Session::send`](crate::Session::send)
+/// fails with [`SessionError::DoNotSend`](crate::SessionError::DoNotSend) carrying the
+/// [`RiskError`]. The reason also stays available from [`PreTradeRisk::last_rejection`]
+/// for the session, even when the checks are shared with other sessions. Resent orders, carrying
+/// PossDupFlag, are not checked again. Every other callback goes to the wrapped
+/// application.
+pub struct RiskGate<A: Application> {
+	risk: Arc<Mutex<PreTradeRisk>>,
+	application: A,
+}
+
+impl<A: Application> RiskGate<A> {
+	/// Check the orders sent through `application` with `risk`, which may be shared with other sessions
+	pub const fn new(risk: Arc<Mutex<PreTradeRisk>>, application: A) -> Self {
+		Self { risk, application }
+	}
+}
+
+impl<A: Application> Application for RiskGate<A> {
+	fn on_create(&mut self, session_id: &SessionId) {
+		self.application.on_create(session_id);
+	}
+
+	fn on_logon(&mut self, session_id: &SessionId) {
+		self.application.on_logon(session_id);
+	}
+
+	fn on_logout(&mut self, session_id: &SessionId) {
+		self.application.on_logout(session_id);
+	}
+
+	fn to_admin(&mut self, message: &mut FixMessage, session_id: &SessionId) {
+		self.application.to_admin(message, session_id);
+	}
+
+	fn from_admin(&mut self, message: &FixMessage, session_id: &SessionId) -> Result<(), RejectLogon> {
+		self.application.from_admin(message, session_id)
+	}
+
+	fn to_app(&mut self, message: &mut FixMessage, session_id: &SessionId) -> Result<(), DoNotSend> {
+		self.application.to_app(message, session_id)?;
+		if message.header.poss_dup_flag == Some(true) {
+			return Ok(());
+		}
+		let mut risk = self.risk.lock().unwrap_or_else(PoisonError::into_inner);
+		risk.check_message(session_id, message).map_err(DoNotSend::because)
+	}
+
+	fn from_app(&mut self, message: &FixMessage, session_id: &SessionId) -> Result<(), BusinessReject> {
+		self.application.from_app(message, session_id)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		MemoryStore, MsgType, Session, SessionError, Side, session::NullApplication, test_support::order_body,
+	};
+
+	fn order(cl_ord_id: &str, symbol: &str, qty: f64, price: Option<f64>) -> NewOrderSingleBody {
+		NewOrderSingleBody { symbol: symbol.into(), ..order_body(cl_ord_id, Side::Buy, qty, price) }
+	}
+
+	#[test]
+	fn test_limits() {
+		let session = SessionId::new("CLIENT", "BROKER");
+		let now = OffsetDateTime::UNIX_EPOCH;
+		let limits = SymbolLimits { max_order_qty: Some(1000.0), max_notional: Some(50_000.0), price_band: Some(0.05) };
+		let mut risk = PreTradeRisk::new()
+			.with_default_limits(SymbolLimits { max_order_qty: Some(10.0), ..Default::default() })
+			.with_limits("AAPL", limits)
+			.with_restricted_symbol("XYZ");
+
+		assert_eq!(
+			risk.check(&session, &order("1", "XYZ", 1.0, None), now),
+			Err(RiskError::RestrictedSymbol("XYZ".into()))
+		);
+		assert!(matches!(
+			risk.check(&session, &order("2", "MSFT", 20.0, None), now),
+			Err(RiskError::MaxOrderQty { .. })
+		));
+		assert_eq!(
+			risk.check(&session, &order("3", "AAPL", 100.0, None), now),
+			Err(RiskError::NoReferencePrice("AAPL".into()))
+		);
+
+		risk.set_reference_price("AAPL", 100.0);
+		assert_eq!(
+			risk.check(&session, &order("4", "AAPL", 600.0, Some(100.0)), now),
+			Err(RiskError::MaxNotional { symbol: "AAPL".into(), notional: 60_000.0, limit: 50_000.0 })
+		);
+		assert!(matches!(
+			risk.check(&session, &order("5", "AAPL", 100.0, Some(106.0)), now),
+			Err(RiskError::OutsidePriceBand { .. })
+		));
+		assert!(risk.check(&session, &order("6", "AAPL", 100.0, Some(104.0)), now).is_ok());
+		assert_eq!(
+			risk.check(&session, &order("6", "AAPL", 100.0, None), now),
+			Err(RiskError::DuplicateClOrdId("6".into()))
+		);
+		assert_eq!(risk.last_rejection(&session), Some(&RiskError::DuplicateClOrdId("6".into())));
+		// ClOrdIDs only need to be unique per session
+		let other = SessionId::new("CLIENT", "OTHER");
+		assert!(risk.check(&other, &order("6", "MSFT", 1.0, None), now).is_ok());
+		assert_eq!(risk.last_rejection(&other), None);
+	}
+
+	#[test]
+	fn test_rate_limit() {
+		let session = SessionId::new("CLIENT", "BROKER");
+		let start = OffsetDateTime::UNIX_EPOCH;
+		let mut risk = PreTradeRisk::new().with_rate_limit(2, Duration::seconds(1));
+		assert!(risk.check(&session, &order("1", "AAPL", 1.0, None), start).is_ok());
+		assert!(risk.check(&session, &order("2", "AAPL", 1.0, None), start + Duration::milliseconds(500)).is_ok());
+		let third = order("3", "AAPL", 1.0, None);
+		assert!(matches!(
+			risk.check(&session, &third, start + Duration::milliseconds(900)),
+			Err(RiskError::RateLimit { max_orders: 2, .. })
+		));
+		assert!(risk.check(&SessionId::new("CLIENT", "OTHER"), &third, start).is_ok());
+		assert!(risk.check(&session, &third, start + Duration::seconds(1)).is_ok());
+	}
+
+	#[test]
+	fn test_gate_vetoes_outbound_orders() {
+		let risk = PreTradeRisk::new().with_restricted_symbol("XYZ").with_restricted_symbol("ABC");
+		let risk = Arc::new(Mutex::new(risk));
+		let now = OffsetDateTime::now_utc();
+		let logged_on = |target: &str| {
+			let mut session = Session::new(SessionId::new("CLIENT", target), MemoryStore::new())
+				.with_application(RiskGate::new(Arc::clone(&risk), NullApplication));
+			session.initiate_logon(now).unwrap();
+			let logon = FixMessage::builder(MsgType::Logon, target, "CLIENT", 1).heart_bt_int(30).build();
+			session.on_message(&logon.to_fix_string(), now).unwrap();
+			session.take_outgoing();
+			session
+		};
+		let (mut session, mut other) = (logged_on("BROKER"), logged_on("OTHER"));
+
+		let message = |target: &str, cl_ord_id: &str, symbol: &str| {
+			FixMessage::builder(MsgType::NewOrderSingle, "CLIENT", target, 0)
+				.cl_ord_id(cl_ord_id)
+				.handl_inst("1")
+				.symbol(symbol)
+				.side(Side::Buy)
+				.order_qty(10.0)
+				.ord_type("1")
+				.build()
+		};
+		let Err(SessionError::DoNotSend(DoNotSend(Some(reason)))) = session.send(message("BROKER", "1", "XYZ"), now)
+		else {
+			panic!("expected a veto with a reason");
+		};
+		assert_eq!(reason.downcast_ref(), Some(&RiskError::RestrictedSymbol("XYZ".into())));
+		assert!(session.take_outgoing().is_empty());
+		assert_eq!(session.send(message("BROKER", "2", "AAPL"), now).unwrap(), 2);
+		// Each session keeps the reason of its own refusal
+		assert!(matches!(other.send(message("OTHER", "1", "ABC"), now), Err(SessionError::DoNotSend(_))));
+		let risk = risk.lock().unwrap();
+		let rejection = |target: &str| risk.last_rejection(&SessionId::new("CLIENT", target)).cloned();
+		assert_eq!(rejection("BROKER"), Some(RiskError::RestrictedSymbol("XYZ".into())));
+		assert_eq!(rejection("OTHER"), Some(RiskError::RestrictedSymbol("ABC".into())));
+	}
+}
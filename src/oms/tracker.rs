@@ -0,0 +1,399 @@
+//! Client-side order state tracking
+//!
+//! [`OrderTracker`] records the orders, cancels and cancel/replaces we send and
+//! applies the Execution Reports and Order Cancel Rejects received for them. An
+//! order is known by every ClOrdID of its cancel/replace chain.
+//!
+//! Reports that cannot be right for the order as we know it, such as a fill
+//! after the order was canceled, CumQty going down or an overfill, are returned
+//! as [`TrackerError`]s and leave the order unchanged.
+
+use crate::{
+	FixMessage, OrdStatus, Side,
+	common::{
+		QTY_EPSILON,
+		enums::{ExecTransType, ExecType},
+	},
+	messages::{
+		ExecutionReportBody, FixMessageBody, NewOrderSingleBody, OrderCancelRejectBody, OrderCancelReplaceRequestBody,
+		OrderCancelRequestBody,
+	},
+};
+use std::{collections::HashMap, fmt::Display};
+
+/// Errors raised while tracking orders
+#[derive(Debug, Clone, PartialEq)]
+pub enum TrackerError {
+	/// A request reuses a ClOrdID already sent
+	DuplicateClOrdId(String),
+	/// A request or report refers to an order we did not send
+	UnknownOrder(String),
+	/// The report moves the order from a final status to another one
+	IllegalTransition { cl_ord_id: String, from: OrdStatus, to: OrdStatus },
+	/// The report carries a fill for an order in a final status
+	FillAfterFinal { cl_ord_id: String, status: OrdStatus },
+	/// CumQty went down without the fill being canceled or corrected
+	CumQtyDecreased { cl_ord_id: String, previous: f64, reported: f64 },
+	/// CumQty exceeds the order quantity
+	Overfill { cl_ord_id: String, order_qty: f64, cum_qty: f64 },
+}
+
+impl Display for TrackerError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::DuplicateClOrdId(id) => write!(f, "Duplicate ClOrdID {}", id),
+			Self::UnknownOrder(id) => write!(f, "Unknown order {}", id),
+			Self::IllegalTransition { cl_ord_id, from, to } => {
+				write!(f, "Illegal transition of order {} from {:?} to {:?}", cl_ord_id, from, to)
+			},
+			Self::FillAfterFinal { cl_ord_id, status } => {
+				write!(f, "Fill for order {} after it was {:?}", cl_ord_id, status)
+			},
+			Self::CumQtyDecreased { cl_ord_id, previous, reported } => {
+				write!(f, "CumQty of order {} went down from {} to {}", cl_ord_id, previous, reported)
+			},
+			Self::Overfill { cl_ord_id, order_qty, cum_qty } => {
+				write!(f, "Order {} overfilled: CumQty {} exceeds OrderQty {}", cl_ord_id, cum_qty, order_qty)
+			},
+		}
+	}
+}
+
+impl std::error::Error for TrackerError {}
+
+/// A cancel or cancel/replace awaiting the broker's answer
+#[derive(Debug, Clone, PartialEq)]
+pub enum PendingRequest {
+	Cancel { cl_ord_id: String },
+	Replace { cl_ord_id: String, order_qty: Option<f64>, price: Option<f64> },
+}
+
+/// State of an order as known from what we sent and what the broker reported
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrackedOrder {
+	/// ClOrdID the order currently goes by
+	pub cl_ord_id: String,
+	/// Every ClOrdID of the order, in the order they were used
+	pub cl_ord_id_chain: Vec<String>,
+	/// OrderID assigned by the broker, once reported
+	pub order_id: Option<String>,
+	pub symbol: String,
+	pub side: Side,
+	/// OrderQty, `None` for orders placed with CashOrderQty
+	pub order_qty: Option<f64>,
+	pub price: Option<f64>,
+	pub ord_status: OrdStatus,
+	pub cum_qty: f64,
+	pub leaves_qty: f64,
+	pub avg_px: f64,
+	pub pending: Option<PendingRequest>,
+}
+
+impl TrackedOrder {
+	fn new(body: &NewOrderSingleBody) -> Self {
+		Self {
+			cl_ord_id: body.cl_ord_id.clone(),
+			cl_ord_id_chain: vec![body.cl_ord_id.clone()],
+			order_id: None,
+			symbol: body.symbol.clone(),
+			side: body.side.clone(),
+			order_qty: body.order_qty,
+			price: body.price,
+			ord_status: OrdStatus::PendingNew,
+			cum_qty: 0.0,
+			leaves_qty: body.order_qty.unwrap_or_default(),
+			avg_px: 0.0,
+			pending: None,
+		}
+	}
+
+	/// Whether the order reached a status it cannot leave
+	pub const fn is_final(&self) -> bool {
+		is_final(&self.ord_status)
+	}
+
+	/// Check `report` against the order, then apply it
+	fn apply(&mut self, report: &ExecutionReportBody) -> Result<(), TrackerError> {
+		let cl_ord_id = self.cl_ord_id.clone();
+		let is_fill = report.last_shares.is_some_and(|qty| qty > 0.0)
+			&& matches!(report.exec_type, ExecType::PartialFill | ExecType::Fill | ExecType::Trade);
+		if self.is_final() {
+			if is_fill {
+				return Err(TrackerError::FillAfterFinal { cl_ord_id, status: self.ord_status.clone() });
+			}
+			if report.ord_status != self.ord_status {
+				let (from, to) = (self.ord_status.clone(), report.ord_status.clone());
+				return Err(TrackerError::IllegalTransition { cl_ord_id, from, to });
+			}
+		}
+		let busted = matches!(report.exec_trans_type, ExecTransType::Cancel | ExecTransType::Correct)
+			|| matches!(report.exec_type, ExecType::TradeCancel | ExecType::TradeCorrect);
+		if report.cum_qty + QTY_EPSILON < self.cum_qty && !busted {
+			return Err(TrackerError::CumQtyDecreased { cl_ord_id, previous: self.cum_qty, reported: report.cum_qty });
+		}
+
+		// A replace takes effect before the overfill check, as it may change the quantity
+		let mut order_qty = self.order_qty;
+		let mut price = self.price;
+		let replaced = report.exec_type == ExecType::Replaced;
+		if replaced && let Some(PendingRequest::Replace { order_qty: new_qty, price: new_price, .. }) = &self.pending {
+			order_qty = new_qty.or(order_qty);
+			price = new_price.or(price);
+		}
+		if let Some(order_qty) = order_qty
+			&& report.cum_qty > order_qty + QTY_EPSILON
+		{
+			return Err(TrackerError::Overfill { cl_ord_id, order_qty, cum_qty: report.cum_qty });
+		}
+
+		if replaced {
+			if let Some(PendingRequest::Replace { cl_ord_id, .. }) = self.pending.take() {
+				self.cl_ord_id = cl_ord_id;
+			}
+			self.order_qty = order_qty;
+			self.price = price;
+		}
+		if matches!(report.exec_type, ExecType::Canceled | ExecType::Rejected | ExecType::Expired) {
+			self.pending = None;
+		}
+		if !report.order_id.is_empty() && report.order_id != "NONE" {
+			self.order_id = Some(report.order_id.clone());
+		}
+		self.ord_status = report.ord_status.clone();
+		self.cum_qty = report.cum_qty;
+		self.leaves_qty = report.leaves_qty;
+		self.avg_px = report.avg_px;
+		Ok(())
+	}
+}
+
+const fn is_final(status: &OrdStatus) -> bool {
+	matches!(status, OrdStatus::Filled | OrdStatus::Canceled | OrdStatus::Rejected | OrdStatus::Expired)
+}
+
+/// Tracks the state of the orders we send from the Execution Reports received for them
+#[derive(Debug, Clone, Default)]
+pub struct OrderTracker {
+	/// Orders by the ClOrdID they were first sent with
+	orders: HashMap<String, TrackedOrder>,
+	/// First ClOrdID of the order by every ClOrdID of its chain
+	cl_ord_ids: HashMap<String, String>,
+}
+
+impl OrderTracker {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Record an outbound order, cancel or cancel/replace; other messages are ignored
+	pub fn on_outbound(&mut self, message: &FixMessage) -> Result<(), TrackerError> {
+		match &message.body {
+			FixMessageBody::NewOrderSingle(body) => self.on_new_order(body),
+			FixMessageBody::OrderCancelRequest(body) => self.on_cancel_request(body),
+			FixMessageBody::OrderCancelReplaceRequest(body) => self.on_cancel_replace_request(body),
+			_ => Ok(()),
+		}
+	}
+
+	/// Apply an inbound Execution Report or Order Cancel Reject; other messages are ignored
+	pub fn on_inbound(&mut self, message: &FixMessage) -> Result<(), TrackerError> {
+		match &message.body {
+			FixMessageBody::ExecutionReport(body) => self.on_execution_report(body).map(|_| ()),
+			FixMessageBody::OrderCancelReject(body) => self.on_cancel_reject(body).map(|_| ()),
+			_ => Ok(()),
+		}
+	}
+
+	pub fn on_new_order(&mut self, body: &NewOrderSingleBody) -> Result<(), TrackerError> {
+		self.claim_cl_ord_id(&body.cl_ord_id, &body.cl_ord_id)?;
+		self.orders.insert(body.cl_ord_id.clone(), TrackedOrder::new(body));
+		Ok(())
+	}
+
+	pub fn on_cancel_request(&mut self, body: &OrderCancelRequestBody) -> Result<(), TrackerError> {
+		let root = self.root(&body.orig_cl_ord_id)?;
+		self.claim_cl_ord_id(&body.cl_ord_id, &root)?;
+		let order = self.orders.get_mut(&root).expect("indexed order");
+		order.cl_ord_id_chain.push(body.cl_ord_id.clone());
+		order.pending = Some(PendingRequest::Cancel { cl_ord_id: body.cl_ord_id.clone() });
+		Ok(())
+	}
+
+	pub fn on_cancel_replace_request(&mut self, body: &OrderCancelReplaceRequestBody) -> Result<(), TrackerError> {
+		let root = self.root(&body.orig_cl_ord_id)?;
+		self.claim_cl_ord_id(&body.cl_ord_id, &root)?;
+		let order = self.orders.get_mut(&root).expect("indexed order");
+		order.cl_ord_id_chain.push(body.cl_ord_id.clone());
+		order.pending = Some(PendingRequest::Replace {
+			cl_ord_id: body.cl_ord_id.clone(),
+			order_qty: body.order_qty,
+			price: body.price,
+		});
+		Ok(())
+	}
+
+	/// Apply an Execution Report to the order it refers to, by ClOrdID or else OrigClOrdID
+	pub fn on_execution_report(&mut self, report: &ExecutionReportBody) -> Result<&TrackedOrder, TrackerError> {
+		let id = report.cl_ord_id.as_ref().or(report.orig_cl_ord_id.as_ref());
+		let root = self.root(id.map_or(report.order_id.as_str(), String::as_str))?;
+		let order = self.orders.get_mut(&root).expect("indexed order");
+		order.apply(report)?;
+		Ok(order)
+	}
+
+	/// Clear the pending cancel or cancel/replace refused by an Order Cancel Reject
+	pub fn on_cancel_reject(&mut self, reject: &OrderCancelRejectBody) -> Result<&TrackedOrder, TrackerError> {
+		let root = self.root(&reject.orig_cl_ord_id).or_else(|_| self.root(&reject.cl_ord_id))?;
+		let order = self.orders.get_mut(&root).expect("indexed order");
+		order.pending = None;
+		if !is_final(&order.ord_status) {
+			order.ord_status = reject.ord_status.clone();
+		}
+		Ok(order)
+	}
+
+	/// The order known by `cl_ord_id`, any ClOrdID of its chain
+	pub fn order(&self, cl_ord_id: &str) -> Option<&TrackedOrder> {
+		self.orders.get(self.cl_ord_ids.get(cl_ord_id)?)
+	}
+
+	/// Orders that have not reached a final status
+	pub fn open_orders(&self) -> impl Iterator<Item = &TrackedOrder> {
+		self.orders.values().filter(|order| !order.is_final())
+	}
+
+	fn root(&self, cl_ord_id: &str) -> Result<String, TrackerError> {
+		self.cl_ord_ids.get(cl_ord_id).cloned().ok_or_else(|| TrackerError::UnknownOrder(cl_ord_id.to_string()))
+	}
+
+	fn claim_cl_ord_id(&mut self, cl_ord_id: &str, root: &str) -> Result<(), TrackerError> {
+		if self.cl_ord_ids.contains_key(cl_ord_id) {
+			return Err(TrackerError::DuplicateClOrdId(cl_ord_id.to_string()));
+		}
+		self.cl_ord_ids.insert(cl_ord_id.to_string(), root.to_string());
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{CxlRejResponseTo, MsgType, test_support::order_body};
+
+	fn order(cl_ord_id: &str, qty: f64) -> NewOrderSingleBody {
+		order_body(cl_ord_id, Side::Buy, qty, Some(150.0))
+	}
+
+	fn report(
+		cl_ord_id: &str,
+		exec_type: ExecType,
+		ord_status: OrdStatus,
+		cum_qty: f64,
+		leaves_qty: f64,
+	) -> ExecutionReportBody {
+		ExecutionReportBody {
+			exec_type,
+			ord_status,
+			cum_qty,
+			leaves_qty,
+			cl_ord_id: Some(cl_ord_id.into()),
+			..ExecutionReportBody::new("BRK1", format!("E{}", cum_qty))
+		}
+	}
+
+	#[test]
+	fn test_fills_and_replace_chain() {
+		let mut tracker = OrderTracker::new();
+		let message = FixMessage::builder(MsgType::NewOrderSingle, "CLIENT", "BROKER", 2)
+			.cl_ord_id("ORD1")
+			.handl_inst("1")
+			.symbol("AAPL")
+			.side(Side::Buy)
+			.order_qty(100.0)
+			.ord_type("2")
+			.price(150.0)
+			.build();
+		tracker.on_outbound(&message).unwrap();
+		assert_eq!(tracker.on_new_order(&order("ORD1", 100.0)), Err(TrackerError::DuplicateClOrdId("ORD1".into())));
+
+		tracker.on_execution_report(&report("ORD1", ExecType::New, OrdStatus::New, 0.0, 100.0)).unwrap();
+		let fill = ExecutionReportBody {
+			last_shares: Some(40.0),
+			last_px: Some(150.0),
+			avg_px: 150.0,
+			..report("ORD1", ExecType::PartialFill, OrdStatus::PartiallyFilled, 40.0, 60.0)
+		};
+		let tracked = tracker.on_execution_report(&fill).unwrap();
+		assert_eq!((tracked.cum_qty, tracked.leaves_qty), (40.0, 60.0));
+		assert_eq!(tracked.order_id.as_deref(), Some("BRK1"));
+
+		tracker
+			.on_cancel_replace_request(&OrderCancelReplaceRequestBody {
+				orig_cl_ord_id: "ORD1".into(),
+				cl_ord_id: "ORD2".into(),
+				order_qty: Some(200.0),
+				price: Some(149.5),
+				..Default::default()
+			})
+			.unwrap();
+		let replaced = ExecutionReportBody {
+			orig_cl_ord_id: Some("ORD1".into()),
+			..report("ORD2", ExecType::Replaced, OrdStatus::Replaced, 40.0, 160.0)
+		};
+		let tracked = tracker.on_execution_report(&replaced).unwrap();
+		assert_eq!(tracked.cl_ord_id, "ORD2");
+		assert_eq!(tracked.cl_ord_id_chain, vec!["ORD1".to_string(), "ORD2".to_string()]);
+		assert_eq!((tracked.order_qty, tracked.price), (Some(200.0), Some(149.5)));
+		assert_eq!(tracker.order("ORD1"), tracker.order("ORD2"));
+
+		let cancel =
+			OrderCancelRequestBody { orig_cl_ord_id: "ORD2".into(), cl_ord_id: "ORD3".into(), ..Default::default() };
+		tracker.on_cancel_request(&cancel).unwrap();
+		let reject = OrderCancelRejectBody::new(
+			"BRK1",
+			"ORD3",
+			"ORD2",
+			OrdStatus::PartiallyFilled,
+			CxlRejResponseTo::OrderCancelRequest,
+		);
+		assert_eq!(tracker.on_cancel_reject(&reject).unwrap().pending, None);
+		assert_eq!(tracker.open_orders().count(), 1);
+	}
+
+	#[test]
+	fn test_illegal_updates() {
+		let mut tracker = OrderTracker::new();
+		tracker.on_new_order(&order("ORD1", 100.0)).unwrap();
+		tracker
+			.on_execution_report(&report("ORD1", ExecType::PartialFill, OrdStatus::PartiallyFilled, 60.0, 40.0))
+			.unwrap();
+
+		let lower = report("ORD1", ExecType::PartialFill, OrdStatus::PartiallyFilled, 50.0, 50.0);
+		assert!(matches!(tracker.on_execution_report(&lower), Err(TrackerError::CumQtyDecreased { .. })));
+		let bust = ExecutionReportBody { exec_trans_type: ExecTransType::Cancel, ..lower };
+		tracker.on_execution_report(&bust).unwrap();
+
+		let over = report("ORD1", ExecType::Fill, OrdStatus::Filled, 120.0, 0.0);
+		assert_eq!(
+			tracker.on_execution_report(&over),
+			Err(TrackerError::Overfill { cl_ord_id: "ORD1".into(), order_qty: 100.0, cum_qty: 120.0 })
+		);
+
+		tracker.on_execution_report(&report("ORD1", ExecType::Canceled, OrdStatus::Canceled, 50.0, 0.0)).unwrap();
+		let late_fill = ExecutionReportBody {
+			last_shares: Some(10.0),
+			..report("ORD1", ExecType::PartialFill, OrdStatus::Canceled, 60.0, 0.0)
+		};
+		assert!(matches!(tracker.on_execution_report(&late_fill), Err(TrackerError::FillAfterFinal { .. })));
+		let revived = report("ORD1", ExecType::New, OrdStatus::New, 50.0, 50.0);
+		assert!(matches!(
+			tracker.on_execution_report(&revived),
+			Err(TrackerError::IllegalTransition { from: OrdStatus::Canceled, to: OrdStatus::New, .. })
+		));
+		assert_eq!(tracker.order("ORD1").unwrap().cum_qty, 50.0);
+		assert_eq!(tracker.open_orders().count(), 0);
+
+		let unknown = report("ORD9", ExecType::New, OrdStatus::New, 0.0, 10.0);
+		assert_eq!(tracker.on_execution_report(&unknown), Err(TrackerError::UnknownOrder("ORD9".into())));
+	}
+}
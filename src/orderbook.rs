@@ -0,0 +1,540 @@
+//! Price-level order books built from market data
+//!
+//! [`OrderBook`] keeps one [`Book`] per Symbol. A Market Data Snapshot/Full
+//! Refresh (MsgType=W) replaces the book of its instrument; the New, Change and
+//! Delete entries of a Market Data Incremental Refresh (MsgType=X) then update
+//! it. Bid and offer entries are aggregated by price, trade entries only update
+//! the last trade.
+//!
+//! Entries carrying an MDEntryID (Tag 278) are individual orders or quotes:
+//! several may share a price, and their sizes add up to the level, which lasts
+//! while any of them does. Entries without one describe whole price levels.
+//!
+//! Updates that do not fit the book, such as deleting a level that does not
+//! exist, are reported as [`BookError`]s and leave the book stale: it keeps
+//! answering queries but refuses incremental updates until the next snapshot.
+//!
+//! The book does not detect gaps in the feed itself: there is no RptSeq
+//! (Tag 83) check, and it relies on the [`Session`](crate::Session) to deliver
+//! every message in MsgSeqNum order, resending the missing ones after a gap.
+
+use crate::{
+	FixMessage,
+	common::{MdEntryType, MdUpdateAction},
+	messages::{
+		FixMessageBody, MarketDataIncrementalRefreshBody, MarketDataSnapshotFullRefreshBody, MdIncrementalEntry,
+	},
+};
+use std::{collections::HashMap, fmt::Display};
+
+/// Side of a book
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BookSide {
+	Bid,
+	Offer,
+}
+
+impl BookSide {
+	/// The book side an MDEntryType (Tag 269) updates, if any
+	pub const fn for_entry_type(md_entry_type: &MdEntryType) -> Option<Self> {
+		match md_entry_type {
+			MdEntryType::Bid => Some(Self::Bid),
+			MdEntryType::Offer => Some(Self::Offer),
+			_ => None,
+		}
+	}
+
+	/// Whether `a` is a better price than `b` on this side
+	fn is_better(self, a: f64, b: f64) -> bool {
+		match self {
+			Self::Bid => a > b,
+			Self::Offer => a < b,
+		}
+	}
+}
+
+impl Display for BookSide {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Bid => write!(f, "bid"),
+			Self::Offer => write!(f, "offer"),
+		}
+	}
+}
+
+/// Aggregated size available at a price
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Level {
+	pub price: f64,
+	pub size: f64,
+}
+
+/// Errors raised while applying market data to an [`OrderBook`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum BookError {
+	/// An incremental refresh arrived for a symbol without a snapshot
+	NoSnapshot(String),
+	/// The book of the symbol is stale after an earlier error and awaits a snapshot
+	Stale(String),
+	/// An incremental entry has no Symbol and no previous entry to inherit it from
+	MissingSymbol,
+	/// A New or Change entry for a book side has no MDEntryPx
+	MissingPrice(String),
+	/// A New entry without MDEntryID was received for a level that already exists
+	DuplicateLevel { symbol: String, side: BookSide, price: f64 },
+	/// A New entry reuses the MDEntryID of an entry still in the book
+	DuplicateEntry { symbol: String, id: String },
+	/// A Change or Delete entry refers to a level that does not exist
+	UnknownLevel { symbol: String, side: Option<BookSide>, reference: String },
+}
+
+impl Display for BookError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::NoSnapshot(symbol) => write!(f, "Incremental refresh for {} before any snapshot", symbol),
+			Self::Stale(symbol) => write!(f, "Book of {} is stale until the next snapshot", symbol),
+			Self::MissingSymbol => write!(f, "Incremental entry without Symbol"),
+			Self::MissingPrice(symbol) => write!(f, "Entry for {} without MDEntryPx", symbol),
+			Self::DuplicateLevel { symbol, side, price } => {
+				write!(f, "New {} level {} for {} already exists", side, price, symbol)
+			},
+			Self::DuplicateEntry { symbol, id } => write!(f, "New entry {} for {} already exists", id, symbol),
+			Self::UnknownLevel { symbol, side: Some(side), reference } => {
+				write!(f, "Unknown {} level {} for {}", side, reference, symbol)
+			},
+			Self::UnknownLevel { symbol, side: None, reference } => {
+				write!(f, "Unknown level {} for {}", reference, symbol)
+			},
+		}
+	}
+}
+
+impl std::error::Error for BookError {}
+
+/// An entry known by its MDEntryID and its share of a level
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Entry {
+	side: BookSide,
+	price: f64,
+	size: f64,
+}
+
+/// Price-level book of a single instrument
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Book {
+	/// Bid levels, best (highest) price first
+	bids: Vec<Level>,
+	/// Offer levels, best (lowest) price first
+	offers: Vec<Level>,
+	/// Entries by MDEntryID (Tag 278), for updates referring to them by ID
+	entry_ids: HashMap<String, Entry>,
+	last_trade: Option<Level>,
+	stale: bool,
+}
+
+impl Book {
+	/// Best bid, if any
+	pub fn best_bid(&self) -> Option<Level> {
+		self.bids.first().copied()
+	}
+
+	/// Best offer, if any
+	pub fn best_offer(&self) -> Option<Level> {
+		self.offers.first().copied()
+	}
+
+	/// Best bid and best offer
+	pub fn top_of_book(&self) -> (Option<Level>, Option<Level>) {
+		(self.best_bid(), self.best_offer())
+	}
+
+	/// Difference between the best offer and the best bid
+	pub fn spread(&self) -> Option<f64> {
+		Some(self.best_offer()?.price - self.best_bid()?.price)
+	}
+
+	/// Whether the best bid is at or above the best offer
+	pub fn is_crossed(&self) -> bool {
+		self.spread().is_some_and(|spread| spread <= 0.0)
+	}
+
+	/// Up to `n` best levels of `side`, best first
+	pub fn depth(&self, side: BookSide, n: usize) -> &[Level] {
+		let levels = self.levels(side);
+		&levels[..n.min(levels.len())]
+	}
+
+	/// Average price of taking `quantity` from `side`, walking the levels from the best
+	///
+	/// Returns `None` when the side does not hold `quantity` in total.
+	pub fn vwap(&self, side: BookSide, quantity: f64) -> Option<f64> {
+		if quantity <= 0.0 {
+			return None;
+		}
+		let mut remaining = quantity;
+		let mut notional = 0.0;
+		for level in self.levels(side) {
+			let taken = remaining.min(level.size);
+			notional += taken * level.price;
+			remaining -= taken;
+			if remaining <= 0.0 {
+				return Some(notional / quantity);
+			}
+		}
+		None
+	}
+
+	/// Price and size of the last trade entry received
+	pub const fn last_trade(&self) -> Option<Level> {
+		self.last_trade
+	}
+
+	/// Whether the book missed or refused an update and awaits a snapshot
+	pub const fn is_stale(&self) -> bool {
+		self.stale
+	}
+
+	fn levels(&self, side: BookSide) -> &Vec<Level> {
+		match side {
+			BookSide::Bid => &self.bids,
+			BookSide::Offer => &self.offers,
+		}
+	}
+
+	fn levels_mut(&mut self, side: BookSide) -> &mut Vec<Level> {
+		match side {
+			BookSide::Bid => &mut self.bids,
+			BookSide::Offer => &mut self.offers,
+		}
+	}
+
+	/// Position of the level at `price`, or where it would be inserted
+	fn position(&self, side: BookSide, price: f64) -> Result<usize, usize> {
+		let levels = self.levels(side);
+		match levels.iter().position(|level| !side.is_better(level.price, price)) {
+			Some(i) if levels[i].price == price => Ok(i),
+			Some(i) => Err(i),
+			None => Err(levels.len()),
+		}
+	}
+
+	/// Add `size` at `price`, creating the level if needed
+	fn add(&mut self, side: BookSide, price: f64, size: f64) {
+		match self.position(side, price) {
+			Ok(i) => self.levels_mut(side)[i].size += size,
+			Err(i) => self.levels_mut(side).insert(i, Level { price, size }),
+		}
+	}
+
+	/// Take an identified entry's size out of its level, dropping the level once nothing is left in it
+	fn remove_entry(&mut self, id: &str) {
+		let Some(entry) = self.entry_ids.remove(id) else {
+			return;
+		};
+		let Ok(i) = self.position(entry.side, entry.price) else {
+			return;
+		};
+		let shared = self.entry_ids.values().any(|other| other.side == entry.side && other.price == entry.price);
+		let level = &mut self.levels_mut(entry.side)[i];
+		level.size -= entry.size;
+		// Without other entries, only rounding is left unless a snapshot level holds size at that price
+		if !shared && level.size <= entry.size * 1e-9 {
+			self.levels_mut(entry.side).remove(i);
+		}
+	}
+
+	fn apply_entry(&mut self, symbol: &str, entry: &MdIncrementalEntry) -> Result<(), BookError> {
+		let entry_side = entry.md_entry_type.as_ref().and_then(BookSide::for_entry_type);
+		if entry.md_entry_type == Some(MdEntryType::Trade) {
+			let price = entry.md_entry_px.ok_or_else(|| BookError::MissingPrice(symbol.to_string()))?;
+			self.last_trade = Some(Level { price, size: entry.md_entry_size.unwrap_or_default() });
+			return Ok(());
+		}
+
+		match entry.md_update_action {
+			MdUpdateAction::New => {
+				let Some(side) = entry_side else {
+					return Ok(());
+				};
+				let price = entry.md_entry_px.ok_or_else(|| BookError::MissingPrice(symbol.to_string()))?;
+				let size = entry.md_entry_size.unwrap_or_default();
+				match entry.md_entry_id {
+					// Orders and quotes known by ID aggregate into their level
+					Some(ref id) => {
+						if self.entry_ids.contains_key(id) {
+							return Err(BookError::DuplicateEntry { symbol: symbol.to_string(), id: id.clone() });
+						}
+						self.entry_ids.insert(id.clone(), Entry { side, price, size });
+					},
+					None if self.position(side, price).is_ok() => {
+						return Err(BookError::DuplicateLevel { symbol: symbol.to_string(), side, price });
+					},
+					None => {},
+				}
+				self.add(side, price, size);
+			},
+			MdUpdateAction::Change => match self.identified(symbol, entry)? {
+				// An entry referred to by ID may move to another price
+				Some((id, old)) => {
+					let price = entry.md_entry_px.unwrap_or(old.price);
+					let size = entry.md_entry_size.unwrap_or(old.size);
+					self.remove_entry(&id);
+					self.add(old.side, price, size);
+					self.entry_ids.insert(id, Entry { side: old.side, price, size });
+				},
+				None => {
+					let (side, index) = self.find(symbol, entry, entry_side)?;
+					let level = &mut self.levels_mut(side)[index];
+					level.size = entry.md_entry_size.unwrap_or(level.size);
+				},
+			},
+			MdUpdateAction::Delete => match self.identified(symbol, entry)? {
+				Some((id, _)) => self.remove_entry(&id),
+				None => {
+					let (side, index) = self.find(symbol, entry, entry_side)?;
+					let price = self.levels_mut(side).remove(index).price;
+					self.entry_ids.retain(|_, other| (other.side, other.price) != (side, price));
+				},
+			},
+		}
+		Ok(())
+	}
+
+	/// The entry a Change or Delete refers to by MDEntryRefID/MDEntryID, `None` for an update by price
+	fn identified(&self, symbol: &str, entry: &MdIncrementalEntry) -> Result<Option<(String, Entry)>, BookError> {
+		let Some(id) = entry.md_entry_ref_id.as_ref().or(entry.md_entry_id.as_ref()) else {
+			return Ok(None);
+		};
+		match self.entry_ids.get(id) {
+			Some(known) => Ok(Some((id.clone(), *known))),
+			None => Err(BookError::UnknownLevel {
+				symbol: symbol.to_string(),
+				side: entry.md_entry_type.as_ref().and_then(BookSide::for_entry_type),
+				reference: id.clone(),
+			}),
+		}
+	}
+
+	/// Locate the level a Change or Delete entry without ID refers to, by price
+	fn find(
+		&self,
+		symbol: &str,
+		entry: &MdIncrementalEntry,
+		entry_side: Option<BookSide>,
+	) -> Result<(BookSide, usize), BookError> {
+		let unknown = || BookError::UnknownLevel {
+			symbol: symbol.to_string(),
+			side: entry_side,
+			reference: entry
+				.md_entry_px
+				.map(|price| price.to_string())
+				.unwrap_or_else(|| "without MDEntryID or MDEntryPx".to_string()),
+		};
+		let (side, price) = entry_side.zip(entry.md_entry_px).ok_or_else(unknown)?;
+		let index = self.position(side, price).map_err(|_| unknown())?;
+		Ok((side, index))
+	}
+}
+
+/// Order books of all instruments seen in market data, keyed by Symbol
+#[derive(Debug, Clone, Default)]
+pub struct OrderBook {
+	books: HashMap<String, Book>,
+}
+
+impl OrderBook {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Apply a market data message; other message types are ignored
+	pub fn apply(&mut self, message: &FixMessage) -> Result<(), BookError> {
+		match &message.body {
+			FixMessageBody::MarketDataSnapshotFullRefresh(body) => {
+				self.apply_snapshot(body);
+				Ok(())
+			},
+			FixMessageBody::MarketDataIncrementalRefresh(body) => self.apply_incremental(body),
+			_ => Ok(()),
+		}
+	}
+
+	/// Replace the book of the snapshot's instrument
+	pub fn apply_snapshot(&mut self, snapshot: &MarketDataSnapshotFullRefreshBody) {
+		let mut book = Book::default();
+		for entry in &snapshot.md_entries {
+			let Some(price) = entry.md_entry_px else {
+				continue;
+			};
+			let size = entry.md_entry_size.unwrap_or_default();
+			match BookSide::for_entry_type(&entry.md_entry_type) {
+				Some(side) => book.add(side, price, size),
+				None if entry.md_entry_type == MdEntryType::Trade => book.last_trade = Some(Level { price, size }),
+				None => {},
+			}
+		}
+		self.books.insert(snapshot.symbol.clone(), book);
+	}
+
+	/// Apply the entries of an incremental refresh in order
+	///
+	/// Stops at the first entry that does not fit the book of its instrument,
+	/// which is then marked stale.
+	pub fn apply_incremental(&mut self, refresh: &MarketDataIncrementalRefreshBody) -> Result<(), BookError> {
+		let mut symbol: Option<&str> = None;
+		for entry in &refresh.md_entries {
+			// Symbol may be omitted on entries for the same instrument as the previous one
+			let current = entry.symbol.as_deref().or(symbol).ok_or(BookError::MissingSymbol)?;
+			symbol = Some(current);
+
+			let book = self.books.get_mut(current).ok_or_else(|| BookError::NoSnapshot(current.to_string()))?;
+			if book.stale {
+				return Err(BookError::Stale(current.to_string()));
+			}
+			if let Err(e) = book.apply_entry(current, entry) {
+				book.stale = true;
+				return Err(e);
+			}
+		}
+		Ok(())
+	}
+
+	/// Book of `symbol`, once a snapshot was received for it
+	pub fn book(&self, symbol: &str) -> Option<&Book> {
+		self.books.get(symbol)
+	}
+
+	/// Symbols with a book
+	pub fn symbols(&self) -> impl Iterator<Item = &str> {
+		self.books.keys().map(String::as_str)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const SNAPSHOT: &str = "8=FIX.4.2\x019=0\x0135=W\x0149=MD\x0156=CLIENT\x0134=2\x0152=20240102-14:30:00.000\x01\
+		262=MD1\x0155=AAPL\x01268=5\x01\
+		269=0\x01270=150.00\x01271=300\x01\
+		269=0\x01270=149.90\x01271=500\x01\
+		269=1\x01270=150.10\x01271=200\x01\
+		269=1\x01270=150.20\x01271=400\x01\
+		269=2\x01270=150.05\x01271=100\x0110=000\x01";
+
+	fn incremental(seq: u32, entries: &str) -> FixMessage {
+		let count = entries.matches("279=").count();
+		let wire = format!(
+			"8=FIX.4.2\x019=0\x0135=X\x0149=MD\x0156=CLIENT\x0134={}\x0152=20240102-14:30:01.000\x01262=MD1\x01268={}\x01{}10=000\x01",
+			seq, count, entries
+		);
+		FixMessage::parse(&wire).unwrap()
+	}
+
+	fn book_from_snapshot() -> OrderBook {
+		let mut book = OrderBook::new();
+		book.apply(&FixMessage::parse(SNAPSHOT).unwrap()).unwrap();
+		book
+	}
+
+	#[test]
+	fn test_snapshot_queries() {
+		let books = book_from_snapshot();
+		let book = books.book("AAPL").unwrap();
+		assert_eq!(book.best_bid(), Some(Level { price: 150.0, size: 300.0 }));
+		assert_eq!(book.best_offer(), Some(Level { price: 150.1, size: 200.0 }));
+		assert!((book.spread().unwrap() - 0.1).abs() < 1e-9);
+		assert!(!book.is_crossed());
+		assert_eq!(book.depth(BookSide::Bid, 5).len(), 2);
+		assert_eq!(book.depth(BookSide::Offer, 1), &[Level { price: 150.1, size: 200.0 }]);
+		assert_eq!(book.last_trade(), Some(Level { price: 150.05, size: 100.0 }));
+
+		// 200 @ 150.10 + 100 @ 150.20
+		let vwap = book.vwap(BookSide::Offer, 300.0).unwrap();
+		assert!((vwap - (200.0 * 150.1 + 100.0 * 150.2) / 300.0).abs() < 1e-9);
+		assert_eq!(book.vwap(BookSide::Bid, 801.0), None);
+		assert!(books.book("MSFT").is_none());
+	}
+
+	#[test]
+	fn test_incremental_updates() {
+		let mut books = book_from_snapshot();
+		let update = incremental(
+			3,
+			"279=0\x01269=0\x01278=B3\x0155=AAPL\x01270=150.05\x01271=100\x01\
+			 279=1\x01269=1\x01270=150.10\x01271=50\x01\
+			 279=2\x01269=0\x01270=149.90\x01\
+			 279=0\x01269=2\x01270=150.10\x01271=150\x01",
+		);
+		books.apply(&update).unwrap();
+		let book = books.book("AAPL").unwrap();
+		assert_eq!(book.best_bid(), Some(Level { price: 150.05, size: 100.0 }));
+		assert_eq!(book.depth(BookSide::Bid, 5).len(), 2);
+		assert_eq!(book.best_offer(), Some(Level { price: 150.1, size: 50.0 }));
+		assert_eq!(book.last_trade(), Some(Level { price: 150.1, size: 150.0 }));
+
+		// Entries referred to by MDEntryID, with the symbol inherited by the second entry
+		books.apply(&incremental(4, "279=1\x01278=B3\x0155=AAPL\x01270=150.08\x01279=2\x01278=B3\x01")).unwrap();
+		let book = books.book("AAPL").unwrap();
+		assert_eq!(book.best_bid(), Some(Level { price: 150.0, size: 300.0 }));
+	}
+
+	#[test]
+	fn test_inconsistencies() {
+		let mut books = OrderBook::new();
+		let update = incremental(3, "279=2\x01269=0\x0155=AAPL\x01270=149.50\x01");
+		assert_eq!(books.apply(&update), Err(BookError::NoSnapshot("AAPL".into())));
+
+		let mut books = book_from_snapshot();
+		assert_eq!(
+			books.apply(&update),
+			Err(BookError::UnknownLevel {
+				symbol: "AAPL".into(),
+				side: Some(BookSide::Bid),
+				reference: "149.5".into()
+			})
+		);
+		assert!(books.book("AAPL").unwrap().is_stale());
+		let later = incremental(4, "279=0\x01269=0\x0155=AAPL\x01270=149.80\x01271=10\x01");
+		assert_eq!(books.apply(&later), Err(BookError::Stale("AAPL".into())));
+
+		// A new snapshot recovers the book
+		books.apply(&FixMessage::parse(SNAPSHOT).unwrap()).unwrap();
+		books.apply(&later).unwrap();
+		let duplicate = incremental(5, "279=0\x01269=1\x0155=AAPL\x01270=150.10\x01271=10\x01");
+		assert!(matches!(books.apply(&duplicate), Err(BookError::DuplicateLevel { side: BookSide::Offer, .. })));
+		assert_eq!(books.apply(&incremental(6, "279=2\x01278=X1\x01")), Err(BookError::MissingSymbol));
+	}
+
+	#[test]
+	fn test_entries_with_ids_share_levels() {
+		let mut books = book_from_snapshot();
+		books
+			.apply(&incremental(
+				3,
+				"279=0\x01269=1\x01278=O1\x0155=AAPL\x01270=150.10\x01271=20\x01\
+				 279=0\x01269=1\x01278=O2\x01270=150.15\x01271=30\x01\
+				 279=0\x01269=1\x01278=O3\x01270=150.15\x01271=40\x01",
+			))
+			.unwrap();
+		let book = books.book("AAPL").unwrap();
+		assert_eq!(book.best_offer(), Some(Level { price: 150.1, size: 220.0 }));
+		assert_eq!(book.depth(BookSide::Offer, 2)[1], Level { price: 150.15, size: 70.0 });
+
+		// Resizing, moving and deleting one order leaves the others at the level
+		books.apply(&incremental(4, "279=1\x01278=O2\x0155=AAPL\x01271=10\x01")).unwrap();
+		assert_eq!(books.book("AAPL").unwrap().depth(BookSide::Offer, 2)[1], Level { price: 150.15, size: 50.0 });
+		books.apply(&incremental(5, "279=1\x01278=O3\x0155=AAPL\x01270=150.10\x01")).unwrap();
+		let book = books.book("AAPL").unwrap();
+		assert_eq!(book.best_offer(), Some(Level { price: 150.1, size: 260.0 }));
+		assert_eq!(book.depth(BookSide::Offer, 2)[1], Level { price: 150.15, size: 10.0 });
+		books.apply(&incremental(6, "279=2\x01278=O2\x0155=AAPL\x01")).unwrap();
+		assert_eq!(books.book("AAPL").unwrap().depth(BookSide::Offer, 2)[1], Level { price: 150.2, size: 400.0 });
+		// The snapshot's size at 150.10 outlives the orders added to it
+		books.apply(&incremental(7, "279=2\x01278=O3\x0155=AAPL\x01")).unwrap();
+		assert_eq!(books.book("AAPL").unwrap().best_offer(), Some(Level { price: 150.1, size: 220.0 }));
+
+		let reused = incremental(8, "279=0\x01269=1\x01278=O1\x0155=AAPL\x01270=150.30\x01271=5\x01");
+		assert_eq!(books.apply(&reused), Err(BookError::DuplicateEntry { symbol: "AAPL".into(), id: "O1".into() }));
+	}
+}
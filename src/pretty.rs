@@ -0,0 +1,132 @@
+//! Human readable rendering of FIX messages
+//!
+//! [`PrettyPrinter`] writes one field per line as `tag Name = value (Description)`,
+//! with names and value descriptions looked up in a [`Dictionary`] and the
+//! entries of repeating groups indented under their NumInGroup field:
+//!
+//! ```text
+//! 8 BeginString = FIX.4.2
+//! 35 MsgType = V (MarketDataRequest)
+//! 146 NoRelatedSym = 1
+//!     55 Symbol = AAPL
+//! ```
+
+use crate::{FixMessage, SOH, common::wire_field, dictionary::Dictionary};
+
+const INDENT: &str = "    ";
+
+/// Formats FIX messages one field per line
+#[derive(Debug, Clone, Copy)]
+pub struct PrettyPrinter<'d> {
+	dictionary: &'d Dictionary,
+}
+
+impl Default for PrettyPrinter<'static> {
+	fn default() -> Self {
+		Self::new(Dictionary::fix42())
+	}
+}
+
+impl<'d> PrettyPrinter<'d> {
+	pub const fn new(dictionary: &'d Dictionary) -> Self {
+		Self { dictionary }
+	}
+
+	pub fn format(&self, message: &FixMessage) -> String {
+		self.format_raw(&message.to_fix_string())
+	}
+
+	/// Format a message in wire format, also when it does not parse
+	///
+	/// Fields are separated by SOH; a trailing separator is optional.
+	pub fn format_raw(&self, message: &str) -> String {
+		// Member tags of the groups the current field is nested in, innermost last
+		let mut groups: Vec<&[u32]> = Vec::new();
+		let mut lines = Vec::new();
+		for field in message.split(SOH).filter(|field| !field.is_empty()) {
+			let Some((tag, value)) = wire_field(field) else {
+				lines.push(format!("{}{}", INDENT.repeat(groups.len()), field));
+				continue;
+			};
+			while groups.last().is_some_and(|members| !members.contains(&tag)) {
+				groups.pop();
+			}
+			lines.push(format!("{}{}", INDENT.repeat(groups.len()), self.format_field(tag, value)));
+			if let Some(members) = self.dictionary.group_members(tag) {
+				groups.push(members);
+			}
+		}
+		lines.join("\n")
+	}
+
+	/// Format one field as `tag Name = value (Description)`
+	pub fn format_field(&self, tag: u32, value: &str) -> String {
+		match (self.dictionary.field_name(tag), self.dictionary.value_description(tag, value)) {
+			(Some(name), Some(description)) => format!("{} {} = {} ({})", tag, name, value, description),
+			(Some(name), None) => format!("{} {} = {}", tag, name, value),
+			(None, _) => format!("{} = {}", tag, value),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::test_support::order;
+
+	#[test]
+	fn test_format_order() {
+		let order = order();
+		let pretty = PrettyPrinter::default().format(&order);
+		let lines: Vec<&str> = pretty.lines().collect();
+		assert_eq!(lines[0], "8 BeginString = FIX.4.2");
+		assert!(lines.contains(&"35 MsgType = D (NewOrderSingle)"));
+		assert!(lines.contains(&"54 Side = 1 (Buy)"));
+		assert!(lines.contains(&"55 Symbol = AAPL"));
+		assert!(lines.last().unwrap().starts_with("10 CheckSum = "));
+		assert_eq!(format!("{:#}", order), pretty);
+	}
+
+	#[test]
+	fn test_format_groups() {
+		let snapshot = [
+			"8=FIX.4.2",
+			"9=0",
+			"35=W",
+			"262=REQ1",
+			"55=AAPL",
+			"268=2",
+			"269=0",
+			"270=10.5",
+			"271=100",
+			"269=1",
+			"270=10.6",
+			"271=200",
+			"58=after",
+			"10=000",
+		]
+		.join(SOH);
+		let pretty = PrettyPrinter::default().format_raw(&snapshot);
+		let expected = [
+			"8 BeginString = FIX.4.2",
+			"9 BodyLength = 0",
+			"35 MsgType = W (MarketDataSnapshotFullRefresh)",
+			"262 MDReqID = REQ1",
+			"55 Symbol = AAPL",
+			"268 NoMDEntries = 2",
+			"    269 MDEntryType = 0 (Bid)",
+			"    270 MDEntryPx = 10.5",
+			"    271 MDEntrySize = 100",
+			"    269 MDEntryType = 1 (Offer)",
+			"    270 MDEntryPx = 10.6",
+			"    271 MDEntrySize = 200",
+			"58 Text = after",
+			"10 CheckSum = 000",
+		];
+		assert_eq!(pretty.lines().collect::<Vec<_>>(), expected);
+		assert_eq!(
+			PrettyPrinter::default().format_raw("35=0\u{1}6000=X\u{1}garbage\u{1}"),
+			"35 MsgType = 0 (Heartbeat)\n6000 = X\ngarbage"
+		);
+	}
+}
@@ -0,0 +1,1326 @@
+//! Simple Binary Encoding (SBE)
+//!
+//! Encodes messages as FIX SBE: little-endian, fixed-layout blocks described by
+//! a [`Schema`]. Each message is laid out as
+//!
+//! - the message header: BlockLength, TemplateID, SchemaID and Version, each a `u16`;
+//! - the root block, holding the header fields of the schema followed by the
+//!   fields of the message template, each at a fixed offset;
+//! - the repeating groups of the template, each a dimension (BlockLength and
+//!   NumInGroup, both `u16`) followed by one block per entry;
+//! - the var-data fields, each a `u16` length followed by its bytes.
+//!
+//! [`ORDERS`] covers the order messages: New Order Single, Order Cancel Request,
+//! Order Cancel/Replace Request, Order Status Request, Execution Report, Order
+//! Cancel Reject and Don't Know Trade. None of them has a repeating group;
+//! schemas of your own can lay them out differently, or add Market Data
+//! Snapshot/Full Refresh with its NoMDEntries group.
+//!
+//! Blocks are written from the fields of the header, body and trailer structs
+//! and read back into them, each field by its tag. Missing optional fields are
+//! written as the null value of their type.
+//!
+//! Requires the `sbe` feature.
+
+use crate::{
+	FixMessage, FixMessageBuilder,
+	common::{FixHeader, FixTrailer, MsgType, Validate, ValidationError},
+	messages::{
+		DontKnowTradeBody, ExecutionReportBody, FixMessageBody, MarketDataSnapshotFullRefreshBody, MdEntry,
+		NewOrderSingleBody, OrderCancelRejectBody, OrderCancelReplaceRequestBody, OrderCancelRequestBody,
+		OrderStatusRequestBody,
+	},
+};
+use std::{borrow::Cow, fmt::Display, str::FromStr};
+use time::OffsetDateTime;
+
+/// Errors raised while converting to or from SBE
+#[derive(Debug)]
+pub enum SbeError {
+	/// The schema has no template for the message type, or the template lacks one of its fields
+	Unsupported(String),
+	/// A value does not fit its encoding, or the buffer is not laid out as the schema says
+	Invalid(String),
+	/// The decoded message fails validation
+	Validation(ValidationError),
+}
+
+impl Display for SbeError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Unsupported(what) => write!(f, "Not supported by the SBE schema: {}", what),
+			Self::Invalid(message) => write!(f, "Invalid SBE message: {}", message),
+			Self::Validation(e) => write!(f, "SBE message is not valid: {}", e),
+		}
+	}
+}
+
+impl std::error::Error for SbeError {}
+
+impl From<ValidationError> for SbeError {
+	fn from(e: ValidationError) -> Self {
+		Self::Validation(e)
+	}
+}
+
+/// Encoding of a field in a block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SbeType {
+	/// One ASCII character, null `0`
+	Char,
+	/// Fixed-length character array, padded and null with `0`
+	Chars(usize),
+	/// `u32`, null `u32::MAX`
+	UInt32,
+	/// `i64` mantissa with a constant exponent, null `i64::MIN`
+	Decimal(i8),
+	/// Nanoseconds since the Unix epoch as `u64`, null `u64::MAX`
+	Timestamp,
+}
+
+impl SbeType {
+	/// Size of the field in its block, in bytes
+	pub const fn size(self) -> usize {
+		match self {
+			Self::Char => 1,
+			Self::Chars(length) => length,
+			Self::UInt32 => 4,
+			Self::Decimal(_) | Self::Timestamp => 8,
+		}
+	}
+
+	/// Append `value` of field `tag`, or the null value when it is `None`
+	fn write(self, tag: u32, value: Option<&SbeValue>, buffer: &mut Vec<u8>) -> Result<(), SbeError> {
+		let invalid_value = |value: &SbeValue| invalid(format!("Tag {} cannot hold {:?} as {:?}", tag, value, self));
+		match (self, value) {
+			(Self::Char, None) => buffer.push(0),
+			(Self::Char, Some(value @ SbeValue::Text(text))) => match text.as_bytes() {
+				[] => buffer.push(0),
+				&[byte] if byte.is_ascii() => buffer.push(byte),
+				_ => return Err(invalid_value(value)),
+			},
+			(Self::Chars(length), None) => buffer.resize(buffer.len() + length, 0),
+			(Self::Chars(length), Some(value @ SbeValue::Text(text))) => {
+				let bytes = text.as_bytes();
+				if bytes.len() > length {
+					return Err(invalid_value(value));
+				}
+				buffer.extend_from_slice(bytes);
+				buffer.resize(buffer.len() + length - bytes.len(), 0);
+			},
+			(Self::UInt32, None) => buffer.extend_from_slice(&u32::MAX.to_le_bytes()),
+			(Self::UInt32, Some(&SbeValue::UInt(number))) if number != u32::MAX => {
+				buffer.extend_from_slice(&number.to_le_bytes());
+			},
+			(Self::Decimal(_), None) => buffer.extend_from_slice(&i64::MIN.to_le_bytes()),
+			(Self::Decimal(exponent), Some(value @ &SbeValue::Decimal(number))) => {
+				let scale = 10f64.powi(-i32::from(exponent));
+				let mantissa = (number * scale).round();
+				// Values with more decimals than the exponent allows are rejected rather than rounded
+				if !mantissa.is_finite()
+					|| mantissa <= i64::MIN as f64
+					|| mantissa >= i64::MAX as f64
+					|| mantissa / scale != number
+				{
+					return Err(invalid_value(value));
+				}
+				buffer.extend_from_slice(&(mantissa as i64).to_le_bytes());
+			},
+			(Self::Timestamp, None) => buffer.extend_from_slice(&u64::MAX.to_le_bytes()),
+			(Self::Timestamp, Some(value @ SbeValue::Timestamp(time))) => {
+				let nanos = u64::try_from(time.unix_timestamp_nanos()).map_err(|_| invalid_value(value))?;
+				buffer.extend_from_slice(&nanos.to_le_bytes());
+			},
+			(_, Some(value)) => return Err(invalid_value(value)),
+		}
+		Ok(())
+	}
+
+	/// Read the value of field `tag` from its `bytes`, `None` for the null value
+	fn read(self, tag: u32, bytes: &[u8]) -> Result<Option<SbeValue<'_>>, SbeError> {
+		let value = match self {
+			Self::Char => (bytes[0] != 0).then(|| utf8(tag, bytes)).transpose()?.map(SbeValue::from),
+			Self::Chars(_) => {
+				let end = bytes.iter().position(|byte| *byte == 0).unwrap_or(bytes.len());
+				(end > 0).then(|| utf8(tag, &bytes[..end])).transpose()?.map(SbeValue::from)
+			},
+			Self::UInt32 => {
+				let number = u32::from_le_bytes(bytes.try_into().expect("UInt32 is 4 bytes"));
+				(number != u32::MAX).then_some(SbeValue::UInt(number))
+			},
+			Self::Decimal(exponent) => {
+				let mantissa = i64::from_le_bytes(bytes.try_into().expect("Decimal is 8 bytes"));
+				(mantissa != i64::MIN).then(|| SbeValue::Decimal(mantissa as f64 / 10f64.powi(-i32::from(exponent))))
+			},
+			Self::Timestamp => {
+				let nanos = u64::from_le_bytes(bytes.try_into().expect("Timestamp is 8 bytes"));
+				if nanos == u64::MAX {
+					None
+				} else {
+					let time = OffsetDateTime::from_unix_timestamp_nanos(i128::from(nanos))
+						.map_err(|_| invalid(format!("Tag {} is out of range", tag)))?;
+					Some(SbeValue::Timestamp(time))
+				}
+			},
+		};
+		Ok(value)
+	}
+}
+
+/// Value of a field, as the typed message structs hand it to a block and take it back
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum SbeValue<'a> {
+	/// Text, or the code of an enum or flag, held by `Char` and `Chars` fields
+	Text(Cow<'a, str>),
+	/// Held by `UInt32` fields
+	UInt(u32),
+	/// Prices and quantities, held by `Decimal` fields
+	Decimal(f64),
+	/// Held by `Timestamp` fields
+	Timestamp(OffsetDateTime),
+}
+
+impl<'a> From<&'a str> for SbeValue<'a> {
+	fn from(text: &'a str) -> Self {
+		Self::Text(Cow::Borrowed(text))
+	}
+}
+
+impl SbeValue<'_> {
+	fn into_string(self, tag: u32) -> Result<String, SbeError> {
+		match self {
+			Self::Text(text) => Ok(text.into_owned()),
+			other => Err(other.mismatch(tag)),
+		}
+	}
+
+	/// The enum whose FIX code is the text
+	fn into_code<T: FromStr>(self, tag: u32) -> Result<T, SbeError> {
+		match self {
+			Self::Text(ref text) => text.parse().map_err(|_| self.mismatch(tag)),
+			other => Err(other.mismatch(tag)),
+		}
+	}
+
+	fn into_flag(self, tag: u32) -> Result<bool, SbeError> {
+		match self {
+			Self::Text(ref text) if text == "Y" => Ok(true),
+			Self::Text(ref text) if text == "N" => Ok(false),
+			other => Err(other.mismatch(tag)),
+		}
+	}
+
+	fn into_uint(self, tag: u32) -> Result<u32, SbeError> {
+		match self {
+			Self::UInt(number) => Ok(number),
+			other => Err(other.mismatch(tag)),
+		}
+	}
+
+	fn into_decimal(self, tag: u32) -> Result<f64, SbeError> {
+		match self {
+			Self::Decimal(number) => Ok(number),
+			other => Err(other.mismatch(tag)),
+		}
+	}
+
+	fn into_timestamp(self, tag: u32) -> Result<OffsetDateTime, SbeError> {
+		match self {
+			Self::Timestamp(time) => Ok(time),
+			other => Err(other.mismatch(tag)),
+		}
+	}
+
+	fn mismatch(&self, tag: u32) -> SbeError {
+		invalid(format!("Tag {} cannot be {:?}", tag, self))
+	}
+}
+
+/// Field `tag` holding `text`
+fn text(tag: u32, text: &str) -> (u32, SbeValue<'_>) {
+	(tag, SbeValue::from(text))
+}
+
+/// Field `tag` holding the FIX code of an enum
+fn code(tag: u32, value: &impl Display) -> (u32, SbeValue<'static>) {
+	(tag, SbeValue::Text(Cow::Owned(value.to_string())))
+}
+
+fn flag(tag: u32, value: bool) -> (u32, SbeValue<'static>) {
+	(tag, SbeValue::from(if value { "Y" } else { "N" }))
+}
+
+const fn uint(tag: u32, number: u32) -> (u32, SbeValue<'static>) {
+	(tag, SbeValue::UInt(number))
+}
+
+const fn decimal(tag: u32, number: f64) -> (u32, SbeValue<'static>) {
+	(tag, SbeValue::Decimal(number))
+}
+
+const fn timestamp(tag: u32, time: OffsetDateTime) -> (u32, SbeValue<'static>) {
+	(tag, SbeValue::Timestamp(time))
+}
+
+/// Access by tag to the fields of the header, the trailer or a message body
+pub(crate) trait SbeFields {
+	/// Fields set in the component, in wire order: a repeating group is its NumInGroup
+	/// field followed by the fields of each entry
+	fn sbe_fields(&self) -> Vec<(u32, SbeValue<'_>)>;
+
+	/// Set field `tag` to a decoded `value`
+	///
+	/// Fields arrive in wire order, the first field of a group entry starting a new entry.
+	fn set_sbe_field(&mut self, tag: u32, value: SbeValue) -> Result<(), SbeError>;
+}
+
+fn unknown_tag(tag: u32) -> SbeError {
+	SbeError::Unsupported(format!("tag {}", tag))
+}
+
+/// A repeating group of a template
+#[derive(Debug, Clone, Copy)]
+pub struct Group {
+	/// The NumInGroup field counting the entries
+	pub count_tag: u32,
+	/// Fields of each entry, the first one starting the entry on the wire
+	pub fields: &'static [(u32, SbeType)],
+}
+
+/// Layout of one message type
+#[derive(Debug, Clone, Copy)]
+pub struct Template {
+	pub id: u16,
+	/// MsgType (Tag 35) of the messages encoded with this template
+	pub msg_type: &'static str,
+	/// Fields of the root block, after the header fields of the schema
+	pub fields: &'static [(u32, SbeType)],
+	pub groups: &'static [Group],
+	/// Fields written as var-data, after the groups
+	pub var_data: &'static [u32],
+}
+
+/// A set of templates, identified in the message header by its ID and version
+#[derive(Debug, Clone, Copy)]
+pub struct Schema {
+	pub id: u16,
+	pub version: u16,
+	/// Standard header fields, starting the root block of every template
+	pub header: &'static [(u32, SbeType)],
+	pub templates: &'static [Template],
+}
+
+impl Schema {
+	pub fn template(&self, msg_type: &str) -> Option<&Template> {
+		self.templates.iter().find(|template| template.msg_type == msg_type)
+	}
+
+	pub fn template_by_id(&self, id: u16) -> Option<&Template> {
+		self.templates.iter().find(|template| template.id == id)
+	}
+}
+
+const COMP_ID: SbeType = SbeType::Chars(16);
+const ID: SbeType = SbeType::Chars(32);
+const SYMBOL: SbeType = SbeType::Chars(16);
+const ACCOUNT: SbeType = SbeType::Chars(16);
+const EXCHANGE: SbeType = SbeType::Chars(8);
+const PRICE: SbeType = SbeType::Decimal(-9);
+const QTY: SbeType = SbeType::Decimal(-4);
+
+/// Header fields of [`ORDERS`]: SenderCompID, TargetCompID, MsgSeqNum, SendingTime, PossDupFlag, PossResend and OrigSendingTime
+const ORDER_HEADER: &[(u32, SbeType)] = &[
+	(49, COMP_ID),
+	(56, COMP_ID),
+	(34, SbeType::UInt32),
+	(52, SbeType::Timestamp),
+	(43, SbeType::Char),
+	(97, SbeType::Char),
+	(122, SbeType::Timestamp),
+];
+
+/// Schema of the order messages
+pub const ORDERS: Schema = Schema {
+	id: 42,
+	version: 0,
+	header: ORDER_HEADER,
+	templates: &[
+		Template {
+			id: 1,
+			msg_type: "D",
+			fields: &[
+				(11, ID),
+				(21, SbeType::Char),
+				(55, SYMBOL),
+				(54, SbeType::Char),
+				(60, SbeType::Timestamp),
+				(40, SbeType::Char),
+				(38, QTY),
+				(152, QTY),
+				(207, EXCHANGE),
+				(44, PRICE),
+			],
+			groups: &[],
+			var_data: &[],
+		},
+		Template {
+			id: 2,
+			msg_type: "F",
+			fields: &[
+				(41, ID),
+				(37, ID),
+				(11, ID),
+				(55, SYMBOL),
+				(54, SbeType::Char),
+				(60, SbeType::Timestamp),
+				(38, QTY),
+				(152, QTY),
+				(1, ACCOUNT),
+			],
+			groups: &[],
+			var_data: &[58],
+		},
+		Template {
+			id: 3,
+			msg_type: "G",
+			fields: &[
+				(37, ID),
+				(41, ID),
+				(11, ID),
+				(1, ACCOUNT),
+				(21, SbeType::Char),
+				(18, SbeType::Chars(16)),
+				(55, SYMBOL),
+				(207, EXCHANGE),
+				(54, SbeType::Char),
+				(60, SbeType::Timestamp),
+				(38, QTY),
+				(152, QTY),
+				(40, SbeType::Char),
+				(44, PRICE),
+				(99, PRICE),
+				(15, SbeType::Chars(3)),
+				(59, SbeType::Char),
+				(126, SbeType::Timestamp),
+			],
+			groups: &[],
+			var_data: &[58],
+		},
+		Template {
+			id: 4,
+			msg_type: "H",
+			fields: &[(37, ID), (11, ID), (1, ACCOUNT), (55, SYMBOL), (207, EXCHANGE), (54, SbeType::Char)],
+			groups: &[],
+			var_data: &[],
+		},
+		Template {
+			id: 5,
+			msg_type: "8",
+			fields: &[
+				(37, ID),
+				(17, ID),
+				(20, SbeType::Char),
+				(19, ID),
+				(150, SbeType::Char),
+				(39, SbeType::Char),
+				(11, ID),
+				(41, ID),
+				(1, ACCOUNT),
+				(55, SYMBOL),
+				(54, SbeType::Char),
+				(60, SbeType::Timestamp),
+				(32, QTY),
+				(31, PRICE),
+				(151, QTY),
+				(14, QTY),
+				(6, PRICE),
+				(103, SbeType::UInt32),
+			],
+			groups: &[],
+			var_data: &[],
+		},
+		Template {
+			id: 6,
+			msg_type: "9",
+			fields: &[(37, ID), (11, ID), (41, ID), (39, SbeType::Char), (434, SbeType::Char), (102, SbeType::Char)],
+			groups: &[],
+			var_data: &[58],
+		},
+		Template {
+			id: 7,
+			msg_type: "Q",
+			fields: &[
+				(37, ID),
+				(17, ID),
+				(127, SbeType::Char),
+				(55, SYMBOL),
+				(54, SbeType::Char),
+				(38, QTY),
+				(152, QTY),
+				(32, QTY),
+				(31, PRICE),
+			],
+			groups: &[],
+			var_data: &[58],
+		},
+	],
+};
+
+/// The header starting every message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MessageHeader {
+	/// Length of the root block
+	pub block_length: u16,
+	pub template_id: u16,
+	pub schema_id: u16,
+	pub version: u16,
+}
+
+impl MessageHeader {
+	pub const ENCODED_LENGTH: usize = 8;
+
+	pub fn encode(&self, buffer: &mut Vec<u8>) {
+		for value in [self.block_length, self.template_id, self.schema_id, self.version] {
+			buffer.extend_from_slice(&value.to_le_bytes());
+		}
+	}
+
+	pub fn decode(buffer: &[u8]) -> Result<Self, SbeError> {
+		let [block_length, template_id, schema_id, version] = read_u16s(buffer, "message header")?;
+		Ok(Self { block_length, template_id, schema_id, version })
+	}
+}
+
+/// The dimension starting every repeating group
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GroupSize {
+	/// Length of the block of each entry
+	pub block_length: u16,
+	pub num_in_group: u16,
+}
+
+impl GroupSize {
+	pub const ENCODED_LENGTH: usize = 4;
+
+	pub fn encode(&self, buffer: &mut Vec<u8>) {
+		buffer.extend_from_slice(&self.block_length.to_le_bytes());
+		buffer.extend_from_slice(&self.num_in_group.to_le_bytes());
+	}
+
+	pub fn decode(buffer: &[u8]) -> Result<Self, SbeError> {
+		let [block_length, num_in_group] = read_u16s(buffer, "group dimension")?;
+		Ok(Self { block_length, num_in_group })
+	}
+}
+
+/// Read `N` consecutive `u16` from the start of `buffer`
+fn read_u16s<const N: usize>(buffer: &[u8], what: &str) -> Result<[u16; N], SbeError> {
+	if buffer.len() < N * 2 {
+		return Err(invalid(format!("Buffer ends inside the {}", what)));
+	}
+	Ok(std::array::from_fn(|i| u16::from_le_bytes([buffer[i * 2], buffer[i * 2 + 1]])))
+}
+
+/// Converts between FIX messages and SBE buffers laid out by a [`Schema`]
+#[derive(Debug, Clone, Copy)]
+pub struct SbeCodec<'s> {
+	schema: &'s Schema,
+}
+
+impl Default for SbeCodec<'static> {
+	fn default() -> Self {
+		Self::new(&ORDERS)
+	}
+}
+
+impl<'s> SbeCodec<'s> {
+	pub const fn new(schema: &'s Schema) -> Self {
+		Self { schema }
+	}
+
+	pub fn encode(&self, message: &FixMessage) -> Result<Vec<u8>, SbeError> {
+		let msg_type = &message.header.msg_type;
+		let template = self
+			.schema
+			.template(&msg_type.to_string())
+			.ok_or_else(|| SbeError::Unsupported(format!("MsgType {}", msg_type)))?;
+		let body = sbe_body(&message.body).ok_or_else(|| SbeError::Unsupported(format!("MsgType {}", msg_type)))?;
+		let fields: Vec<(u32, SbeValue)> =
+			[message.header.sbe_fields(), body.sbe_fields(), message.trailer.sbe_fields()]
+				.into_iter()
+				.flatten()
+				.collect();
+		let root_fields = self.root_fields(template);
+
+		let mut root = vec![None; root_fields.len()];
+		let mut groups = vec![Vec::new(); template.groups.len()];
+		let mut var_data = vec![None; template.var_data.len()];
+		let mut position = 0;
+		while let Some((tag, value)) = fields.get(position) {
+			let tag = *tag;
+			position += 1;
+			if let Some(index) = root_fields.iter().position(|(field, _)| *field == tag) {
+				root[index] = Some(value);
+			} else if let Some(index) = template.var_data.iter().position(|field| *field == tag) {
+				var_data[index] = Some(value);
+			} else if let Some(index) = template.groups.iter().position(|group| group.count_tag == tag) {
+				let count = value.clone().into_uint(tag)?;
+				groups[index] = group_entries(&template.groups[index], &fields, &mut position, count as usize)?;
+			} else {
+				return Err(SbeError::Unsupported(format!("tag {} in MsgType {}", tag, msg_type)));
+			}
+		}
+
+		let mut buffer = Vec::with_capacity(MessageHeader::ENCODED_LENGTH + block_length(&root_fields));
+		MessageHeader {
+			block_length: block_length(&root_fields) as u16,
+			template_id: template.id,
+			schema_id: self.schema.id,
+			version: self.schema.version,
+		}
+		.encode(&mut buffer);
+		for (&(tag, sbe_type), value) in root_fields.iter().zip(&root) {
+			sbe_type.write(tag, *value, &mut buffer)?;
+		}
+		for (group, entries) in template.groups.iter().zip(&groups) {
+			let num_in_group = u16::try_from(entries.len())
+				.map_err(|_| invalid(format!("Group {} has more than {} entries", group.count_tag, u16::MAX)))?;
+			GroupSize { block_length: block_length(group.fields) as u16, num_in_group }.encode(&mut buffer);
+			for entry in entries {
+				for (&(tag, sbe_type), value) in group.fields.iter().zip(entry) {
+					sbe_type.write(tag, *value, &mut buffer)?;
+				}
+			}
+		}
+		for (&tag, value) in template.var_data.iter().zip(&var_data) {
+			let value = match value {
+				None => "",
+				Some(SbeValue::Text(text)) => text,
+				Some(value) => return Err(value.mismatch(tag)),
+			};
+			let length = u16::try_from(value.len())
+				.map_err(|_| invalid(format!("Tag {} is longer than {} bytes", tag, u16::MAX)))?;
+			buffer.extend_from_slice(&length.to_le_bytes());
+			buffer.extend_from_slice(value.as_bytes());
+		}
+		Ok(buffer)
+	}
+
+	pub fn decode(&self, buffer: &[u8]) -> Result<FixMessage, SbeError> {
+		let header = MessageHeader::decode(buffer)?;
+		if header.schema_id != self.schema.id {
+			return Err(invalid(format!("Schema {} instead of {}", header.schema_id, self.schema.id)));
+		}
+		let template = self
+			.schema
+			.template_by_id(header.template_id)
+			.ok_or_else(|| SbeError::Unsupported(format!("template {}", header.template_id)))?;
+		let root_fields = self.root_fields(template);
+
+		let mut rest = &buffer[MessageHeader::ENCODED_LENGTH..];
+		let mut fields = Vec::new();
+		read_block(&mut rest, &root_fields, header.block_length, &mut fields)?;
+		for group in template.groups {
+			let size = GroupSize::decode(rest)?;
+			rest = &rest[GroupSize::ENCODED_LENGTH..];
+			fields.push((group.count_tag, SbeValue::UInt(u32::from(size.num_in_group))));
+			for _ in 0..size.num_in_group {
+				read_block(&mut rest, group.fields, size.block_length, &mut fields)?;
+			}
+		}
+		for &tag in template.var_data {
+			let [length] = read_u16s(rest, "var-data length")?;
+			let data = rest
+				.get(2..2 + usize::from(length))
+				.ok_or_else(|| invalid(format!("Buffer ends inside tag {}", tag)))?;
+			rest = &rest[2 + usize::from(length)..];
+			if !data.is_empty() {
+				fields.push((tag, SbeValue::from(utf8(tag, data)?)));
+			}
+		}
+
+		let msg_type: MsgType =
+			template.msg_type.parse().map_err(|_| invalid(format!("MsgType {}", template.msg_type)))?;
+		let mut message = FixMessage::new(msg_type, "", "", 0);
+		let unsupported = || SbeError::Unsupported(format!("MsgType {}", template.msg_type));
+		let body = sbe_body_mut(&mut message.body).ok_or_else(unsupported)?;
+		for (tag, value) in fields {
+			if self.schema.header.iter().any(|(field, _)| *field == tag) {
+				message.header.set_sbe_field(tag, value)?;
+			} else if matches!(tag, 93 | 89) {
+				message.trailer.set_sbe_field(tag, value)?;
+			} else {
+				body.set_sbe_field(tag, value)?;
+			}
+		}
+		let message = FixMessageBuilder::from_message(message).build();
+		message.validate()?;
+		Ok(message)
+	}
+
+	fn root_fields(&self, template: &Template) -> Vec<(u32, SbeType)> {
+		self.schema.header.iter().chain(template.fields).copied().collect()
+	}
+}
+
+/// The fields of `body`, for the message types with an SBE encoding
+fn sbe_body(body: &FixMessageBody) -> Option<&dyn SbeFields> {
+	match body {
+		FixMessageBody::NewOrderSingle(body) => Some(body),
+		FixMessageBody::OrderCancelRequest(body) => Some(body),
+		FixMessageBody::OrderCancelReplaceRequest(body) => Some(body),
+		FixMessageBody::OrderStatusRequest(body) => Some(body),
+		FixMessageBody::ExecutionReport(body) => Some(body),
+		FixMessageBody::OrderCancelReject(body) => Some(body),
+		FixMessageBody::DontKnowTrade(body) => Some(body),
+		FixMessageBody::MarketDataSnapshotFullRefresh(body) => Some(body),
+		_ => None,
+	}
+}
+
+fn sbe_body_mut(body: &mut FixMessageBody) -> Option<&mut dyn SbeFields> {
+	match body {
+		FixMessageBody::NewOrderSingle(body) => Some(body),
+		FixMessageBody::OrderCancelRequest(body) => Some(body),
+		FixMessageBody::OrderCancelReplaceRequest(body) => Some(body),
+		FixMessageBody::OrderStatusRequest(body) => Some(body),
+		FixMessageBody::ExecutionReport(body) => Some(body),
+		FixMessageBody::OrderCancelReject(body) => Some(body),
+		FixMessageBody::DontKnowTrade(body) => Some(body),
+		FixMessageBody::MarketDataSnapshotFullRefresh(body) => Some(body),
+		_ => None,
+	}
+}
+
+/// Fields of the `count` entries of `group`, taken from `fields[*position]` on
+fn group_entries<'f, 'v>(
+	group: &Group,
+	fields: &'f [(u32, SbeValue<'v>)],
+	position: &mut usize,
+	count: usize,
+) -> Result<Vec<Vec<Option<&'f SbeValue<'v>>>>, SbeError> {
+	let mut entries = Vec::with_capacity(count);
+	while entries.len() < count {
+		let mut entry = vec![None; group.fields.len()];
+		let start = *position;
+		while let Some((tag, value)) = fields.get(*position) {
+			match group.fields.iter().position(|(field, _)| field == tag) {
+				// Outside of the group, or the start of its next entry
+				None => break,
+				Some(index) if entry[index].is_some() => break,
+				Some(index) => entry[index] = Some(value),
+			}
+			*position += 1;
+		}
+		if *position == start {
+			return Err(invalid(format!("Group {} has fewer than {} entries", group.count_tag, count)));
+		}
+		entries.push(entry);
+	}
+	Ok(entries)
+}
+
+fn block_length(fields: &[(u32, SbeType)]) -> usize {
+	fields.iter().map(|(_, sbe_type)| sbe_type.size()).sum()
+}
+
+/// Read a block of `length` bytes laid out as `layout` from the start of `rest`, adding its non-null fields
+///
+/// Blocks longer than the layout, written with a later version of the schema, have their extra bytes skipped.
+fn read_block<'b>(
+	rest: &mut &'b [u8],
+	layout: &[(u32, SbeType)],
+	length: u16,
+	fields: &mut Vec<(u32, SbeValue<'b>)>,
+) -> Result<(), SbeError> {
+	let length = usize::from(length);
+	if length < block_length(layout) {
+		return Err(invalid(format!(
+			"Block of {} bytes is shorter than its {} byte layout",
+			length,
+			block_length(layout)
+		)));
+	}
+	let block = rest.get(..length).ok_or_else(|| invalid("Buffer ends inside a block"))?;
+	let mut offset = 0;
+	for &(tag, sbe_type) in layout {
+		if let Some(value) = sbe_type.read(tag, &block[offset..offset + sbe_type.size()])? {
+			fields.push((tag, value));
+		}
+		offset += sbe_type.size();
+	}
+	*rest = &rest[length..];
+	Ok(())
+}
+
+fn utf8(tag: u32, bytes: &[u8]) -> Result<&str, SbeError> {
+	std::str::from_utf8(bytes).map_err(|_| invalid(format!("Tag {} is not UTF-8", tag)))
+}
+
+fn invalid(message: impl Into<String>) -> SbeError {
+	SbeError::Invalid(message.into())
+}
+
+impl SbeFields for FixHeader {
+	fn sbe_fields(&self) -> Vec<(u32, SbeValue<'_>)> {
+		[
+			Some(text(49, &self.sender_comp_id)),
+			Some(text(56, &self.target_comp_id)),
+			Some(uint(34, self.msg_seq_num)),
+			Some(timestamp(52, self.sending_time)),
+			self.poss_dup_flag.map(|value| flag(43, value)),
+			self.poss_resend.map(|value| flag(97, value)),
+			self.orig_sending_time.map(|time| timestamp(122, time)),
+		]
+		.into_iter()
+		.flatten()
+		.collect()
+	}
+
+	fn set_sbe_field(&mut self, tag: u32, value: SbeValue) -> Result<(), SbeError> {
+		match tag {
+			49 => self.sender_comp_id = value.into_string(tag)?,
+			56 => self.target_comp_id = value.into_string(tag)?,
+			34 => self.msg_seq_num = value.into_uint(tag)?,
+			52 => self.sending_time = value.into_timestamp(tag)?,
+			43 => self.poss_dup_flag = Some(value.into_flag(tag)?),
+			97 => self.poss_resend = Some(value.into_flag(tag)?),
+			122 => self.orig_sending_time = Some(value.into_timestamp(tag)?),
+			_ => return Err(unknown_tag(tag)),
+		}
+		Ok(())
+	}
+}
+
+impl SbeFields for FixTrailer {
+	fn sbe_fields(&self) -> Vec<(u32, SbeValue<'_>)> {
+		[self.signature_length.map(|length| uint(93, length)), self.signature.as_deref().map(|value| text(89, value))]
+			.into_iter()
+			.flatten()
+			.collect()
+	}
+
+	fn set_sbe_field(&mut self, tag: u32, value: SbeValue) -> Result<(), SbeError> {
+		match tag {
+			93 => self.signature_length = Some(value.into_uint(tag)?),
+			89 => self.signature = Some(value.into_string(tag)?),
+			_ => return Err(unknown_tag(tag)),
+		}
+		Ok(())
+	}
+}
+
+impl SbeFields for NewOrderSingleBody {
+	fn sbe_fields(&self) -> Vec<(u32, SbeValue<'_>)> {
+		[
+			Some(text(11, &self.cl_ord_id)),
+			Some(text(21, &self.handl_inst)),
+			Some(text(55, &self.symbol)),
+			Some(code(54, &self.side)),
+			self.order_qty.map(|qty| decimal(38, qty)),
+			self.cash_order_qty.map(|qty| decimal(152, qty)),
+			Some(timestamp(60, self.transact_time)),
+			Some(text(40, &self.ord_type)),
+			self.price.map(|price| decimal(44, price)),
+			self.security_exchange.as_deref().map(|value| text(207, value)),
+		]
+		.into_iter()
+		.flatten()
+		.collect()
+	}
+
+	fn set_sbe_field(&mut self, tag: u32, value: SbeValue) -> Result<(), SbeError> {
+		match tag {
+			11 => self.cl_ord_id = value.into_string(tag)?,
+			21 => self.handl_inst = value.into_string(tag)?,
+			55 => self.symbol = value.into_string(tag)?,
+			54 => self.side = value.into_code(tag)?,
+			38 => self.order_qty = Some(value.into_decimal(tag)?),
+			152 => self.cash_order_qty = Some(value.into_decimal(tag)?),
+			60 => self.transact_time = value.into_timestamp(tag)?,
+			40 => self.ord_type = value.into_string(tag)?,
+			44 => self.price = Some(value.into_decimal(tag)?),
+			207 => self.security_exchange = Some(value.into_string(tag)?),
+			_ => return Err(unknown_tag(tag)),
+		}
+		Ok(())
+	}
+}
+
+impl SbeFields for OrderCancelRequestBody {
+	fn sbe_fields(&self) -> Vec<(u32, SbeValue<'_>)> {
+		[
+			Some(text(41, &self.orig_cl_ord_id)),
+			self.order_id.as_deref().map(|value| text(37, value)),
+			Some(text(11, &self.cl_ord_id)),
+			self.account.as_deref().map(|value| text(1, value)),
+			Some(text(55, &self.symbol)),
+			Some(code(54, &self.side)),
+			Some(timestamp(60, self.transact_time)),
+			self.order_qty.map(|qty| decimal(38, qty)),
+			self.cash_order_qty.map(|qty| decimal(152, qty)),
+			self.text.as_deref().map(|value| text(58, value)),
+		]
+		.into_iter()
+		.flatten()
+		.collect()
+	}
+
+	fn set_sbe_field(&mut self, tag: u32, value: SbeValue) -> Result<(), SbeError> {
+		match tag {
+			41 => self.orig_cl_ord_id = value.into_string(tag)?,
+			37 => self.order_id = Some(value.into_string(tag)?),
+			11 => self.cl_ord_id = value.into_string(tag)?,
+			1 => self.account = Some(value.into_string(tag)?),
+			55 => self.symbol = value.into_string(tag)?,
+			54 => self.side = value.into_code(tag)?,
+			60 => self.transact_time = value.into_timestamp(tag)?,
+			38 => self.order_qty = Some(value.into_decimal(tag)?),
+			152 => self.cash_order_qty = Some(value.into_decimal(tag)?),
+			58 => self.text = Some(value.into_string(tag)?),
+			_ => return Err(unknown_tag(tag)),
+		}
+		Ok(())
+	}
+}
+
+impl SbeFields for OrderCancelReplaceRequestBody {
+	fn sbe_fields(&self) -> Vec<(u32, SbeValue<'_>)> {
+		[
+			self.order_id.as_deref().map(|value| text(37, value)),
+			Some(text(41, &self.orig_cl_ord_id)),
+			Some(text(11, &self.cl_ord_id)),
+			self.account.as_deref().map(|value| text(1, value)),
+			Some(text(21, &self.handl_inst)),
+			self.exec_inst.as_deref().map(|value| text(18, value)),
+			Some(text(55, &self.symbol)),
+			self.security_exchange.as_deref().map(|value| text(207, value)),
+			Some(code(54, &self.side)),
+			Some(timestamp(60, self.transact_time)),
+			self.order_qty.map(|qty| decimal(38, qty)),
+			self.cash_order_qty.map(|qty| decimal(152, qty)),
+			Some(text(40, &self.ord_type)),
+			self.price.map(|price| decimal(44, price)),
+			self.stop_px.map(|price| decimal(99, price)),
+			self.currency.as_deref().map(|value| text(15, value)),
+			self.time_in_force.as_ref().map(|value| code(59, value)),
+			self.expire_time.map(|time| timestamp(126, time)),
+			self.text.as_deref().map(|value| text(58, value)),
+		]
+		.into_iter()
+		.flatten()
+		.collect()
+	}
+
+	fn set_sbe_field(&mut self, tag: u32, value: SbeValue) -> Result<(), SbeError> {
+		match tag {
+			37 => self.order_id = Some(value.into_string(tag)?),
+			41 => self.orig_cl_ord_id = value.into_string(tag)?,
+			11 => self.cl_ord_id = value.into_string(tag)?,
+			1 => self.account = Some(value.into_string(tag)?),
+			21 => self.handl_inst = value.into_string(tag)?,
+			18 => self.exec_inst = Some(value.into_string(tag)?),
+			55 => self.symbol = value.into_string(tag)?,
+			207 => self.security_exchange = Some(value.into_string(tag)?),
+			54 => self.side = value.into_code(tag)?,
+			60 => self.transact_time = value.into_timestamp(tag)?,
+			38 => self.order_qty = Some(value.into_decimal(tag)?),
+			152 => self.cash_order_qty = Some(value.into_decimal(tag)?),
+			40 => self.ord_type = value.into_string(tag)?,
+			44 => self.price = Some(value.into_decimal(tag)?),
+			99 => self.stop_px = Some(value.into_decimal(tag)?),
+			15 => self.currency = Some(value.into_string(tag)?),
+			59 => self.time_in_force = Some(value.into_code(tag)?),
+			126 => self.expire_time = Some(value.into_timestamp(tag)?),
+			58 => self.text = Some(value.into_string(tag)?),
+			_ => return Err(unknown_tag(tag)),
+		}
+		Ok(())
+	}
+}
+
+impl SbeFields for OrderStatusRequestBody {
+	fn sbe_fields(&self) -> Vec<(u32, SbeValue<'_>)> {
+		[
+			self.order_id.as_deref().map(|value| text(37, value)),
+			Some(text(11, &self.cl_ord_id)),
+			self.account.as_deref().map(|value| text(1, value)),
+			Some(text(55, &self.symbol)),
+			self.security_exchange.as_deref().map(|value| text(207, value)),
+			Some(code(54, &self.side)),
+		]
+		.into_iter()
+		.flatten()
+		.collect()
+	}
+
+	fn set_sbe_field(&mut self, tag: u32, value: SbeValue) -> Result<(), SbeError> {
+		match tag {
+			37 => self.order_id = Some(value.into_string(tag)?),
+			11 => self.cl_ord_id = value.into_string(tag)?,
+			1 => self.account = Some(value.into_string(tag)?),
+			55 => self.symbol = value.into_string(tag)?,
+			207 => self.security_exchange = Some(value.into_string(tag)?),
+			54 => self.side = value.into_code(tag)?,
+			_ => return Err(unknown_tag(tag)),
+		}
+		Ok(())
+	}
+}
+
+impl SbeFields for ExecutionReportBody {
+	fn sbe_fields(&self) -> Vec<(u32, SbeValue<'_>)> {
+		[
+			Some(text(37, &self.order_id)),
+			Some(text(17, &self.exec_id)),
+			Some(code(20, &self.exec_trans_type)),
+			self.exec_ref_id.as_deref().map(|value| text(19, value)),
+			Some(code(150, &self.exec_type)),
+			Some(code(39, &self.ord_status)),
+			self.cl_ord_id.as_deref().map(|value| text(11, value)),
+			self.orig_cl_ord_id.as_deref().map(|value| text(41, value)),
+			self.account.as_deref().map(|value| text(1, value)),
+			Some(text(55, &self.symbol)),
+			Some(code(54, &self.side)),
+			self.transact_time.map(|time| timestamp(60, time)),
+			self.last_shares.map(|qty| decimal(32, qty)),
+			self.last_px.map(|price| decimal(31, price)),
+			Some(decimal(151, self.leaves_qty)),
+			Some(decimal(14, self.cum_qty)),
+			Some(decimal(6, self.avg_px)),
+			self.ord_rej_reason.map(|reason| uint(103, reason)),
+		]
+		.into_iter()
+		.flatten()
+		.collect()
+	}
+
+	fn set_sbe_field(&mut self, tag: u32, value: SbeValue) -> Result<(), SbeError> {
+		match tag {
+			37 => self.order_id = value.into_string(tag)?,
+			17 => self.exec_id = value.into_string(tag)?,
+			20 => self.exec_trans_type = value.into_code(tag)?,
+			19 => self.exec_ref_id = Some(value.into_string(tag)?),
+			150 => self.exec_type = value.into_code(tag)?,
+			39 => self.ord_status = value.into_code(tag)?,
+			11 => self.cl_ord_id = Some(value.into_string(tag)?),
+			41 => self.orig_cl_ord_id = Some(value.into_string(tag)?),
+			1 => self.account = Some(value.into_string(tag)?),
+			55 => self.symbol = value.into_string(tag)?,
+			54 => self.side = value.into_code(tag)?,
+			60 => self.transact_time = Some(value.into_timestamp(tag)?),
+			32 => self.last_shares = Some(value.into_decimal(tag)?),
+			31 => self.last_px = Some(value.into_decimal(tag)?),
+			151 => self.leaves_qty = value.into_decimal(tag)?,
+			14 => self.cum_qty = value.into_decimal(tag)?,
+			6 => self.avg_px = value.into_decimal(tag)?,
+			103 => self.ord_rej_reason = Some(value.into_uint(tag)?),
+			_ => return Err(unknown_tag(tag)),
+		}
+		Ok(())
+	}
+}
+
+impl SbeFields for OrderCancelRejectBody {
+	fn sbe_fields(&self) -> Vec<(u32, SbeValue<'_>)> {
+		[
+			Some(text(37, &self.order_id)),
+			Some(text(11, &self.cl_ord_id)),
+			Some(text(41, &self.orig_cl_ord_id)),
+			Some(code(39, &self.ord_status)),
+			Some(code(434, &self.cxl_rej_response_to)),
+			self.cxl_rej_reason.as_ref().map(|reason| code(102, reason)),
+			self.text.as_deref().map(|value| text(58, value)),
+		]
+		.into_iter()
+		.flatten()
+		.collect()
+	}
+
+	fn set_sbe_field(&mut self, tag: u32, value: SbeValue) -> Result<(), SbeError> {
+		match tag {
+			37 => self.order_id = value.into_string(tag)?,
+			11 => self.cl_ord_id = value.into_string(tag)?,
+			41 => self.orig_cl_ord_id = value.into_string(tag)?,
+			39 => self.ord_status = value.into_code(tag)?,
+			434 => self.cxl_rej_response_to = value.into_code(tag)?,
+			102 => self.cxl_rej_reason = Some(value.into_code(tag)?),
+			58 => self.text = Some(value.into_string(tag)?),
+			_ => return Err(unknown_tag(tag)),
+		}
+		Ok(())
+	}
+}
+
+impl SbeFields for DontKnowTradeBody {
+	fn sbe_fields(&self) -> Vec<(u32, SbeValue<'_>)> {
+		[
+			Some(text(37, &self.order_id)),
+			Some(text(17, &self.exec_id)),
+			Some(code(127, &self.dk_reason)),
+			Some(text(55, &self.symbol)),
+			Some(code(54, &self.side)),
+			self.order_qty.map(|qty| decimal(38, qty)),
+			self.cash_order_qty.map(|qty| decimal(152, qty)),
+			self.last_shares.map(|qty| decimal(32, qty)),
+			self.last_px.map(|price| decimal(31, price)),
+			self.text.as_deref().map(|value| text(58, value)),
+		]
+		.into_iter()
+		.flatten()
+		.collect()
+	}
+
+	fn set_sbe_field(&mut self, tag: u32, value: SbeValue) -> Result<(), SbeError> {
+		match tag {
+			37 => self.order_id = value.into_string(tag)?,
+			17 => self.exec_id = value.into_string(tag)?,
+			127 => self.dk_reason = value.into_code(tag)?,
+			55 => self.symbol = value.into_string(tag)?,
+			54 => self.side = value.into_code(tag)?,
+			38 => self.order_qty = Some(value.into_decimal(tag)?),
+			152 => self.cash_order_qty = Some(value.into_decimal(tag)?),
+			32 => self.last_shares = Some(value.into_decimal(tag)?),
+			31 => self.last_px = Some(value.into_decimal(tag)?),
+			58 => self.text = Some(value.into_string(tag)?),
+			_ => return Err(unknown_tag(tag)),
+		}
+		Ok(())
+	}
+}
+
+impl SbeFields for MarketDataSnapshotFullRefreshBody {
+	fn sbe_fields(&self) -> Vec<(u32, SbeValue<'_>)> {
+		let mut fields: Vec<_> = [
+			self.md_req_id.as_deref().map(|value| text(262, value)),
+			Some(text(55, &self.symbol)),
+			self.security_exchange.as_deref().map(|value| text(207, value)),
+			Some(uint(268, self.md_entries.len() as u32)),
+		]
+		.into_iter()
+		.flatten()
+		.collect();
+		for entry in &self.md_entries {
+			fields.extend(
+				[
+					Some(code(269, &entry.md_entry_type)),
+					entry.md_entry_px.map(|price| decimal(270, price)),
+					entry.md_entry_size.map(|size| decimal(271, size)),
+					entry.md_entry_position_no.map(|position| uint(290, position)),
+					entry.number_of_orders.map(|orders| uint(346, orders)),
+				]
+				.into_iter()
+				.flatten(),
+			);
+		}
+		fields
+	}
+
+	fn set_sbe_field(&mut self, tag: u32, value: SbeValue) -> Result<(), SbeError> {
+		match tag {
+			262 => self.md_req_id = Some(value.into_string(tag)?),
+			55 => self.symbol = value.into_string(tag)?,
+			207 => self.security_exchange = Some(value.into_string(tag)?),
+			// The entries are counted as they arrive
+			268 => {
+				value.into_uint(tag)?;
+			},
+			269 => self.md_entries.push(MdEntry {
+				md_entry_type: value.into_code(tag)?,
+				md_entry_px: None,
+				md_entry_size: None,
+				md_entry_position_no: None,
+				number_of_orders: None,
+			}),
+			270 => current_entry(&mut self.md_entries, tag)?.md_entry_px = Some(value.into_decimal(tag)?),
+			271 => current_entry(&mut self.md_entries, tag)?.md_entry_size = Some(value.into_decimal(tag)?),
+			290 => current_entry(&mut self.md_entries, tag)?.md_entry_position_no = Some(value.into_uint(tag)?),
+			346 => current_entry(&mut self.md_entries, tag)?.number_of_orders = Some(value.into_uint(tag)?),
+			_ => return Err(unknown_tag(tag)),
+		}
+		Ok(())
+	}
+}
+
+fn current_entry(entries: &mut [MdEntry], tag: u32) -> Result<&mut MdEntry, SbeError> {
+	entries.last_mut().ok_or_else(|| invalid(format!("Tag {} outside of NoMDEntries", tag)))
+}
+
+impl FixMessage {
+	/// Encode the message as SBE with the [`ORDERS`] schema
+	pub fn to_sbe(&self) -> Result<Vec<u8>, SbeError> {
+		SbeCodec::default().encode(self)
+	}
+
+	/// Decode an SBE message with the [`ORDERS`] schema
+	pub fn from_sbe(buffer: &[u8]) -> Result<Self, SbeError> {
+		SbeCodec::default().decode(buffer)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		CxlRejReason, CxlRejResponseTo, MdEntryType, OrdStatus, Side,
+		test_support::{assert_round_trip, cancel, order},
+	};
+	use time::macros::datetime;
+
+	fn round_trip(message: &FixMessage) {
+		assert_round_trip(message, |message| FixMessage::from_sbe(&message.to_sbe()?));
+	}
+
+	#[test]
+	fn test_encode_order() {
+		let order = order();
+		let buffer = order.to_sbe().unwrap();
+		let header = MessageHeader::decode(&buffer).unwrap();
+		assert_eq!(header, MessageHeader { block_length: 145, template_id: 1, schema_id: 42, version: 0 });
+		assert_eq!(buffer.len(), MessageHeader::ENCODED_LENGTH + 145);
+
+		let block = &buffer[MessageHeader::ENCODED_LENGTH..];
+		assert_eq!(&block[..16], b"CLIENT\0\0\0\0\0\0\0\0\0\0");
+		assert_eq!(&block[32..36], &7u32.to_le_bytes());
+		assert_eq!(&block[36..44], &1_704_164_645_006_000_000u64.to_le_bytes());
+		assert_eq!(block[44], 0, "PossDupFlag is null");
+		assert_eq!(&block[46..54], &u64::MAX.to_le_bytes(), "OrigSendingTime is null");
+		// Price is the last field, with an exponent of -9
+		assert_eq!(&block[137..], &10_500_000_000i64.to_le_bytes());
+		round_trip(&order);
+	}
+
+	#[test]
+	fn test_order_messages_round_trip() {
+		let cancel = cancel();
+		let buffer = cancel.to_sbe().unwrap();
+		// Text is var-data, after the root block
+		assert_eq!(&buffer[buffer.len() - 10..], b"\x08\x00Too late");
+		round_trip(&cancel);
+
+		let report = FixMessage::builder(MsgType::ExecutionReport, "SERVER", "CLIENT", 3)
+			.sending_time(datetime!(2024-01-02 03:04:05 UTC))
+			.order_id("OID1")
+			.exec_id("EXEC1")
+			.exec_trans_type("0")
+			.exec_type("1")
+			.ord_status(OrdStatus::PartiallyFilled)
+			.symbol("AAPL")
+			.side(Side::Buy)
+			.leaves_qty(60.0)
+			.cum_qty(40.0)
+			.avg_px(10.25)
+			.last_shares(40.0)
+			.last_px(10.25)
+			.build();
+		round_trip(&report);
+
+		// A possible duplicate carries PossDupFlag and OrigSendingTime in the root block
+		let resent = FixMessageBuilder::from_message(order())
+			.poss_dup_flag(true)
+			.orig_sending_time(datetime!(2024-01-02 03:04:05.006 UTC))
+			.build();
+		round_trip(&resent);
+
+		let reject = FixMessage::builder(MsgType::OrderCancelReject, "SERVER", "CLIENT", 4)
+			.sending_time(datetime!(2024-01-02 03:04:05 UTC))
+			.order_id("OID1")
+			.cl_ord_id("ORD2")
+			.orig_cl_ord_id("ORD1")
+			.ord_status(OrdStatus::Filled)
+			.cxl_rej_response_to(CxlRejResponseTo::OrderCancelRequest)
+			.cxl_rej_reason(CxlRejReason::TooLateToCancel)
+			.build();
+		round_trip(&reject);
+	}
+
+	#[test]
+	fn test_groups() {
+		const SNAPSHOTS: Schema = Schema {
+			id: 7,
+			version: 1,
+			header: ORDER_HEADER,
+			templates: &[Template {
+				id: 1,
+				msg_type: "W",
+				fields: &[(262, ID), (55, SYMBOL)],
+				groups: &[Group { count_tag: 268, fields: &[(269, SbeType::Char), (270, PRICE), (271, QTY)] }],
+				var_data: &[],
+			}],
+		};
+		let codec = SbeCodec::new(&SNAPSHOTS);
+		let snapshot = FixMessage::builder(MsgType::MarketDataSnapshotFullRefresh, "SERVER", "CLIENT", 3)
+			.sending_time(datetime!(2024-01-02 03:04:05 UTC))
+			.md_req_id("REQ1")
+			.symbol("AAPL")
+			.md_entry(MdEntry::new(MdEntryType::Bid, 10.5).with_size(100.0))
+			.md_entry(MdEntry::new(MdEntryType::Offer, 10.6).with_size(200.0))
+			.build();
+
+		let buffer = codec.encode(&snapshot).unwrap();
+		let root_length = block_length(ORDER_HEADER) + 48;
+		let group = &buffer[MessageHeader::ENCODED_LENGTH + root_length..];
+		assert_eq!(GroupSize::decode(group).unwrap(), GroupSize { block_length: 17, num_in_group: 2 });
+		assert_eq!(group.len(), GroupSize::ENCODED_LENGTH + 2 * 17);
+		assert_eq!(group[GroupSize::ENCODED_LENGTH + 17], b'1');
+		assert_eq!(codec.decode(&buffer).unwrap(), snapshot);
+
+		// Entries written with a later version of the schema, with a longer block, are still read
+		let mut longer = buffer[..MessageHeader::ENCODED_LENGTH + root_length].to_vec();
+		GroupSize { block_length: 20, num_in_group: 2 }.encode(&mut longer);
+		for entry in group[GroupSize::ENCODED_LENGTH..].chunks(17) {
+			longer.extend_from_slice(entry);
+			longer.extend_from_slice(&[0xff; 3]);
+		}
+		assert_eq!(codec.decode(&longer).unwrap(), snapshot);
+
+		// An empty book is a group without entries
+		let empty = FixMessage::builder(MsgType::MarketDataSnapshotFullRefresh, "SERVER", "CLIENT", 4)
+			.sending_time(datetime!(2024-01-02 03:04:05 UTC))
+			.symbol("AAPL")
+			.build();
+		assert_round_trip(&empty, |message| codec.decode(&codec.encode(message)?));
+	}
+
+	#[test]
+	fn test_errors() {
+		let heartbeat = FixMessage::builder(MsgType::Heartbeat, "CLIENT", "SERVER", 1).build();
+		assert!(matches!(heartbeat.to_sbe(), Err(SbeError::Unsupported(_))));
+		let long_id = FixMessageBuilder::from_message(order()).cl_ord_id("X".repeat(33)).build();
+		assert!(matches!(long_id.to_sbe(), Err(SbeError::Invalid(_))));
+		// Price has nine decimals and OrderQty four
+		let precise = FixMessageBuilder::from_message(order()).price(10.000_000_001).order_qty(0.0001).build();
+		round_trip(&precise);
+		let too_precise = FixMessageBuilder::from_message(order()).price(10.000_000_000_1).build();
+		assert!(matches!(too_precise.to_sbe(), Err(SbeError::Invalid(_))));
+		let fractional_qty = FixMessageBuilder::from_message(order()).order_qty(0.00001).build();
+		assert!(matches!(fractional_qty.to_sbe(), Err(SbeError::Invalid(_))));
+		// A schema without Price cannot encode a limit order
+		const NO_PRICE: Schema = Schema {
+			id: 42,
+			version: 0,
+			header: ORDER_HEADER,
+			templates: &[Template { fields: &[(11, ID), (55, SYMBOL), (54, SbeType::Char)], ..ORDERS.templates[0] }],
+		};
+		assert!(matches!(SbeCodec::new(&NO_PRICE).encode(&order()), Err(SbeError::Unsupported(_))));
+
+		let buffer = order().to_sbe().unwrap();
+		assert!(matches!(FixMessage::from_sbe(&buffer[..6]), Err(SbeError::Invalid(_))));
+		assert!(matches!(FixMessage::from_sbe(&buffer[..100]), Err(SbeError::Invalid(_))));
+		let mut other_schema = buffer.clone();
+		other_schema[4] = 43;
+		assert!(matches!(FixMessage::from_sbe(&other_schema), Err(SbeError::Invalid(_))));
+		let mut unknown_template = buffer.clone();
+		unknown_template[2] = 99;
+		assert!(matches!(FixMessage::from_sbe(&unknown_template), Err(SbeError::Unsupported(_))));
+		// The order needs an OrderQty, the eighth field of its block
+		let mut no_quantity = buffer;
+		let offset = MessageHeader::ENCODED_LENGTH + block_length(ORDER_HEADER) + 32 + 1 + 16 + 1 + 8 + 1;
+		no_quantity[offset..offset + 8].copy_from_slice(&i64::MIN.to_le_bytes());
+		assert!(matches!(FixMessage::from_sbe(&no_quantity), Err(SbeError::Validation(_))));
+		// Side, after ClOrdID, HandlInst and Symbol, must be one of its codes
+		let mut unknown_side = order().to_sbe().unwrap();
+		unknown_side[MessageHeader::ENCODED_LENGTH + block_length(ORDER_HEADER) + 32 + 1 + 16] = b'X';
+		assert!(matches!(FixMessage::from_sbe(&unknown_side), Err(SbeError::Invalid(_))));
+	}
+}
@@ -0,0 +1,191 @@
+//! serde support
+//!
+//! With the `serde` feature, the header, trailer, message bodies and
+//! [`FixMessage`](crate::FixMessage) implement `Serialize` and `Deserialize`:
+//!
+//! - Enums defined with [`fix_enum!`](crate::fix_enum) serialize as their FIX
+//!   code (`"1"` for `Side::Buy`) and deserialize from their code or their name.
+//!   Fields of your own types can be written by name with
+//!   `#[serde(with = "fix_learning::serde_fix::enum_name")]`.
+//! - Timestamps serialize in FIX format, `YYYYMMDD-HH:MM:SS.sss`, see [`timestamp`].
+//! - [`FixMessageBody`](crate::FixMessageBody) is tagged with its variant:
+//!   `{"type": "Heartbeat", "body": {...}}`.
+
+use crate::{
+	common::{
+		BusinessRejectReason, CxlRejReason, CxlRejResponseTo, DkReason, EncryptMethod, MdEntryType, MdReqRejReason,
+		MdUpdateAction, MdUpdateType, MsgType, OrdStatus, SessionRejectReason, Side, SubscriptionRequestType,
+		TimeInForce,
+		enums::{ExecTransType, ExecType},
+	},
+	macros::FixEnum,
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error};
+
+/// Read an enum from its FIX code, or else from its name
+fn enum_from_str<'de, E: FixEnum, D: Deserializer<'de>>(value: &str) -> Result<E, D::Error> {
+	value
+		.parse()
+		.ok()
+		.or_else(|| E::from_name(value))
+		.ok_or_else(|| D::Error::custom(format!("unknown value {}", value)))
+}
+
+/// Implements serde for enums defined with `fix_enum!`, using their FIX code
+macro_rules! serde_fix_enums {
+	($($name:ty),* $(,)?) => {
+		$(
+			impl Serialize for $name {
+				fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+					serializer.collect_str(self)
+				}
+			}
+
+			impl<'de> Deserialize<'de> for $name {
+				fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+					enum_from_str::<Self, D>(&String::deserialize(deserializer)?)
+				}
+			}
+		)*
+	};
+}
+
+serde_fix_enums!(
+	BusinessRejectReason,
+	CxlRejReason,
+	CxlRejResponseTo,
+	DkReason,
+	EncryptMethod,
+	ExecTransType,
+	ExecType,
+	MdEntryType,
+	MdReqRejReason,
+	MdUpdateAction,
+	MdUpdateType,
+	MsgType,
+	OrdStatus,
+	SessionRejectReason,
+	Side,
+	SubscriptionRequestType,
+	TimeInForce,
+);
+
+/// Serialize a `fix_enum!` enum by variant name, for `#[serde(with = "...")]`
+///
+/// Values without a name of their own, those held by `Other`, keep their FIX
+/// code. Deserializing accepts names and codes.
+pub mod enum_name {
+	use super::*;
+
+	pub fn serialize<E: FixEnum, S: Serializer>(value: &E, serializer: S) -> Result<S::Ok, S::Error> {
+		match E::from_name(value.name()) {
+			Some(_) => serializer.serialize_str(value.name()),
+			None => serializer.collect_str(value),
+		}
+	}
+
+	pub fn deserialize<'de, E: FixEnum, D: Deserializer<'de>>(deserializer: D) -> Result<E, D::Error> {
+		enum_from_str::<E, D>(&String::deserialize(deserializer)?)
+	}
+}
+
+/// Serialize an `OffsetDateTime` as a FIX UTCTimestamp, for `#[serde(with = "...")]`
+pub mod timestamp {
+	use super::*;
+	use crate::common::{parse_fix_timestamp, write_timestamp};
+	use time::OffsetDateTime;
+
+	pub fn serialize<S: Serializer>(time: &OffsetDateTime, serializer: S) -> Result<S::Ok, S::Error> {
+		let mut buffer = String::with_capacity(21);
+		write_timestamp(&mut buffer, *time);
+		serializer.serialize_str(&buffer)
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<OffsetDateTime, D::Error> {
+		parse_fix_timestamp(&String::deserialize(deserializer)?).map_err(D::Error::custom)
+	}
+
+	/// The same for `Option<OffsetDateTime>`
+	pub mod option {
+		use super::*;
+
+		pub fn serialize<S: Serializer>(time: &Option<OffsetDateTime>, serializer: S) -> Result<S::Ok, S::Error> {
+			match time {
+				Some(time) => super::serialize(time, serializer),
+				None => serializer.serialize_none(),
+			}
+		}
+
+		pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<OffsetDateTime>, D::Error> {
+			Option::<String>::deserialize(deserializer)?
+				.map(|time| parse_fix_timestamp(&time).map_err(D::Error::custom))
+				.transpose()
+		}
+	}
+}
+
+/// The BeginString of a deserialized [`FixHeader`](crate::FixHeader)
+///
+/// The header only supports FIX.4.2, so BeginString is written out but not read back.
+pub fn begin_string() -> &'static str {
+	"FIX.4.2"
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{FixMessage, FixMessageBody, HeartbeatBody, messages::RelatedSym};
+	use time::macros::datetime;
+
+	#[test]
+	fn test_enums_and_timestamps() {
+		assert_eq!(serde_json::to_string(&Side::Buy).unwrap(), r#""1""#);
+		assert_eq!(serde_json::from_str::<Side>(r#""2""#).unwrap(), Side::Sell);
+		assert_eq!(serde_json::from_str::<Side>(r#""Sell""#).unwrap(), Side::Sell);
+		assert!(serde_json::from_str::<Side>(r#""X""#).is_err());
+		assert_eq!(serde_json::from_str::<MsgType>(r#""U1""#).unwrap(), MsgType::Other("U1".into()));
+
+		#[derive(Debug, PartialEq, Serialize, Deserialize)]
+		struct Named {
+			#[serde(with = "enum_name")]
+			side: Side,
+			#[serde(with = "enum_name")]
+			msg_type: MsgType,
+			#[serde(with = "timestamp::option")]
+			time: Option<time::OffsetDateTime>,
+		}
+		let named = Named {
+			side: Side::Buy,
+			msg_type: MsgType::Other("U1".into()),
+			time: Some(datetime!(2024-01-02 03:04:05.006 UTC)),
+		};
+		let json = serde_json::to_string(&named).unwrap();
+		assert_eq!(json, r#"{"side":"Buy","msg_type":"U1","time":"20240102-03:04:05.006"}"#);
+		assert_eq!(serde_json::from_str::<Named>(&json).unwrap(), named);
+	}
+
+	#[test]
+	fn test_message_round_trip() {
+		let request = FixMessage::builder(MsgType::MarketDataRequest, "CLIENT", "SERVER", 2)
+			.sending_time(datetime!(2024-01-02 03:04:05.006 UTC))
+			.md_req_id("REQ1")
+			.subscription_request_type(SubscriptionRequestType::Snapshot)
+			.md_entry_type(MdEntryType::Bid)
+			.related_sym(RelatedSym::new("AAPL"))
+			.build();
+		let json = serde_json::to_value(&request).unwrap();
+		assert_eq!(json["header"]["msg_type"], "V");
+		assert_eq!(json["header"]["sending_time"], "20240102-03:04:05.006");
+		assert_eq!(json["body"]["type"], "MarketDataRequest");
+		assert_eq!(json["body"]["body"]["md_entry_types"], serde_json::json!(["0"]));
+		assert_eq!(serde_json::from_value::<FixMessage>(json).unwrap(), request);
+
+		let other = FixMessageBody::Other(vec![(148, "Hello".into())]);
+		let json = serde_json::to_string(&other).unwrap();
+		assert_eq!(json, r#"{"type":"Other","body":[[148,"Hello"]]}"#);
+		assert_eq!(serde_json::from_str::<FixMessageBody>(&json).unwrap(), other);
+
+		let heartbeat: FixMessageBody = serde_json::from_str(r#"{"type":"Heartbeat","body":{}}"#).unwrap();
+		assert_eq!(heartbeat, FixMessageBody::Heartbeat(HeartbeatBody::default()));
+	}
+}
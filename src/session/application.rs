@@ -0,0 +1,153 @@
+//! Application callbacks
+//!
+//! [`Application`] is the extension point through which the session layer
+//! reports lifecycle events and hands every inbound and outbound message to
+//! user code, modelled on QuickFIX's `Application` interface. Attach one with
+//! [`Session::with_application`](crate::Session::with_application); the blocking
+//! and async transports drive the session and therefore the callbacks.
+
+use crate::{BusinessRejectReason, FixMessage, session::SessionId};
+use std::fmt::Display;
+
+/// Callbacks invoked by a [`Session`](crate::Session)
+///
+/// Every method has a default that accepts everything, so an implementation
+/// only overrides the hooks it needs. Callbacks run while the session is locked
+/// by the transport and should return quickly.
+// The from_* names mirror QuickFIX rather than Rust's conversion convention
+#[allow(clippy::wrong_self_convention)]
+pub trait Application {
+	/// The application was attached to a session
+	fn on_create(&mut self, _session_id: &SessionId) {}
+
+	/// The logon handshake completed
+	fn on_logon(&mut self, _session_id: &SessionId) {}
+
+	/// A logged-on session ended, by logout, timeout or disconnection
+	fn on_logout(&mut self, _session_id: &SessionId) {}
+
+	/// An administrative message is about to be sent
+	///
+	/// The header is already stamped; the message may be modified, e.g. to add
+	/// credentials to the outgoing Logon.
+	fn to_admin(&mut self, _message: &mut FixMessage, _session_id: &SessionId) {}
+
+	/// An in-sequence administrative message was received
+	///
+	/// Returning an error for a Logon refuses it: the session answers with a
+	/// Logout carrying the reason and disconnects. The result is ignored for
+	/// other administrative messages.
+	fn from_admin(&mut self, _message: &FixMessage, _session_id: &SessionId) -> Result<(), RejectLogon> {
+		Ok(())
+	}
+
+	/// An application message is about to be sent
+	///
+	/// Returning [`DoNotSend`] drops the message without consuming a sequence
+	/// number, and hands the veto back to the sender in
+	/// [`SessionError::DoNotSend`](crate::SessionError::DoNotSend). This hook is also consulted when messages are resent, with
+	/// PossDupFlag set; vetoed messages are then gap filled and changes made
+	/// to the message are ignored.
+	fn to_app(&mut self, _message: &mut FixMessage, _session_id: &SessionId) -> Result<(), DoNotSend> {
+		Ok(())
+	}
+
+	/// An in-sequence application message was received
+	///
+	/// Returning a [`BusinessReject`] answers the message with a
+	/// BusinessMessageReject (MsgType=j) instead of delivering it.
+	fn from_app(&mut self, _message: &FixMessage, _session_id: &SessionId) -> Result<(), BusinessReject> {
+		Ok(())
+	}
+}
+
+/// Application that accepts everything, used when none is attached
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NullApplication;
+
+impl Application for NullApplication {}
+
+/// Refusal of an inbound Logon, returned from [`Application::from_admin`]
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RejectLogon(pub String);
+
+impl Display for RejectLogon {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "Logon rejected: {}", self.0)
+	}
+}
+
+impl std::error::Error for RejectLogon {}
+
+/// Veto of an outbound application message, returned from [`Application::to_app`]
+///
+/// The optional reason, e.g. the check the message failed, is the error's source.
+#[derive(Debug, Default)]
+pub struct DoNotSend(pub Option<Box<dyn std::error::Error + Send + Sync>>);
+
+impl DoNotSend {
+	/// Veto for `reason`
+	pub fn because(reason: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> Self {
+		Self(Some(reason.into()))
+	}
+}
+
+impl Display for DoNotSend {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match &self.0 {
+			Some(reason) => write!(f, "Message vetoed by the application: {}", reason),
+			None => write!(f, "Message vetoed by the application"),
+		}
+	}
+}
+
+impl std::error::Error for DoNotSend {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		self.0.as_deref().map(|reason| reason as &(dyn std::error::Error + 'static))
+	}
+}
+
+/// Rejection of an inbound application message, returned from [`Application::from_app`]
+///
+/// The session fills RefSeqNum, RefMsgType and, unless `ref_id` is set,
+/// BusinessRejectRefID from the rejected message.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct BusinessReject {
+	/// BusinessRejectReason (Tag 380)
+	pub reason: BusinessRejectReason,
+	/// Business-level ID of the rejected message, e.g. its ClOrdID (Tag 379)
+	pub ref_id: Option<String>,
+	/// Free format explanation (Tag 58)
+	pub text: Option<String>,
+}
+
+impl BusinessReject {
+	/// Reject for `reason`
+	pub const fn new(reason: BusinessRejectReason) -> Self {
+		Self { reason, ref_id: None, text: None }
+	}
+
+	/// Set the BusinessRejectRefID
+	pub fn with_ref_id(mut self, ref_id: impl Into<String>) -> Self {
+		self.ref_id = Some(ref_id.into());
+		self
+	}
+
+	/// Set the explanatory text
+	pub fn with_text(mut self, text: impl Into<String>) -> Self {
+		self.text = Some(text.into());
+		self
+	}
+}
+
+impl Display for BusinessReject {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "Business reject (reason {})", self.reason)?;
+		if let Some(text) = &self.text {
+			write!(f, ": {}", text)?;
+		}
+		Ok(())
+	}
+}
+
+impl std::error::Error for BusinessReject {}
@@ -0,0 +1,54 @@
+//! FIX session layer
+//!
+//! This module contains the building blocks that sit on top of the message
+//! types: persistence of outbound messages and sequence numbers, message
+//! recovery (answering ResendRequests) built on that persistence, the
+//! I/O-free session state machine driving logon, heartbeats and logout, and
+//! the [`Application`] callbacks through which user code plugs into it, the
+//! [`SessionSchedule`] restricting when a session runs, and the
+//! [`SessionSettings`] configuration file describing sessions.
+
+pub mod application;
+pub mod resend;
+pub mod schedule;
+pub mod settings;
+pub mod state;
+pub mod store;
+
+use std::fmt::Display;
+
+// Re-export commonly used types
+pub use application::{Application, BusinessReject, DoNotSend, NullApplication, RejectLogon};
+pub use resend::{ResendHook, resend};
+pub use schedule::SessionSchedule;
+pub use settings::{ConnectionType, SessionConfig, SessionSettings, SettingsError};
+pub use state::{Session, SessionError, SessionState};
+pub use store::{FileStore, MemoryStore, MessageStore, StoreError};
+
+/// Identifies a FIX session from our point of view
+///
+/// `sender_comp_id` is our CompID (Tag 49 on outbound messages) and
+/// `target_comp_id` the counterparty's (Tag 56 on outbound messages).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SessionId {
+	pub sender_comp_id: String,
+	pub target_comp_id: String,
+}
+
+impl SessionId {
+	/// Create a session identifier from our and the counterparty's CompIDs
+	pub fn new(sender_comp_id: impl Into<String>, target_comp_id: impl Into<String>) -> Self {
+		Self { sender_comp_id: sender_comp_id.into(), target_comp_id: target_comp_id.into() }
+	}
+
+	/// File name prefix used by [`FileStore`] for this session, e.g. `FIX.4.2-CLIENT-SERVER`
+	pub fn store_name(&self) -> String {
+		format!("FIX.4.2-{}-{}", self.sender_comp_id, self.target_comp_id)
+	}
+}
+
+impl Display for SessionId {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "FIX.4.2:{}->{}", self.sender_comp_id, self.target_comp_id)
+	}
+}
@@ -0,0 +1,292 @@
+//! Message recovery (answering ResendRequests)
+//!
+//! When the counterparty sends ResendRequest(BeginSeqNo, EndSeqNo) the session
+//! replays its stored outbound messages:
+//!
+//! - application messages are resent with PossDupFlag=Y, OrigSendingTime(122) set to
+//!   their original SendingTime and a fresh SendingTime
+//! - administrative messages, application messages vetoed by the [`ResendHook`] and
+//!   sequence numbers missing from the store are collapsed into one
+//!   SequenceReset-GapFill per consecutive run, carrying the MsgSeqNum of the first
+//!   skipped message and a NewSeqNo pointing just past the run
+
+use crate::{
+	FixMessage, FixMessageBuilder, MsgType,
+	messages::ResendRequestBody,
+	session::{
+		SessionId,
+		store::{MessageStore, StoreError},
+	},
+};
+use time::OffsetDateTime;
+
+/// Application hook consulted before an application message is resent
+///
+/// Any `FnMut(&FixMessage) -> bool` closure can be used as a hook.
+pub trait ResendHook {
+	/// Decide whether an application message should be resent
+	///
+	/// `message` is the replay as it would be sent, with PossDupFlag and
+	/// OrigSendingTime already set. Returning `false` replaces it with a gap fill,
+	/// e.g. for orders that are too old to be acted upon by the counterparty.
+	fn allow_resend(&mut self, message: &FixMessage) -> bool;
+}
+
+impl<F: FnMut(&FixMessage) -> bool> ResendHook for F {
+	fn allow_resend(&mut self, message: &FixMessage) -> bool {
+		self(message)
+	}
+}
+
+/// Build the messages answering a ResendRequest, in the order they must be sent
+///
+/// An EndSeqNo of zero, or one beyond the last message we sent, is treated as
+/// "up to the last message sent". Application messages whose type has no typed
+/// body (`FixMessageBody::Other`) are replayed from their stored fields like any
+/// other.
+///
+/// # Errors
+/// Returns a [`StoreError`] if the store cannot be read or a stored message cannot be parsed.
+pub fn resend<S: MessageStore + ?Sized>(
+	store: &S,
+	session_id: &SessionId,
+	request: &ResendRequestBody,
+	now: OffsetDateTime,
+	hook: &mut impl ResendHook,
+) -> Result<Vec<FixMessage>, StoreError> {
+	let last_sent = store.next_sender_msg_seq_num().saturating_sub(1);
+	let begin = request.begin_seq_no.max(1);
+	let end = if request.end_seq_no == 0 { last_sent } else { request.end_seq_no.min(last_sent) };
+
+	let mut replay = Vec::new();
+	if begin > end {
+		return Ok(replay);
+	}
+
+	// First sequence number of the run currently being gap filled, if any
+	let mut gap_start: Option<u32> = None;
+	let mut next_seq = begin;
+
+	for (seq, raw) in store.get(begin, end)? {
+		if seq > next_seq {
+			// Messages missing from the store join the gap
+			gap_start.get_or_insert(next_seq);
+		}
+		next_seq = seq + 1;
+
+		let message = FixMessage::from_fix_string(&raw)
+			.map_err(|e| StoreError::Corrupt(format!("Stored message {} cannot be parsed: {}", seq, e)))?;
+		if message.header.msg_type.is_admin() {
+			gap_start.get_or_insert(seq);
+			continue;
+		}
+
+		let message = possible_duplicate(message, now);
+		if !hook.allow_resend(&message) {
+			gap_start.get_or_insert(seq);
+			continue;
+		}
+
+		if let Some(start) = gap_start.take() {
+			replay.push(gap_fill(session_id, start, seq, now));
+		}
+		replay.push(message);
+	}
+
+	if next_seq <= end {
+		gap_start.get_or_insert(next_seq);
+	}
+	if let Some(start) = gap_start {
+		replay.push(gap_fill(session_id, start, end + 1, now));
+	}
+
+	Ok(replay)
+}
+
+/// Turn a stored message into its replay: PossDupFlag=Y, OrigSendingTime preserved, fresh SendingTime
+fn possible_duplicate(message: FixMessage, now: OffsetDateTime) -> FixMessage {
+	let orig_sending_time = message.header.orig_sending_time.unwrap_or(message.header.sending_time);
+	FixMessageBuilder::from_message(message)
+		.poss_dup_flag(true)
+		.orig_sending_time(orig_sending_time)
+		.sending_time(now)
+		.build()
+}
+
+/// SequenceReset-GapFill sent with MsgSeqNum `msg_seq_num`, moving the receiver to `new_seq_no`
+fn gap_fill(session_id: &SessionId, msg_seq_num: u32, new_seq_no: u32, now: OffsetDateTime) -> FixMessage {
+	FixMessage::builder(MsgType::SequenceReset, &session_id.sender_comp_id, &session_id.target_comp_id, msg_seq_num)
+		.sending_time(now)
+		.poss_dup_flag(true)
+		.orig_sending_time(now)
+		.gap_fill_flag(true)
+		.new_seq_no(new_seq_no)
+		.build()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Side, messages::FixMessageBody, session::MemoryStore};
+	use time::macros::datetime;
+
+	const ORIGINAL_TIME: OffsetDateTime = datetime!(2024-03-01 09:00:00 UTC);
+	const NOW: OffsetDateTime = datetime!(2024-03-01 09:05:00 UTC);
+
+	fn session_id() -> SessionId {
+		SessionId::new("CLIENT", "BROKER")
+	}
+
+	fn order(seq: u32, cl_ord_id: &str) -> FixMessage {
+		FixMessage::builder(MsgType::NewOrderSingle, "CLIENT", "BROKER", seq)
+			.sending_time(ORIGINAL_TIME)
+			.cl_ord_id(cl_ord_id)
+			.symbol("AAPL")
+			.side(Side::Buy)
+			.order_qty(100.0)
+			.ord_type("1")
+			.handl_inst("1")
+			.transact_time(ORIGINAL_TIME)
+			.build()
+	}
+
+	fn admin(msg_type: MsgType, seq: u32) -> FixMessage {
+		FixMessage::builder(msg_type, "CLIENT", "BROKER", seq).sending_time(ORIGINAL_TIME).build()
+	}
+
+	/// Store: 1 Logon, 2 Order, 3 Heartbeat, 4 Heartbeat, 5 Order, 6 Heartbeat
+	fn populated_store() -> MemoryStore {
+		let mut store = MemoryStore::new();
+		let messages = [
+			admin(MsgType::Logon, 1),
+			order(2, "ORD2"),
+			admin(MsgType::Heartbeat, 3),
+			admin(MsgType::Heartbeat, 4),
+			order(5, "ORD5"),
+			admin(MsgType::Heartbeat, 6),
+		];
+		for message in &messages {
+			store.set(message.header.msg_seq_num, &message.to_fix_string()).unwrap();
+			store.incr_next_sender_msg_seq_num().unwrap();
+		}
+		store
+	}
+
+	fn expect_gap_fill(message: &FixMessage, msg_seq_num: u32, new_seq_no: u32) {
+		assert_eq!(message.header.msg_type, MsgType::SequenceReset);
+		assert_eq!(message.header.msg_seq_num, msg_seq_num);
+		assert_eq!(message.header.poss_dup_flag, Some(true));
+		assert_eq!(message.body, FixMessageBody::SequenceReset(crate::SequenceResetBody::gap_fill(new_seq_no)));
+	}
+
+	#[test]
+	fn test_resend_replays_application_and_gap_fills_admin() {
+		let store = populated_store();
+		let replay =
+			resend(&store, &session_id(), &ResendRequestBody::new(1, 0), NOW, &mut |_: &FixMessage| true).unwrap();
+
+		assert_eq!(replay.len(), 5);
+		expect_gap_fill(&replay[0], 1, 2);
+		assert_eq!(replay[1].header.msg_seq_num, 2);
+		expect_gap_fill(&replay[2], 3, 5);
+		assert_eq!(replay[3].header.msg_seq_num, 5);
+		expect_gap_fill(&replay[4], 6, 7);
+	}
+
+	#[test]
+	fn test_replayed_message_headers() {
+		let store = populated_store();
+		let replay =
+			resend(&store, &session_id(), &ResendRequestBody::new(2, 2), NOW, &mut |_: &FixMessage| true).unwrap();
+
+		assert_eq!(replay.len(), 1);
+		let message = &replay[0];
+		assert_eq!(message.header.poss_dup_flag, Some(true));
+		assert_eq!(message.header.orig_sending_time, Some(ORIGINAL_TIME));
+		assert_eq!(message.header.sending_time, NOW);
+
+		// The replay must survive the wire with its flags intact
+		let wire = message.to_fix_string();
+		assert!(wire.contains("\x0143=Y\x01"));
+		assert!(wire.contains("\x01122=20240301-09:00:00.000\x01"));
+		let parsed = FixMessage::from_fix_string(&wire).unwrap();
+		assert_eq!(parsed.header.poss_dup_flag, Some(true));
+		assert_eq!(parsed.header.orig_sending_time, Some(ORIGINAL_TIME));
+		assert_eq!(parsed.body, order(2, "ORD2").body);
+	}
+
+	#[test]
+	fn test_messages_without_typed_body_are_replayed() {
+		let mut store = populated_store();
+		// TradeCaptureReport (MsgType=AE) in place of the Heartbeat at 3
+		let report = FixMessage::parse(
+			"8=FIX.4.2\x019=0\x0135=AE\x0149=CLIENT\x0156=BROKER\x0134=3\x0152=20240301-09:00:00.000\x01\
+			 571=TR1\x0155=AAPL\x0110=000\x01",
+		)
+		.unwrap();
+		store.set(3, &FixMessageBuilder::from_message(report).build().to_fix_string()).unwrap();
+
+		let replay =
+			resend(&store, &session_id(), &ResendRequestBody::new(2, 4), NOW, &mut |_: &FixMessage| true).unwrap();
+		assert_eq!(replay.len(), 3);
+		assert_eq!(replay[0].header.msg_seq_num, 2);
+		let replayed = FixMessage::from_fix_string(&replay[1].to_fix_string()).unwrap();
+		assert_eq!(replayed.header.msg_seq_num, 3);
+		assert_eq!(replayed.header.poss_dup_flag, Some(true));
+		assert_eq!(replayed.header.orig_sending_time, Some(ORIGINAL_TIME));
+		assert_eq!(replayed.body, FixMessageBody::Other(vec![(571, "TR1".into()), (55, "AAPL".into())]));
+		expect_gap_fill(&replay[2], 4, 5);
+	}
+
+	#[test]
+	fn test_hook_can_veto_stale_orders() {
+		let store = populated_store();
+		let mut vetoed = Vec::new();
+		let mut hook = |message: &FixMessage| {
+			let FixMessageBody::NewOrderSingle(body) = &message.body else { return true };
+			if body.cl_ord_id == "ORD2" {
+				vetoed.push(message.header.msg_seq_num);
+				return false;
+			}
+			true
+		};
+		let replay = resend(&store, &session_id(), &ResendRequestBody::new(1, 0), NOW, &mut hook).unwrap();
+
+		assert_eq!(vetoed, vec![2]);
+		assert_eq!(replay.len(), 3);
+		expect_gap_fill(&replay[0], 1, 5);
+		assert_eq!(replay[1].header.msg_seq_num, 5);
+		expect_gap_fill(&replay[2], 6, 7);
+	}
+
+	#[test]
+	fn test_missing_messages_are_gap_filled() {
+		let mut store = MemoryStore::new();
+		store.set(3, &order(3, "ORD3").to_fix_string()).unwrap();
+		store.set_next_sender_msg_seq_num(6).unwrap();
+
+		let replay =
+			resend(&store, &session_id(), &ResendRequestBody::new(1, 5), NOW, &mut |_: &FixMessage| true).unwrap();
+
+		assert_eq!(replay.len(), 3);
+		expect_gap_fill(&replay[0], 1, 3);
+		assert_eq!(replay[1].header.msg_seq_num, 3);
+		expect_gap_fill(&replay[2], 4, 6);
+	}
+
+	#[test]
+	fn test_end_seq_no_is_clamped_to_last_sent() {
+		let store = populated_store();
+		let replay =
+			resend(&store, &session_id(), &ResendRequestBody::new(5, 100), NOW, &mut |_: &FixMessage| true).unwrap();
+
+		assert_eq!(replay.len(), 2);
+		assert_eq!(replay[0].header.msg_seq_num, 5);
+		expect_gap_fill(&replay[1], 6, 7);
+
+		// Nothing to resend beyond the last message sent
+		let replay =
+			resend(&store, &session_id(), &ResendRequestBody::new(10, 0), NOW, &mut |_: &FixMessage| true).unwrap();
+		assert!(replay.is_empty());
+	}
+}
@@ -0,0 +1,292 @@
+//! Session schedules
+//!
+//! Venues only accept connections during trading windows, e.g. 07:00-17:30
+//! Europe/London every weekday, and restart sequence numbers with each window.
+//! [`SessionSchedule`] describes such a window, either daily or weekly, in any
+//! IANA time zone. Daily windows may be limited to some days of the week
+//! ([`SessionSchedule::on_days`]). Windows may span midnight (22:00-06:00) or
+//! the weekend (Sunday 18:00 to Friday 17:00).
+//!
+//! Every method takes the current time as a parameter, so the schedule is
+//! evaluated against whatever clock drives the [`Session`](crate::Session).
+
+use std::fmt::Display;
+use time::{Duration, OffsetDateTime, PrimitiveDateTime, Time, Weekday};
+use time_tz::{Offset, OffsetResult, PrimitiveDateTimeExt, TimeZone, Tz, timezones};
+
+/// Recurring window during which a session may be logged on
+///
+/// A period starts at the start time and lasts until the next end time, so an
+/// end time earlier than the start time means the period ends the following
+/// day. Equal start and end times describe a session that runs around the
+/// clock and restarts once a day (or once a week).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionSchedule {
+	start_time: Time,
+	end_time: Time,
+	/// StartDay and EndDay of a weekly session
+	days: Option<(Weekday, Weekday)>,
+	/// Days on which periods start, one bit per day from Monday
+	weekdays: u8,
+	time_zone: &'static Tz,
+}
+
+/// `weekdays` of a schedule running every day
+const EVERY_DAY: u8 = 0x7f;
+
+impl SessionSchedule {
+	/// A session running from `start_time` to `end_time` every day, in UTC
+	pub fn daily(start_time: Time, end_time: Time) -> Self {
+		Self { start_time, end_time, days: None, weekdays: EVERY_DAY, time_zone: timezones::db::UTC }
+	}
+
+	/// A session running from `start_time` on `start_day` to `end_time` on `end_day` every week, in UTC
+	pub fn weekly(start_day: Weekday, start_time: Time, end_day: Weekday, end_time: Time) -> Self {
+		Self {
+			start_time,
+			end_time,
+			days: Some((start_day, end_day)),
+			weekdays: EVERY_DAY,
+			time_zone: timezones::db::UTC,
+		}
+	}
+
+	/// Only run the periods starting on `days`, such as Monday to Friday for a daily session
+	///
+	/// A period spanning midnight belongs to the day it starts on.
+	pub fn on_days(mut self, days: &[Weekday]) -> Self {
+		self.weekdays = days.iter().fold(0, |weekdays, day| weekdays | weekday_bit(*day));
+		self
+	}
+
+	/// Interpret the start and end times in an IANA time zone such as `Europe/London`
+	pub fn with_time_zone(mut self, name: &str) -> Result<Self, String> {
+		self.time_zone = timezones::get_by_name(name).ok_or_else(|| format!("Unknown time zone '{}'", name))?;
+		Ok(self)
+	}
+
+	pub const fn start_time(&self) -> Time {
+		self.start_time
+	}
+
+	pub const fn end_time(&self) -> Time {
+		self.end_time
+	}
+
+	/// StartDay and EndDay, for weekly sessions
+	pub const fn days(&self) -> Option<(Weekday, Weekday)> {
+		self.days
+	}
+
+	/// Whether periods start on `day`
+	pub const fn runs_on(&self, day: Weekday) -> bool {
+		self.weekdays & weekday_bit(day) != 0
+	}
+
+	/// Name of the time zone the times are expressed in
+	pub fn time_zone(&self) -> &str {
+		self.time_zone.name()
+	}
+
+	/// Whether `now` falls within a session period
+	pub fn is_active(&self, now: OffsetDateTime) -> bool {
+		self.period_start(now).is_some()
+	}
+
+	/// Start of the period containing `now`, or `None` outside of session time
+	pub fn period_start(&self, now: OffsetDateTime) -> Option<OffsetDateTime> {
+		let local = now.to_offset(self.time_zone.get_offset_utc(&now).to_utc());
+		let local = PrimitiveDateTime::new(local.date(), local.time());
+
+		// Most recent start in local time, then step back one cycle if it is still ahead of `now`
+		let (back, cycle) = match self.days {
+			None => (0, 1),
+			Some((start_day, _)) => (days_between(start_day, local.weekday()), 7),
+		};
+		let mut start = PrimitiveDateTime::new(local.date() - Duration::days(back), self.start_time);
+		if self.resolve(start) > now {
+			start -= Duration::days(cycle);
+		}
+		if !self.runs_on(start.weekday()) {
+			return None;
+		}
+
+		let forward = match self.days {
+			None => 0,
+			Some((start_day, end_day)) => days_between(start_day, end_day),
+		};
+		let mut end = PrimitiveDateTime::new(start.date() + Duration::days(forward), self.end_time);
+		if end <= start {
+			end += Duration::days(cycle);
+		}
+
+		let start = self.resolve(start);
+		(now < self.resolve(end)).then_some(start)
+	}
+
+	/// Whether `a` and `b` fall within the same session period
+	pub fn is_same_period(&self, a: OffsetDateTime, b: OffsetDateTime) -> bool {
+		match (self.period_start(a), self.period_start(b)) {
+			(Some(a), Some(b)) => a == b,
+			_ => false,
+		}
+	}
+
+	/// Turn a local wall clock time into an instant
+	///
+	/// Repeated times (clocks going back) resolve to their first occurrence;
+	/// skipped times (clocks going forward) to the end of the gap.
+	fn resolve(&self, local: PrimitiveDateTime) -> OffsetDateTime {
+		match local.assume_timezone(self.time_zone) {
+			OffsetResult::Some(instant) | OffsetResult::Ambiguous(instant, _) => instant,
+			OffsetResult::None => {
+				// Apply the offset in force before the gap, which lands past it
+				let before = local.assume_utc() - Duration::days(1);
+				local.assume_offset(self.time_zone.get_offset_utc(&before).to_utc())
+			},
+		}
+	}
+}
+
+impl Display for SessionSchedule {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self.days {
+			None => write!(f, "{}-{}", self.start_time, self.end_time)?,
+			Some((start_day, end_day)) => {
+				write!(f, "{} {}-{} {}", start_day, self.start_time, end_day, self.end_time)?;
+			},
+		}
+		if self.weekdays != EVERY_DAY {
+			let mut day = Weekday::Monday;
+			let mut separator = " on ";
+			for _ in 0..7 {
+				if self.runs_on(day) {
+					write!(f, "{}{}", separator, day)?;
+					separator = ",";
+				}
+				day = day.next();
+			}
+		}
+		write!(f, " {}", self.time_zone.name())
+	}
+}
+
+const fn weekday_bit(day: Weekday) -> u8 {
+	1 << day.number_days_from_monday()
+}
+
+/// Days from `from` forward to `to`, in `0..7`
+fn days_between(from: Weekday, to: Weekday) -> i64 {
+	(to.number_days_from_monday() as i64 - from.number_days_from_monday() as i64).rem_euclid(7)
+}
+
+/// Parse a day of the week as in QuickFIX's StartDay, EndDay and Weekdays (`Monday`, `mon`, ...)
+pub(crate) fn parse_weekday(value: &str) -> Option<Weekday> {
+	const DAYS: [Weekday; 7] = [
+		Weekday::Monday,
+		Weekday::Tuesday,
+		Weekday::Wednesday,
+		Weekday::Thursday,
+		Weekday::Friday,
+		Weekday::Saturday,
+		Weekday::Sunday,
+	];
+	let value = value.to_ascii_lowercase();
+	if value.len() < 2 {
+		return None;
+	}
+	DAYS.into_iter().find(|day| day.to_string().to_ascii_lowercase().starts_with(&value))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use time::macros::{datetime, time};
+
+	#[test]
+	fn test_daily_window_in_time_zone() {
+		let schedule = SessionSchedule::daily(time!(07:00), time!(17:30)).with_time_zone("Europe/London").unwrap();
+
+		// Winter: London is on UTC
+		assert!(!schedule.is_active(datetime!(2024-01-15 06:59:59 UTC)));
+		assert_eq!(schedule.period_start(datetime!(2024-01-15 12:00 UTC)), Some(datetime!(2024-01-15 07:00 UTC)));
+		assert!(!schedule.is_active(datetime!(2024-01-15 17:30 UTC)));
+
+		// Summer: 07:00 BST is 06:00 UTC
+		assert_eq!(schedule.period_start(datetime!(2024-07-15 06:00 UTC)), Some(datetime!(2024-07-15 06:00 UTC)));
+		assert!(!schedule.is_active(datetime!(2024-07-15 16:30 UTC)));
+		assert!(!schedule.is_same_period(datetime!(2024-07-15 09:00 UTC), datetime!(2024-07-16 09:00 UTC)));
+		assert!(schedule.is_same_period(datetime!(2024-07-15 09:00 UTC), datetime!(2024-07-15 16:00 UTC)));
+	}
+
+	#[test]
+	fn test_daily_window_on_weekdays() {
+		const WEEKDAYS: [Weekday; 5] =
+			[Weekday::Monday, Weekday::Tuesday, Weekday::Wednesday, Weekday::Thursday, Weekday::Friday];
+		let schedule = SessionSchedule::daily(time!(07:00), time!(17:30))
+			.on_days(&WEEKDAYS)
+			.with_time_zone("Europe/London")
+			.unwrap();
+
+		// Friday 2024-07-12, then the weekend, then Monday with a new period
+		assert_eq!(schedule.period_start(datetime!(2024-07-12 09:00 UTC)), Some(datetime!(2024-07-12 06:00 UTC)));
+		assert!(!schedule.is_active(datetime!(2024-07-13 09:00 UTC)));
+		assert!(!schedule.is_active(datetime!(2024-07-14 09:00 UTC)));
+		assert_eq!(schedule.period_start(datetime!(2024-07-15 09:00 UTC)), Some(datetime!(2024-07-15 06:00 UTC)));
+		assert!(!schedule.runs_on(Weekday::Saturday));
+		assert_eq!(
+			schedule.to_string(),
+			"7:00:00.0-17:30:00.0 on Monday,Tuesday,Wednesday,Thursday,Friday Europe/London"
+		);
+
+		// A night session belongs to the day it starts on
+		let night = SessionSchedule::daily(time!(22:00), time!(06:00)).on_days(&[Weekday::Friday]);
+		assert!(night.is_active(datetime!(2024-07-13 05:00 UTC)));
+		assert!(!night.is_active(datetime!(2024-07-13 23:00 UTC)));
+	}
+
+	#[test]
+	fn test_daily_window_spanning_midnight() {
+		let schedule = SessionSchedule::daily(time!(22:00), time!(06:00));
+
+		assert_eq!(schedule.period_start(datetime!(2024-03-01 23:00 UTC)), Some(datetime!(2024-03-01 22:00 UTC)));
+		assert_eq!(schedule.period_start(datetime!(2024-03-02 05:59 UTC)), Some(datetime!(2024-03-01 22:00 UTC)));
+		assert!(!schedule.is_active(datetime!(2024-03-02 06:00 UTC)));
+		assert!(!schedule.is_active(datetime!(2024-03-02 21:59 UTC)));
+	}
+
+	#[test]
+	fn test_weekly_window() {
+		// Sunday 18:00 to Friday 17:00 New York time
+		let schedule = SessionSchedule::weekly(Weekday::Sunday, time!(18:00), Weekday::Friday, time!(17:00))
+			.with_time_zone("America/New_York")
+			.unwrap();
+		let start = datetime!(2024-03-03 23:00 UTC);
+
+		assert!(!schedule.is_active(datetime!(2024-03-03 22:59 UTC)));
+		assert_eq!(schedule.period_start(datetime!(2024-03-03 23:00 UTC)), Some(start));
+		assert_eq!(schedule.period_start(datetime!(2024-03-08 21:59 UTC)), Some(start));
+		assert!(!schedule.is_active(datetime!(2024-03-08 22:00 UTC)));
+		assert!(!schedule.is_active(datetime!(2024-03-09 12:00 UTC)));
+		// Daylight saving time started on 2024-03-10, so the next period starts an hour earlier in UTC
+		assert_eq!(schedule.period_start(datetime!(2024-03-11 12:00 UTC)), Some(datetime!(2024-03-10 22:00 UTC)));
+	}
+
+	#[test]
+	fn test_continuous_session_restarts_daily() {
+		let schedule = SessionSchedule::daily(time!(17:00), time!(17:00));
+
+		assert!(schedule.is_active(datetime!(2024-03-01 16:59 UTC)));
+		assert_eq!(schedule.period_start(datetime!(2024-03-01 16:59 UTC)), Some(datetime!(2024-02-29 17:00 UTC)));
+		assert_eq!(schedule.period_start(datetime!(2024-03-01 17:00 UTC)), Some(datetime!(2024-03-01 17:00 UTC)));
+	}
+
+	#[test]
+	fn test_parse_weekday() {
+		assert_eq!(parse_weekday("Sunday"), Some(Weekday::Sunday));
+		assert_eq!(parse_weekday("mon"), Some(Weekday::Monday));
+		assert_eq!(parse_weekday("TH"), Some(Weekday::Thursday));
+		assert_eq!(parse_weekday("t"), None);
+		assert_eq!(parse_weekday("Funday"), None);
+	}
+}
@@ -0,0 +1,583 @@
+//! Session configuration (QuickFIX-style settings files)
+//!
+//! Settings are read from an INI file with one `[DEFAULT]` section, whose keys
+//! apply to every session, and one `[SESSION]` section per session, whose keys
+//! override the defaults:
+//!
+//! ```text
+//! [DEFAULT]
+//! ConnectionType=initiator
+//! BeginString=FIX.4.2
+//! HeartBtInt=30
+//! FileStorePath=store
+//!
+//! [SESSION]
+//! SenderCompID=CLIENT
+//! TargetCompID=BROKER
+//! SocketConnectHost=127.0.0.1
+//! SocketConnectPort=9878
+//! StartTime=07:00:00
+//! EndTime=17:30:00
+//! TimeZone=Europe/London
+//! ResetOnLogon=Y
+//! ```
+//!
+//! StartTime and EndTime define a daily [`SessionSchedule`]; adding StartDay
+//! and EndDay makes it weekly, while Weekdays (e.g. `Mon,Tue,Wed,Thu,Fri`)
+//! limits a daily one to some days. Times are UTC unless TimeZone names an IANA
+//! time zone.
+//!
+//! Keys are case-insensitive. Lines starting with `#` or `;` are comments.
+//! Keys this module does not interpret remain available through
+//! [`SessionConfig::get`].
+
+use crate::{
+	EncryptMethod,
+	messages::LogonBody,
+	session::{
+		FileStore, MessageStore, Session, SessionId, StoreError,
+		schedule::{SessionSchedule, parse_weekday},
+	},
+};
+use std::{
+	collections::{BTreeMap, HashSet},
+	fmt::Display,
+	fs, io,
+	path::{Path, PathBuf},
+	str::FromStr,
+};
+use time::Time;
+
+/// The only BeginString this crate speaks
+const BEGIN_STRING: &str = "FIX.4.2";
+/// Seconds between connection attempts when ReconnectInterval is not set
+const DEFAULT_RECONNECT_INTERVAL: u32 = 30;
+
+/// Errors raised while loading or validating settings
+#[derive(Debug)]
+pub enum SettingsError {
+	/// The settings file could not be read
+	Io(io::Error),
+	/// A line of the file is malformed
+	Syntax { line: usize, message: String },
+	/// A session's settings are missing or invalid; `session` is its SessionID or `#n` position
+	Invalid { session: String, message: String },
+	/// No session of the required connection type is configured
+	NoSessions(ConnectionType),
+}
+
+impl Display for SettingsError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Io(e) => write!(f, "Settings I/O error: {}", e),
+			Self::Syntax { line, message } => write!(f, "Settings line {}: {}", line, message),
+			Self::Invalid { session, message } => write!(f, "Settings of session {}: {}", session, message),
+			Self::NoSessions(connection_type) => write!(f, "No {} session configured", connection_type),
+		}
+	}
+}
+
+impl std::error::Error for SettingsError {}
+
+impl From<io::Error> for SettingsError {
+	fn from(e: io::Error) -> Self {
+		Self::Io(e)
+	}
+}
+
+/// Whether a session connects out or waits for the counterparty
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionType {
+	Initiator,
+	Acceptor,
+}
+
+impl Display for ConnectionType {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Initiator => write!(f, "initiator"),
+			Self::Acceptor => write!(f, "acceptor"),
+		}
+	}
+}
+
+impl FromStr for ConnectionType {
+	type Err = ();
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.to_ascii_lowercase().as_str() {
+			"initiator" => Ok(Self::Initiator),
+			"acceptor" => Ok(Self::Acceptor),
+			_ => Err(()),
+		}
+	}
+}
+
+/// Validated settings of one session
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionConfig {
+	/// SenderCompID and TargetCompID
+	pub session_id: SessionId,
+	/// ConnectionType
+	pub connection_type: ConnectionType,
+	/// HeartBtInt in seconds, required for initiators
+	pub heart_bt_int: Option<u32>,
+	/// SocketConnectHost, required for initiators
+	pub socket_connect_host: Option<String>,
+	/// SocketConnectPort, required for initiators
+	pub socket_connect_port: Option<u16>,
+	/// SocketAcceptHost, the interface acceptors listen on (default all)
+	pub socket_accept_host: Option<String>,
+	/// SocketAcceptPort, required for acceptors
+	pub socket_accept_port: Option<u16>,
+	/// Seconds between connection attempts (ReconnectInterval, default 30)
+	pub reconnect_interval: u32,
+	/// StartTime of the daily session, if scheduled
+	pub start_time: Option<Time>,
+	/// EndTime of the daily session, if scheduled
+	pub end_time: Option<Time>,
+	/// Session time built from StartTime, EndTime, StartDay, EndDay and TimeZone
+	pub schedule: Option<SessionSchedule>,
+	/// ResetOnLogon: request a sequence reset (ResetSeqNumFlag) on every logon
+	pub reset_on_logon: bool,
+	/// DataDictionary path
+	pub data_dictionary: Option<PathBuf>,
+	/// FileStorePath, the directory of the session's [`FileStore`]
+	pub file_store_path: Option<PathBuf>,
+	/// Every key of the merged `[DEFAULT]` and `[SESSION]` sections, lowercased
+	settings: BTreeMap<String, String>,
+}
+
+impl SessionConfig {
+	/// Raw value of any key, including keys not interpreted by this module
+	pub fn get(&self, key: &str) -> Option<&str> {
+		self.settings.get(&key.to_ascii_lowercase()).map(String::as_str)
+	}
+
+	/// The Logon sent when this session initiates
+	pub fn logon_body(&self) -> LogonBody {
+		let mut logon = LogonBody::default();
+		if let Some(heart_bt_int) = self.heart_bt_int {
+			logon = LogonBody::new(EncryptMethod::None, heart_bt_int);
+		}
+		if self.reset_on_logon {
+			logon = logon.with_reset_seq_num_flag(true);
+		}
+		logon
+	}
+
+	/// `host:port` an initiator connects to
+	pub fn connect_address(&self) -> Option<String> {
+		Some(format!("{}:{}", self.socket_connect_host.as_ref()?, self.socket_connect_port?))
+	}
+
+	/// `host:port` an acceptor listens on
+	pub fn accept_address(&self) -> Option<String> {
+		let host = self.socket_accept_host.as_deref().unwrap_or("0.0.0.0");
+		Some(format!("{}:{}", host, self.socket_accept_port?))
+	}
+
+	/// Error for settings that cannot be used the way they were requested
+	pub fn invalid(&self, message: impl Into<String>) -> SettingsError {
+		SettingsError::Invalid { session: self.session_id.to_string(), message: message.into() }
+	}
+
+	/// Create the session described by these settings on top of `store`
+	pub fn session<S: MessageStore>(&self, store: S) -> Session<S> {
+		let session = Session::new(self.session_id.clone(), store).with_logon_body(self.logon_body());
+		match self.schedule {
+			Some(schedule) => session.with_schedule(schedule),
+			None => session,
+		}
+	}
+
+	/// Open the [`FileStore`] under FileStorePath
+	///
+	/// # Errors
+	/// Fails with [`StoreError::Corrupt`] when FileStorePath is not set.
+	pub fn file_store(&self) -> Result<FileStore, StoreError> {
+		let Some(dir) = &self.file_store_path else {
+			return Err(StoreError::Corrupt(format!("No FileStorePath configured for {}", self.session_id)));
+		};
+		FileStore::open(dir, self.session_id.store_name())
+	}
+}
+
+/// Settings of every session in a configuration file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionSettings {
+	sessions: Vec<SessionConfig>,
+}
+
+impl SessionSettings {
+	/// Load and validate a settings file
+	pub fn load(path: impl AsRef<Path>) -> Result<Self, SettingsError> {
+		fs::read_to_string(path)?.parse()
+	}
+
+	/// All sessions, in file order
+	pub fn sessions(&self) -> &[SessionConfig] {
+		&self.sessions
+	}
+
+	/// Settings of the session identified by `id`
+	pub fn get(&self, id: &SessionId) -> Option<&SessionConfig> {
+		self.sessions.iter().find(|config| &config.session_id == id)
+	}
+
+	/// Sessions with the given connection type
+	pub fn of_type(&self, connection_type: ConnectionType) -> impl Iterator<Item = &SessionConfig> {
+		self.sessions.iter().filter(move |config| config.connection_type == connection_type)
+	}
+}
+
+impl FromStr for SessionSettings {
+	type Err = SettingsError;
+
+	fn from_str(s: &str) -> Result<Self, SettingsError> {
+		let (defaults, sections) = parse_sections(s)?;
+
+		let mut seen = HashSet::new();
+		let mut sessions = Vec::with_capacity(sections.len());
+		for (index, section) in sections.into_iter().enumerate() {
+			let mut settings = defaults.clone();
+			settings.extend(section);
+			let config = build_config(settings)
+				.map_err(|message| SettingsError::Invalid { session: format!("#{}", index + 1), message })?;
+			if !seen.insert(config.session_id.clone()) {
+				return Err(SettingsError::Invalid {
+					session: format!("#{}", index + 1),
+					message: format!("Duplicate session {}", config.session_id),
+				});
+			}
+			sessions.push(config);
+		}
+		Ok(Self { sessions })
+	}
+}
+
+type Section = BTreeMap<String, String>;
+
+/// Split the file into the `[DEFAULT]` keys and one map per `[SESSION]`
+fn parse_sections(s: &str) -> Result<(Section, Vec<Section>), SettingsError> {
+	let mut defaults = Section::new();
+	let mut sessions: Vec<Section> = Vec::new();
+	// None before the first section header, Some(true) inside [DEFAULT]
+	let mut in_default: Option<bool> = None;
+
+	for (index, line) in s.lines().enumerate() {
+		let line = line.trim();
+		if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+			continue;
+		}
+		let syntax = |message: String| SettingsError::Syntax { line: index + 1, message };
+
+		if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+			match name.trim().to_ascii_uppercase().as_str() {
+				"DEFAULT" => in_default = Some(true),
+				"SESSION" => {
+					in_default = Some(false);
+					sessions.push(Section::new());
+				},
+				other => return Err(syntax(format!("Unknown section [{}]", other))),
+			}
+			continue;
+		}
+
+		let Some((key, value)) = line.split_once('=') else {
+			return Err(syntax(format!("Expected key=value, found '{}'", line)));
+		};
+		let (key, value) = (key.trim().to_ascii_lowercase(), value.trim().to_string());
+		if key.is_empty() {
+			return Err(syntax("Empty key".to_string()));
+		}
+		match in_default {
+			None => return Err(syntax(format!("Key '{}' outside of a section", key))),
+			Some(true) => defaults.insert(key, value),
+			Some(false) => sessions.last_mut().expect("a [SESSION] section was opened").insert(key, value),
+		};
+	}
+	Ok((defaults, sessions))
+}
+
+/// Validate the merged keys of one session
+fn build_config(settings: Section) -> Result<SessionConfig, String> {
+	let required = |key: &str, name: &str| settings.get(key).cloned().ok_or_else(|| format!("{} is required", name));
+
+	let begin_string = required("beginstring", "BeginString")?;
+	if begin_string != BEGIN_STRING {
+		return Err(format!("Unsupported BeginString {}, only {} is supported", begin_string, BEGIN_STRING));
+	}
+	let session_id =
+		SessionId::new(required("sendercompid", "SenderCompID")?, required("targetcompid", "TargetCompID")?);
+	let connection_type = required("connectiontype", "ConnectionType")?
+		.parse()
+		.map_err(|_| "ConnectionType must be initiator or acceptor".to_string())?;
+
+	let heart_bt_int = parse_optional::<u32>(&settings, "heartbtint", "HeartBtInt")?;
+	if heart_bt_int == Some(0) {
+		return Err("HeartBtInt must be positive".to_string());
+	}
+	let start_time = parse_time(&settings, "starttime", "StartTime")?;
+	let end_time = parse_time(&settings, "endtime", "EndTime")?;
+	if start_time.is_some() != end_time.is_some() {
+		return Err("StartTime and EndTime must be set together".to_string());
+	}
+	let schedule = build_schedule(&settings, start_time.zip(end_time))?;
+
+	let config = SessionConfig {
+		session_id,
+		connection_type,
+		heart_bt_int,
+		socket_connect_host: settings.get("socketconnecthost").cloned(),
+		socket_connect_port: parse_optional(&settings, "socketconnectport", "SocketConnectPort")?,
+		socket_accept_host: settings.get("socketaccepthost").cloned(),
+		socket_accept_port: parse_optional(&settings, "socketacceptport", "SocketAcceptPort")?,
+		reconnect_interval: parse_optional(&settings, "reconnectinterval", "ReconnectInterval")?
+			.unwrap_or(DEFAULT_RECONNECT_INTERVAL),
+		start_time,
+		end_time,
+		schedule,
+		reset_on_logon: parse_flag(&settings, "resetonlogon", "ResetOnLogon")?,
+		data_dictionary: settings.get("datadictionary").map(PathBuf::from),
+		file_store_path: settings.get("filestorepath").map(PathBuf::from),
+		settings,
+	};
+
+	match config.connection_type {
+		ConnectionType::Initiator => {
+			if config.heart_bt_int.is_none() {
+				return Err("HeartBtInt is required for initiators".to_string());
+			}
+			if config.connect_address().is_none() {
+				return Err("SocketConnectHost and SocketConnectPort are required for initiators".to_string());
+			}
+		},
+		ConnectionType::Acceptor => {
+			if config.socket_accept_port.is_none() {
+				return Err("SocketAcceptPort is required for acceptors".to_string());
+			}
+		},
+	}
+	Ok(config)
+}
+
+fn parse_optional<T: FromStr>(settings: &Section, key: &str, name: &str) -> Result<Option<T>, String> {
+	settings.get(key).map(|value| value.parse().map_err(|_| format!("Invalid {} '{}'", name, value))).transpose()
+}
+
+fn parse_flag(settings: &Section, key: &str, name: &str) -> Result<bool, String> {
+	match settings.get(key).map(String::as_str) {
+		None | Some("N") => Ok(false),
+		Some("Y") => Ok(true),
+		Some(value) => Err(format!("{} must be Y or N, found '{}'", name, value)),
+	}
+}
+
+/// Combine StartTime and EndTime with StartDay, EndDay and TimeZone
+fn build_schedule(settings: &Section, times: Option<(Time, Time)>) -> Result<Option<SessionSchedule>, String> {
+	let day = |key: &str, name: &str| {
+		let value = settings.get(key)?;
+		Some(parse_weekday(value).ok_or_else(|| format!("Invalid {} '{}'", name, value)))
+	};
+	let start_day = day("startday", "StartDay").transpose()?;
+	let end_day = day("endday", "EndDay").transpose()?;
+	let weekdays = settings
+		.get("weekdays")
+		.map(|value| {
+			value
+				.split(',')
+				.map(|day| parse_weekday(day.trim()).ok_or_else(|| format!("Invalid Weekdays '{}'", value)))
+				.collect::<Result<Vec<_>, _>>()
+		})
+		.transpose()?;
+	let time_zone = settings.get("timezone");
+
+	let Some((start_time, end_time)) = times else {
+		if start_day.is_some() || end_day.is_some() || weekdays.is_some() || time_zone.is_some() {
+			return Err("StartDay, EndDay, Weekdays and TimeZone require StartTime and EndTime".to_string());
+		}
+		return Ok(None);
+	};
+	let schedule = match (start_day, end_day, weekdays) {
+		(None, None, None) => SessionSchedule::daily(start_time, end_time),
+		(None, None, Some(weekdays)) => SessionSchedule::daily(start_time, end_time).on_days(&weekdays),
+		(Some(start_day), Some(end_day), None) => SessionSchedule::weekly(start_day, start_time, end_day, end_time),
+		(Some(_), Some(_), Some(_)) => return Err("Weekdays cannot be combined with StartDay and EndDay".to_string()),
+		_ => return Err("StartDay and EndDay must be set together".to_string()),
+	};
+	time_zone.map_or(Ok(schedule), |name| schedule.with_time_zone(name)).map(Some)
+}
+
+/// Parse `HH:MM:SS`
+fn parse_time(settings: &Section, key: &str, name: &str) -> Result<Option<Time>, String> {
+	let Some(value) = settings.get(key) else { return Ok(None) };
+	let invalid = || format!("Invalid {} '{}', expected HH:MM:SS", name, value);
+	let parts: Vec<u8> = value.split(':').map(|part| part.parse().map_err(|_| invalid())).collect::<Result<_, _>>()?;
+	let [hour, minute, second] = parts[..] else { return Err(invalid()) };
+	Time::from_hms(hour, minute, second).map(Some).map_err(|_| invalid())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::session::MemoryStore;
+
+	const CONFIG: &str = "
+# Shared settings
+[DEFAULT]
+BeginString=FIX.4.2
+ConnectionType=initiator
+HeartBtInt=30
+ReconnectInterval=5
+FileStorePath=store
+Venue=LSE
+
+[SESSION]
+SenderCompID=CLIENT
+TargetCompID=BROKER
+SocketConnectHost=127.0.0.1
+SocketConnectPort=9878
+StartTime=07:00:00
+EndTime=17:30:00
+TimeZone=Europe/London
+ResetOnLogon=Y
+
+; An acceptor overriding the defaults
+[SESSION]
+ConnectionType=acceptor
+SenderCompID=BROKER
+TargetCompID=CLIENT2
+socketacceptport=9879
+HeartBtInt=60
+DataDictionary=spec/FIX42.xml
+";
+
+	fn invalid(config: &str) -> String {
+		match config.parse::<SessionSettings>() {
+			Err(SettingsError::Invalid { message, .. }) => message,
+			other => panic!("Expected invalid settings, got {:?}", other),
+		}
+	}
+
+	#[test]
+	fn test_parse_defaults_and_overrides() {
+		let settings: SessionSettings = CONFIG.parse().unwrap();
+		assert_eq!(settings.sessions().len(), 2);
+
+		let initiator = settings.get(&SessionId::new("CLIENT", "BROKER")).unwrap();
+		assert_eq!(initiator.connection_type, ConnectionType::Initiator);
+		assert_eq!(initiator.heart_bt_int, Some(30));
+		assert_eq!(initiator.connect_address().as_deref(), Some("127.0.0.1:9878"));
+		assert_eq!(initiator.reconnect_interval, 5);
+		assert_eq!(initiator.start_time, Some(Time::from_hms(7, 0, 0).unwrap()));
+		assert_eq!(initiator.end_time, Some(Time::from_hms(17, 30, 0).unwrap()));
+		let schedule = initiator.schedule.unwrap();
+		assert_eq!(schedule.time_zone(), "Europe/London");
+		assert_eq!(schedule.days(), None);
+		assert!(initiator.reset_on_logon);
+		assert_eq!(initiator.file_store_path, Some(PathBuf::from("store")));
+		assert_eq!(initiator.get("VENUE"), Some("LSE"));
+
+		let acceptor = settings.get(&SessionId::new("BROKER", "CLIENT2")).unwrap();
+		assert_eq!(acceptor.connection_type, ConnectionType::Acceptor);
+		assert_eq!(acceptor.heart_bt_int, Some(60));
+		assert_eq!(acceptor.socket_accept_port, Some(9879));
+		assert_eq!(acceptor.accept_address().as_deref(), Some("0.0.0.0:9879"));
+		assert_eq!(acceptor.data_dictionary, Some(PathBuf::from("spec/FIX42.xml")));
+		assert!(!acceptor.reset_on_logon);
+		assert_eq!(acceptor.schedule, None);
+
+		assert_eq!(settings.of_type(ConnectionType::Acceptor).count(), 1);
+	}
+
+	#[test]
+	fn test_logon_body_comes_from_settings() {
+		let settings: SessionSettings = CONFIG.parse().unwrap();
+		let config = &settings.sessions()[0];
+		assert_eq!(config.logon_body(), LogonBody::new(EncryptMethod::None, 30).with_reset_seq_num_flag(true));
+
+		let session = config.session(MemoryStore::new());
+		assert_eq!(session.session_id(), &SessionId::new("CLIENT", "BROKER"));
+		assert_eq!(session.heart_bt_int(), 30);
+		assert_eq!(session.schedule(), config.schedule.as_ref());
+	}
+
+	#[test]
+	fn test_weekly_schedule() {
+		let config = "[SESSION]\nBeginString=FIX.4.2\nSenderCompID=A\nTargetCompID=B\nConnectionType=acceptor\n\
+			SocketAcceptPort=1\nStartTime=18:00:00\nEndTime=17:00:00\nStartDay=sun\nEndDay=Friday\n";
+		let settings: SessionSettings = config.parse().unwrap();
+		let schedule = settings.sessions()[0].schedule.unwrap();
+		assert_eq!(schedule.days(), Some((time::Weekday::Sunday, time::Weekday::Friday)));
+		assert_eq!(schedule.time_zone(), "UTC");
+
+		assert!(invalid(&config.replace("EndDay=Friday\n", "")).contains("StartDay and EndDay"));
+		assert_eq!(invalid(&config.replace("sun", "someday")), "Invalid StartDay 'someday'");
+		assert_eq!(invalid(&format!("{}TimeZone=Mars/Olympus\n", config)), "Unknown time zone 'Mars/Olympus'");
+	}
+
+	#[test]
+	fn test_daily_schedule_on_weekdays() {
+		let config = "[SESSION]\nBeginString=FIX.4.2\nSenderCompID=A\nTargetCompID=B\nConnectionType=acceptor\n\
+			SocketAcceptPort=1\nStartTime=07:00:00\nEndTime=17:30:00\nTimeZone=Europe/London\n\
+			Weekdays=Mon,Tue,Wed,Thu,Fri\n";
+		let settings: SessionSettings = config.parse().unwrap();
+		let schedule = settings.sessions()[0].schedule.unwrap();
+		assert_eq!(schedule.days(), None);
+		assert!(schedule.is_active(time::macros::datetime!(2024-07-12 09:00 UTC)));
+		// Saturday
+		assert!(!schedule.is_active(time::macros::datetime!(2024-07-13 09:00 UTC)));
+
+		assert_eq!(invalid(&config.replace("Fri", "Fry")), "Invalid Weekdays 'Mon,Tue,Wed,Thu,Fry'");
+		assert!(invalid(&format!("{}StartDay=mon\nEndDay=fri\n", config)).contains("cannot be combined"));
+	}
+
+	#[test]
+	fn test_syntax_errors() {
+		assert!(matches!("SenderCompID=X".parse::<SessionSettings>(), Err(SettingsError::Syntax { line: 1, .. })));
+		assert!(matches!("[SESSIONS]".parse::<SessionSettings>(), Err(SettingsError::Syntax { line: 1, .. })));
+		assert!(matches!(
+			"[DEFAULT]\nno separator".parse::<SessionSettings>(),
+			Err(SettingsError::Syntax { line: 2, .. })
+		));
+	}
+
+	#[test]
+	fn test_validation_errors() {
+		let base = "[SESSION]\nBeginString=FIX.4.2\nSenderCompID=A\nTargetCompID=B\n";
+		let initiator = format!("{}ConnectionType=initiator\nSocketConnectHost=localhost\nSocketConnectPort=1\n", base);
+
+		assert_eq!(invalid(&format!("{}HeartBtInt=30\n", base)), "ConnectionType is required");
+		assert_eq!(invalid(&initiator), "HeartBtInt is required for initiators");
+		assert_eq!(invalid(&format!("{}HeartBtInt=abc\n", initiator)), "Invalid HeartBtInt 'abc'");
+		assert_eq!(
+			invalid(&format!("{}ConnectionType=acceptor\n", base)),
+			"SocketAcceptPort is required for acceptors"
+		);
+		assert!(
+			invalid(&format!("{}HeartBtInt=30\nStartTime=25:00:00\nEndTime=17:00:00\n", initiator))
+				.contains("StartTime")
+		);
+		assert!(invalid(&format!("{}HeartBtInt=30\nStartTime=07:00:00\n", initiator)).contains("together"));
+		assert!(invalid(&format!("{}HeartBtInt=30\nStartDay=mon\n", initiator)).contains("require StartTime"));
+		assert!(invalid(&format!("{}HeartBtInt=30\nResetOnLogon=yes\n", initiator)).contains("ResetOnLogon"));
+		assert!(invalid(&initiator.replace("FIX.4.2", "FIX.4.4")).contains("Unsupported BeginString"));
+
+		let duplicate = format!("{}HeartBtInt=30\n{}HeartBtInt=30\n", initiator, initiator);
+		assert_eq!(invalid(&duplicate), "Duplicate session FIX.4.2:A->B");
+	}
+
+	#[test]
+	fn test_load_from_file() {
+		let dir = std::env::temp_dir().join(format!("fix-settings-{}", std::process::id()));
+		fs::create_dir_all(&dir).unwrap();
+		let path = dir.join("session.cfg");
+		fs::write(&path, CONFIG).unwrap();
+
+		let settings = SessionSettings::load(&path).unwrap();
+		assert_eq!(settings, CONFIG.parse().unwrap());
+		assert!(matches!(SessionSettings::load(dir.join("missing.cfg")), Err(SettingsError::Io(_))));
+		fs::remove_dir_all(&dir).unwrap();
+	}
+}
@@ -0,0 +1,1148 @@
+//! Session state machine
+//!
+//! [`Session`] implements the FIX session protocol (logon, heartbeats, test
+//! requests, sequence number checks, message recovery and logout) without doing
+//! any I/O itself. A transport feeds it inbound frames and timer ticks, and writes
+//! out the wire messages the session queued, which keeps the protocol logic
+//! deterministic and testable with an injected clock.
+
+use crate::{
+	BusinessRejectReason, FixHeader, FixMessage, FixMessageBuilder, MsgType, ParseError, SessionRejectReason,
+	messages::{BusinessMessageRejectBody, FixMessageBody, HeartbeatBody, LogonBody, RejectBody},
+	session::{
+		SessionId,
+		application::{Application, BusinessReject, DoNotSend, NullApplication, RejectLogon},
+		resend::resend,
+		schedule::SessionSchedule,
+		store::{MessageStore, StoreError},
+	},
+};
+use std::{collections::VecDeque, fmt::Display};
+use time::{Duration, OffsetDateTime};
+
+/// How long to wait for the counterparty's Logon before giving up
+const LOGON_TIMEOUT: Duration = Duration::seconds(10);
+/// How long to wait for the counterparty's Logout before disconnecting anyway
+const LOGOUT_TIMEOUT: Duration = Duration::seconds(2);
+/// Inbound silence, in heartbeat intervals, after which a TestRequest is sent
+const TEST_REQUEST_THRESHOLD: f64 = 1.2;
+/// Inbound silence, in heartbeat intervals, after which the counterparty is considered gone
+const TIMEOUT_THRESHOLD: f64 = 2.4;
+
+/// Lifecycle of a FIX session
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionState {
+	/// Not logged on; an acceptor session waits for the counterparty's Logon in this state
+	Disconnected,
+	/// We sent a Logon and wait for the counterparty's
+	LogonSent,
+	/// Logged on, application messages may flow
+	Active,
+	/// We sent a Logout and wait for the counterparty's
+	LogoutSent,
+}
+
+/// Errors raised while driving a [`Session`]
+#[derive(Debug)]
+pub enum SessionError {
+	/// The message store failed
+	Store(StoreError),
+	/// An inbound message could not be parsed; it was answered with a Reject when
+	/// its header could be read, and ignored otherwise
+	InvalidMessage(String),
+	/// An application message was sent while the session is not logged on
+	NotLoggedOn,
+	/// The application vetoed an outbound message in [`Application::to_app`]
+	DoNotSend(DoNotSend),
+	/// A logon was attempted outside of the [`SessionSchedule`]
+	OutsideSessionTime,
+	/// The session was terminated; a Logout has been queued when the session was logged on
+	Terminated(String),
+}
+
+impl Display for SessionError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Store(e) => write!(f, "{}", e),
+			Self::InvalidMessage(reason) => write!(f, "Invalid message: {}", reason),
+			Self::NotLoggedOn => write!(f, "Session is not logged on"),
+			Self::DoNotSend(veto) => write!(f, "{}", veto),
+			Self::OutsideSessionTime => write!(f, "Outside of session time"),
+			Self::Terminated(reason) => write!(f, "Session terminated: {}", reason),
+		}
+	}
+}
+
+impl std::error::Error for SessionError {}
+
+impl From<StoreError> for SessionError {
+	fn from(e: StoreError) -> Self {
+		Self::Store(e)
+	}
+}
+
+/// A FIX 4.2 session, independent of the underlying connection
+///
+/// Outbound messages are stamped with our CompIDs, the next MsgSeqNum and the
+/// current time, persisted in the [`MessageStore`] and queued; the transport
+/// collects them with [`Session::take_outgoing`]. Lifecycle events and every
+/// message are reported to the attached [`Application`].
+///
+/// A session with a [`SessionSchedule`] only logs on during session time, logs
+/// out when the period ends and resets its sequence numbers when a new period
+/// starts.
+pub struct Session<S: MessageStore> {
+	session_id: SessionId,
+	store: S,
+	application: Box<dyn Application + Send>,
+	/// Template of the Logon we send when initiating
+	logon: LogonBody,
+	/// Session time, `None` for a session that is always available
+	schedule: Option<SessionSchedule>,
+	state: SessionState,
+	/// Negotiated heartbeat interval in seconds
+	heart_bt_int: u32,
+	/// When the current state was entered, for logon and logout timeouts
+	state_since: OffsetDateTime,
+	last_sent: OffsetDateTime,
+	last_received: OffsetDateTime,
+	test_request_pending: bool,
+	test_request_counter: u32,
+	/// Highest inbound MsgSeqNum seen while a ResendRequest is outstanding
+	resend_target: Option<u32>,
+	outgoing: VecDeque<String>,
+}
+
+impl<S: MessageStore + std::fmt::Debug> std::fmt::Debug for Session<S> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("Session")
+			.field("session_id", &self.session_id)
+			.field("store", &self.store)
+			.field("state", &self.state)
+			.field("heart_bt_int", &self.heart_bt_int)
+			.field("schedule", &self.schedule)
+			.field("outgoing", &self.outgoing.len())
+			.finish_non_exhaustive()
+	}
+}
+
+impl<S: MessageStore> Session<S> {
+	/// Create a disconnected session using `store` for persistence
+	pub fn new(session_id: SessionId, store: S) -> Self {
+		let logon = LogonBody::default();
+		Self {
+			session_id,
+			store,
+			application: Box::new(NullApplication),
+			heart_bt_int: logon.heart_bt_int,
+			logon,
+			schedule: None,
+			state: SessionState::Disconnected,
+			state_since: OffsetDateTime::UNIX_EPOCH,
+			last_sent: OffsetDateTime::UNIX_EPOCH,
+			last_received: OffsetDateTime::UNIX_EPOCH,
+			test_request_pending: false,
+			test_request_counter: 0,
+			resend_target: None,
+			outgoing: VecDeque::new(),
+		}
+	}
+
+	/// Attach the application receiving this session's callbacks
+	pub fn with_application(mut self, application: impl Application + Send + 'static) -> Self {
+		self.application = Box::new(application);
+		self.application.on_create(&self.session_id);
+		self
+	}
+
+	/// Set the Logon sent when initiating (heartbeat interval, ResetSeqNumFlag, ...)
+	pub fn with_logon_body(mut self, logon: LogonBody) -> Self {
+		self.heart_bt_int = logon.heart_bt_int;
+		self.logon = logon;
+		self
+	}
+
+	/// Restrict the session to the periods of `schedule`
+	pub const fn with_schedule(mut self, schedule: SessionSchedule) -> Self {
+		self.schedule = Some(schedule);
+		self
+	}
+
+	pub const fn session_id(&self) -> &SessionId {
+		&self.session_id
+	}
+
+	pub const fn state(&self) -> SessionState {
+		self.state
+	}
+
+	pub fn is_logged_on(&self) -> bool {
+		self.state == SessionState::Active
+	}
+
+	pub const fn schedule(&self) -> Option<&SessionSchedule> {
+		self.schedule.as_ref()
+	}
+
+	/// Whether the session may be logged on at `now`; always true without a schedule
+	pub fn is_session_time(&self, now: OffsetDateTime) -> bool {
+		self.schedule.is_none_or(|schedule| schedule.is_active(now))
+	}
+
+	/// Negotiated heartbeat interval in seconds
+	pub const fn heart_bt_int(&self) -> u32 {
+		self.heart_bt_int
+	}
+
+	pub const fn store(&self) -> &S {
+		&self.store
+	}
+
+	pub const fn store_mut(&mut self) -> &mut S {
+		&mut self.store
+	}
+
+	/// Take the wire messages queued for sending, in order
+	pub fn take_outgoing(&mut self) -> Vec<String> {
+		self.outgoing.drain(..).collect()
+	}
+
+	/// Start the logon handshake as the initiator
+	///
+	/// # Errors
+	/// Fails with [`SessionError::OutsideSessionTime`] outside of the schedule.
+	pub fn initiate_logon(&mut self, now: OffsetDateTime) -> Result<(), SessionError> {
+		if !self.is_session_time(now) {
+			return Err(SessionError::OutsideSessionTime);
+		}
+		self.reset_if_new_period(now)?;
+		if self.logon.reset_seq_num_flag == Some(true) {
+			self.store.reset(now)?;
+		}
+		self.heart_bt_int = self.logon.heart_bt_int;
+		self.last_received = now;
+		self.set_state(SessionState::LogonSent, now);
+		let logon = self.admin(MsgType::Logon).build();
+		let logon = FixMessage { body: FixMessageBody::Logon(self.logon.clone()), ..logon };
+		self.send_message(logon, now)?;
+		Ok(())
+	}
+
+	/// Start the logout handshake
+	pub fn logout(&mut self, text: Option<&str>, now: OffsetDateTime) -> Result<(), SessionError> {
+		if self.state != SessionState::Active {
+			return Err(SessionError::NotLoggedOn);
+		}
+		self.send_logout(text, now)?;
+		self.set_state(SessionState::LogoutSent, now);
+		Ok(())
+	}
+
+	/// The connection is gone: forget any in-flight protocol state
+	///
+	/// Sequence numbers are kept in the store, so the next logon resumes where
+	/// this connection stopped.
+	pub fn disconnect(&mut self) {
+		self.set_disconnected();
+		self.test_request_pending = false;
+		self.resend_target = None;
+		self.outgoing.clear();
+	}
+
+	/// Send an application message
+	///
+	/// The header is stamped with our CompIDs, the next MsgSeqNum and `now`; the
+	/// assigned MsgSeqNum is returned. Fails with [`SessionError::DoNotSend`],
+	/// carrying the application's reason, if [`Application::to_app`] vetoes the
+	/// message.
+	pub fn send(&mut self, message: FixMessage, now: OffsetDateTime) -> Result<u32, SessionError> {
+		if self.state != SessionState::Active {
+			return Err(SessionError::NotLoggedOn);
+		}
+		self.send_message(message, now)
+	}
+
+	/// Process one inbound frame
+	///
+	/// Session-level messages are handled internally (responses are queued for
+	/// sending); an in-sequence application message is returned to the caller.
+	/// A malformed message is answered with a Reject (MsgType=3) and reported as
+	/// [`SessionError::InvalidMessage`]. Application messages of a type without a
+	/// typed body, or lacking a conditionally required field, are answered with a
+	/// Business Message Reject (MsgType=j).
+	pub fn on_message(&mut self, raw: &str, now: OffsetDateTime) -> Result<Option<FixMessage>, SessionError> {
+		let message = match FixMessage::parse(raw) {
+			Ok(message) => message,
+			Err(error) => return self.on_invalid_message(error, now),
+		};
+		self.last_received = now;
+		self.test_request_pending = false;
+
+		if !self.check_comp_ids(&message.header, now)? {
+			return Err(self.terminate("CompID problem", now));
+		}
+
+		let msg_type = message.header.msg_type.clone();
+		if matches!(self.state, SessionState::Disconnected | SessionState::LogonSent) && msg_type != MsgType::Logon {
+			return Err(self.terminate("First message is not a Logon", now));
+		}
+
+		if msg_type == MsgType::Logon && self.state == SessionState::Disconnected {
+			if !self.is_session_time(now) {
+				return Err(self.terminate("Logon outside of session time", now));
+			}
+			self.reset_if_new_period(now)?;
+		}
+
+		// An acceptor honours the counterparty's request to restart sequence numbers
+		if let FixMessageBody::Logon(body) = &message.body
+			&& body.reset_seq_num_flag == Some(true)
+			&& self.state == SessionState::Disconnected
+		{
+			self.store.reset(now)?;
+		}
+
+		// SequenceReset in Reset mode ignores MsgSeqNum entirely
+		if let FixMessageBody::SequenceReset(body) = &message.body
+			&& !body.is_gap_fill()
+		{
+			// Only a Logon can be refused, the result is irrelevant here
+			let _ = self.application.from_admin(&message, &self.session_id);
+			if body.new_seq_no > self.store.next_target_msg_seq_num() {
+				self.store.set_next_target_msg_seq_num(body.new_seq_no)?;
+			}
+			return Ok(None);
+		}
+
+		let expected = self.store.next_target_msg_seq_num();
+		let seq = message.header.msg_seq_num;
+
+		if seq < expected {
+			if message.header.poss_dup_flag == Some(true) {
+				// Already processed, ignore the duplicate
+				return Ok(None);
+			}
+			return Err(self.terminate(&format!("MsgSeqNum too low, expecting {} but received {}", expected, seq), now));
+		}
+
+		if seq > expected {
+			// Logon, Logout and ResendRequest are acted upon even when out of sequence
+			let app = match msg_type {
+				MsgType::Logon | MsgType::Logout | MsgType::ResendRequest => self.process(message, now)?,
+				_ => None,
+			};
+			if self.state == SessionState::Active {
+				self.request_resend(expected, seq, now)?;
+			}
+			return Ok(app);
+		}
+
+		self.store.incr_next_target_msg_seq_num()?;
+		let app = self.process(message, now)?;
+		if self.resend_target.is_some_and(|target| self.store.next_target_msg_seq_num() > target) {
+			self.resend_target = None;
+		}
+		Ok(app)
+	}
+
+	/// Handle an inbound message that failed to parse
+	///
+	/// A message whose header is readable consumes its sequence number like any
+	/// other and is answered with a Reject. Garbled messages are ignored.
+	fn on_invalid_message(
+		&mut self,
+		error: ParseError,
+		now: OffsetDateTime,
+	) -> Result<Option<FixMessage>, SessionError> {
+		let Some(header) = error.header.as_deref() else {
+			return Err(SessionError::InvalidMessage(error.text));
+		};
+		self.last_received = now;
+		self.test_request_pending = false;
+
+		if !self.check_comp_ids(header, now)? {
+			return Err(self.terminate("CompID problem", now));
+		}
+		if !matches!(self.state, SessionState::Active | SessionState::LogoutSent) {
+			return Err(self.terminate(&format!("Invalid Logon: {}", error.text), now));
+		}
+
+		let expected = self.store.next_target_msg_seq_num();
+		let seq = header.msg_seq_num;
+		if seq < expected {
+			if header.poss_dup_flag == Some(true) {
+				return Err(SessionError::InvalidMessage(error.text));
+			}
+			return Err(self.terminate(&format!("MsgSeqNum too low, expecting {} but received {}", expected, seq), now));
+		}
+		if seq > expected {
+			// The message is rejected once it is resent in sequence
+			self.request_resend(expected, seq, now)?;
+			return Err(SessionError::InvalidMessage(error.text));
+		}
+
+		self.store.incr_next_target_msg_seq_num()?;
+		match error.business_reject_reason.clone() {
+			// Well formed at session level, but missing a field the application needs
+			Some(reason) if !header.msg_type.is_admin() => {
+				let body = BusinessMessageRejectBody {
+					ref_seq_num: Some(seq),
+					..BusinessMessageRejectBody::new(header.msg_type.clone(), reason)
+				};
+				self.send_business_reject(body.with_text(error.text.clone()), now)?;
+			},
+			_ => {
+				if let Some(reject) = RejectBody::for_parse_error(&error) {
+					self.send_reject(reject, now)?;
+				}
+			},
+		}
+		if self.resend_target.is_some_and(|target| self.store.next_target_msg_seq_num() > target) {
+			self.resend_target = None;
+		}
+		Err(SessionError::InvalidMessage(error.text))
+	}
+
+	/// Check the CompIDs of an inbound message
+	///
+	/// On a mismatch while logged on, the message is answered with a Reject
+	/// (SessionRejectReason 9) and `false` is returned; the caller then logs out.
+	fn check_comp_ids(&mut self, header: &FixHeader, now: OffsetDateTime) -> Result<bool, SessionError> {
+		if header.sender_comp_id == self.session_id.target_comp_id
+			&& header.target_comp_id == self.session_id.sender_comp_id
+		{
+			return Ok(true);
+		}
+		if self.state == SessionState::Active {
+			let mut reject = RejectBody::new(header.msg_seq_num, SessionRejectReason::CompIdProblem);
+			reject.ref_msg_type = Some(header.msg_type.clone());
+			reject.text = Some("CompID problem".to_string());
+			if header.msg_seq_num == self.store.next_target_msg_seq_num() {
+				self.store.incr_next_target_msg_seq_num()?;
+			}
+			self.send_reject(reject, now)?;
+		}
+		Ok(false)
+	}
+
+	/// Drive time-based behaviour: heartbeats, test requests, timeouts and the schedule
+	pub fn on_timer(&mut self, now: OffsetDateTime) -> Result<(), SessionError> {
+		if let Some(schedule) = &self.schedule {
+			let period_start = schedule.period_start(now);
+			let period_over = period_start.is_none_or(|start| self.store.creation_time() < start);
+			match self.state {
+				SessionState::Disconnected => {
+					self.reset_if_new_period(now)?;
+					return Ok(());
+				},
+				SessionState::LogonSent if period_over => {
+					self.disconnect();
+					return Err(SessionError::Terminated("Outside of session time".to_string()));
+				},
+				SessionState::Active if period_over => {
+					let reason = if period_start.is_none() { "Session time ended" } else { "New session period" };
+					return self.logout(Some(reason), now);
+				},
+				_ => {},
+			}
+		}
+
+		match self.state {
+			SessionState::Disconnected => Ok(()),
+			SessionState::LogonSent if now - self.state_since >= LOGON_TIMEOUT => {
+				self.disconnect();
+				Err(SessionError::Terminated("Logon timeout".to_string()))
+			},
+			SessionState::LogoutSent if now - self.state_since >= LOGOUT_TIMEOUT => {
+				self.disconnect();
+				Err(SessionError::Terminated("Logout timeout".to_string()))
+			},
+			SessionState::LogonSent | SessionState::LogoutSent => Ok(()),
+			SessionState::Active => {
+				let interval = Duration::seconds(self.heart_bt_int as i64);
+				let silence = now - self.last_received;
+				if silence >= interval * TIMEOUT_THRESHOLD {
+					self.disconnect();
+					return Err(SessionError::Terminated("Heartbeat timeout".to_string()));
+				}
+				if silence >= interval * TEST_REQUEST_THRESHOLD && !self.test_request_pending {
+					self.test_request_counter += 1;
+					let test_request =
+						self.admin(MsgType::TestRequest).test_req_id(format!("TEST{}", self.test_request_counter));
+					self.send_message(test_request.build(), now)?;
+					self.test_request_pending = true;
+				}
+				if now - self.last_sent >= interval {
+					let heartbeat = self.admin(MsgType::Heartbeat).build();
+					self.send_message(heartbeat, now)?;
+				}
+				Ok(())
+			},
+		}
+	}
+
+	/// Act on an inbound message whose sequence number has been checked
+	fn process(&mut self, message: FixMessage, now: OffsetDateTime) -> Result<Option<FixMessage>, SessionError> {
+		if message.header.msg_type.is_admin()
+			&& let Err(RejectLogon(reason)) = self.application.from_admin(&message, &self.session_id)
+			&& message.header.msg_type == MsgType::Logon
+		{
+			// Refuse the Logon: explain why in a Logout and disconnect
+			self.send_logout(Some(&reason), now)?;
+			self.set_disconnected();
+			return Err(SessionError::Terminated(reason));
+		}
+
+		match message.body {
+			FixMessageBody::Logon(body) => {
+				match self.state {
+					SessionState::Disconnected => {
+						// Acceptor: adopt the initiator's heartbeat interval and answer
+						self.heart_bt_int = body.heart_bt_int;
+						let mut reply = LogonBody::new(body.encrypt_method, body.heart_bt_int);
+						reply.reset_seq_num_flag = body.reset_seq_num_flag.filter(|&flag| flag);
+						let logon = self.admin(MsgType::Logon).build();
+						self.set_state(SessionState::Active, now);
+						self.send_message(FixMessage { body: FixMessageBody::Logon(reply), ..logon }, now)?;
+					},
+					SessionState::LogonSent => self.set_state(SessionState::Active, now),
+					SessionState::Active | SessionState::LogoutSent => {},
+				}
+				Ok(None)
+			},
+			FixMessageBody::TestRequest(body) => {
+				let heartbeat = self.admin(MsgType::Heartbeat).build();
+				let body = FixMessageBody::Heartbeat(HeartbeatBody::responding_to_test_request(body.test_req_id));
+				self.send_message(FixMessage { body, ..heartbeat }, now)?;
+				Ok(None)
+			},
+			FixMessageBody::ResendRequest(body) => {
+				let (application, session_id) = (&mut self.application, &self.session_id);
+				let mut hook = |message: &FixMessage| application.to_app(&mut message.clone(), session_id).is_ok();
+				let replay = resend(&self.store, session_id, &body, now, &mut hook)?;
+				for message in replay {
+					self.outgoing.push_back(message.to_fix_string());
+				}
+				self.last_sent = now;
+				Ok(None)
+			},
+			FixMessageBody::SequenceReset(body) => {
+				// Only gap fills get here; Reset mode is handled before the sequence check
+				if body.new_seq_no > self.store.next_target_msg_seq_num() {
+					self.store.set_next_target_msg_seq_num(body.new_seq_no)?;
+				}
+				Ok(None)
+			},
+			FixMessageBody::Logout(_) => {
+				if self.state != SessionState::LogoutSent {
+					self.send_logout(None, now)?;
+				}
+				self.set_disconnected();
+				Ok(None)
+			},
+			_ if message.header.msg_type.is_admin() => Ok(None),
+			// Without a typed body the message cannot be handed to the application
+			FixMessageBody::Other(_) => {
+				let reason = BusinessRejectReason::UnsupportedMessageType;
+				let text = format!("Unsupported message type {}", message.header.msg_type);
+				self.send_business_reject(
+					BusinessMessageRejectBody::for_message(&message, reason).with_text(text),
+					now,
+				)?;
+				Ok(None)
+			},
+			_ => match self.application.from_app(&message, &self.session_id) {
+				Ok(()) => Ok(Some(message)),
+				Err(BusinessReject { reason, ref_id, text }) => {
+					let mut body = BusinessMessageRejectBody::for_message(&message, reason);
+					if ref_id.is_some() {
+						body.business_reject_ref_id = ref_id;
+					}
+					body.text = text;
+					self.send_business_reject(body, now)?;
+					Ok(None)
+				},
+			},
+		}
+	}
+
+	/// Queue a Business Message Reject, unless the application vetoes it
+	fn send_business_reject(
+		&mut self,
+		body: BusinessMessageRejectBody,
+		now: OffsetDateTime,
+	) -> Result<(), SessionError> {
+		let reply = self.admin(MsgType::BusinessMessageReject).build();
+		match self.send_message(FixMessage { body: FixMessageBody::BusinessMessageReject(body), ..reply }, now) {
+			Ok(_) | Err(SessionError::DoNotSend(_)) => Ok(()),
+			Err(e) => Err(e),
+		}
+	}
+
+	/// Queue a session-level Reject
+	fn send_reject(&mut self, body: RejectBody, now: OffsetDateTime) -> Result<u32, SessionError> {
+		let reject = self.admin(MsgType::Reject).build();
+		self.send_message(FixMessage { body: FixMessageBody::Reject(body), ..reject }, now)
+	}
+
+	/// Ask the counterparty to resend from `expected`, unless a request is already outstanding
+	fn request_resend(&mut self, expected: u32, received: u32, now: OffsetDateTime) -> Result<(), SessionError> {
+		if let Some(target) = self.resend_target {
+			self.resend_target = Some(target.max(received));
+			return Ok(());
+		}
+		let request = self.admin(MsgType::ResendRequest).begin_seq_no(expected).end_seq_no(0).build();
+		self.send_message(request, now)?;
+		self.resend_target = Some(received);
+		Ok(())
+	}
+
+	/// Restart sequence numbers if the store predates the current session period
+	fn reset_if_new_period(&mut self, now: OffsetDateTime) -> Result<(), SessionError> {
+		let period_start = self.schedule.and_then(|schedule| schedule.period_start(now));
+		if period_start.is_some_and(|start| self.store.creation_time() < start) {
+			self.store.reset(now)?;
+		}
+		Ok(())
+	}
+
+	/// Disconnect because of a protocol violation, logging out first if we were logged on
+	fn terminate(&mut self, reason: &str, now: OffsetDateTime) -> SessionError {
+		if matches!(self.state, SessionState::Active | SessionState::LogoutSent) {
+			// Best effort: the session is going away whether or not the Logout can be stored
+			let _ = self.send_logout(Some(reason), now);
+		}
+		self.set_disconnected();
+		SessionError::Terminated(reason.to_string())
+	}
+
+	fn send_logout(&mut self, text: Option<&str>, now: OffsetDateTime) -> Result<u32, SessionError> {
+		let mut logout = self.admin(MsgType::Logout);
+		if let Some(text) = text {
+			logout = logout.logout_text(text);
+		}
+		self.send_message(logout.build(), now)
+	}
+
+	/// Builder for a session-level message; the header is completed by [`Session::send_message`]
+	fn admin(&self, msg_type: MsgType) -> FixMessageBuilder {
+		FixMessage::builder(msg_type, &self.session_id.sender_comp_id, &self.session_id.target_comp_id, 1)
+	}
+
+	/// Stamp, persist and queue an outbound message, returning its MsgSeqNum
+	///
+	/// The stamped message is passed to [`Application::to_admin`] or
+	/// [`Application::to_app`] before it is serialized.
+	fn send_message(&mut self, mut message: FixMessage, now: OffsetDateTime) -> Result<u32, SessionError> {
+		let seq = self.store.next_sender_msg_seq_num();
+		message.header.sender_comp_id.clone_from(&self.session_id.sender_comp_id);
+		message.header.target_comp_id.clone_from(&self.session_id.target_comp_id);
+		message.header.msg_seq_num = seq;
+		message.header.sending_time = now;
+		if message.header.msg_type.is_admin() {
+			self.application.to_admin(&mut message, &self.session_id);
+		} else if let Err(veto) = self.application.to_app(&mut message, &self.session_id) {
+			return Err(SessionError::DoNotSend(veto));
+		}
+		// Rebuild so BodyLength and CheckSum reflect any change made by the application
+		let message = FixMessageBuilder::from_message(message).build();
+
+		let wire = message.to_fix_string();
+		self.store.set(seq, &wire)?;
+		self.store.incr_next_sender_msg_seq_num()?;
+		self.outgoing.push_back(wire);
+		self.last_sent = now;
+		Ok(seq)
+	}
+
+	fn set_state(&mut self, state: SessionState, now: OffsetDateTime) {
+		let was_active = self.state == SessionState::Active;
+		self.state = state;
+		self.state_since = now;
+		if state == SessionState::Active {
+			self.last_sent = now;
+			self.last_received = now;
+			if !was_active {
+				self.application.on_logon(&self.session_id);
+			}
+		}
+	}
+
+	/// Enter [`SessionState::Disconnected`], telling the application if we were logged on
+	fn set_disconnected(&mut self) {
+		let was_logged_on = matches!(self.state, SessionState::Active | SessionState::LogoutSent);
+		self.state = SessionState::Disconnected;
+		if was_logged_on {
+			self.application.on_logout(&self.session_id);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{EncryptMethod, Side, session::MemoryStore};
+	use std::sync::{Arc, Mutex};
+	use time::macros::{datetime, time};
+
+	const T0: OffsetDateTime = datetime!(2024-05-01 09:00:00 UTC);
+
+	fn pair() -> (Session<MemoryStore>, Session<MemoryStore>) {
+		let initiator = Session::new(SessionId::new("CLIENT", "BROKER"), MemoryStore::new());
+		let acceptor = Session::new(SessionId::new("BROKER", "CLIENT"), MemoryStore::new());
+		(initiator, acceptor)
+	}
+
+	/// Deliver everything `from` queued to `to`, returning the application messages
+	fn pump(from: &mut Session<MemoryStore>, to: &mut Session<MemoryStore>, now: OffsetDateTime) -> Vec<FixMessage> {
+		from.take_outgoing().iter().filter_map(|wire| to.on_message(wire, now).unwrap()).collect()
+	}
+
+	fn logged_on_pair() -> (Session<MemoryStore>, Session<MemoryStore>) {
+		let (mut initiator, mut acceptor) = pair();
+		initiator.initiate_logon(T0).unwrap();
+		pump(&mut initiator, &mut acceptor, T0);
+		pump(&mut acceptor, &mut initiator, T0);
+		(initiator, acceptor)
+	}
+
+	fn order(cl_ord_id: &str) -> FixMessage {
+		FixMessage::builder(MsgType::NewOrderSingle, "CLIENT", "BROKER", 1)
+			.cl_ord_id(cl_ord_id)
+			.handl_inst("1")
+			.symbol("AAPL")
+			.side(Side::Buy)
+			.order_qty(100.0)
+			.ord_type("1")
+			.transact_time(T0)
+			.build()
+	}
+
+	fn outgoing_types(session: &mut Session<MemoryStore>) -> Vec<MsgType> {
+		session.take_outgoing().iter().map(|wire| FixMessage::from_fix_string(wire).unwrap().header.msg_type).collect()
+	}
+
+	#[test]
+	fn test_logon_handshake() {
+		let (initiator, acceptor) = logged_on_pair();
+		assert!(initiator.is_logged_on());
+		assert!(acceptor.is_logged_on());
+		assert_eq!(initiator.store().next_sender_msg_seq_num(), 2);
+		assert_eq!(initiator.store().next_target_msg_seq_num(), 2);
+		assert_eq!(acceptor.store().next_sender_msg_seq_num(), 2);
+		assert_eq!(acceptor.store().next_target_msg_seq_num(), 2);
+	}
+
+	#[test]
+	fn test_acceptor_adopts_heartbeat_interval() {
+		let (initiator, mut acceptor) = pair();
+		let mut initiator = initiator.with_logon_body(LogonBody::new(EncryptMethod::None, 5));
+		initiator.initiate_logon(T0).unwrap();
+		pump(&mut initiator, &mut acceptor, T0);
+		assert_eq!(acceptor.heart_bt_int(), 5);
+	}
+
+	#[test]
+	fn test_application_message_delivery() {
+		let (mut initiator, mut acceptor) = logged_on_pair();
+		assert_eq!(initiator.send(order("ORD1"), T0).unwrap(), 2);
+
+		let delivered = pump(&mut initiator, &mut acceptor, T0);
+		assert_eq!(delivered.len(), 1);
+		assert_eq!(delivered[0].header.sender_comp_id, "CLIENT");
+		assert_eq!(delivered[0].header.msg_seq_num, 2);
+		assert_eq!(initiator.store().get(2, 2).unwrap().len(), 1);
+	}
+
+	#[test]
+	fn test_send_requires_logon() {
+		let (mut initiator, _) = pair();
+		assert!(matches!(initiator.send(order("ORD1"), T0), Err(SessionError::NotLoggedOn)));
+	}
+
+	#[test]
+	fn test_test_request_is_answered() {
+		let (mut initiator, mut acceptor) = logged_on_pair();
+		let test_request = FixMessage::builder(MsgType::TestRequest, "X", "Y", 1).test_req_id("PING").build();
+		initiator.send_message(test_request, T0).unwrap();
+		pump(&mut initiator, &mut acceptor, T0);
+
+		let reply = acceptor.take_outgoing();
+		assert_eq!(reply.len(), 1);
+		let heartbeat = FixMessage::from_fix_string(&reply[0]).unwrap();
+		assert_eq!(heartbeat.body, FixMessageBody::Heartbeat(HeartbeatBody::responding_to_test_request("PING")));
+	}
+
+	#[test]
+	fn test_heartbeat_and_test_request_timers() {
+		let (mut initiator, _) = logged_on_pair();
+
+		initiator.on_timer(T0 + Duration::seconds(29)).unwrap();
+		assert!(initiator.take_outgoing().is_empty());
+
+		initiator.on_timer(T0 + Duration::seconds(30)).unwrap();
+		assert_eq!(outgoing_types(&mut initiator), vec![MsgType::Heartbeat]);
+
+		initiator.on_timer(T0 + Duration::seconds(36)).unwrap();
+		assert_eq!(outgoing_types(&mut initiator), vec![MsgType::TestRequest]);
+
+		// Only one outstanding test request at a time
+		initiator.on_timer(T0 + Duration::seconds(40)).unwrap();
+		assert!(initiator.take_outgoing().is_empty());
+
+		let result = initiator.on_timer(T0 + Duration::seconds(72));
+		assert!(matches!(result, Err(SessionError::Terminated(_))));
+		assert_eq!(initiator.state(), SessionState::Disconnected);
+	}
+
+	#[test]
+	fn test_gap_triggers_resend_and_replay() {
+		let (mut initiator, mut acceptor) = logged_on_pair();
+		initiator.send(order("ORD1"), T0).unwrap();
+		initiator.send(order("ORD2"), T0).unwrap();
+		let mut wires = initiator.take_outgoing();
+
+		// ORD1 is lost in transit
+		wires.remove(0);
+		assert!(acceptor.on_message(&wires[0], T0).unwrap().is_none());
+		let request = acceptor.take_outgoing();
+		assert_eq!(request.len(), 1);
+		let request = FixMessage::from_fix_string(&request[0]).unwrap();
+		assert_eq!(request.body, FixMessageBody::ResendRequest(crate::ResendRequestBody::new(2, 0)));
+
+		// The initiator replays both orders, which are delivered in order as possible duplicates
+		initiator.on_message(&request.to_fix_string(), T0).unwrap();
+		let delivered = pump(&mut initiator, &mut acceptor, T0);
+		assert_eq!(delivered.len(), 2);
+		assert!(delivered.iter().all(|m| m.header.poss_dup_flag == Some(true)));
+		assert_eq!(acceptor.store().next_target_msg_seq_num(), 4);
+	}
+
+	#[test]
+	fn test_seq_num_too_low_terminates() {
+		let (mut initiator, mut acceptor) = logged_on_pair();
+		initiator.send(order("ORD1"), T0).unwrap();
+		let wire = initiator.take_outgoing().remove(0);
+		acceptor.on_message(&wire, T0).unwrap();
+
+		let result = acceptor.on_message(&wire, T0);
+		assert!(matches!(result, Err(SessionError::Terminated(_))));
+		assert_eq!(outgoing_types(&mut acceptor), vec![MsgType::Logout]);
+	}
+
+	#[test]
+	fn test_logout_handshake() {
+		let (mut initiator, mut acceptor) = logged_on_pair();
+		initiator.logout(Some("Done"), T0).unwrap();
+		assert_eq!(initiator.state(), SessionState::LogoutSent);
+
+		pump(&mut initiator, &mut acceptor, T0);
+		assert_eq!(acceptor.state(), SessionState::Disconnected);
+		pump(&mut acceptor, &mut initiator, T0);
+		assert_eq!(initiator.state(), SessionState::Disconnected);
+	}
+
+	#[test]
+	fn test_reset_seq_num_flag_on_logon() {
+		let (mut initiator, mut acceptor) = logged_on_pair();
+		initiator.send(order("ORD1"), T0).unwrap();
+		pump(&mut initiator, &mut acceptor, T0);
+		initiator.disconnect();
+		acceptor.disconnect();
+
+		let mut initiator = initiator.with_logon_body(LogonBody::default().with_reset_seq_num_flag(true));
+		initiator.initiate_logon(T0).unwrap();
+		pump(&mut initiator, &mut acceptor, T0);
+		pump(&mut acceptor, &mut initiator, T0);
+
+		assert!(initiator.is_logged_on());
+		assert!(acceptor.is_logged_on());
+		assert_eq!(acceptor.store().next_target_msg_seq_num(), 2);
+		assert_eq!(initiator.store().next_target_msg_seq_num(), 2);
+	}
+
+	#[test]
+	fn test_comp_id_mismatch_terminates() {
+		let (_, mut acceptor) = pair();
+		let logon = FixMessage::builder(MsgType::Logon, "STRANGER", "BROKER", 1).build();
+		let result = acceptor.on_message(&logon.to_fix_string(), T0);
+		assert!(matches!(result, Err(SessionError::Terminated(_))));
+	}
+
+	#[test]
+	fn test_malformed_message_is_rejected() {
+		let (mut initiator, mut acceptor) = logged_on_pair();
+		initiator.send(order("ORD1"), T0).unwrap();
+		let wire = initiator.take_outgoing().remove(0).replace("\x0154=1\x01", "\x0154=X\x01");
+
+		assert!(matches!(acceptor.on_message(&wire, T0), Err(SessionError::InvalidMessage(_))));
+		assert_eq!(acceptor.state(), SessionState::Active);
+		assert_eq!(acceptor.store().next_target_msg_seq_num(), 3);
+
+		let reject = FixMessage::from_fix_string(&acceptor.take_outgoing()[0]).unwrap();
+		let FixMessageBody::Reject(body) = reject.body else { panic!("Expected a reject") };
+		assert_eq!(body.ref_seq_num, 2);
+		assert_eq!(body.ref_tag_id, Some(54));
+		assert_eq!(body.ref_msg_type, Some(MsgType::NewOrderSingle));
+		assert_eq!(body.session_reject_reason, Some(SessionRejectReason::IncorrectDataFormatForValue));
+		assert!(body.text.is_some());
+
+		// The Reject is an ordinary admin message for the initiator, and the next order flows normally
+		assert!(pump(&mut acceptor, &mut initiator, T0).is_empty());
+		initiator.send(order("ORD2"), T0).unwrap();
+		assert_eq!(pump(&mut initiator, &mut acceptor, T0).len(), 1);
+	}
+
+	#[test]
+	fn test_malformed_message_after_gap_requests_resend() {
+		let (mut initiator, mut acceptor) = logged_on_pair();
+		initiator.send(order("ORD1"), T0).unwrap();
+		initiator.send(order("ORD2"), T0).unwrap();
+		let wire = initiator.take_outgoing().remove(1).replace("\x0154=1\x01", "\x0154=X\x01");
+
+		assert!(acceptor.on_message(&wire, T0).is_err());
+		assert_eq!(outgoing_types(&mut acceptor), vec![MsgType::ResendRequest]);
+		assert_eq!(acceptor.store().next_target_msg_seq_num(), 2);
+	}
+
+	#[test]
+	fn test_comp_id_problem_while_logged_on() {
+		let (_, mut acceptor) = logged_on_pair();
+		let heartbeat = FixMessage::builder(MsgType::Heartbeat, "STRANGER", "BROKER", 2).build();
+		let result = acceptor.on_message(&heartbeat.to_fix_string(), T0);
+		assert!(matches!(result, Err(SessionError::Terminated(_))));
+
+		let outgoing = acceptor.take_outgoing();
+		let reject = FixMessage::from_fix_string(&outgoing[0]).unwrap();
+		let FixMessageBody::Reject(body) = reject.body else { panic!("Expected a reject") };
+		assert_eq!(body.session_reject_reason, Some(SessionRejectReason::CompIdProblem));
+		assert_eq!(FixMessage::from_fix_string(&outgoing[1]).unwrap().header.msg_type, MsgType::Logout);
+	}
+
+	#[test]
+	fn test_unsupported_message_type_gets_business_reject() {
+		let (_, mut acceptor) = logged_on_pair();
+		let wire =
+			"8=FIX.4.2\x019=50\x0135=ZZ\x0149=CLIENT\x0156=BROKER\x0134=2\x0152=20240501-09:00:00.000\x0110=000\x01";
+		assert!(acceptor.on_message(wire, T0).unwrap().is_none());
+		assert_eq!(acceptor.store().next_target_msg_seq_num(), 3);
+
+		let reject = FixMessage::from_fix_string(&acceptor.take_outgoing()[0]).unwrap();
+		let FixMessageBody::BusinessMessageReject(body) = reject.body else { panic!("Expected a business reject") };
+		assert_eq!(body.ref_seq_num, Some(2));
+		assert_eq!(body.ref_msg_type, MsgType::Other("ZZ".to_string()));
+		assert_eq!(body.business_reject_reason, BusinessRejectReason::UnsupportedMessageType);
+	}
+
+	#[test]
+	fn test_missing_conditional_field_gets_business_reject() {
+		let (mut initiator, mut acceptor) = logged_on_pair();
+		initiator.send(order("ORD1"), T0).unwrap();
+		let wire = initiator.take_outgoing().remove(0).replace("\x0138=100\x01", "\x01");
+
+		assert!(matches!(acceptor.on_message(&wire, T0), Err(SessionError::InvalidMessage(_))));
+		assert_eq!(acceptor.store().next_target_msg_seq_num(), 3);
+		let reject = FixMessage::from_fix_string(&acceptor.take_outgoing()[0]).unwrap();
+		let FixMessageBody::BusinessMessageReject(body) = reject.body else { panic!("Expected a business reject") };
+		assert_eq!(body.ref_seq_num, Some(2));
+		assert_eq!(body.business_reject_reason, BusinessRejectReason::ConditionallyRequiredFieldMissing);
+	}
+
+	/// Records callbacks and applies the configured policy
+	#[derive(Clone, Default)]
+	struct Recorder {
+		events: Arc<Mutex<Vec<String>>>,
+		credentials: Option<(&'static str, &'static str)>,
+		required_password: Option<&'static str>,
+		reject_orders: bool,
+		veto_orders: bool,
+	}
+
+	impl Recorder {
+		fn events(&self) -> Vec<String> {
+			self.events.lock().unwrap().clone()
+		}
+
+		fn record(&self, event: String) {
+			self.events.lock().unwrap().push(event);
+		}
+	}
+
+	impl Application for Recorder {
+		fn on_create(&mut self, session_id: &SessionId) {
+			self.record(format!("create {}", session_id));
+		}
+
+		fn on_logon(&mut self, _session_id: &SessionId) {
+			self.record("logon".to_string());
+		}
+
+		fn on_logout(&mut self, _session_id: &SessionId) {
+			self.record("logout".to_string());
+		}
+
+		fn to_admin(&mut self, message: &mut FixMessage, _session_id: &SessionId) {
+			if let (FixMessageBody::Logon(body), Some((username, password))) = (&mut message.body, self.credentials) {
+				*body = body.clone().with_credentials(username, password);
+			}
+		}
+
+		fn from_admin(&mut self, message: &FixMessage, _session_id: &SessionId) -> Result<(), RejectLogon> {
+			if let (FixMessageBody::Logon(body), Some(required)) = (&message.body, self.required_password)
+				&& body.password.as_deref() != Some(required)
+			{
+				return Err(RejectLogon("Invalid password".to_string()));
+			}
+			Ok(())
+		}
+
+		fn to_app(&mut self, _message: &mut FixMessage, _session_id: &SessionId) -> Result<(), DoNotSend> {
+			if self.veto_orders { Err(DoNotSend(None)) } else { Ok(()) }
+		}
+
+		fn from_app(&mut self, message: &FixMessage, _session_id: &SessionId) -> Result<(), BusinessReject> {
+			self.record(format!("from_app {}", message.header.msg_seq_num));
+			if self.reject_orders {
+				return Err(BusinessReject::new(BusinessRejectReason::Other).with_text("Unknown account"));
+			}
+			Ok(())
+		}
+	}
+
+	fn application_pair(client: &Recorder, broker: &Recorder) -> (Session<MemoryStore>, Session<MemoryStore>) {
+		let (initiator, acceptor) = pair();
+		(initiator.with_application(client.clone()), acceptor.with_application(broker.clone()))
+	}
+
+	#[test]
+	fn test_application_lifecycle_callbacks() {
+		let client = Recorder { credentials: Some(("trader", "secret")), ..Default::default() };
+		let broker = Recorder { required_password: Some("secret"), ..Default::default() };
+		let (mut initiator, mut acceptor) = application_pair(&client, &broker);
+
+		initiator.initiate_logon(T0).unwrap();
+		pump(&mut initiator, &mut acceptor, T0);
+		pump(&mut acceptor, &mut initiator, T0);
+		assert!(acceptor.is_logged_on());
+
+		initiator.send(order("ORD1"), T0).unwrap();
+		assert_eq!(pump(&mut initiator, &mut acceptor, T0).len(), 1);
+
+		initiator.logout(None, T0).unwrap();
+		pump(&mut initiator, &mut acceptor, T0);
+		pump(&mut acceptor, &mut initiator, T0);
+
+		assert_eq!(client.events(), ["create FIX.4.2:CLIENT->BROKER", "logon", "logout"]);
+		assert_eq!(broker.events(), ["create FIX.4.2:BROKER->CLIENT", "logon", "from_app 2", "logout"]);
+	}
+
+	#[test]
+	fn test_from_admin_refuses_logon() {
+		let client = Recorder { credentials: Some(("trader", "wrong")), ..Default::default() };
+		let broker = Recorder { required_password: Some("secret"), ..Default::default() };
+		let (mut initiator, mut acceptor) = application_pair(&client, &broker);
+
+		initiator.initiate_logon(T0).unwrap();
+		let logon = initiator.take_outgoing().remove(0);
+		assert!(logon.contains("\x01553=trader\x01554=wrong\x01"));
+		let result = acceptor.on_message(&logon, T0);
+		assert!(matches!(result, Err(SessionError::Terminated(reason)) if reason == "Invalid password"));
+		assert_eq!(acceptor.state(), SessionState::Disconnected);
+
+		let reply = FixMessage::from_fix_string(&acceptor.take_outgoing()[0]).unwrap();
+		assert_eq!(reply.body, FixMessageBody::Logout(crate::LogoutBody::with_text("Invalid password")));
+		assert_eq!(broker.events(), ["create FIX.4.2:BROKER->CLIENT"]);
+	}
+
+	#[test]
+	fn test_from_app_sends_business_message_reject() {
+		let broker = Recorder { reject_orders: true, ..Default::default() };
+		let (mut initiator, mut acceptor) = application_pair(&Recorder::default(), &broker);
+		initiator.initiate_logon(T0).unwrap();
+		pump(&mut initiator, &mut acceptor, T0);
+		pump(&mut acceptor, &mut initiator, T0);
+
+		initiator.send(order("ORD1"), T0).unwrap();
+		assert!(pump(&mut initiator, &mut acceptor, T0).is_empty());
+
+		let reject = FixMessage::from_fix_string(&acceptor.take_outgoing()[0]).unwrap();
+		let FixMessageBody::BusinessMessageReject(body) = reject.body else { panic!("Expected a reject") };
+		assert_eq!(body.ref_seq_num, Some(2));
+		assert_eq!(body.ref_msg_type, MsgType::NewOrderSingle);
+		assert_eq!(body.business_reject_ref_id.as_deref(), Some("ORD1"));
+		assert_eq!(body.business_reject_reason, BusinessRejectReason::Other);
+		assert_eq!(body.text.as_deref(), Some("Unknown account"));
+	}
+
+	#[test]
+	fn test_to_app_veto_keeps_sequence_number() {
+		let client = Recorder { veto_orders: true, ..Default::default() };
+		let (mut initiator, mut acceptor) = application_pair(&client, &Recorder::default());
+		initiator.initiate_logon(T0).unwrap();
+		pump(&mut initiator, &mut acceptor, T0);
+		pump(&mut acceptor, &mut initiator, T0);
+
+		assert!(matches!(initiator.send(order("ORD1"), T0), Err(SessionError::DoNotSend(_))));
+		assert!(initiator.take_outgoing().is_empty());
+		assert_eq!(initiator.store().next_sender_msg_seq_num(), 2);
+	}
+
+	fn scheduled_pair() -> (Session<MemoryStore>, Session<MemoryStore>) {
+		// 08:00-17:00 UTC every day, T0 is 09:00
+		let schedule = SessionSchedule::daily(time!(08:00), time!(17:00));
+		let (initiator, acceptor) = pair();
+		(initiator.with_schedule(schedule), acceptor.with_schedule(schedule))
+	}
+
+	#[test]
+	fn test_schedule_controls_logon_and_logout() {
+		let (mut initiator, mut acceptor) = scheduled_pair();
+		let early = T0 - Duration::hours(2);
+		assert!(!initiator.is_session_time(early));
+		assert!(matches!(initiator.initiate_logon(early), Err(SessionError::OutsideSessionTime)));
+
+		// An acceptor refuses a Logon outside of session time
+		let (mut unscheduled, _) = pair();
+		unscheduled.initiate_logon(early).unwrap();
+		let logon = unscheduled.take_outgoing().remove(0);
+		assert!(matches!(acceptor.on_message(&logon, early), Err(SessionError::Terminated(_))));
+
+		initiator.initiate_logon(T0).unwrap();
+		pump(&mut initiator, &mut acceptor, T0);
+		pump(&mut acceptor, &mut initiator, T0);
+		assert!(initiator.is_logged_on());
+
+		// Both sides log out when the period ends
+		let end = datetime!(2024-05-01 17:00:00 UTC);
+		initiator.on_timer(end).unwrap();
+		assert_eq!(initiator.state(), SessionState::LogoutSent);
+		pump(&mut initiator, &mut acceptor, end);
+		pump(&mut acceptor, &mut initiator, end);
+		assert_eq!(initiator.state(), SessionState::Disconnected);
+		assert_eq!(acceptor.state(), SessionState::Disconnected);
+	}
+
+	#[test]
+	fn test_new_period_resets_sequence_numbers() {
+		let (mut initiator, mut acceptor) = scheduled_pair();
+		let yesterday = T0 - Duration::days(1);
+		for session in [&mut initiator, &mut acceptor] {
+			session.store_mut().reset(yesterday).unwrap();
+			session.store_mut().set_next_sender_msg_seq_num(10).unwrap();
+			session.store_mut().set_next_target_msg_seq_num(10).unwrap();
+		}
+
+		// A disconnected session resets as soon as the new period is seen
+		acceptor.on_timer(T0).unwrap();
+		assert_eq!(acceptor.store().next_target_msg_seq_num(), 1);
+		assert_eq!(acceptor.store().creation_time(), T0);
+
+		// An initiator resets before sending its Logon
+		initiator.initiate_logon(T0).unwrap();
+		pump(&mut initiator, &mut acceptor, T0);
+		pump(&mut acceptor, &mut initiator, T0);
+		assert!(acceptor.is_logged_on());
+		assert_eq!(initiator.store().next_sender_msg_seq_num(), 2);
+		assert_eq!(initiator.store().next_target_msg_seq_num(), 2);
+	}
+}
@@ -0,0 +1,553 @@
+//! Message and sequence number stores
+//!
+//! A session must keep every outbound message it sent, keyed by MsgSeqNum, so it
+//! can answer ResendRequests, and it must remember its next sender and target
+//! sequence numbers across restarts. [`MessageStore`] abstracts that storage;
+//! [`MemoryStore`] keeps everything in memory and [`FileStore`] persists it on disk
+//! using the QuickFIX file layout:
+//!
+//! - `<name>.body`    - the raw outbound messages, appended back to back
+//! - `<name>.header`  - one `seqnum,offset,size` index line per stored message
+//! - `<name>.seqnums` - the next sender and target sequence numbers (`sender : target`)
+//! - `<name>.session` - the creation time of the store
+//!
+//! Messages are stored exactly as produced by [`FixMessage::to_fix_string`](crate::FixMessage::to_fix_string).
+
+use crate::common::{parse_fix_timestamp, write_timestamp};
+use std::{
+	collections::BTreeMap,
+	fmt::Display,
+	fs::{self, File, OpenOptions},
+	io::{self, Read, Seek, SeekFrom, Write},
+	path::{Path, PathBuf},
+};
+use time::OffsetDateTime;
+
+/// Errors raised by a [`MessageStore`]
+#[derive(Debug)]
+pub enum StoreError {
+	/// The underlying storage failed
+	Io(io::Error),
+	/// The persisted data could not be interpreted
+	Corrupt(String),
+}
+
+impl Display for StoreError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Io(e) => write!(f, "Store I/O error: {}", e),
+			Self::Corrupt(reason) => write!(f, "Corrupt store: {}", reason),
+		}
+	}
+}
+
+impl std::error::Error for StoreError {}
+
+impl From<io::Error> for StoreError {
+	fn from(e: io::Error) -> Self {
+		Self::Io(e)
+	}
+}
+
+/// Storage for outbound messages and session sequence numbers
+///
+/// Sequence numbers start at 1. A freshly created (or reset) store expects to send
+/// and receive MsgSeqNum 1 next.
+pub trait MessageStore {
+	/// Store the wire representation of an outbound message under its MsgSeqNum
+	fn set(&mut self, msg_seq_num: u32, message: &str) -> Result<(), StoreError>;
+
+	/// Fetch the stored messages with `begin <= MsgSeqNum <= end`, in ascending order
+	///
+	/// Sequence numbers that were never stored are simply absent from the result.
+	fn get(&self, begin: u32, end: u32) -> Result<Vec<(u32, String)>, StoreError>;
+
+	/// The MsgSeqNum to use for the next outbound message
+	fn next_sender_msg_seq_num(&self) -> u32;
+
+	/// The MsgSeqNum expected on the next inbound message
+	fn next_target_msg_seq_num(&self) -> u32;
+
+	/// Set the MsgSeqNum to use for the next outbound message
+	fn set_next_sender_msg_seq_num(&mut self, seq_num: u32) -> Result<(), StoreError>;
+
+	/// Set the MsgSeqNum expected on the next inbound message
+	fn set_next_target_msg_seq_num(&mut self, seq_num: u32) -> Result<(), StoreError>;
+
+	/// Advance the next outbound MsgSeqNum by one
+	fn incr_next_sender_msg_seq_num(&mut self) -> Result<(), StoreError> {
+		self.set_next_sender_msg_seq_num(self.next_sender_msg_seq_num() + 1)
+	}
+
+	/// Advance the next expected inbound MsgSeqNum by one
+	fn incr_next_target_msg_seq_num(&mut self) -> Result<(), StoreError> {
+		self.set_next_target_msg_seq_num(self.next_target_msg_seq_num() + 1)
+	}
+
+	/// When the current store contents were created (or last reset)
+	fn creation_time(&self) -> OffsetDateTime;
+
+	/// Drop all stored messages and reset both sequence numbers to 1
+	fn reset(&mut self, now: OffsetDateTime) -> Result<(), StoreError>;
+}
+
+/// In-memory message store
+///
+/// Nothing survives the process; useful for tests and sessions that reset their
+/// sequence numbers on every logon.
+#[derive(Debug, Clone)]
+pub struct MemoryStore {
+	messages: BTreeMap<u32, String>,
+	next_sender_msg_seq_num: u32,
+	next_target_msg_seq_num: u32,
+	creation_time: OffsetDateTime,
+}
+
+impl Default for MemoryStore {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl MemoryStore {
+	/// Create an empty store expecting MsgSeqNum 1 in both directions
+	pub fn new() -> Self {
+		Self {
+			messages: BTreeMap::new(),
+			next_sender_msg_seq_num: 1,
+			next_target_msg_seq_num: 1,
+			creation_time: OffsetDateTime::now_utc(),
+		}
+	}
+}
+
+impl MessageStore for MemoryStore {
+	fn set(&mut self, msg_seq_num: u32, message: &str) -> Result<(), StoreError> {
+		self.messages.insert(msg_seq_num, message.to_string());
+		Ok(())
+	}
+
+	fn get(&self, begin: u32, end: u32) -> Result<Vec<(u32, String)>, StoreError> {
+		if begin > end {
+			return Ok(Vec::new());
+		}
+		Ok(self.messages.range(begin..=end).map(|(&seq, msg)| (seq, msg.clone())).collect())
+	}
+
+	fn next_sender_msg_seq_num(&self) -> u32 {
+		self.next_sender_msg_seq_num
+	}
+
+	fn next_target_msg_seq_num(&self) -> u32 {
+		self.next_target_msg_seq_num
+	}
+
+	fn set_next_sender_msg_seq_num(&mut self, seq_num: u32) -> Result<(), StoreError> {
+		self.next_sender_msg_seq_num = seq_num;
+		Ok(())
+	}
+
+	fn set_next_target_msg_seq_num(&mut self, seq_num: u32) -> Result<(), StoreError> {
+		self.next_target_msg_seq_num = seq_num;
+		Ok(())
+	}
+
+	fn creation_time(&self) -> OffsetDateTime {
+		self.creation_time
+	}
+
+	fn reset(&mut self, now: OffsetDateTime) -> Result<(), StoreError> {
+		self.messages.clear();
+		self.next_sender_msg_seq_num = 1;
+		self.next_target_msg_seq_num = 1;
+		self.creation_time = now;
+		Ok(())
+	}
+}
+
+/// File-backed message store using the QuickFIX `.body/.header/.seqnums/.session` layout
+///
+/// Crash safety relies on write ordering: a message is appended and synced to the
+/// `.body` file before its index line is appended and synced to `.header`, so an
+/// index entry never points at bytes that were not written. A torn trailing index
+/// line is discarded when the store is reopened, or before the next line is appended
+/// when the write failed without a crash. The `.seqnums` and `.session`
+/// files are replaced atomically (write to a temporary file, sync, rename).
+///
+/// The whole index is kept in memory, so range lookups by MsgSeqNum only touch the
+/// `.body` file for the messages actually returned.
+#[derive(Debug)]
+pub struct FileStore {
+	dir: PathBuf,
+	name: String,
+	body: File,
+	header: File,
+	body_len: u64,
+	/// Length of the `.header` file up to its last complete index line
+	header_len: u64,
+	/// MsgSeqNum -> (offset, size) within the `.body` file
+	index: BTreeMap<u32, (u64, u64)>,
+	next_sender_msg_seq_num: u32,
+	next_target_msg_seq_num: u32,
+	creation_time: OffsetDateTime,
+}
+
+impl FileStore {
+	/// Open (or create) the store named `name` inside `dir`
+	///
+	/// Existing files are loaded, so a session reopened after a restart continues
+	/// with the sequence numbers and messages it had persisted.
+	pub fn open(dir: impl AsRef<Path>, name: impl Into<String>) -> Result<Self, StoreError> {
+		let dir = dir.as_ref().to_path_buf();
+		let name = name.into();
+		fs::create_dir_all(&dir)?;
+
+		let mut store = Self {
+			body: OpenOptions::new().read(true).append(true).create(true).open(dir.join(format!("{}.body", name)))?,
+			header: OpenOptions::new()
+				.read(true)
+				.append(true)
+				.create(true)
+				.open(dir.join(format!("{}.header", name)))?,
+			dir,
+			name,
+			body_len: 0,
+			header_len: 0,
+			index: BTreeMap::new(),
+			next_sender_msg_seq_num: 1,
+			next_target_msg_seq_num: 1,
+			creation_time: OffsetDateTime::now_utc(),
+		};
+		store.body_len = store.body.metadata()?.len();
+		store.load_index()?;
+		store.load_seqnums()?;
+		store.load_creation_time()?;
+		Ok(store)
+	}
+
+	/// Path of one of the store files, e.g. `path("body")`
+	fn path(&self, extension: &str) -> PathBuf {
+		self.dir.join(format!("{}.{}", self.name, extension))
+	}
+
+	fn load_index(&mut self) -> Result<(), StoreError> {
+		let mut content = String::new();
+		(&self.header).seek(SeekFrom::Start(0))?;
+		(&self.header).read_to_string(&mut content)?;
+
+		let mut valid_len = 0;
+		for line in content.split_inclusive('\n') {
+			// A line without terminator is a torn write from a crash, stop here
+			let Some(entry) = line.strip_suffix('\n') else { break };
+			let parts: Vec<&str> = entry.split(',').collect();
+			let [seq, offset, size] = parts.as_slice() else {
+				return Err(StoreError::Corrupt(format!("Invalid header entry: {}", entry)));
+			};
+			let seq: u32 = seq.parse().map_err(|_| StoreError::Corrupt(format!("Invalid header entry: {}", entry)))?;
+			let offset: u64 =
+				offset.parse().map_err(|_| StoreError::Corrupt(format!("Invalid header entry: {}", entry)))?;
+			let size: u64 =
+				size.parse().map_err(|_| StoreError::Corrupt(format!("Invalid header entry: {}", entry)))?;
+			// Past the end of the body, or garbage: a torn entry like the missing terminator
+			if offset.checked_add(size).is_none_or(|end| end > self.body_len) {
+				break;
+			}
+			self.index.insert(seq, (offset, size));
+			valid_len += line.len() as u64;
+		}
+
+		if valid_len != content.len() as u64 {
+			self.header.set_len(valid_len)?;
+			self.header.sync_all()?;
+		}
+		self.header_len = valid_len;
+		Ok(())
+	}
+
+	fn load_seqnums(&mut self) -> Result<(), StoreError> {
+		let path = self.path("seqnums");
+		if !path.exists() {
+			return self.write_seqnums();
+		}
+		let content = fs::read_to_string(&path)?;
+		let (sender, target) = content
+			.trim()
+			.split_once(':')
+			.ok_or_else(|| StoreError::Corrupt(format!("Invalid seqnums file: {}", content)))?;
+		self.next_sender_msg_seq_num =
+			sender.trim().parse().map_err(|_| StoreError::Corrupt(format!("Invalid sender seqnum: {}", sender)))?;
+		self.next_target_msg_seq_num =
+			target.trim().parse().map_err(|_| StoreError::Corrupt(format!("Invalid target seqnum: {}", target)))?;
+		Ok(())
+	}
+
+	fn load_creation_time(&mut self) -> Result<(), StoreError> {
+		let path = self.path("session");
+		if !path.exists() {
+			return self.write_creation_time();
+		}
+		let content = fs::read_to_string(&path)?;
+		self.creation_time = parse_fix_timestamp(content.trim()).map_err(StoreError::Corrupt)?;
+		Ok(())
+	}
+
+	fn write_seqnums(&self) -> Result<(), StoreError> {
+		let content = format!("{:010} : {:010}", self.next_sender_msg_seq_num, self.next_target_msg_seq_num);
+		self.replace_file("seqnums", content.as_bytes())
+	}
+
+	fn write_creation_time(&self) -> Result<(), StoreError> {
+		let mut content = String::with_capacity(21);
+		write_timestamp(&mut content, self.creation_time);
+		self.replace_file("session", content.as_bytes())
+	}
+
+	/// Atomically replace a store file: write a temporary file, sync it, rename it over the original
+	fn replace_file(&self, extension: &str, content: &[u8]) -> Result<(), StoreError> {
+		let tmp_path = self.path(&format!("{}.tmp", extension));
+		let mut tmp = File::create(&tmp_path)?;
+		tmp.write_all(content)?;
+		tmp.sync_all()?;
+		fs::rename(&tmp_path, self.path(extension))?;
+		// Make the rename itself durable
+		#[cfg(unix)]
+		File::open(&self.dir)?.sync_all()?;
+		Ok(())
+	}
+}
+
+impl MessageStore for FileStore {
+	fn set(&mut self, msg_seq_num: u32, message: &str) -> Result<(), StoreError> {
+		let offset = self.body_len;
+		let size = message.len() as u64;
+
+		// Body first: an index entry must never point at unwritten bytes
+		if let Err(e) = self.body.write_all(message.as_bytes()).and_then(|()| self.body.sync_data()) {
+			// Part of the message may have been appended: later offsets start after it
+			self.body_len = self.body.metadata()?.len();
+			return Err(e.into());
+		}
+		self.body_len += size;
+
+		// A failed append may have left part of an index line: drop it so this line starts on its own
+		if self.header.metadata()?.len() != self.header_len {
+			self.header.set_len(self.header_len)?;
+		}
+		let line = format!("{},{},{}\n", msg_seq_num, offset, size);
+		self.header.write_all(line.as_bytes())?;
+		self.header.sync_data()?;
+		self.header_len += line.len() as u64;
+
+		self.index.insert(msg_seq_num, (offset, size));
+		Ok(())
+	}
+
+	fn get(&self, begin: u32, end: u32) -> Result<Vec<(u32, String)>, StoreError> {
+		if begin > end {
+			return Ok(Vec::new());
+		}
+		let mut messages = Vec::new();
+		for (&seq, &(offset, size)) in self.index.range(begin..=end) {
+			let mut bytes = vec![0; size as usize];
+			(&self.body).seek(SeekFrom::Start(offset))?;
+			(&self.body).read_exact(&mut bytes)?;
+			let message = String::from_utf8(bytes)
+				.map_err(|_| StoreError::Corrupt(format!("Message {} is not valid UTF-8", seq)))?;
+			messages.push((seq, message));
+		}
+		Ok(messages)
+	}
+
+	fn next_sender_msg_seq_num(&self) -> u32 {
+		self.next_sender_msg_seq_num
+	}
+
+	fn next_target_msg_seq_num(&self) -> u32 {
+		self.next_target_msg_seq_num
+	}
+
+	fn set_next_sender_msg_seq_num(&mut self, seq_num: u32) -> Result<(), StoreError> {
+		self.next_sender_msg_seq_num = seq_num;
+		self.write_seqnums()
+	}
+
+	fn set_next_target_msg_seq_num(&mut self, seq_num: u32) -> Result<(), StoreError> {
+		self.next_target_msg_seq_num = seq_num;
+		self.write_seqnums()
+	}
+
+	fn creation_time(&self) -> OffsetDateTime {
+		self.creation_time
+	}
+
+	fn reset(&mut self, now: OffsetDateTime) -> Result<(), StoreError> {
+		self.body.set_len(0)?;
+		self.body.sync_all()?;
+		self.header.set_len(0)?;
+		self.header.sync_all()?;
+		self.body_len = 0;
+		self.header_len = 0;
+		self.index.clear();
+
+		self.next_sender_msg_seq_num = 1;
+		self.next_target_msg_seq_num = 1;
+		self.write_seqnums()?;
+
+		self.creation_time = now;
+		self.write_creation_time()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use time::macros::datetime;
+
+	/// A fresh, empty directory for a file store test
+	fn test_dir(test_name: &str) -> PathBuf {
+		let dir = std::env::temp_dir().join(format!("fix-learning-{}-{}", test_name, std::process::id()));
+		let _ = fs::remove_dir_all(&dir);
+		dir
+	}
+
+	#[test]
+	fn test_memory_store_sequence_numbers() {
+		let mut store = MemoryStore::new();
+		assert_eq!(store.next_sender_msg_seq_num(), 1);
+		assert_eq!(store.next_target_msg_seq_num(), 1);
+
+		store.incr_next_sender_msg_seq_num().unwrap();
+		store.incr_next_sender_msg_seq_num().unwrap();
+		store.set_next_target_msg_seq_num(10).unwrap();
+		assert_eq!(store.next_sender_msg_seq_num(), 3);
+		assert_eq!(store.next_target_msg_seq_num(), 10);
+	}
+
+	#[test]
+	fn test_memory_store_range_lookup() {
+		let mut store = MemoryStore::new();
+		for seq in [1, 2, 3, 5] {
+			store.set(seq, &format!("MSG{}", seq)).unwrap();
+		}
+
+		let range = store.get(2, 5).unwrap();
+		assert_eq!(range, vec![(2, "MSG2".to_string()), (3, "MSG3".to_string()), (5, "MSG5".to_string())]);
+		assert!(store.get(6, 10).unwrap().is_empty());
+		assert!(store.get(5, 2).unwrap().is_empty());
+	}
+
+	#[test]
+	fn test_memory_store_reset() {
+		let mut store = MemoryStore::new();
+		store.set(1, "MSG1").unwrap();
+		store.set_next_sender_msg_seq_num(2).unwrap();
+
+		store.reset(datetime!(2024-01-01 00:00:00 UTC)).unwrap();
+		assert!(store.get(1, 1).unwrap().is_empty());
+		assert_eq!(store.next_sender_msg_seq_num(), 1);
+		assert_eq!(store.creation_time(), datetime!(2024-01-01 00:00:00 UTC));
+	}
+
+	#[test]
+	fn test_file_store_persists_across_reopen() {
+		let dir = test_dir("store-reopen");
+		let message = "8=FIX.4.2\x019=5\x0135=0\x0110=161\x01";
+		{
+			let mut store = FileStore::open(&dir, "FIX.4.2-CLIENT-SERVER").unwrap();
+			store.set(1, message).unwrap();
+			store.set(2, "SECOND").unwrap();
+			store.set_next_sender_msg_seq_num(3).unwrap();
+			store.set_next_target_msg_seq_num(7).unwrap();
+		}
+
+		let store = FileStore::open(&dir, "FIX.4.2-CLIENT-SERVER").unwrap();
+		assert_eq!(store.next_sender_msg_seq_num(), 3);
+		assert_eq!(store.next_target_msg_seq_num(), 7);
+		assert_eq!(store.get(1, 2).unwrap(), vec![(1, message.to_string()), (2, "SECOND".to_string())]);
+		assert_eq!(fs::read_to_string(dir.join("FIX.4.2-CLIENT-SERVER.seqnums")).unwrap(), "0000000003 : 0000000007");
+
+		fs::remove_dir_all(&dir).unwrap();
+	}
+
+	#[test]
+	fn test_file_store_range_lookup() {
+		let dir = test_dir("store-range");
+		let mut store = FileStore::open(&dir, "S").unwrap();
+		for seq in 1..=10 {
+			store.set(seq, &format!("MSG{}", seq)).unwrap();
+		}
+
+		let range = store.get(4, 6).unwrap();
+		assert_eq!(range.iter().map(|(seq, _)| *seq).collect::<Vec<_>>(), vec![4, 5, 6]);
+		assert_eq!(range[1].1, "MSG5");
+		assert_eq!(store.get(9, 100).unwrap().len(), 2);
+
+		fs::remove_dir_all(&dir).unwrap();
+	}
+
+	#[test]
+	fn test_file_store_discards_torn_header_entry() {
+		let dir = test_dir("store-torn");
+		{
+			let mut store = FileStore::open(&dir, "S").unwrap();
+			store.set(1, "MSG1").unwrap();
+			store.set(2, "MSG2").unwrap();
+		}
+		// Simulate a crash in the middle of writing the index line for message 3
+		let mut header = OpenOptions::new().append(true).open(dir.join("S.header")).unwrap();
+		header.write_all(b"3,8,").unwrap();
+		drop(header);
+
+		let store = FileStore::open(&dir, "S").unwrap();
+		assert_eq!(store.get(1, 3).unwrap().len(), 2);
+
+		// An entry whose offset and size overflow is dropped the same way
+		drop(store);
+		let mut header = OpenOptions::new().append(true).open(dir.join("S.header")).unwrap();
+		header.write_all(format!("3,{},1\n", u64::MAX).as_bytes()).unwrap();
+		drop(header);
+		let mut store = FileStore::open(&dir, "S").unwrap();
+		assert_eq!(store.get(1, 3).unwrap().len(), 2);
+
+		// The store keeps working after recovery
+		store.set(3, "MSG3").unwrap();
+		let store = FileStore::open(&dir, "S").unwrap();
+		assert_eq!(store.get(3, 3).unwrap(), vec![(3, "MSG3".to_string())]);
+
+		fs::remove_dir_all(&dir).unwrap();
+	}
+
+	#[test]
+	fn test_file_store_drops_partial_header_entry_of_failed_append() {
+		let dir = test_dir("store-failed-append");
+		let mut store = FileStore::open(&dir, "S").unwrap();
+		store.set(1, "MSG1").unwrap();
+		// What a header write failing halfway through leaves behind
+		let mut header = OpenOptions::new().append(true).open(dir.join("S.header")).unwrap();
+		header.write_all(b"2,4,").unwrap();
+		drop(header);
+
+		store.set(2, "MSG2").unwrap();
+		assert_eq!(fs::read_to_string(dir.join("S.header")).unwrap(), "1,0,4\n2,4,4\n");
+		let store = FileStore::open(&dir, "S").unwrap();
+		assert_eq!(store.get(1, 2).unwrap().len(), 2);
+
+		fs::remove_dir_all(&dir).unwrap();
+	}
+
+	#[test]
+	fn test_file_store_reset() {
+		let dir = test_dir("store-reset");
+		let mut store = FileStore::open(&dir, "S").unwrap();
+		store.set(1, "MSG1").unwrap();
+		store.set_next_sender_msg_seq_num(2).unwrap();
+		store.reset(datetime!(2024-06-03 07:00:00 UTC)).unwrap();
+
+		let store = FileStore::open(&dir, "S").unwrap();
+		assert!(store.get(1, 1).unwrap().is_empty());
+		assert_eq!(store.next_sender_msg_seq_num(), 1);
+		assert_eq!(store.creation_time(), datetime!(2024-06-03 07:00:00 UTC));
+
+		fs::remove_dir_all(&dir).unwrap();
+	}
+}
@@ -0,0 +1,203 @@
+//! Per-symbol limit order book with price-time priority
+
+use crate::{OrdStatus, Side, common::QTY_EPSILON, session::SessionId};
+
+/// An order held by the simulator
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimOrder {
+	/// OrderID assigned by the simulator
+	pub order_id: String,
+	/// Session the order was received on
+	pub owner: SessionId,
+	/// ClOrdID the order currently goes by
+	pub cl_ord_id: String,
+	pub symbol: String,
+	pub side: Side,
+	/// Limit price, `None` for market orders
+	pub price: Option<f64>,
+	pub order_qty: f64,
+	pub cum_qty: f64,
+	/// Sum of fill quantity times fill price, for AvgPx
+	pub cum_notional: f64,
+}
+
+impl SimOrder {
+	pub fn leaves_qty(&self) -> f64 {
+		(self.order_qty - self.cum_qty).max(0.0)
+	}
+
+	pub fn avg_px(&self) -> f64 {
+		if self.cum_qty > 0.0 { self.cum_notional / self.cum_qty } else { 0.0 }
+	}
+
+	pub fn is_filled(&self) -> bool {
+		self.leaves_qty() <= QTY_EPSILON
+	}
+
+	/// Status of a live order from its fills
+	pub fn ord_status(&self) -> OrdStatus {
+		if self.is_filled() {
+			OrdStatus::Filled
+		} else if self.cum_qty > 0.0 {
+			OrdStatus::PartiallyFilled
+		} else {
+			OrdStatus::New
+		}
+	}
+
+	fn fill(&mut self, qty: f64, price: f64) {
+		self.cum_qty += qty;
+		self.cum_notional += qty * price;
+	}
+
+	/// Whether this order may trade against a resting order at `price`
+	fn crosses(&self, price: f64) -> bool {
+		match (self.price, &self.side) {
+			(None, _) => true,
+			(Some(limit), Side::Buy) => limit >= price,
+			(Some(limit), Side::Sell) => limit <= price,
+		}
+	}
+}
+
+/// A trade between an incoming order and a resting one
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fill {
+	/// The resting order after the trade
+	pub resting: SimOrder,
+	pub qty: f64,
+	/// Trade price, the price of the resting order
+	pub price: f64,
+}
+
+/// Resting limit orders of one symbol, best price first and oldest first within a price
+#[derive(Debug, Clone, Default)]
+pub struct MatchingBook {
+	bids: Vec<SimOrder>,
+	offers: Vec<SimOrder>,
+}
+
+impl MatchingBook {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Resting orders of `side`, in priority order
+	pub fn orders(&self, side: &Side) -> &[SimOrder] {
+		match side {
+			Side::Buy => &self.bids,
+			Side::Sell => &self.offers,
+		}
+	}
+
+	pub fn best_bid(&self) -> Option<f64> {
+		self.bids.first().and_then(|order| order.price)
+	}
+
+	pub fn best_offer(&self) -> Option<f64> {
+		self.offers.first().and_then(|order| order.price)
+	}
+
+	pub fn get(&self, order_id: &str) -> Option<&SimOrder> {
+		self.bids.iter().chain(&self.offers).find(|order| order.order_id == order_id)
+	}
+
+	pub(crate) fn get_mut(&mut self, order_id: &str) -> Option<&mut SimOrder> {
+		self.bids.iter_mut().chain(&mut self.offers).find(|order| order.order_id == order_id)
+	}
+
+	/// Trade `order` against the opposite side for as long as prices cross
+	///
+	/// Resting orders that are filled leave the book; `order` itself is not added.
+	pub fn match_order(&mut self, order: &mut SimOrder) -> Vec<Fill> {
+		let opposite = match order.side {
+			Side::Buy => &mut self.offers,
+			Side::Sell => &mut self.bids,
+		};
+		let mut fills = Vec::new();
+		while !order.is_filled() {
+			let Some(resting) = opposite.first_mut() else { break };
+			let price = resting.price.expect("resting orders have a limit price");
+			if !order.crosses(price) {
+				break;
+			}
+			let qty = order.leaves_qty().min(resting.leaves_qty());
+			order.fill(qty, price);
+			resting.fill(qty, price);
+			fills.push(Fill { resting: resting.clone(), qty, price });
+			if resting.is_filled() {
+				opposite.remove(0);
+			}
+		}
+		fills
+	}
+
+	/// Rest a limit order behind the orders at the same or a better price
+	pub fn insert(&mut self, order: SimOrder) {
+		let price = order.price.expect("only limit orders rest in the book");
+		let (orders, behind): (_, fn(f64, f64) -> bool) = match order.side {
+			Side::Buy => (&mut self.bids, |resting, price| resting >= price),
+			Side::Sell => (&mut self.offers, |resting, price| resting <= price),
+		};
+		let position = orders.iter().take_while(|resting| behind(resting.price.unwrap_or_default(), price)).count();
+		orders.insert(position, order);
+	}
+
+	pub fn remove(&mut self, order_id: &str) -> Option<SimOrder> {
+		for orders in [&mut self.bids, &mut self.offers] {
+			if let Some(position) = orders.iter().position(|order| order.order_id == order_id) {
+				return Some(orders.remove(position));
+			}
+		}
+		None
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn order(order_id: &str, side: Side, price: Option<f64>, qty: f64) -> SimOrder {
+		SimOrder {
+			order_id: order_id.into(),
+			owner: SessionId::new("SIM", "CLIENT"),
+			cl_ord_id: order_id.into(),
+			symbol: "AAPL".into(),
+			side,
+			price,
+			order_qty: qty,
+			cum_qty: 0.0,
+			cum_notional: 0.0,
+		}
+	}
+
+	#[test]
+	fn test_price_time_priority() {
+		let mut book = MatchingBook::new();
+		book.insert(order("S1", Side::Sell, Some(101.0), 100.0));
+		book.insert(order("S2", Side::Sell, Some(100.0), 100.0));
+		book.insert(order("S3", Side::Sell, Some(100.0), 100.0));
+		book.insert(order("B1", Side::Buy, Some(99.0), 100.0));
+		assert_eq!((book.best_bid(), book.best_offer()), (Some(99.0), Some(100.0)));
+		let ids: Vec<&str> = book.orders(&Side::Sell).iter().map(|order| order.order_id.as_str()).collect();
+		assert_eq!(ids, ["S2", "S3", "S1"]);
+
+		let mut buy = order("B2", Side::Buy, Some(100.5), 150.0);
+		let fills = book.match_order(&mut buy);
+		assert_eq!(fills.len(), 2);
+		assert_eq!((fills[0].resting.order_id.as_str(), fills[0].qty), ("S2", 100.0));
+		assert_eq!((fills[1].resting.order_id.as_str(), fills[1].qty), ("S3", 50.0));
+		assert!(buy.is_filled());
+		assert_eq!(book.get("S3").unwrap().leaves_qty(), 50.0);
+		assert!(book.get("S2").is_none());
+
+		let mut market = order("B3", Side::Buy, None, 200.0);
+		let fills = book.match_order(&mut market);
+		assert_eq!(fills.iter().map(|fill| fill.price).collect::<Vec<_>>(), [100.0, 101.0]);
+		assert_eq!(market.avg_px(), (50.0 * 100.0 + 100.0 * 101.0) / 150.0);
+		assert_eq!(market.ord_status(), OrdStatus::PartiallyFilled);
+		assert_eq!(book.best_offer(), None);
+		assert!(book.remove("B1").is_some());
+		assert!(book.remove("B1").is_none());
+	}
+}
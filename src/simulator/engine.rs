@@ -0,0 +1,413 @@
+//! Order handling of the exchange simulator
+//!
+//! [`Exchange`] takes the orders, cancels and cancel/replaces received on any
+//! session, matches them in the [`MatchingBook`] of their symbol and returns the
+//! Execution Reports and Order Cancel Rejects to send, each addressed to the
+//! session owning the order. It does no I/O and takes the time as an argument.
+
+use crate::{
+	CxlRejReason, CxlRejResponseTo, FixMessage, MsgType, OrdStatus,
+	common::enums::ExecType,
+	messages::{
+		ExecutionReportBody, FixMessageBody, NewOrderSingleBody, OrderCancelRejectBody, OrderCancelReplaceRequestBody,
+		OrderCancelRequestBody,
+	},
+	session::SessionId,
+	simulator::{Fill, MatchingBook, SimOrder},
+};
+use std::collections::{HashMap, HashSet};
+use time::OffsetDateTime;
+
+/// OrdRejReason (Tag 103) of orders refused for their content
+pub const REJECT_BROKER_OPTION: u32 = 0;
+/// OrdRejReason (Tag 103) of orders reusing a ClOrdID
+pub const REJECT_DUPLICATE_ORDER: u32 = 6;
+
+/// A message for the session owning the order it reports on
+pub type Outbound = (SessionId, FixMessage);
+
+/// Matching engine holding one [`MatchingBook`] per symbol
+#[derive(Debug, Default)]
+pub struct Exchange {
+	books: HashMap<String, MatchingBook>,
+	/// Symbol and OrderID of the live orders by owning session and ClOrdID
+	live: HashMap<(SessionId, String), (String, String)>,
+	/// Every ClOrdID received, by session
+	cl_ord_ids: HashSet<(SessionId, String)>,
+	next_order_id: u64,
+	next_exec_id: u64,
+}
+
+impl Exchange {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// The book of `symbol`, if it ever received an order
+	pub fn book(&self, symbol: &str) -> Option<&MatchingBook> {
+		self.books.get(symbol)
+	}
+
+	/// Handle an application message received on `session_id`; unsupported messages are ignored
+	pub fn handle(&mut self, session_id: &SessionId, message: &FixMessage, now: OffsetDateTime) -> Vec<Outbound> {
+		match &message.body {
+			FixMessageBody::NewOrderSingle(body) => self.new_order(session_id, body, now),
+			FixMessageBody::OrderCancelRequest(body) => self.cancel(session_id, body, now),
+			FixMessageBody::OrderCancelReplaceRequest(body) => self.replace(session_id, body, now),
+			_ => Vec::new(),
+		}
+	}
+
+	pub fn new_order(
+		&mut self,
+		session_id: &SessionId,
+		body: &NewOrderSingleBody,
+		now: OffsetDateTime,
+	) -> Vec<Outbound> {
+		let reject = if !self.cl_ord_ids.insert((session_id.clone(), body.cl_ord_id.clone())) {
+			Some(REJECT_DUPLICATE_ORDER)
+		} else if body.symbol.is_empty() || !body.order_qty.is_some_and(|qty| qty > 0.0) {
+			Some(REJECT_BROKER_OPTION)
+		} else {
+			match body.ord_type.as_str() {
+				"1" => None,
+				"2" if body.price.is_some_and(|price| price > 0.0) => None,
+				_ => Some(REJECT_BROKER_OPTION),
+			}
+		};
+		if let Some(reason) = reject {
+			let report = ExecutionReportBody {
+				exec_id: self.exec_id(),
+				exec_type: ExecType::Rejected,
+				ord_status: OrdStatus::Rejected,
+				symbol: body.symbol.clone(),
+				side: body.side.clone(),
+				transact_time: Some(now),
+				cl_ord_id: Some(body.cl_ord_id.clone()),
+				ord_rej_reason: Some(reason),
+				..ExecutionReportBody::new("NONE", "")
+			};
+			return vec![message(session_id, FixMessageBody::ExecutionReport(report))];
+		}
+
+		self.next_order_id += 1;
+		let mut order = SimOrder {
+			order_id: format!("O{}", self.next_order_id),
+			owner: session_id.clone(),
+			cl_ord_id: body.cl_ord_id.clone(),
+			symbol: body.symbol.clone(),
+			side: body.side.clone(),
+			price: if body.ord_type == "1" { None } else { body.price },
+			order_qty: body.order_qty.unwrap_or_default(),
+			cum_qty: 0.0,
+			cum_notional: 0.0,
+		};
+		let mut outbound = vec![self.report(&order, ExecType::New, OrdStatus::New, None, now)];
+		self.execute(&mut order, &mut outbound, now);
+		outbound
+	}
+
+	pub fn cancel(
+		&mut self,
+		session_id: &SessionId,
+		body: &OrderCancelRequestBody,
+		now: OffsetDateTime,
+	) -> Vec<Outbound> {
+		let response_to = CxlRejResponseTo::OrderCancelRequest;
+		if !self.cl_ord_ids.insert((session_id.clone(), body.cl_ord_id.clone())) {
+			return vec![self.cancel_reject(session_id, &body.cl_ord_id, &body.orig_cl_ord_id, response_to)];
+		}
+		let Some(mut order) = self.take_live(session_id, &body.orig_cl_ord_id) else {
+			return vec![self.cancel_reject(session_id, &body.cl_ord_id, &body.orig_cl_ord_id, response_to)];
+		};
+		order.cl_ord_id = body.cl_ord_id.clone();
+		let mut report = self.report(&order, ExecType::Canceled, OrdStatus::Canceled, None, now);
+		set_orig_cl_ord_id(&mut report, &body.orig_cl_ord_id);
+		vec![report]
+	}
+
+	pub fn replace(
+		&mut self,
+		session_id: &SessionId,
+		body: &OrderCancelReplaceRequestBody,
+		now: OffsetDateTime,
+	) -> Vec<Outbound> {
+		let response_to = CxlRejResponseTo::OrderCancelReplaceRequest;
+		let refused = |exchange: &Self| {
+			vec![exchange.cancel_reject(session_id, &body.cl_ord_id, &body.orig_cl_ord_id, response_to)]
+		};
+		let key = (session_id.clone(), body.orig_cl_ord_id.clone());
+		let Some((symbol, order_id)) = self.live.get(&key).cloned() else { return refused(self) };
+		let order = self.books[&symbol].get(&order_id).expect("live order");
+		let order_qty = body.order_qty.unwrap_or(order.order_qty);
+		let price = order.price.map(|price| body.price.unwrap_or(price));
+		if order_qty <= order.cum_qty || price.is_some_and(|price| price <= 0.0) {
+			return refused(self);
+		}
+		if !self.cl_ord_ids.insert((session_id.clone(), body.cl_ord_id.clone())) {
+			return refused(self);
+		}
+
+		// The order keeps its time priority only if the price is unchanged and the quantity not increased
+		let book = self.books.get_mut(&symbol).expect("live order book");
+		let order = book.get_mut(&order_id).expect("live order");
+		let keeps_priority = price == order.price && order_qty <= order.order_qty;
+		order.cl_ord_id = body.cl_ord_id.clone();
+		order.order_qty = order_qty;
+		order.price = price;
+		let mut order = order.clone();
+		self.live.remove(&key);
+		let mut report = self.report(&order, ExecType::Replaced, OrdStatus::Replaced, None, now);
+		set_orig_cl_ord_id(&mut report, &body.orig_cl_ord_id);
+		let mut outbound = vec![report];
+		if keeps_priority {
+			self.live.insert((session_id.clone(), order.cl_ord_id), (symbol, order_id));
+		} else {
+			self.books.get_mut(&symbol).expect("live order book").remove(&order_id);
+			self.execute(&mut order, &mut outbound, now);
+		}
+		outbound
+	}
+
+	/// Match `order` and rest what is left of a limit order; a market order's remainder is canceled
+	fn execute(&mut self, order: &mut SimOrder, outbound: &mut Vec<Outbound>, now: OffsetDateTime) {
+		let book = self.books.entry(order.symbol.clone()).or_default();
+		let fills = book.match_order(order);
+		let mut filled = order.clone();
+		filled.cum_qty = order.cum_qty - fills.iter().map(|fill| fill.qty).sum::<f64>();
+		filled.cum_notional = order.cum_notional - fills.iter().map(|fill| fill.qty * fill.price).sum::<f64>();
+		for Fill { resting, qty, price } in fills {
+			if resting.is_filled() {
+				self.live.remove(&(resting.owner.clone(), resting.cl_ord_id.clone()));
+			}
+			outbound.push(self.fill_report(&resting, qty, price, now));
+			filled.cum_qty += qty;
+			filled.cum_notional += qty * price;
+			outbound.push(self.fill_report(&filled, qty, price, now));
+		}
+
+		if order.is_filled() {
+			return;
+		}
+		if order.price.is_none() {
+			outbound.push(self.report(order, ExecType::Canceled, OrdStatus::Canceled, None, now));
+			return;
+		}
+		self.live
+			.insert((order.owner.clone(), order.cl_ord_id.clone()), (order.symbol.clone(), order.order_id.clone()));
+		self.books.get_mut(&order.symbol).expect("order book").insert(order.clone());
+	}
+
+	/// The live order `session_id` knows as `cl_ord_id`
+	fn live_order(&self, session_id: &SessionId, cl_ord_id: &str) -> Option<&SimOrder> {
+		let (symbol, order_id) = self.live.get(&(session_id.clone(), cl_ord_id.to_string()))?;
+		self.books.get(symbol)?.get(order_id)
+	}
+
+	/// Remove a live order from its book
+	fn take_live(&mut self, session_id: &SessionId, orig_cl_ord_id: &str) -> Option<SimOrder> {
+		let (symbol, order_id) = self.live.remove(&(session_id.clone(), orig_cl_ord_id.to_string()))?;
+		self.books.get_mut(&symbol)?.remove(&order_id)
+	}
+
+	/// Refuse a cancel or cancel/replace of `orig_cl_ord_id`
+	///
+	/// A live order is reported with its OrderID and status, refused at the
+	/// broker's option; an order that is not live is unknown.
+	fn cancel_reject(
+		&self,
+		session_id: &SessionId,
+		cl_ord_id: &str,
+		orig_cl_ord_id: &str,
+		response_to: CxlRejResponseTo,
+	) -> Outbound {
+		let (order_id, ord_status, reason) = match self.live_order(session_id, orig_cl_ord_id) {
+			Some(order) => (order.order_id.clone(), order.ord_status(), CxlRejReason::BrokerOption),
+			None => ("NONE".to_string(), OrdStatus::Rejected, CxlRejReason::UnknownOrder),
+		};
+		let mut reject = OrderCancelRejectBody::new(order_id, cl_ord_id, orig_cl_ord_id, ord_status, response_to);
+		reject.cxl_rej_reason = Some(reason);
+		message(session_id, FixMessageBody::OrderCancelReject(reject))
+	}
+
+	fn fill_report(&mut self, order: &SimOrder, qty: f64, price: f64, now: OffsetDateTime) -> Outbound {
+		let exec_type = if order.is_filled() { ExecType::Fill } else { ExecType::PartialFill };
+		self.report(order, exec_type, order.ord_status(), Some((qty, price)), now)
+	}
+
+	fn report(
+		&mut self,
+		order: &SimOrder,
+		exec_type: ExecType,
+		ord_status: OrdStatus,
+		last: Option<(f64, f64)>,
+		now: OffsetDateTime,
+	) -> Outbound {
+		let done = matches!(ord_status, OrdStatus::Canceled | OrdStatus::Filled);
+		let report = ExecutionReportBody {
+			exec_id: self.exec_id(),
+			exec_type,
+			ord_status,
+			symbol: order.symbol.clone(),
+			side: order.side.clone(),
+			leaves_qty: if done { 0.0 } else { order.leaves_qty() },
+			cum_qty: order.cum_qty,
+			avg_px: order.avg_px(),
+			last_shares: last.map(|(qty, _)| qty),
+			last_px: last.map(|(_, price)| price),
+			transact_time: Some(now),
+			cl_ord_id: Some(order.cl_ord_id.clone()),
+			..ExecutionReportBody::new(order.order_id.clone(), "")
+		};
+		message(&order.owner, FixMessageBody::ExecutionReport(report))
+	}
+
+	fn exec_id(&mut self) -> String {
+		self.next_exec_id += 1;
+		format!("E{}", self.next_exec_id)
+	}
+}
+
+fn set_orig_cl_ord_id(outbound: &mut Outbound, orig_cl_ord_id: &str) {
+	if let FixMessageBody::ExecutionReport(report) = &mut outbound.1.body {
+		report.orig_cl_ord_id = Some(orig_cl_ord_id.to_string());
+	}
+}
+
+/// Wrap `body` in a message from the simulator to the counterparty of `session_id`
+fn message(session_id: &SessionId, body: FixMessageBody) -> Outbound {
+	let msg_type = match body {
+		FixMessageBody::OrderCancelReject(_) => MsgType::OrderCancelReject,
+		_ => MsgType::ExecutionReport,
+	};
+	let header = FixMessage::builder(msg_type, &session_id.sender_comp_id, &session_id.target_comp_id, 0).build();
+	(session_id.clone(), FixMessage { body, ..header })
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Side, test_support::order_body};
+
+	fn session(target: &str) -> SessionId {
+		SessionId::new("SIM", target)
+	}
+
+	fn reports(outbound: &[Outbound]) -> Vec<(&str, ExecType, OrdStatus, f64, f64)> {
+		outbound
+			.iter()
+			.map(|(id, message)| {
+				let FixMessageBody::ExecutionReport(report) = &message.body else { panic!("not a report") };
+				let (exec_type, ord_status) = (report.exec_type.clone(), report.ord_status.clone());
+				(id.target_comp_id.as_str(), exec_type, ord_status, report.cum_qty, report.leaves_qty)
+			})
+			.collect()
+	}
+
+	#[test]
+	fn test_execution_report_sequences() {
+		let (alice, bob) = (session("ALICE"), session("BOB"));
+		let now = OffsetDateTime::UNIX_EPOCH;
+		let mut exchange = Exchange::new();
+
+		let out = exchange.new_order(&alice, &order_body("A1", Side::Sell, 100.0, Some(10.0)), now);
+		assert_eq!(reports(&out), [("ALICE", ExecType::New, OrdStatus::New, 0.0, 100.0)]);
+
+		let out = exchange.new_order(&bob, &order_body("B1", Side::Buy, 60.0, Some(10.5)), now);
+		assert_eq!(
+			reports(&out),
+			[
+				("BOB", ExecType::New, OrdStatus::New, 0.0, 60.0),
+				("ALICE", ExecType::PartialFill, OrdStatus::PartiallyFilled, 60.0, 40.0),
+				("BOB", ExecType::Fill, OrdStatus::Filled, 60.0, 0.0),
+			]
+		);
+		let FixMessageBody::ExecutionReport(fill) = &out[2].1.body else { unreachable!() };
+		assert_eq!((fill.last_shares, fill.last_px, fill.avg_px), (Some(60.0), Some(10.0), 10.0));
+
+		let out = exchange.new_order(&bob, &order_body("B2", Side::Buy, 50.0, None), now);
+		assert_eq!(
+			reports(&out),
+			[
+				("BOB", ExecType::New, OrdStatus::New, 0.0, 50.0),
+				("ALICE", ExecType::Fill, OrdStatus::Filled, 100.0, 0.0),
+				("BOB", ExecType::PartialFill, OrdStatus::PartiallyFilled, 40.0, 10.0),
+				("BOB", ExecType::Canceled, OrdStatus::Canceled, 40.0, 0.0),
+			]
+		);
+		assert!(exchange.book("AAPL").unwrap().orders(&Side::Sell).is_empty());
+
+		let out = exchange.new_order(&bob, &order_body("B2", Side::Buy, 50.0, Some(9.0)), now);
+		assert_eq!(reports(&out), [("BOB", ExecType::Rejected, OrdStatus::Rejected, 0.0, 0.0)]);
+		let out = exchange.new_order(&bob, &order_body("B3", Side::Buy, 0.0, Some(9.0)), now);
+		assert_eq!(reports(&out)[0].1, ExecType::Rejected);
+	}
+
+	#[test]
+	fn test_cancel_and_replace() {
+		let alice = session("ALICE");
+		let now = OffsetDateTime::UNIX_EPOCH;
+		let mut exchange = Exchange::new();
+		exchange.new_order(&alice, &order_body("A1", Side::Buy, 100.0, Some(10.0)), now);
+		exchange.new_order(&alice, &order_body("A2", Side::Buy, 100.0, Some(10.0)), now);
+
+		let replace = |orig: &str, cl_ord_id: &str, qty: f64, price: f64| OrderCancelReplaceRequestBody {
+			orig_cl_ord_id: orig.into(),
+			cl_ord_id: cl_ord_id.into(),
+			order_qty: Some(qty),
+			price: Some(price),
+			..Default::default()
+		};
+		let out = exchange.replace(&alice, &replace("A1", "A1R", 80.0, 10.0), now);
+		assert_eq!(reports(&out), [("ALICE", ExecType::Replaced, OrdStatus::Replaced, 0.0, 80.0)]);
+		let bids = exchange.book("AAPL").unwrap().orders(&Side::Buy);
+		assert_eq!((bids[0].cl_ord_id.as_str(), bids[0].order_qty), ("A1R", 80.0));
+
+		exchange.replace(&alice, &replace("A1R", "A1R2", 80.0, 9.5), now);
+		let bids = exchange.book("AAPL").unwrap().orders(&Side::Buy);
+		assert_eq!(bids.iter().map(|order| order.cl_ord_id.as_str()).collect::<Vec<_>>(), ["A2", "A1R2"]);
+
+		let cancel =
+			OrderCancelRequestBody { orig_cl_ord_id: "A2".into(), cl_ord_id: "A2C".into(), ..Default::default() };
+		let out = exchange.cancel(&alice, &cancel, now);
+		assert_eq!(reports(&out), [("ALICE", ExecType::Canceled, OrdStatus::Canceled, 0.0, 0.0)]);
+		let FixMessageBody::ExecutionReport(canceled) = &out[0].1.body else { unreachable!() };
+		assert_eq!(canceled.orig_cl_ord_id.as_deref(), Some("A2"));
+
+		let out = exchange.cancel(&alice, &OrderCancelRequestBody { cl_ord_id: "A2C2".into(), ..cancel }, now);
+		let FixMessageBody::OrderCancelReject(reject) = &out[0].1.body else { panic!("expected a cancel reject") };
+		assert_eq!(reject.cxl_rej_reason, Some(CxlRejReason::UnknownOrder));
+		assert_eq!(out[0].1.header.msg_type, MsgType::OrderCancelReject);
+	}
+
+	#[test]
+	fn test_refusals_of_live_orders() {
+		let (alice, bob) = (session("ALICE"), session("BOB"));
+		let now = OffsetDateTime::UNIX_EPOCH;
+		let mut exchange = Exchange::new();
+		exchange.new_order(&alice, &order_body("A1", Side::Sell, 100.0, Some(10.0)), now);
+		exchange.new_order(&bob, &order_body("B1", Side::Buy, 40.0, Some(10.0)), now);
+
+		let cancel_reject = |out: &[Outbound]| {
+			let FixMessageBody::OrderCancelReject(reject) = &out[0].1.body else { panic!("expected a cancel reject") };
+			(reject.order_id.clone(), reject.ord_status.clone(), reject.cxl_rej_reason.clone())
+		};
+		let live = ("O1".to_string(), OrdStatus::PartiallyFilled, Some(CxlRejReason::BrokerOption));
+
+		// Below the filled quantity
+		let replace = OrderCancelReplaceRequestBody {
+			orig_cl_ord_id: "A1".into(),
+			cl_ord_id: "A1R".into(),
+			order_qty: Some(30.0),
+			..Default::default()
+		};
+		assert_eq!(cancel_reject(&exchange.replace(&alice, &replace, now)), live);
+		// Reusing a ClOrdID
+		let cancel =
+			OrderCancelRequestBody { orig_cl_ord_id: "A1".into(), cl_ord_id: "A1".into(), ..Default::default() };
+		assert_eq!(cancel_reject(&exchange.cancel(&alice, &cancel, now)), live);
+		let replace = OrderCancelReplaceRequestBody { cl_ord_id: "A1".into(), order_qty: Some(80.0), ..replace };
+		assert_eq!(cancel_reject(&exchange.replace(&alice, &replace, now)), live);
+		assert_eq!(exchange.book("AAPL").unwrap().orders(&Side::Sell)[0].order_qty, 100.0);
+	}
+}
@@ -0,0 +1,17 @@
+//! Exchange simulator
+//!
+//! A venue for testing order flow end to end with no external counterparty.
+//! [`Exchange`] matches the orders received on any session in a per-symbol
+//! [`MatchingBook`] with price-time priority and produces the Execution Reports
+//! for them; [`Simulator`] serves it to FIX sessions over the blocking
+//! [`Acceptor`](crate::Acceptor). The `fix-simulator` binary runs one from a
+//! session settings file.
+
+pub mod book;
+pub mod engine;
+pub mod server;
+
+// Re-export commonly used types
+pub use book::{Fill, MatchingBook, SimOrder};
+pub use engine::{Exchange, Outbound};
+pub use server::Simulator;
@@ -0,0 +1,133 @@
+//! Serving the exchange simulator to FIX sessions
+
+use crate::{
+	session::{MessageStore, SessionId},
+	simulator::Exchange,
+	transport::{Acceptor, Connection, SharedSession, TransportError, connection::lock},
+};
+use std::{
+	collections::HashMap,
+	io,
+	net::SocketAddr,
+	sync::{Arc, Mutex, PoisonError},
+	thread::{self, JoinHandle},
+};
+use time::OffsetDateTime;
+
+/// Serves one [`Exchange`] to every session of an [`Acceptor`]
+///
+/// Each connection is served on its own thread. Reports for an order owned by
+/// another session are queued on that session and written by its connection on
+/// its next timer tick; they are dropped if that session is not logged on.
+#[derive(Debug)]
+pub struct Simulator<S: MessageStore> {
+	acceptor: Acceptor<S>,
+	sessions: Arc<HashMap<SessionId, SharedSession<S>>>,
+	exchange: Arc<Mutex<Exchange>>,
+}
+
+impl<S: MessageStore + Send + 'static> Simulator<S> {
+	/// Serve the sessions registered with `acceptor`
+	pub fn new(acceptor: Acceptor<S>) -> Self {
+		let sessions = acceptor.sessions().map(|(id, session)| (id.clone(), Arc::clone(session))).collect();
+		Self { acceptor, sessions: Arc::new(sessions), exchange: Arc::new(Mutex::new(Exchange::new())) }
+	}
+
+	/// Address the simulator listens on
+	pub fn local_addr(&self) -> io::Result<SocketAddr> {
+		self.acceptor.local_addr()
+	}
+
+	/// The exchange shared by all connections
+	pub fn exchange(&self) -> Arc<Mutex<Exchange>> {
+		Arc::clone(&self.exchange)
+	}
+
+	/// Wait for the next connection and serve it on a new thread until it closes
+	///
+	/// # Errors
+	/// Fails like [`Acceptor::accept`] if the connection does not log on.
+	pub fn accept(&self) -> Result<JoinHandle<()>, TransportError> {
+		let connection = self.acceptor.accept()?;
+		let sessions = Arc::clone(&self.sessions);
+		let exchange = Arc::clone(&self.exchange);
+		Ok(thread::spawn(move || serve(connection, &sessions, &exchange)))
+	}
+}
+
+fn serve<S: MessageStore>(
+	mut connection: Connection<S>,
+	sessions: &HashMap<SessionId, SharedSession<S>>,
+	exchange: &Mutex<Exchange>,
+) {
+	let session_id = lock(connection.session()).session_id().clone();
+	loop {
+		let message = match connection.recv() {
+			Ok(Some(message)) => message,
+			Ok(None) => return,
+			// Frame errors and invalid messages leave the connection open
+			Err(_) if connection.is_open() => continue,
+			Err(_) => return,
+		};
+		let now = OffsetDateTime::now_utc();
+		let outbound = exchange.lock().unwrap_or_else(PoisonError::into_inner).handle(&session_id, &message, now);
+		for (target, report) in outbound {
+			// A failed send means the target is no longer logged on, the report is dropped
+			if target == session_id {
+				let _ = connection.send(report);
+			} else if let Some(session) = sessions.get(&target) {
+				let _ = lock(session).send(report, now);
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{FixMessage, FixMessageBody, Initiator, MemoryStore, MsgType, OrdStatus, Session, Side};
+
+	#[test]
+	fn test_orders_over_a_session() {
+		let mut acceptor = Acceptor::bind("127.0.0.1:0").unwrap();
+		acceptor.add_session(Session::new(SessionId::new("SIM", "CLIENT"), MemoryStore::new()));
+		let simulator = Simulator::new(acceptor);
+		let address = simulator.local_addr().unwrap().to_string();
+		let server = thread::spawn(move || simulator.accept().map(|_| ()));
+
+		let session = Session::new(SessionId::new("CLIENT", "SIM"), MemoryStore::new());
+		let mut initiator = Initiator::new(address, session).with_max_attempts(1);
+		initiator.connect().unwrap();
+		server.join().unwrap().unwrap();
+
+		for (cl_ord_id, side) in [("S1", Side::Sell), ("B1", Side::Buy)] {
+			let order = FixMessage::builder(MsgType::NewOrderSingle, "CLIENT", "SIM", 0)
+				.cl_ord_id(cl_ord_id)
+				.handl_inst("1")
+				.symbol("AAPL")
+				.side(side)
+				.order_qty(100.0)
+				.ord_type("2")
+				.price(150.0)
+				.build();
+			initiator.send(order).unwrap();
+		}
+
+		let mut statuses = Vec::new();
+		while statuses.len() < 4 {
+			let message = initiator.recv().unwrap().unwrap();
+			let FixMessageBody::ExecutionReport(report) = message.body else { panic!("expected a report") };
+			statuses.push((report.cl_ord_id.unwrap(), report.ord_status));
+		}
+		assert_eq!(
+			statuses,
+			[
+				("S1".to_string(), OrdStatus::New),
+				("B1".to_string(), OrdStatus::New),
+				("S1".to_string(), OrdStatus::Filled),
+				("B1".to_string(), OrdStatus::Filled),
+			]
+		);
+		initiator.logout(None).unwrap();
+	}
+}
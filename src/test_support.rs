@@ -0,0 +1,65 @@
+//! Fixtures shared by the unit tests
+
+use crate::{FixMessage, FixMessageBuilder, MsgType, Side, messages::NewOrderSingleBody};
+use time::macros::datetime;
+
+/// Builder of a limit order from CLIENT to SERVER: ORD1 buying 100 AAPL at 10.5
+pub(crate) fn order_builder(msg_seq_num: u32) -> FixMessageBuilder {
+	FixMessage::builder(MsgType::NewOrderSingle, "CLIENT", "SERVER", msg_seq_num)
+		.sending_time(datetime!(2024-01-02 03:04:05.006 UTC))
+		.transact_time(datetime!(2024-01-02 03:04:05 UTC))
+		.cl_ord_id("ORD1")
+		.handl_inst("1")
+		.symbol("AAPL")
+		.side(Side::Buy)
+		.order_qty(100.0)
+		.ord_type("2")
+		.price(10.5)
+}
+
+/// The order of [`order_builder`] as MsgSeqNum 7
+pub(crate) fn order() -> FixMessage {
+	order_builder(7).build()
+}
+
+/// Cancel of that order, as ORD2 with a Text
+#[cfg(any(feature = "fixml", feature = "sbe"))]
+pub(crate) fn cancel() -> FixMessage {
+	FixMessage::builder(MsgType::OrderCancelRequest, "CLIENT", "SERVER", 8)
+		.sending_time(datetime!(2024-01-02 03:04:05 UTC))
+		.cancel_transact_time(datetime!(2024-01-02 03:04:05 UTC))
+		.orig_cl_ord_id("ORD1")
+		.cancel_cl_ord_id("ORD2")
+		.cancel_symbol("AAPL")
+		.cancel_side(Side::Buy)
+		.cancel_order_qty(100.0)
+		.cancel_text("Too late")
+		.build()
+}
+
+/// New Order Single body for AAPL, a market order when there is no price
+pub(crate) fn order_body(cl_ord_id: &str, side: Side, qty: f64, price: Option<f64>) -> NewOrderSingleBody {
+	NewOrderSingleBody {
+		cl_ord_id: cl_ord_id.into(),
+		handl_inst: "1".into(),
+		symbol: "AAPL".into(),
+		side,
+		order_qty: Some(qty),
+		ord_type: if price.is_some() { "2" } else { "1" }.into(),
+		price,
+		..Default::default()
+	}
+}
+
+/// Assert that `message` comes back from an encoding unchanged, down to its wire format
+///
+/// `codec` encodes the message and decodes the result.
+#[cfg(any(feature = "json", feature = "fixml", feature = "sbe"))]
+pub(crate) fn assert_round_trip<E: std::fmt::Debug>(
+	message: &FixMessage,
+	codec: impl FnOnce(&FixMessage) -> Result<FixMessage, E>,
+) {
+	let decoded = codec(message).unwrap();
+	assert_eq!(&decoded, message);
+	assert_eq!(decoded.to_fix_string(), message.to_fix_string());
+}
@@ -0,0 +1,222 @@
+//! Blocking TCP acceptor
+
+use crate::{
+	FixMessage, MsgType,
+	session::{
+		ConnectionType, MessageStore, Session, SessionConfig, SessionError, SessionId, SessionSettings, SessionState,
+		SettingsError, StoreError,
+	},
+	transport::{Connection, FrameDecoder, SharedSession, TransportError, connection::lock},
+};
+use std::{
+	collections::HashMap,
+	io::{self, Read},
+	net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+	sync::{Arc, Mutex},
+	time::Duration,
+};
+
+/// How long a new connection may take to send its Logon
+pub(crate) const LOGON_WAIT: Duration = Duration::from_secs(10);
+
+/// Listens for counterparties and routes each connection to its session
+///
+/// Incoming connections are matched on the CompIDs of their first message,
+/// which must be a Logon; connections for unknown or already connected
+/// sessions are refused.
+#[derive(Debug)]
+pub struct Acceptor<S: MessageStore> {
+	listener: TcpListener,
+	sessions: HashMap<SessionId, SharedSession<S>>,
+}
+
+impl<S: MessageStore> Acceptor<S> {
+	/// Listen on `address`; use port 0 to pick a free port
+	pub fn bind(address: impl ToSocketAddrs) -> Result<Self, TransportError> {
+		Ok(Self { listener: TcpListener::bind(address)?, sessions: HashMap::new() })
+	}
+
+	/// Create an acceptor for every acceptor session in `settings`
+	///
+	/// All acceptor sessions must share one SocketAcceptPort. `store` creates
+	/// the message store of each session, e.g. [`SessionConfig::file_store`].
+	pub fn from_settings(
+		settings: &SessionSettings,
+		mut store: impl FnMut(&SessionConfig) -> Result<S, StoreError>,
+	) -> Result<Self, TransportError> {
+		let configs: Vec<&SessionConfig> = settings.of_type(ConnectionType::Acceptor).collect();
+		let Some(first) = configs.first() else {
+			return Err(SettingsError::NoSessions(ConnectionType::Acceptor).into());
+		};
+		let address = first.accept_address().ok_or_else(|| first.invalid("No accept address"))?;
+		if let Some(other) = configs.iter().find(|config| config.accept_address().as_ref() != Some(&address)) {
+			return Err(other.invalid(format!("All acceptor sessions must listen on {}", address)).into());
+		}
+
+		let mut acceptor = Self::bind(address)?;
+		for config in configs {
+			acceptor.add_session(config.session(store(config).map_err(SessionError::from)?));
+		}
+		Ok(acceptor)
+	}
+
+	/// Address the acceptor listens on
+	pub fn local_addr(&self) -> io::Result<SocketAddr> {
+		self.listener.local_addr()
+	}
+
+	/// Accept connections for `session`, returning the handle shared with its connections
+	pub fn add_session(&mut self, session: Session<S>) -> SharedSession<S> {
+		let id = session.session_id().clone();
+		let session = Arc::new(Mutex::new(session));
+		self.sessions.insert(id, Arc::clone(&session));
+		session
+	}
+
+	/// Every session registered with the acceptor
+	pub fn sessions(&self) -> impl Iterator<Item = (&SessionId, &SharedSession<S>)> {
+		self.sessions.iter()
+	}
+
+	/// The session registered under `id`, if any
+	pub fn session(&self, id: &SessionId) -> Option<SharedSession<S>> {
+		self.sessions.get(id).cloned()
+	}
+
+	/// Wait for the next connection and complete its logon handshake
+	///
+	/// # Errors
+	/// Fails if the connection does not start with a Logon for a known,
+	/// disconnected session; the connection is closed in that case.
+	pub fn accept(&self) -> Result<Connection<S>, TransportError> {
+		let (mut stream, _) = self.listener.accept()?;
+		stream.set_read_timeout(Some(LOGON_WAIT))?;
+
+		let mut decoder = FrameDecoder::new();
+		let frame = read_first_frame(&mut stream, &mut decoder)?;
+		let session = route_logon(&self.sessions, &frame)?;
+
+		let mut connection = Connection::new(stream, decoder, Arc::clone(&session))?;
+		connection.handle_frame(&frame)?;
+		if !lock(&session).is_logged_on() {
+			return Err(TransportError::LogonFailed(format!("Session {} did not log on", lock(&session).session_id())));
+		}
+		Ok(connection)
+	}
+}
+
+/// Find the disconnected session an incoming Logon frame is addressed to
+pub(crate) fn route_logon<S: MessageStore>(
+	sessions: &HashMap<SessionId, SharedSession<S>>,
+	frame: &str,
+) -> Result<SharedSession<S>, TransportError> {
+	let logon = FixMessage::from_fix_string(frame).map_err(TransportError::LogonFailed)?;
+	if logon.header.msg_type != MsgType::Logon {
+		return Err(TransportError::LogonFailed("First message is not a Logon".to_string()));
+	}
+
+	// The counterparty's TargetCompID is our SenderCompID
+	let id = SessionId::new(logon.header.target_comp_id, logon.header.sender_comp_id);
+	let Some(session) = sessions.get(&id) else { return Err(TransportError::UnknownSession(id)) };
+	if lock(session).state() != SessionState::Disconnected {
+		return Err(TransportError::AlreadyConnected(id));
+	}
+	Ok(Arc::clone(session))
+}
+
+fn read_first_frame(stream: &mut TcpStream, decoder: &mut FrameDecoder) -> Result<String, TransportError> {
+	let mut buffer = [0; 1024];
+	loop {
+		if let Some(frame) = decoder.next_frame()? {
+			return Ok(frame);
+		}
+		match stream.read(&mut buffer) {
+			Ok(0) => return Err(TransportError::LogonFailed("Connection closed before Logon".to_string())),
+			Ok(n) => decoder.extend(&buffer[..n]),
+			Err(e) if matches!(e.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut) => {
+				return Err(TransportError::LogonFailed("No Logon received".to_string()));
+			},
+			Err(e) if e.kind() == io::ErrorKind::Interrupted => {},
+			Err(e) => return Err(e.into()),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Initiator, session::MemoryStore};
+	use std::thread;
+
+	#[test]
+	fn test_unknown_session_is_refused() {
+		let mut acceptor = Acceptor::bind("127.0.0.1:0").unwrap();
+		acceptor.add_session(Session::new(SessionId::new("SERVER", "CLIENT"), MemoryStore::new()));
+		let address = acceptor.local_addr().unwrap().to_string();
+		let server = thread::spawn(move || acceptor.accept().map(|_| ()));
+
+		let session = Session::new(SessionId::new("STRANGER", "SERVER"), MemoryStore::new());
+		let mut initiator = Initiator::new(address, session).with_max_attempts(1);
+		assert!(initiator.connect().is_err());
+
+		let refused = server.join().unwrap();
+		assert!(
+			matches!(refused, Err(TransportError::UnknownSession(id)) if id == SessionId::new("SERVER", "STRANGER"))
+		);
+	}
+
+	#[test]
+	fn test_first_message_must_be_logon() {
+		let acceptor = Acceptor::<MemoryStore>::bind("127.0.0.1:0").unwrap();
+		let address = acceptor.local_addr().unwrap();
+		let server = thread::spawn(move || acceptor.accept().map(|_| ()));
+
+		let heartbeat = FixMessage::builder(MsgType::Heartbeat, "CLIENT", "SERVER", 1).build();
+		let mut stream = TcpStream::connect(address).unwrap();
+		std::io::Write::write_all(&mut stream, heartbeat.to_fix_string().as_bytes()).unwrap();
+
+		assert!(matches!(server.join().unwrap(), Err(TransportError::LogonFailed(_))));
+	}
+
+	#[test]
+	fn test_configured_from_settings() {
+		let acceptor_settings: SessionSettings = "
+[DEFAULT]
+BeginString=FIX.4.2
+ConnectionType=acceptor
+SocketAcceptHost=127.0.0.1
+SocketAcceptPort=0
+[SESSION]
+SenderCompID=SERVER
+TargetCompID=CLIENT
+[SESSION]
+SenderCompID=SERVER
+TargetCompID=OTHER
+"
+		.parse()
+		.unwrap();
+		let acceptor = Acceptor::from_settings(&acceptor_settings, |_| Ok(MemoryStore::new())).unwrap();
+		assert!(acceptor.session(&SessionId::new("SERVER", "OTHER")).is_some());
+		let port = acceptor.local_addr().unwrap().port();
+		let server =
+			thread::spawn(move || acceptor.accept().map(|connection| lock(connection.session()).heart_bt_int()));
+
+		let initiator_settings: SessionSettings = format!(
+			"[SESSION]\nBeginString=FIX.4.2\nConnectionType=initiator\nSenderCompID=CLIENT\nTargetCompID=SERVER\n\
+			 HeartBtInt=45\nSocketConnectHost=127.0.0.1\nSocketConnectPort={}\n",
+			port
+		)
+		.parse()
+		.unwrap();
+		let config = &initiator_settings.sessions()[0];
+		assert!(matches!(
+			Acceptor::from_settings(&initiator_settings, |_| Ok(MemoryStore::new())),
+			Err(TransportError::Settings(SettingsError::NoSessions(ConnectionType::Acceptor)))
+		));
+
+		let mut initiator = Initiator::from_settings(config, MemoryStore::new()).unwrap().with_max_attempts(1);
+		initiator.connect().unwrap();
+		// The acceptor adopts the HeartBtInt configured for the initiator
+		assert_eq!(server.join().unwrap().unwrap(), 45);
+	}
+}
@@ -0,0 +1,172 @@
+//! A logged-on TCP connection carrying one session
+
+use crate::{
+	FixMessage,
+	session::{MessageStore, Session, SessionError, SessionState},
+	transport::{FrameDecoder, TransportError},
+};
+use std::{
+	io::{self, Read, Write},
+	net::{Shutdown, SocketAddr, TcpStream},
+	sync::{Arc, Mutex, MutexGuard, PoisonError},
+	time::Duration,
+};
+use time::OffsetDateTime;
+
+/// Interval at which the session timer runs while waiting for data
+pub(crate) const TICK: Duration = Duration::from_secs(1);
+/// Size of the socket read buffer
+const READ_BUFFER_SIZE: usize = 4096;
+
+/// A session shared between the transport and the application
+///
+/// Messages queued directly on the session (e.g. from another thread) are
+/// written by the connection on its next timer tick.
+pub type SharedSession<S> = Arc<Mutex<Session<S>>>;
+
+/// Lock a shared session, recovering it if a thread panicked while holding the lock
+pub(crate) fn lock<S: MessageStore>(session: &SharedSession<S>) -> MutexGuard<'_, Session<S>> {
+	session.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// A TCP connection bound to a [`Session`]
+///
+/// Dropping the connection closes the socket and marks the session disconnected.
+#[derive(Debug)]
+pub struct Connection<S: MessageStore> {
+	stream: TcpStream,
+	decoder: FrameDecoder,
+	session: SharedSession<S>,
+	open: bool,
+}
+
+impl<S: MessageStore> Connection<S> {
+	/// Wrap a connected socket; `decoder` may already hold bytes read from it
+	pub(crate) fn new(stream: TcpStream, decoder: FrameDecoder, session: SharedSession<S>) -> io::Result<Self> {
+		stream.set_read_timeout(Some(TICK))?;
+		stream.set_nodelay(true)?;
+		Ok(Self { stream, decoder, session, open: true })
+	}
+
+	/// The session carried by this connection
+	pub fn session(&self) -> &SharedSession<S> {
+		&self.session
+	}
+
+	/// Address of the counterparty
+	pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+		self.stream.peer_addr()
+	}
+
+	/// Whether the socket is still open
+	pub const fn is_open(&self) -> bool {
+		self.open
+	}
+
+	/// Send an application message, returning its MsgSeqNum
+	pub fn send(&mut self, message: FixMessage) -> Result<u32, TransportError> {
+		let seq = lock(&self.session).send(message, OffsetDateTime::now_utc())?;
+		self.flush()?;
+		Ok(seq)
+	}
+
+	/// Start the logout handshake; [`Connection::recv`] returns `None` once it completes
+	pub fn logout(&mut self, text: Option<&str>) -> Result<(), TransportError> {
+		lock(&self.session).logout(text, OffsetDateTime::now_utc())?;
+		self.flush()
+	}
+
+	/// Block until the next application message arrives
+	///
+	/// Session-level messages are handled on the way. Returns `Ok(None)` once the
+	/// connection is closed, either by a completed logout or by the counterparty.
+	/// Frame errors and unparsable messages are reported but leave the connection
+	/// open, so `recv` can be called again; any other error closes it.
+	pub fn recv(&mut self) -> Result<Option<FixMessage>, TransportError> {
+		while self.open {
+			if let Some(message) = self.poll()? {
+				return Ok(Some(message));
+			}
+		}
+		Ok(None)
+	}
+
+	/// Handle one buffered frame, or wait for data for at most one tick and run the session timer
+	pub(crate) fn poll(&mut self) -> Result<Option<FixMessage>, TransportError> {
+		if let Some(frame) = self.decoder.next_frame()? {
+			return self.handle_frame(&frame);
+		}
+
+		let mut buffer = [0; READ_BUFFER_SIZE];
+		match self.stream.read(&mut buffer) {
+			Ok(0) => {
+				self.close();
+				return Ok(None);
+			},
+			Ok(n) => self.decoder.extend(&buffer[..n]),
+			Err(e)
+				if matches!(
+					e.kind(),
+					io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut | io::ErrorKind::Interrupted
+				) => {},
+			Err(e) => {
+				self.close();
+				return Err(e.into());
+			},
+		}
+
+		let result = lock(&self.session).on_timer(OffsetDateTime::now_utc());
+		self.flush()?;
+		if let Err(e) = result {
+			self.close();
+			return Err(e.into());
+		}
+		Ok(None)
+	}
+
+	/// Pass one frame to the session and write its responses
+	pub(crate) fn handle_frame(&mut self, frame: &str) -> Result<Option<FixMessage>, TransportError> {
+		let result = lock(&self.session).on_message(frame, OffsetDateTime::now_utc());
+		self.flush()?;
+		match result {
+			Ok(message) => {
+				if lock(&self.session).state() == SessionState::Disconnected {
+					self.close();
+				}
+				Ok(message)
+			},
+			Err(e @ SessionError::InvalidMessage(_)) => Err(e.into()),
+			Err(e) => {
+				self.close();
+				Err(e.into())
+			},
+		}
+	}
+
+	/// Write everything the session has queued
+	pub(crate) fn flush(&mut self) -> Result<(), TransportError> {
+		let outgoing = lock(&self.session).take_outgoing();
+		for wire in outgoing {
+			if let Err(e) = self.stream.write_all(wire.as_bytes()) {
+				self.close();
+				return Err(e.into());
+			}
+		}
+		Ok(())
+	}
+
+	fn close(&mut self) {
+		if self.open {
+			self.open = false;
+			// The peer may already be gone, nothing to do about a failed shutdown
+			let _ = self.stream.shutdown(Shutdown::Both);
+			lock(&self.session).disconnect();
+		}
+	}
+}
+
+impl<S: MessageStore> Drop for Connection<S> {
+	fn drop(&mut self) {
+		self.close();
+	}
+}
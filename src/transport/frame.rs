@@ -0,0 +1,245 @@
+//! FIX frame decoder
+//!
+//! TCP delivers a byte stream, not messages. [`FrameDecoder`] buffers incoming
+//! bytes and cuts them into complete FIX frames using the BodyLength (Tag 9)
+//! field, then verifies the CheckSum (Tag 10) of each frame. Bytes that cannot
+//! start a frame are skipped until the next `8=FIX` so the stream can resync.
+
+use crate::common::calculate_checksum;
+use std::fmt::Display;
+
+/// Beginning of every FIX frame
+const FRAME_START: &[u8] = b"8=FIX";
+/// Length of the trailing `10=nnn<SOH>` field
+const CHECKSUM_FIELD_LEN: usize = 7;
+/// Frames announcing a larger body are treated as garbled
+const MAX_BODY_LENGTH: usize = 1024 * 1024;
+/// The BeginString and BodyLength fields must fit in this many bytes
+const MAX_PREFIX_LENGTH: usize = 32;
+
+/// Errors reported by the [`FrameDecoder`]; the offending bytes have already been discarded
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum FrameError {
+	/// The frame's CheckSum does not match its content
+	InvalidChecksum { expected: String, actual: String },
+	/// The bytes do not form a valid frame (bad BodyLength, missing CheckSum, not UTF-8, ...)
+	Garbled(String),
+}
+
+impl Display for FrameError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::InvalidChecksum { expected, actual } => {
+				write!(f, "Invalid checksum: expected {} but frame carries {}", expected, actual)
+			},
+			Self::Garbled(reason) => write!(f, "Garbled frame: {}", reason),
+		}
+	}
+}
+
+impl std::error::Error for FrameError {}
+
+/// What the start of a buffer holds
+#[derive(Debug)]
+pub(crate) enum Scan {
+	/// More bytes are needed to decide
+	Incomplete,
+	/// This many leading bytes cannot start a frame
+	Skip(usize),
+	/// A valid frame of this length
+	Frame(usize),
+	/// This many bytes form an invalid frame
+	Invalid(usize, FrameError),
+}
+
+/// Look for a frame at the start of `buffer`
+///
+/// Shared by [`FrameDecoder`] and the tokio codec, which only differ in how
+/// they hold the buffered bytes.
+pub(crate) fn scan(buffer: &[u8]) -> Scan {
+	if !buffer.starts_with(FRAME_START) {
+		return match skip_len(buffer) {
+			0 => Scan::Incomplete,
+			n => Scan::Skip(n),
+		};
+	}
+
+	let (body_start, body_length) = match parse_prefix(buffer) {
+		Ok(Some(prefix)) => prefix,
+		Ok(None) => return Scan::Incomplete,
+		// Skip the "8" so the next scan resyncs to the following frame start
+		Err(reason) => return Scan::Invalid(1, FrameError::Garbled(reason.to_string())),
+	};
+	let body_end = body_start + body_length;
+	let frame_end = body_end + CHECKSUM_FIELD_LEN;
+	if buffer.len() < frame_end {
+		return Scan::Incomplete;
+	}
+
+	let checksum_field = &buffer[body_end..frame_end];
+	if !checksum_field.starts_with(b"10=") || checksum_field[CHECKSUM_FIELD_LEN - 1] != b'\x01' {
+		return Scan::Invalid(1, FrameError::Garbled("BodyLength does not point at the CheckSum field".to_string()));
+	}
+
+	let actual = String::from_utf8_lossy(&checksum_field[3..6]).into_owned();
+	let expected = format!("{:03}", calculate_checksum(&buffer[..body_end]));
+	if actual != expected {
+		return Scan::Invalid(frame_end, FrameError::InvalidChecksum { expected, actual });
+	}
+	Scan::Frame(frame_end)
+}
+
+/// Turn the bytes of a valid frame into a string
+pub(crate) fn frame_to_string(frame: Vec<u8>) -> Result<String, FrameError> {
+	String::from_utf8(frame).map_err(|_| FrameError::Garbled("Frame is not valid UTF-8".to_string()))
+}
+
+/// Number of bytes before the next `8=FIX`, keeping a possibly incomplete start at the end
+fn skip_len(buffer: &[u8]) -> usize {
+	buffer.windows(FRAME_START.len()).position(|w| w == FRAME_START).unwrap_or_else(|| {
+		// Keep a trailing partial "8=FIX" that may be completed by the next read
+		(1..FRAME_START.len())
+			.rev()
+			.find(|&n| buffer.ends_with(&FRAME_START[..n]))
+			.map_or(buffer.len(), |n| buffer.len() - n)
+	})
+}
+
+/// Parse `8=...<SOH>9=nnn<SOH>`, returning where the body starts and its length
+fn parse_prefix(buffer: &[u8]) -> Result<Option<(usize, usize)>, &'static str> {
+	let search_len = buffer.len().min(MAX_PREFIX_LENGTH);
+	let mut fields = buffer[..search_len].split_inclusive(|&b| b == b'\x01');
+
+	let (Some(begin_string), Some(body_length)) = (fields.next(), fields.next()) else {
+		return incomplete_prefix(search_len);
+	};
+	if !body_length.ends_with(b"\x01") {
+		return incomplete_prefix(search_len);
+	}
+	let Some(digits) = body_length.strip_prefix(b"9=").and_then(|f| f.strip_suffix(b"\x01")) else {
+		return Err("BodyLength (9) must follow BeginString (8)");
+	};
+	let length = std::str::from_utf8(digits).ok().and_then(|d| d.parse::<usize>().ok());
+	match length {
+		Some(length) if length <= MAX_BODY_LENGTH => Ok(Some((begin_string.len() + body_length.len(), length))),
+		_ => Err("Invalid BodyLength"),
+	}
+}
+
+fn incomplete_prefix(search_len: usize) -> Result<Option<(usize, usize)>, &'static str> {
+	if search_len == MAX_PREFIX_LENGTH {
+		return Err("BeginString and BodyLength not found");
+	}
+	Ok(None)
+}
+
+/// Incremental decoder turning a byte stream into FIX frames
+#[derive(Debug, Default)]
+pub struct FrameDecoder {
+	buffer: Vec<u8>,
+}
+
+impl FrameDecoder {
+	/// Create an empty decoder
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Append bytes read from the connection
+	pub fn extend(&mut self, bytes: &[u8]) {
+		self.buffer.extend_from_slice(bytes);
+	}
+
+	/// Number of buffered bytes not yet returned as frames
+	pub fn buffered_len(&self) -> usize {
+		self.buffer.len()
+	}
+
+	/// Extract the next complete frame, if one is buffered
+	///
+	/// Returns `Ok(None)` when more bytes are needed. After an error the decoder
+	/// has skipped the bad bytes and can be called again.
+	pub fn next_frame(&mut self) -> Result<Option<String>, FrameError> {
+		loop {
+			match scan(&self.buffer) {
+				Scan::Incomplete => return Ok(None),
+				Scan::Skip(n) => {
+					self.buffer.drain(..n);
+				},
+				Scan::Frame(n) => return frame_to_string(self.buffer.drain(..n).collect()).map(Some),
+				Scan::Invalid(n, e) => {
+					self.buffer.drain(..n);
+					return Err(e);
+				},
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{FixMessage, MsgType};
+	use time::macros::datetime;
+
+	fn frame(seq: u32) -> String {
+		FixMessage::builder(MsgType::Heartbeat, "CLIENT", "SERVER", seq)
+			.sending_time(datetime!(2024-03-01 09:00:00 UTC))
+			.build()
+			.to_fix_string()
+	}
+
+	#[test]
+	fn test_decodes_complete_frames() {
+		let mut decoder = FrameDecoder::new();
+		decoder.extend(format!("{}{}", frame(1), frame(2)).as_bytes());
+
+		assert_eq!(decoder.next_frame().unwrap(), Some(frame(1)));
+		assert_eq!(decoder.next_frame().unwrap(), Some(frame(2)));
+		assert_eq!(decoder.next_frame().unwrap(), None);
+		assert_eq!(decoder.buffered_len(), 0);
+	}
+
+	#[test]
+	fn test_waits_for_partial_frames() {
+		let wire = frame(1);
+		let mut decoder = FrameDecoder::new();
+		for chunk in wire.as_bytes().chunks(5) {
+			assert_eq!(decoder.next_frame().unwrap(), None);
+			decoder.extend(chunk);
+		}
+		assert_eq!(decoder.next_frame().unwrap(), Some(wire));
+	}
+
+	#[test]
+	fn test_skips_leading_garbage() {
+		let mut decoder = FrameDecoder::new();
+		decoder.extend(format!("garbage\x01{}", frame(1)).as_bytes());
+		assert_eq!(decoder.next_frame().unwrap(), Some(frame(1)));
+	}
+
+	#[test]
+	fn test_rejects_bad_checksum_and_continues() {
+		let wire = frame(1);
+		let checksum_pos = wire.rfind("10=").unwrap();
+		let bad = format!("{}10=000\x01", &wire[..checksum_pos]);
+
+		let mut decoder = FrameDecoder::new();
+		decoder.extend(format!("{}{}", bad, frame(2)).as_bytes());
+		assert!(matches!(decoder.next_frame(), Err(FrameError::InvalidChecksum { .. })));
+		assert_eq!(decoder.next_frame().unwrap(), Some(frame(2)));
+	}
+
+	#[test]
+	fn test_resyncs_after_wrong_body_length() {
+		// A BodyLength that is too short makes the CheckSum field land mid-body
+		let wire = frame(1);
+		let length_start = wire.find("\x019=").unwrap() + 3;
+		let length_end = length_start + wire[length_start..].find('\x01').unwrap();
+		let bad = format!("{}10{}", &wire[..length_start], &wire[length_end..]);
+		let mut decoder = FrameDecoder::new();
+		decoder.extend(format!("{}{}", bad, frame(2)).as_bytes());
+		assert!(matches!(decoder.next_frame(), Err(FrameError::Garbled(_))));
+		assert_eq!(decoder.next_frame().unwrap(), Some(frame(2)));
+	}
+}
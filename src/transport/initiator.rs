@@ -0,0 +1,285 @@
+//! Blocking TCP initiator
+
+use crate::{
+	FixMessage,
+	session::{ConnectionType, MessageStore, Session, SessionConfig, SessionError},
+	transport::{Connection, FrameDecoder, SharedSession, TransportError, connection::lock},
+};
+use std::{
+	net::TcpStream,
+	sync::{Arc, Mutex},
+	thread,
+	time::Duration,
+};
+use time::OffsetDateTime;
+
+/// Connects to a counterparty and keeps one session logged on
+///
+/// When the connection drops, [`Initiator::recv`] reconnects and logs on again
+/// with exponential backoff; the session's sequence numbers carry over. Outside
+/// of the session's schedule, connection attempts fail and are retried the same way.
+#[derive(Debug)]
+pub struct Initiator<S: MessageStore> {
+	address: String,
+	session: SharedSession<S>,
+	initial_backoff: Duration,
+	max_backoff: Duration,
+	/// Connection attempts before giving up, `None` to retry forever
+	max_attempts: Option<u32>,
+	connection: Option<Connection<S>>,
+	/// Set by [`Initiator::logout`] so a closed connection is not reopened
+	logout_requested: bool,
+}
+
+impl<S: MessageStore> Initiator<S> {
+	/// Create an initiator for `session` connecting to `address` (e.g. `"127.0.0.1:9878"`)
+	pub fn new(address: impl Into<String>, session: Session<S>) -> Self {
+		Self {
+			address: address.into(),
+			session: Arc::new(Mutex::new(session)),
+			initial_backoff: Duration::from_secs(1),
+			max_backoff: Duration::from_secs(30),
+			max_attempts: None,
+			connection: None,
+			logout_requested: false,
+		}
+	}
+
+	/// Create an initiator from a session's settings
+	///
+	/// Connects to SocketConnectHost:SocketConnectPort, waiting ReconnectInterval
+	/// seconds before the first reconnection attempt, and logs on with the
+	/// settings' HeartBtInt and ResetOnLogon.
+	pub fn from_settings(config: &SessionConfig, store: S) -> Result<Self, TransportError> {
+		if config.connection_type != ConnectionType::Initiator {
+			return Err(config.invalid("Not an initiator session").into());
+		}
+		let address = config.connect_address().ok_or_else(|| config.invalid("No connect address"))?;
+		let interval = Duration::from_secs(config.reconnect_interval.into());
+		Ok(Self::new(address, config.session(store)).with_backoff(interval, interval.max(Duration::from_secs(30))))
+	}
+
+	/// Set the delay before the first reconnection attempt and the cap it doubles up to
+	pub const fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+		self.initial_backoff = initial;
+		self.max_backoff = max;
+		self
+	}
+
+	/// Give up after `attempts` failed connection attempts instead of retrying forever
+	pub const fn with_max_attempts(mut self, attempts: u32) -> Self {
+		self.max_attempts = Some(attempts);
+		self
+	}
+
+	/// The session driven by this initiator
+	pub fn session(&self) -> SharedSession<S> {
+		Arc::clone(&self.session)
+	}
+
+	/// Whether a connection is currently open
+	pub fn is_connected(&self) -> bool {
+		self.connection.as_ref().is_some_and(Connection::is_open)
+	}
+
+	/// Connect and complete the logon handshake, retrying with backoff
+	///
+	/// # Errors
+	/// Returns the error of the last attempt once the maximum number of attempts is reached.
+	pub fn connect(&mut self) -> Result<(), TransportError> {
+		self.logout_requested = false;
+		self.connection = None;
+		let mut backoff = self.initial_backoff;
+		let mut attempts = 0;
+		loop {
+			match self.logon() {
+				Ok(connection) => {
+					self.connection = Some(connection);
+					return Ok(());
+				},
+				Err(e) => {
+					attempts += 1;
+					if self.max_attempts.is_some_and(|max| attempts >= max) {
+						return Err(e);
+					}
+					thread::sleep(backoff);
+					backoff = (backoff * 2).min(self.max_backoff);
+				},
+			}
+		}
+	}
+
+	/// Send an application message, returning its MsgSeqNum
+	pub fn send(&mut self, message: FixMessage) -> Result<u32, TransportError> {
+		match &mut self.connection {
+			Some(connection) => connection.send(message),
+			None => Err(SessionError::NotLoggedOn.into()),
+		}
+	}
+
+	/// Start the logout handshake; [`Initiator::recv`] returns `None` once it completes
+	pub fn logout(&mut self, text: Option<&str>) -> Result<(), TransportError> {
+		self.logout_requested = true;
+		match &mut self.connection {
+			Some(connection) => connection.logout(text),
+			None => Err(SessionError::NotLoggedOn.into()),
+		}
+	}
+
+	/// Block until the next application message arrives
+	///
+	/// A dropped connection is reopened transparently, unless a logout was
+	/// requested, in which case `Ok(None)` is returned. Frame errors and
+	/// unparsable messages are reported without closing the connection.
+	pub fn recv(&mut self) -> Result<Option<FixMessage>, TransportError> {
+		loop {
+			let connection = match &mut self.connection {
+				Some(connection) if connection.is_open() => connection,
+				_ if self.logout_requested => {
+					self.connection = None;
+					return Ok(None);
+				},
+				_ => {
+					self.connect()?;
+					continue;
+				},
+			};
+			match connection.recv() {
+				Ok(Some(message)) => return Ok(Some(message)),
+				Err(e) if connection.is_open() || self.logout_requested => return Err(e),
+				// Closed by the counterparty or after an error: reconnect on the next iteration
+				Ok(None) | Err(_) => {},
+			}
+		}
+	}
+
+	/// One connection attempt: connect, send our Logon and wait for the reply
+	fn logon(&mut self) -> Result<Connection<S>, TransportError> {
+		if !lock(&self.session).is_session_time(OffsetDateTime::now_utc()) {
+			return Err(SessionError::OutsideSessionTime.into());
+		}
+		let stream = TcpStream::connect(&self.address)?;
+		let mut connection = Connection::new(stream, FrameDecoder::new(), Arc::clone(&self.session))?;
+		lock(&self.session).initiate_logon(OffsetDateTime::now_utc())?;
+		connection.flush()?;
+
+		while !lock(&self.session).is_logged_on() {
+			if !connection.is_open() {
+				return Err(TransportError::LogonFailed("Connection closed during logon".to_string()));
+			}
+			connection.poll()?;
+		}
+		Ok(connection)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		Acceptor, MsgType, Side,
+		messages::FixMessageBody,
+		session::{MemoryStore, SessionId},
+	};
+
+	fn order(cl_ord_id: &str) -> FixMessage {
+		// CompIDs and MsgSeqNum are stamped by the session
+		FixMessage::builder(MsgType::NewOrderSingle, "", "", 0)
+			.cl_ord_id(cl_ord_id)
+			.symbol("AAPL")
+			.side(Side::Buy)
+			.order_qty(100.0)
+			.ord_type("1")
+			.handl_inst("1")
+			.transact_time(OffsetDateTime::now_utc())
+			.build()
+	}
+
+	fn cl_ord_id(message: &FixMessage) -> &str {
+		match &message.body {
+			FixMessageBody::NewOrderSingle(body) => &body.cl_ord_id,
+			other => panic!("Unexpected body {:?}", other),
+		}
+	}
+
+	fn acceptor() -> (Acceptor<MemoryStore>, String) {
+		let mut acceptor = Acceptor::bind("127.0.0.1:0").unwrap();
+		acceptor.add_session(Session::new(SessionId::new("SERVER", "CLIENT"), MemoryStore::new()));
+		let address = acceptor.local_addr().unwrap().to_string();
+		(acceptor, address)
+	}
+
+	fn initiator(address: String) -> Initiator<MemoryStore> {
+		Initiator::new(address, Session::new(SessionId::new("CLIENT", "SERVER"), MemoryStore::new()))
+			.with_backoff(Duration::from_millis(10), Duration::from_millis(50))
+			.with_max_attempts(5)
+	}
+
+	#[test]
+	fn test_logon_exchange_and_logout() {
+		let (acceptor, address) = acceptor();
+		let server = thread::spawn(move || {
+			let mut connection = acceptor.accept().unwrap();
+			let order = connection.recv().unwrap().unwrap();
+			assert_eq!(cl_ord_id(&order), "ORD1");
+			connection.send(self::order("ECHO1")).unwrap();
+			// The client logs out next
+			assert!(connection.recv().unwrap().is_none());
+			assert!(!lock(connection.session()).is_logged_on());
+		});
+
+		let mut initiator = initiator(address);
+		initiator.connect().unwrap();
+		assert!(initiator.is_connected());
+		assert_eq!(initiator.send(order("ORD1")).unwrap(), 2);
+
+		let reply = initiator.recv().unwrap().unwrap();
+		assert_eq!(cl_ord_id(&reply), "ECHO1");
+		assert_eq!(reply.header.sender_comp_id, "SERVER");
+		assert_eq!(reply.header.msg_seq_num, 2);
+
+		initiator.logout(None).unwrap();
+		assert!(initiator.recv().unwrap().is_none());
+		server.join().unwrap();
+
+		// Logon, order and logout were sent; logon, echo and logout received
+		let session = initiator.session();
+		let session = lock(&session);
+		assert_eq!(session.store().next_sender_msg_seq_num(), 4);
+		assert_eq!(session.store().next_target_msg_seq_num(), 4);
+	}
+
+	#[test]
+	fn test_reconnects_after_drop() {
+		let (acceptor, address) = acceptor();
+		let server = thread::spawn(move || {
+			let connection = acceptor.accept().unwrap();
+			drop(connection);
+
+			let mut connection = acceptor.accept().unwrap();
+			connection.send(order("AFTER-RECONNECT")).unwrap();
+			assert!(connection.recv().unwrap().is_none());
+		});
+
+		let mut initiator = initiator(address);
+		initiator.connect().unwrap();
+		let message = initiator.recv().unwrap().unwrap();
+		assert_eq!(cl_ord_id(&message), "AFTER-RECONNECT");
+		// Sequence numbers carry over: first Logon, second Logon, then the order
+		assert_eq!(message.header.msg_seq_num, 3);
+
+		initiator.logout(Some("Done")).unwrap();
+		assert!(initiator.recv().unwrap().is_none());
+		server.join().unwrap();
+	}
+
+	#[test]
+	fn test_connect_gives_up_after_max_attempts() {
+		// Bind and drop a listener to get a port nobody listens on
+		let address = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().to_string();
+		let mut initiator = initiator(address).with_max_attempts(2);
+		assert!(matches!(initiator.connect(), Err(TransportError::Io(_))));
+		assert!(!initiator.is_connected());
+		assert!(matches!(initiator.send(order("ORD1")), Err(TransportError::Session(SessionError::NotLoggedOn))));
+	}
+}
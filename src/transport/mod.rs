@@ -0,0 +1,86 @@
+//! Blocking TCP transport
+//!
+//! [`Initiator`] connects to a counterparty and [`Acceptor`] listens for
+//! incoming connections. Both hand the bytes read from the socket to a
+//! [`FrameDecoder`] and the resulting frames to the [`Session`](crate::Session)
+//! state machine, writing whatever the session queues in response. Time-based
+//! session behaviour (heartbeats, test requests, timeouts) is driven by a one
+//! second read timeout on the socket.
+//!
+//! With the `tokio` feature, the `transport::tokio` module provides the
+//! same on the tokio runtime.
+
+pub mod acceptor;
+pub mod connection;
+pub mod frame;
+pub mod initiator;
+#[cfg(feature = "tokio")]
+pub mod tokio;
+
+use crate::session::{SessionError, SessionId, SettingsError};
+use std::{fmt::Display, io};
+
+// Re-export commonly used types
+pub use acceptor::Acceptor;
+pub use connection::{Connection, SharedSession};
+pub use frame::{FrameDecoder, FrameError};
+pub use initiator::Initiator;
+
+/// Errors raised by the TCP transport
+#[derive(Debug)]
+pub enum TransportError {
+	/// Socket I/O failed
+	Io(io::Error),
+	/// The session rejected a message or was terminated
+	Session(SessionError),
+	/// The byte stream could not be cut into valid frames
+	Frame(FrameError),
+	/// An incoming Logon names a session the acceptor does not know
+	UnknownSession(SessionId),
+	/// An incoming Logon names a session that is already connected
+	AlreadyConnected(SessionId),
+	/// The logon handshake did not complete
+	LogonFailed(String),
+	/// The session settings cannot configure this transport
+	Settings(SettingsError),
+}
+
+impl Display for TransportError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Io(e) => write!(f, "I/O error: {}", e),
+			Self::Session(e) => write!(f, "{}", e),
+			Self::Frame(e) => write!(f, "{}", e),
+			Self::UnknownSession(id) => write!(f, "Unknown session {}", id),
+			Self::AlreadyConnected(id) => write!(f, "Session {} is already connected", id),
+			Self::LogonFailed(reason) => write!(f, "Logon failed: {}", reason),
+			Self::Settings(e) => write!(f, "{}", e),
+		}
+	}
+}
+
+impl std::error::Error for TransportError {}
+
+impl From<io::Error> for TransportError {
+	fn from(e: io::Error) -> Self {
+		Self::Io(e)
+	}
+}
+
+impl From<SessionError> for TransportError {
+	fn from(e: SessionError) -> Self {
+		Self::Session(e)
+	}
+}
+
+impl From<FrameError> for TransportError {
+	fn from(e: FrameError) -> Self {
+		Self::Frame(e)
+	}
+}
+
+impl From<SettingsError> for TransportError {
+	fn from(e: SettingsError) -> Self {
+		Self::Settings(e)
+	}
+}
@@ -0,0 +1,119 @@
+//! Async TCP acceptor
+
+use crate::{
+	session::{
+		ConnectionType, MessageStore, Session, SessionConfig, SessionError, SessionId, SessionSettings, SettingsError,
+		StoreError,
+	},
+	transport::{
+		SharedSession, TransportError,
+		acceptor::{LOGON_WAIT, route_logon},
+		connection::lock,
+		tokio::{AsyncConnection, FixCodec, connection::Driver},
+	},
+};
+use bytes::BytesMut;
+use std::{
+	collections::HashMap,
+	io,
+	net::SocketAddr,
+	sync::{Arc, Mutex},
+};
+use tokio::{
+	io::AsyncReadExt,
+	net::{TcpListener, TcpStream, ToSocketAddrs},
+};
+use tokio_util::codec::Decoder;
+
+/// Listens for counterparties and routes each connection to its session
+///
+/// Routing follows the blocking [`Acceptor`](crate::transport::Acceptor): the
+/// first message must be a Logon for a known session that is not connected yet.
+#[derive(Debug)]
+pub struct AsyncAcceptor<S: MessageStore> {
+	listener: TcpListener,
+	sessions: HashMap<SessionId, SharedSession<S>>,
+}
+
+impl<S: MessageStore + Send + 'static> AsyncAcceptor<S> {
+	/// Listen on `address`; use port 0 to pick a free port
+	pub async fn bind(address: impl ToSocketAddrs) -> Result<Self, TransportError> {
+		Ok(Self { listener: TcpListener::bind(address).await?, sessions: HashMap::new() })
+	}
+
+	/// Create an acceptor for every acceptor session in `settings`
+	///
+	/// All acceptor sessions must share one SocketAcceptPort. `store` creates
+	/// the message store of each session, e.g. [`SessionConfig::file_store`].
+	pub async fn from_settings(
+		settings: &SessionSettings,
+		mut store: impl FnMut(&SessionConfig) -> Result<S, StoreError>,
+	) -> Result<Self, TransportError> {
+		let configs: Vec<&SessionConfig> = settings.of_type(ConnectionType::Acceptor).collect();
+		let Some(first) = configs.first() else {
+			return Err(SettingsError::NoSessions(ConnectionType::Acceptor).into());
+		};
+		let address = first.accept_address().ok_or_else(|| first.invalid("No accept address"))?;
+		if let Some(other) = configs.iter().find(|config| config.accept_address().as_ref() != Some(&address)) {
+			return Err(other.invalid(format!("All acceptor sessions must listen on {}", address)).into());
+		}
+
+		let mut acceptor = Self::bind(address).await?;
+		for config in configs {
+			acceptor.add_session(config.session(store(config).map_err(SessionError::from)?));
+		}
+		Ok(acceptor)
+	}
+
+	/// Address the acceptor listens on
+	pub fn local_addr(&self) -> io::Result<SocketAddr> {
+		self.listener.local_addr()
+	}
+
+	/// Accept connections for `session`, returning the handle shared with its connections
+	pub fn add_session(&mut self, session: Session<S>) -> SharedSession<S> {
+		let id = session.session_id().clone();
+		let session = Arc::new(Mutex::new(session));
+		self.sessions.insert(id, Arc::clone(&session));
+		session
+	}
+
+	/// The session registered under `id`, if any
+	pub fn session(&self, id: &SessionId) -> Option<SharedSession<S>> {
+		self.sessions.get(id).cloned()
+	}
+
+	/// Wait for the next connection and complete its logon handshake
+	///
+	/// # Errors
+	/// Fails if the connection does not start with a Logon for a known,
+	/// disconnected session; the connection is closed in that case.
+	pub async fn accept(&self) -> Result<AsyncConnection<S>, TransportError> {
+		let (mut stream, _) = self.listener.accept().await?;
+		let mut buffer = BytesMut::new();
+		let frame = tokio::time::timeout(LOGON_WAIT, read_first_frame(&mut stream, &mut buffer))
+			.await
+			.map_err(|_| TransportError::LogonFailed("No Logon received".to_string()))??;
+		let session = route_logon(&self.sessions, &frame)?;
+
+		let mut driver = Driver::new(stream, buffer, Arc::clone(&session))?;
+		driver.handle_frame(&frame).await?;
+		if let Err(e) = driver.await_logon().await {
+			lock(&session).disconnect();
+			return Err(e);
+		}
+		Ok(AsyncConnection::spawn(driver))
+	}
+}
+
+async fn read_first_frame(stream: &mut TcpStream, buffer: &mut BytesMut) -> Result<String, TransportError> {
+	let mut codec = FixCodec::new();
+	loop {
+		if let Some(frame) = codec.decode(buffer)? {
+			return Ok(frame);
+		}
+		if stream.read_buf(buffer).await? == 0 {
+			return Err(TransportError::LogonFailed("Connection closed before Logon".to_string()));
+		}
+	}
+}
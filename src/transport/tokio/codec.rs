@@ -0,0 +1,114 @@
+//! `tokio_util` codec for FIX frames
+
+use crate::{
+	FixMessage,
+	transport::{
+		TransportError,
+		frame::{Scan, frame_to_string, scan},
+	},
+};
+use bytes::{Buf, BytesMut};
+use tokio_util::codec::{Decoder, Encoder};
+
+/// Cuts a byte stream into FIX frames and writes messages to it
+///
+/// Decoding follows the same rules as [`FrameDecoder`](crate::transport::FrameDecoder):
+/// frames are delimited by BodyLength, their CheckSum is verified and bytes that
+/// cannot start a frame are skipped. An invalid frame is discarded before its
+/// error is returned, so `decode` can be called again; note that `Framed` ends
+/// the stream after the first error.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FixCodec;
+
+impl FixCodec {
+	/// Create a codec
+	pub const fn new() -> Self {
+		Self
+	}
+}
+
+impl Decoder for FixCodec {
+	type Item = String;
+	type Error = TransportError;
+
+	fn decode(&mut self, src: &mut BytesMut) -> Result<Option<String>, TransportError> {
+		loop {
+			match scan(src) {
+				Scan::Incomplete => return Ok(None),
+				Scan::Skip(n) => src.advance(n),
+				Scan::Frame(n) => return Ok(Some(frame_to_string(src.split_to(n).to_vec())?)),
+				Scan::Invalid(n, e) => {
+					src.advance(n);
+					return Err(e.into());
+				},
+			}
+		}
+	}
+}
+
+impl Encoder<String> for FixCodec {
+	type Error = TransportError;
+
+	fn encode(&mut self, item: String, dst: &mut BytesMut) -> Result<(), TransportError> {
+		dst.extend_from_slice(item.as_bytes());
+		Ok(())
+	}
+}
+
+impl Encoder<&FixMessage> for FixCodec {
+	type Error = TransportError;
+
+	fn encode(&mut self, item: &FixMessage, dst: &mut BytesMut) -> Result<(), TransportError> {
+		dst.extend_from_slice(item.to_fix_string().as_bytes());
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{MsgType, transport::FrameError};
+	use time::macros::datetime;
+
+	fn heartbeat(seq: u32) -> FixMessage {
+		FixMessage::builder(MsgType::Heartbeat, "CLIENT", "SERVER", seq)
+			.sending_time(datetime!(2024-03-01 09:00:00 UTC))
+			.build()
+	}
+
+	#[test]
+	fn test_round_trip() {
+		let mut codec = FixCodec::new();
+		let mut buffer = BytesMut::new();
+		codec.encode(&heartbeat(1), &mut buffer).unwrap();
+		codec.encode(heartbeat(2).to_fix_string(), &mut buffer).unwrap();
+
+		assert_eq!(codec.decode(&mut buffer).unwrap(), Some(heartbeat(1).to_fix_string()));
+		assert_eq!(codec.decode(&mut buffer).unwrap(), Some(heartbeat(2).to_fix_string()));
+		assert_eq!(codec.decode(&mut buffer).unwrap(), None);
+		assert!(buffer.is_empty());
+	}
+
+	#[test]
+	fn test_partial_frame_and_garbage() {
+		let wire = heartbeat(1).to_fix_string();
+		let (head, tail) = wire.split_at(20);
+		let mut codec = FixCodec::new();
+		let mut buffer = BytesMut::from(format!("noise{}", head).as_bytes());
+
+		assert_eq!(codec.decode(&mut buffer).unwrap(), None);
+		buffer.extend_from_slice(tail.as_bytes());
+		assert_eq!(codec.decode(&mut buffer).unwrap(), Some(wire));
+	}
+
+	#[test]
+	fn test_bad_checksum_is_discarded() {
+		let wire = heartbeat(1).to_fix_string();
+		let checksum_pos = wire.rfind("10=").unwrap();
+		let mut buffer = BytesMut::from(format!("{}10=000\x01{}", &wire[..checksum_pos], wire).as_bytes());
+
+		let mut codec = FixCodec::new();
+		assert!(matches!(codec.decode(&mut buffer), Err(TransportError::Frame(FrameError::InvalidChecksum { .. }))));
+		assert_eq!(codec.decode(&mut buffer).unwrap(), Some(wire));
+	}
+}
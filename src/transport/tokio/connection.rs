@@ -0,0 +1,336 @@
+//! Async connection carrying one session
+
+use crate::{
+	FixMessage,
+	session::{MessageStore, SessionError, SessionState},
+	transport::{SharedSession, TransportError, connection::TICK, connection::lock, tokio::FixCodec},
+};
+use bytes::BytesMut;
+use futures_core::Stream;
+use futures_sink::Sink;
+use std::{
+	collections::VecDeque,
+	future, io,
+	pin::Pin,
+	sync::Arc,
+	task::{Context, Poll},
+};
+use time::OffsetDateTime;
+use tokio::{
+	io::{AsyncReadExt, AsyncWriteExt},
+	net::{
+		TcpStream,
+		tcp::{OwnedReadHalf, OwnedWriteHalf},
+	},
+	sync::{
+		mpsc::{self, error::TrySendError},
+		oneshot,
+	},
+	task::JoinHandle,
+	time::{Interval, MissedTickBehavior},
+};
+use tokio_util::{codec::Decoder, sync::PollSender};
+
+/// Capacity of the channels between an [`AsyncConnection`] and its task
+const CHANNEL_CAPACITY: usize = 64;
+
+/// Requests from the [`AsyncConnection`] handle to its connection task
+#[derive(Debug)]
+enum Command {
+	Send(Box<FixMessage>, Option<oneshot::Sender<Result<u32, TransportError>>>),
+	Logout(Option<String>, oneshot::Sender<Result<(), TransportError>>),
+}
+
+/// A logged-on session running on the tokio runtime
+///
+/// The socket is owned by a background task that drives the session, including
+/// heartbeats and timeouts. Inbound application messages are read through the
+/// [`Stream`] implementation (or [`AsyncConnection::recv`]); outbound ones are
+/// written through the [`Sink`] implementation or [`AsyncConnection::send`],
+/// which also reports the assigned MsgSeqNum. Dropping the handle closes the
+/// connection.
+///
+/// Reading the stream slowly never holds up the session: once the inbound channel
+/// is full, the task keeps reading the socket, answering TestRequests and sending
+/// heartbeats, and queues the application messages it receives until the stream
+/// takes them. That queue grows without bound while nobody reads.
+#[derive(Debug)]
+pub struct AsyncConnection<S: MessageStore> {
+	session: SharedSession<S>,
+	inbound: mpsc::Receiver<FixMessage>,
+	commands: mpsc::Sender<Command>,
+	sink: PollSender<Command>,
+	task: JoinHandle<Result<(), TransportError>>,
+}
+
+impl<S: MessageStore + Send + 'static> AsyncConnection<S> {
+	/// Hand a logged-on driver to a background task
+	pub(crate) fn spawn(driver: Driver<S>) -> Self {
+		let (inbound_tx, inbound) = mpsc::channel(CHANNEL_CAPACITY);
+		let (commands, commands_rx) = mpsc::channel(CHANNEL_CAPACITY);
+		let session = Arc::clone(&driver.session);
+		let task = tokio::spawn(driver.run(inbound_tx, commands_rx));
+		Self { session, inbound, sink: PollSender::new(commands.clone()), commands, task }
+	}
+
+	/// The session carried by this connection
+	pub fn session(&self) -> &SharedSession<S> {
+		&self.session
+	}
+
+	/// Send an application message, returning its MsgSeqNum
+	pub async fn send(&self, message: FixMessage) -> Result<u32, TransportError> {
+		let (reply, result) = oneshot::channel();
+		self.commands.send(Command::Send(Box::new(message), Some(reply))).await.map_err(|_| closed())?;
+		result.await.map_err(|_| closed())?
+	}
+
+	/// Start the logout handshake; the stream ends once it completes
+	pub async fn logout(&self, text: Option<&str>) -> Result<(), TransportError> {
+		let (reply, result) = oneshot::channel();
+		self.commands.send(Command::Logout(text.map(str::to_string), reply)).await.map_err(|_| closed())?;
+		result.await.map_err(|_| closed())?
+	}
+
+	/// Wait for the next application message, `None` once the connection is closed
+	pub async fn recv(&mut self) -> Option<FixMessage> {
+		self.inbound.recv().await
+	}
+
+	/// Wait until the connection closes and report why
+	///
+	/// Application messages that have not been received yet are dropped.
+	pub async fn closed(self) -> Result<(), TransportError> {
+		let Self { inbound, task, .. } = self;
+		// The task stops queueing messages for the stream
+		drop(inbound);
+		task.await.unwrap_or_else(|e| Err(io::Error::other(e).into()))
+	}
+}
+
+impl<S: MessageStore> Stream for AsyncConnection<S> {
+	type Item = FixMessage;
+
+	fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<FixMessage>> {
+		self.get_mut().inbound.poll_recv(cx)
+	}
+}
+
+/// Messages written through the sink are sent in order; use [`AsyncConnection::send`]
+/// to learn their MsgSeqNum or whether the session accepted them.
+impl<S: MessageStore> Sink<FixMessage> for AsyncConnection<S> {
+	type Error = TransportError;
+
+	fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), TransportError>> {
+		self.get_mut().sink.poll_reserve(cx).map_err(|_| closed())
+	}
+
+	fn start_send(self: Pin<&mut Self>, item: FixMessage) -> Result<(), TransportError> {
+		self.get_mut().sink.send_item(Command::Send(Box::new(item), None)).map_err(|_| closed())
+	}
+
+	fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), TransportError>> {
+		Poll::Ready(Ok(()))
+	}
+
+	fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), TransportError>> {
+		self.get_mut().sink.close();
+		Poll::Ready(Ok(()))
+	}
+}
+
+fn closed() -> TransportError {
+	SessionError::NotLoggedOn.into()
+}
+
+/// What woke the connection task up
+enum Input<'a> {
+	Read(usize),
+	Tick,
+	Command(Option<Command>),
+	/// Room in the inbound channel for a queued message, or an error once the stream is dropped
+	Room(Result<mpsc::Permit<'a, FixMessage>, mpsc::error::SendError<()>>),
+}
+
+/// Owns the socket of one connection and feeds it to the session
+#[derive(Debug)]
+pub(crate) struct Driver<S: MessageStore> {
+	reader: OwnedReadHalf,
+	writer: OwnedWriteHalf,
+	buffer: BytesMut,
+	codec: FixCodec,
+	session: SharedSession<S>,
+	ticker: Interval,
+	open: bool,
+	/// Application messages waiting for room in the inbound channel
+	pending: VecDeque<FixMessage>,
+}
+
+impl<S: MessageStore + Send + 'static> Driver<S> {
+	/// Wrap a connected socket; `buffer` may already hold bytes read from it
+	pub(crate) fn new(stream: TcpStream, buffer: BytesMut, session: SharedSession<S>) -> io::Result<Self> {
+		stream.set_nodelay(true)?;
+		let (reader, writer) = stream.into_split();
+		let mut ticker = tokio::time::interval(TICK);
+		ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+		Ok(Self { reader, writer, buffer, codec: FixCodec, session, ticker, open: true, pending: VecDeque::new() })
+	}
+
+	/// Send our Logon and wait for the counterparty's
+	pub(crate) async fn initiate_logon(&mut self) -> Result<(), TransportError> {
+		lock(&self.session).initiate_logon(OffsetDateTime::now_utc())?;
+		self.flush().await?;
+		self.await_logon().await
+	}
+
+	/// Process frames until the session is logged on
+	pub(crate) async fn await_logon(&mut self) -> Result<(), TransportError> {
+		loop {
+			while let Some(frame) = self.next_frame() {
+				self.handle_frame(&frame).await?;
+				if !self.open {
+					return Err(TransportError::LogonFailed("Session disconnected during logon".to_string()));
+				}
+			}
+			if lock(&self.session).is_logged_on() {
+				return Ok(());
+			}
+			match self.wait(None, None).await? {
+				Input::Read(0) => {
+					return Err(TransportError::LogonFailed("Connection closed during logon".to_string()));
+				},
+				Input::Tick => self.on_timer().await?,
+				Input::Read(_) | Input::Command(_) | Input::Room(_) => {},
+			}
+		}
+	}
+
+	/// Serve a logged-on session until the connection closes
+	async fn run(
+		mut self,
+		inbound: mpsc::Sender<FixMessage>,
+		mut commands: mpsc::Receiver<Command>,
+	) -> Result<(), TransportError> {
+		let result = self.serve(&inbound, &mut commands).await;
+		lock(&self.session).disconnect();
+		result
+	}
+
+	async fn serve(
+		&mut self,
+		inbound: &mpsc::Sender<FixMessage>,
+		commands: &mut mpsc::Receiver<Command>,
+	) -> Result<(), TransportError> {
+		loop {
+			while let Some(frame) = self.next_frame() {
+				if let Some(message) = self.handle_frame(&frame).await? {
+					self.deliver(inbound, message);
+				}
+				if !self.open {
+					return Ok(());
+				}
+			}
+			match self.wait(Some(commands), Some(inbound)).await? {
+				Input::Read(0) | Input::Command(None) => return Ok(()),
+				Input::Read(_) => {},
+				Input::Room(Ok(permit)) => {
+					permit.send(self.pending.pop_front().expect("Room is only awaited for pending messages"))
+				},
+				// Nobody reads the stream anymore, the session stays up
+				Input::Room(Err(_)) => self.pending.clear(),
+				Input::Tick => self.on_timer().await?,
+				Input::Command(Some(Command::Send(message, reply))) => {
+					let result = lock(&self.session).send(*message, OffsetDateTime::now_utc());
+					self.flush().await?;
+					if let Some(reply) = reply {
+						let _ = reply.send(result.map_err(Into::into));
+					}
+				},
+				Input::Command(Some(Command::Logout(text, reply))) => {
+					let result = lock(&self.session).logout(text.as_deref(), OffsetDateTime::now_utc());
+					self.flush().await?;
+					let _ = reply.send(result.map_err(Into::into));
+				},
+			}
+		}
+	}
+
+	/// Hand an application message to the stream, queueing it while the inbound channel is full
+	fn deliver(&mut self, inbound: &mpsc::Sender<FixMessage>, message: FixMessage) {
+		if !self.pending.is_empty() {
+			self.pending.push_back(message);
+			return;
+		}
+		match inbound.try_send(message) {
+			Err(TrySendError::Full(message)) => self.pending.push_back(message),
+			// Keep the session alive even if nobody reads the stream anymore
+			Ok(()) | Err(TrySendError::Closed(_)) => {},
+		}
+	}
+
+	/// Wait for bytes, a timer tick, a command or room for a queued message; the futures raced here are cancel safe
+	async fn wait<'a>(
+		&mut self,
+		commands: Option<&mut mpsc::Receiver<Command>>,
+		inbound: Option<&'a mpsc::Sender<FixMessage>>,
+	) -> io::Result<Input<'a>> {
+		let command = async {
+			match commands {
+				Some(commands) => commands.recv().await,
+				None => future::pending().await,
+			}
+		};
+		let room = async {
+			match inbound {
+				Some(inbound) if !self.pending.is_empty() => inbound.reserve().await,
+				_ => future::pending().await,
+			}
+		};
+		tokio::select! {
+			read = self.reader.read_buf(&mut self.buffer) => read.map(Input::Read),
+			_ = self.ticker.tick() => Ok(Input::Tick),
+			command = command => Ok(Input::Command(command)),
+			room = room => Ok(Input::Room(room)),
+		}
+	}
+
+	/// Next valid buffered frame; garbled frames are ignored as the FIX spec requires
+	pub(crate) fn next_frame(&mut self) -> Option<String> {
+		loop {
+			match self.codec.decode(&mut self.buffer) {
+				Ok(frame) => return frame,
+				Err(_) => continue,
+			}
+		}
+	}
+
+	/// Pass one frame to the session and write its responses
+	pub(crate) async fn handle_frame(&mut self, frame: &str) -> Result<Option<FixMessage>, TransportError> {
+		let result = lock(&self.session).on_message(frame, OffsetDateTime::now_utc());
+		self.flush().await?;
+		match result {
+			Ok(message) => {
+				self.open = lock(&self.session).state() != SessionState::Disconnected;
+				Ok(message)
+			},
+			// Unparsable messages are ignored, the connection stays up
+			Err(SessionError::InvalidMessage(_)) => Ok(None),
+			Err(e) => Err(e.into()),
+		}
+	}
+
+	async fn on_timer(&mut self) -> Result<(), TransportError> {
+		let result = lock(&self.session).on_timer(OffsetDateTime::now_utc());
+		self.flush().await?;
+		Ok(result?)
+	}
+
+	/// Write everything the session has queued
+	async fn flush(&mut self) -> Result<(), TransportError> {
+		let outgoing = lock(&self.session).take_outgoing();
+		for wire in outgoing {
+			self.writer.write_all(wire.as_bytes()).await?;
+		}
+		Ok(())
+	}
+}
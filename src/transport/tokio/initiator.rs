@@ -0,0 +1,264 @@
+//! Async TCP initiator
+
+use crate::{
+	session::{ConnectionType, MessageStore, Session, SessionConfig, SessionError, SessionState},
+	transport::{
+		SharedSession, TransportError,
+		connection::lock,
+		tokio::{AsyncConnection, connection::Driver},
+	},
+};
+use bytes::BytesMut;
+use std::{
+	sync::{Arc, Mutex},
+	time::Duration,
+};
+use time::OffsetDateTime;
+use tokio::net::TcpStream;
+
+/// Connects to a counterparty and logs one session on
+///
+/// Unlike the blocking [`Initiator`](crate::transport::Initiator), a dropped
+/// connection is not reopened automatically: once the [`AsyncConnection`]
+/// stream ends, call [`AsyncInitiator::connect`] again. The session's sequence
+/// numbers carry over.
+#[derive(Debug)]
+pub struct AsyncInitiator<S: MessageStore> {
+	address: String,
+	session: SharedSession<S>,
+	initial_backoff: Duration,
+	max_backoff: Duration,
+	/// Connection attempts before giving up, `None` to retry forever
+	max_attempts: Option<u32>,
+}
+
+impl<S: MessageStore + Send + 'static> AsyncInitiator<S> {
+	/// Create an initiator for `session` connecting to `address` (e.g. `"127.0.0.1:9878"`)
+	pub fn new(address: impl Into<String>, session: Session<S>) -> Self {
+		Self {
+			address: address.into(),
+			session: Arc::new(Mutex::new(session)),
+			initial_backoff: Duration::from_secs(1),
+			max_backoff: Duration::from_secs(30),
+			max_attempts: None,
+		}
+	}
+
+	/// Create an initiator from a session's settings
+	///
+	/// Connects to SocketConnectHost:SocketConnectPort, waiting ReconnectInterval
+	/// seconds before the first reconnection attempt, and logs on with the
+	/// settings' HeartBtInt and ResetOnLogon.
+	pub fn from_settings(config: &SessionConfig, store: S) -> Result<Self, TransportError> {
+		if config.connection_type != ConnectionType::Initiator {
+			return Err(config.invalid("Not an initiator session").into());
+		}
+		let address = config.connect_address().ok_or_else(|| config.invalid("No connect address"))?;
+		let interval = Duration::from_secs(config.reconnect_interval.into());
+		Ok(Self::new(address, config.session(store)).with_backoff(interval, interval.max(Duration::from_secs(30))))
+	}
+
+	/// Set the delay before the first reconnection attempt and the cap it doubles up to
+	pub const fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+		self.initial_backoff = initial;
+		self.max_backoff = max;
+		self
+	}
+
+	/// Give up after `attempts` failed connection attempts instead of retrying forever
+	pub const fn with_max_attempts(mut self, attempts: u32) -> Self {
+		self.max_attempts = Some(attempts);
+		self
+	}
+
+	/// The session driven by this initiator
+	pub fn session(&self) -> SharedSession<S> {
+		Arc::clone(&self.session)
+	}
+
+	/// Connect and complete the logon handshake, retrying with backoff
+	///
+	/// # Errors
+	/// Fails with [`TransportError::AlreadyConnected`] while a previous connection
+	/// is still open, otherwise with the error of the last attempt once the
+	/// maximum number of attempts is reached.
+	pub async fn connect(&self) -> Result<AsyncConnection<S>, TransportError> {
+		{
+			let session = lock(&self.session);
+			if session.state() != SessionState::Disconnected {
+				return Err(TransportError::AlreadyConnected(session.session_id().clone()));
+			}
+		}
+
+		let mut backoff = self.initial_backoff;
+		let mut attempts = 0;
+		loop {
+			match self.logon().await {
+				Ok(driver) => return Ok(AsyncConnection::spawn(driver)),
+				Err(e) => {
+					lock(&self.session).disconnect();
+					attempts += 1;
+					if self.max_attempts.is_some_and(|max| attempts >= max) {
+						return Err(e);
+					}
+					tokio::time::sleep(backoff).await;
+					backoff = (backoff * 2).min(self.max_backoff);
+				},
+			}
+		}
+	}
+
+	/// One connection attempt: connect, send our Logon and wait for the reply
+	async fn logon(&self) -> Result<Driver<S>, TransportError> {
+		if !lock(&self.session).is_session_time(OffsetDateTime::now_utc()) {
+			return Err(SessionError::OutsideSessionTime.into());
+		}
+		let stream = TcpStream::connect(&self.address).await?;
+		let mut driver = Driver::new(stream, BytesMut::new(), Arc::clone(&self.session))?;
+		driver.initiate_logon().await?;
+		Ok(driver)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		FixMessage, MsgType, Side,
+		messages::FixMessageBody,
+		session::{MemoryStore, SessionId},
+		transport::tokio::AsyncAcceptor,
+	};
+	use futures_util::{SinkExt, StreamExt};
+	use time::OffsetDateTime;
+
+	fn order(cl_ord_id: &str) -> FixMessage {
+		// CompIDs and MsgSeqNum are stamped by the session
+		FixMessage::builder(MsgType::NewOrderSingle, "", "", 0)
+			.cl_ord_id(cl_ord_id)
+			.symbol("AAPL")
+			.side(Side::Buy)
+			.order_qty(100.0)
+			.ord_type("1")
+			.handl_inst("1")
+			.transact_time(OffsetDateTime::now_utc())
+			.build()
+	}
+
+	fn cl_ord_id(message: &FixMessage) -> &str {
+		match &message.body {
+			FixMessageBody::NewOrderSingle(body) => &body.cl_ord_id,
+			other => panic!("Unexpected body {:?}", other),
+		}
+	}
+
+	async fn acceptor() -> (AsyncAcceptor<MemoryStore>, String) {
+		let mut acceptor = AsyncAcceptor::bind("127.0.0.1:0").await.unwrap();
+		acceptor.add_session(Session::new(SessionId::new("SERVER", "CLIENT"), MemoryStore::new()));
+		let address = acceptor.local_addr().unwrap().to_string();
+		(acceptor, address)
+	}
+
+	fn initiator(address: String) -> AsyncInitiator<MemoryStore> {
+		AsyncInitiator::new(address, Session::new(SessionId::new("CLIENT", "SERVER"), MemoryStore::new()))
+			.with_backoff(Duration::from_millis(10), Duration::from_millis(50))
+			.with_max_attempts(5)
+	}
+
+	#[tokio::test]
+	async fn test_stream_and_sink_exchange() {
+		let (acceptor, address) = acceptor().await;
+		let server = tokio::spawn(async move {
+			let mut connection = acceptor.accept().await.unwrap();
+			let order = connection.next().await.unwrap();
+			assert_eq!(cl_ord_id(&order), "ORD1");
+			connection.send(self::order("ECHO1")).await.unwrap();
+			// The client logs out next, which ends the stream
+			assert!(connection.next().await.is_none());
+			connection.closed().await.unwrap();
+		});
+
+		let initiator = initiator(address);
+		let mut connection = initiator.connect().await.unwrap();
+		SinkExt::send(&mut connection, order("ORD1")).await.unwrap();
+
+		let reply = connection.next().await.unwrap();
+		assert_eq!(cl_ord_id(&reply), "ECHO1");
+		assert_eq!(reply.header.msg_seq_num, 2);
+
+		connection.logout(None).await.unwrap();
+		assert!(connection.next().await.is_none());
+		connection.closed().await.unwrap();
+		server.await.unwrap();
+
+		let session = initiator.session();
+		let session = lock(&session);
+		assert!(!session.is_logged_on());
+		assert_eq!(session.store().next_sender_msg_seq_num(), 4);
+		assert_eq!(session.store().next_target_msg_seq_num(), 4);
+	}
+
+	#[tokio::test]
+	async fn test_reconnect_after_drop() {
+		let (acceptor, address) = acceptor().await;
+		let server = tokio::spawn(async move {
+			let connection = acceptor.accept().await.unwrap();
+			drop(connection);
+
+			let connection = acceptor.accept().await.unwrap();
+			assert_eq!(connection.send(order("AFTER-RECONNECT")).await.unwrap(), 3);
+			connection.closed().await.unwrap();
+		});
+
+		let initiator = initiator(address);
+		let mut connection = initiator.connect().await.unwrap();
+		assert!(matches!(initiator.connect().await, Err(TransportError::AlreadyConnected(_))));
+		assert!(connection.recv().await.is_none());
+		connection.closed().await.unwrap();
+
+		let mut connection = initiator.connect().await.unwrap();
+		let message = connection.recv().await.unwrap();
+		assert_eq!(cl_ord_id(&message), "AFTER-RECONNECT");
+		connection.logout(Some("Done")).await.unwrap();
+		assert!(connection.recv().await.is_none());
+		server.await.unwrap();
+	}
+
+	#[tokio::test]
+	async fn test_slow_reader_does_not_stall_the_session() {
+		const ORDERS: usize = 100;
+		let (acceptor, address) = acceptor().await;
+		let server = tokio::spawn(async move {
+			let mut connection = acceptor.accept().await.unwrap();
+			for i in 0..ORDERS {
+				connection.send(order(&format!("ORD{}", i))).await.unwrap();
+			}
+			let ping = connection.next().await.unwrap();
+			assert_eq!(cl_ord_id(&ping), "PING");
+			connection
+		});
+
+		let initiator = initiator(address);
+		let mut connection = initiator.connect().await.unwrap();
+		// More messages arrive than the inbound channel holds while the stream is not read
+		tokio::time::sleep(Duration::from_millis(100)).await;
+		let sent = tokio::time::timeout(Duration::from_secs(5), connection.send(order("PING"))).await;
+		assert!(sent.expect("The connection task is blocked").is_ok());
+		let _server_connection = server.await.unwrap();
+
+		for i in 0..ORDERS {
+			assert_eq!(cl_ord_id(&connection.next().await.unwrap()), format!("ORD{}", i));
+		}
+	}
+
+	#[tokio::test]
+	async fn test_unknown_session_is_refused() {
+		let (acceptor, address) = acceptor().await;
+		let server = tokio::spawn(async move { acceptor.accept().await.map(|_| ()) });
+
+		let session = Session::new(SessionId::new("STRANGER", "SERVER"), MemoryStore::new());
+		let initiator = AsyncInitiator::new(address, session).with_max_attempts(1);
+		assert!(initiator.connect().await.is_err());
+		assert!(matches!(server.await.unwrap(), Err(TransportError::UnknownSession(_))));
+	}
+}
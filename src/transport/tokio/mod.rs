@@ -0,0 +1,18 @@
+//! Async TCP transport on the tokio runtime
+//!
+//! Available with the `tokio` feature. [`AsyncInitiator`] and [`AsyncAcceptor`]
+//! mirror their blocking counterparts but hand out an [`AsyncConnection`],
+//! whose background task drives the session with tokio timers and which
+//! exposes inbound application messages as a `Stream` and outbound ones as a
+//! `Sink`. [`FixCodec`] can be used on its own with `tokio_util::codec::Framed`.
+
+pub mod acceptor;
+pub mod codec;
+pub mod connection;
+pub mod initiator;
+
+// Re-export commonly used types
+pub use acceptor::AsyncAcceptor;
+pub use codec::FixCodec;
+pub use connection::AsyncConnection;
+pub use initiator::AsyncInitiator;
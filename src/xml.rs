@@ -0,0 +1,141 @@
+//! A minimal XML scanner, enough for data dictionaries and FIXML
+//!
+//! Elements are read one tag at a time, without building a tree; text content,
+//! comments, declarations and processing instructions are skipped.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ElementKind {
+	/// `<name ...>`
+	Start,
+	/// `</name>`
+	End,
+	/// `<name ... />`
+	Empty,
+}
+
+/// A tag of an XML document, with its attributes
+#[derive(Debug)]
+pub(crate) struct XmlElement<'a> {
+	pub name: &'a str,
+	pub kind: ElementKind,
+	pub attributes: Vec<(&'a str, &'a str)>,
+}
+
+impl XmlElement<'_> {
+	pub fn attribute(&self, name: &str) -> Option<&str> {
+		self.attributes.iter().find(|(key, _)| *key == name).map(|(_, value)| *value)
+	}
+
+	pub fn required(&self, name: &str) -> Result<String, String> {
+		let value = self.attribute(name).ok_or_else(|| format!("<{}> without {}", self.name, name))?;
+		Ok(unescape(value))
+	}
+}
+
+/// The tags of an XML document, skipping text, comments and declarations
+pub(crate) struct XmlElements<'a> {
+	rest: &'a str,
+}
+
+impl<'a> XmlElements<'a> {
+	pub const fn new(xml: &'a str) -> Self {
+		Self { rest: xml }
+	}
+}
+
+impl<'a> Iterator for XmlElements<'a> {
+	type Item = Result<XmlElement<'a>, String>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		loop {
+			let start = self.rest.find('<')?;
+			self.rest = &self.rest[start..];
+			if let Some(comment) = self.rest.strip_prefix("<!--") {
+				let Some(end) = comment.find("-->") else { return Some(Err("Unterminated comment".to_string())) };
+				self.rest = &comment[end + 3..];
+				continue;
+			}
+			let Some(end) = tag_end(self.rest) else { return Some(Err("Unterminated tag".to_string())) };
+			let tag = &self.rest[1..end];
+			self.rest = &self.rest[end + 1..];
+			if tag.starts_with('?') || tag.starts_with('!') {
+				continue;
+			}
+			return Some(parse_element(tag));
+		}
+	}
+}
+
+/// Position of the `>` closing the tag that `xml` starts with, skipping quoted attribute values
+fn tag_end(xml: &str) -> Option<usize> {
+	let mut quote = None;
+	for (position, c) in xml.char_indices() {
+		match (quote, c) {
+			(None, '>') => return Some(position),
+			(None, '"' | '\'') => quote = Some(c),
+			(Some(open), _) if c == open => quote = None,
+			_ => {},
+		}
+	}
+	None
+}
+
+fn parse_element(tag: &str) -> Result<XmlElement<'_>, String> {
+	if let Some(name) = tag.strip_prefix('/') {
+		return Ok(XmlElement { name: name.trim(), kind: ElementKind::End, attributes: Vec::new() });
+	}
+	let (tag, kind) = match tag.strip_suffix('/') {
+		Some(tag) => (tag, ElementKind::Empty),
+		None => (tag, ElementKind::Start),
+	};
+	let tag = tag.trim();
+	let name_end = tag.find(char::is_whitespace).unwrap_or(tag.len());
+	let mut rest = &tag[name_end..];
+	let mut attributes = Vec::new();
+	loop {
+		rest = rest.trim_start();
+		if rest.is_empty() {
+			break;
+		}
+		let Some((key, value)) = rest.split_once('=') else { return Err(format!("Malformed tag <{}>", tag)) };
+		let value = value.trim_start();
+		let Some(quote) = value.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+			return Err(format!("Unquoted attribute in <{}>", tag));
+		};
+		let Some(end) = value[1..].find(quote) else {
+			return Err(format!("Unterminated attribute in <{}>", tag));
+		};
+		attributes.push((key.trim(), &value[1..end + 1]));
+		rest = &value[end + 2..];
+	}
+	Ok(XmlElement { name: &tag[..name_end], kind, attributes })
+}
+
+pub(crate) fn unescape(value: &str) -> String {
+	value.replace("&lt;", "<").replace("&gt;", ">").replace("&quot;", "\"").replace("&apos;", "'").replace("&amp;", "&")
+}
+
+/// Escape `value` for use in an attribute
+#[cfg(feature = "fixml")]
+pub(crate) fn escape(value: &str) -> String {
+	value.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;").replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_quoted_angle_brackets() {
+		let xml = r#"<?xml version="1.0"?><Order Txt="a>b" Alt='x"y>z'><Hdr SID="C"/></Order>"#;
+		let elements: Vec<XmlElement> = XmlElements::new(xml).collect::<Result<_, _>>().unwrap();
+		assert_eq!(elements.len(), 3);
+		assert_eq!((elements[0].name, elements[0].kind), ("Order", ElementKind::Start));
+		assert_eq!(elements[0].attribute("Txt"), Some("a>b"));
+		assert_eq!(elements[0].attribute("Alt"), Some("x\"y>z"));
+		assert_eq!((elements[1].name, elements[1].kind), ("Hdr", ElementKind::Empty));
+		assert_eq!((elements[2].name, elements[2].kind), ("Order", ElementKind::End));
+
+		assert!(XmlElements::new(r#"<Order Txt="a>"#).next().unwrap().is_err());
+	}
+}